@@ -0,0 +1,16 @@
+#!/usr/bin/env bash
+# Fails if any criterion benchmark compared against a saved baseline got
+# slower by more than THRESHOLD (a fraction, default 0.10) on its mean.
+set -euo pipefail
+
+threshold=${THRESHOLD:-0.10}
+status=0
+while IFS= read -r estimates; do
+    change=$(jq '.mean.point_estimate' "$estimates")
+    if awk -v c="$change" -v t="$threshold" 'BEGIN { exit !(c > t) }'; then
+        bench=${estimates#target/criterion/}
+        echo "regression: ${bench%/change/estimates.json} is $(awk -v c="$change" 'BEGIN { printf "%.1f", c * 100 }')% slower"
+        status=1
+    fi
+done < <(find target/criterion -path '*/change/estimates.json')
+exit $status
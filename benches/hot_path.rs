@@ -0,0 +1,79 @@
+//! Microbenchmarks of the verification hot path, per cube size.
+//!
+//! CI runs these against a baseline saved from the target branch and fails
+//! on regressions, see `.github/scripts/bench-regressions.sh`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use qbitcoin_core::spec::{self, SpecVersion};
+use qbitcoin_core::{challenge_seed, Cube, Move, PowSeal};
+
+const SIZES: std::ops::RangeInclusive<usize> = 2..=10;
+
+fn scrambled(size: usize) -> (Cube, Vec<Move>) {
+    let mut cube = Cube::new(size);
+    let scramble = cube.scramble_deterministic(12345, b"hot_path");
+    (cube, scramble)
+}
+
+fn bench_apply_move(c: &mut Criterion) {
+    let mut group = c.benchmark_group("apply_move");
+    for size in SIZES {
+        let (cube, _) = scrambled(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &cube, |b, cube| {
+            let mut cube = cube.clone();
+            b.iter(|| cube.apply_move(black_box(&Move::R(1))))
+        });
+    }
+    group.finish();
+}
+
+fn bench_state_commitment(c: &mut Criterion) {
+    let mut group = c.benchmark_group("state_commitment");
+    for size in SIZES {
+        let (cube, _) = scrambled(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &cube, |b, cube| {
+            b.iter(|| black_box(cube).state_commitment())
+        });
+    }
+    group.finish();
+}
+
+fn bench_decoding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    for size in SIZES {
+        let (cube, scramble) = scrambled(size);
+        let packed = cube.to_packed_bytes();
+        group.bench_with_input(BenchmarkId::new("packed_cube", size), &packed, |b, packed| {
+            b.iter(|| Cube::from_packed_bytes(black_box(packed)))
+        });
+
+        let seal = PowSeal {
+            cube_size: size as u32,
+            nonce: 12345,
+            moves: scramble,
+        }
+        .encode();
+        group.bench_with_input(BenchmarkId::new("seal", size), &seal, |b, seal| {
+            b.iter(|| PowSeal::decode(black_box(seal)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_scramble(c: &mut Criterion) {
+    let seed = challenge_seed(12345, b"hot_path");
+    c.bench_function("scramble/derive_moves", |b| {
+        b.iter(|| spec::scramble_moves(SpecVersion::CURRENT, black_box(&seed)))
+    });
+
+    let mut group = c.benchmark_group("scramble/apply");
+    for size in SIZES {
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            b.iter(|| Cube::new(size).scramble_deterministic(black_box(12345), b"hot_path"))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_apply_move, bench_state_commitment, bench_decoding, bench_scramble);
+criterion_main!(benches);
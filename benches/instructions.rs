@@ -0,0 +1,67 @@
+//! Instruction counts of the consensus-critical paths, measured under
+//! Callgrind with iai-callgrind. Unlike wall-clock benches the counts are
+//! deterministic, so CI fails on any increase above the configured limit.
+//! Needs valgrind and a matching `iai-callgrind-runner` installed.
+
+use std::hint::black_box;
+
+use iai_callgrind::{library_benchmark, library_benchmark_group, main};
+use qbitcoin_core::{invert_sequence, verify_seal, Cube, Move, PowSeal, Target, VerifyError};
+
+const NONCE: u64 = 12345;
+const HEADER: &[u8] = b"instructions";
+
+fn scrambled(size: usize) -> Cube {
+    let mut cube = Cube::new(size);
+    cube.scramble_deterministic(NONCE, HEADER);
+    cube
+}
+
+fn solved_seal(size: usize) -> PowSeal {
+    let mut cube = Cube::new(size);
+    let scramble = cube.scramble_deterministic(NONCE, HEADER);
+    let moves = invert_sequence(&scramble);
+    PowSeal {
+        cube_size: size as u32,
+        nonce: NONCE,
+        moves,
+    }
+}
+
+// Arguments are built outside the measurement
+#[library_benchmark]
+#[bench::size_2(scrambled(2))]
+#[bench::size_3(scrambled(3))]
+#[bench::size_5(scrambled(5))]
+#[bench::size_7(scrambled(7))]
+fn apply_move(mut cube: Cube) -> Cube {
+    cube.apply_move(black_box(&Move::R(1)));
+    cube
+}
+
+#[library_benchmark]
+#[bench::size_2(solved_seal(2))]
+#[bench::size_3(solved_seal(3))]
+#[bench::size_5(solved_seal(5))]
+fn verify(seal: PowSeal) -> Result<Cube, VerifyError> {
+    verify_seal(black_box(&seal), black_box(HEADER), &Target::MAX)
+}
+
+// Challenge seed and scramble of a fresh cube, as every verification and
+// mining attempt starts.
+#[library_benchmark]
+#[bench::size_2(2)]
+#[bench::size_3(3)]
+#[bench::size_5(5)]
+fn derive_challenge(size: usize) -> Cube {
+    let mut cube = Cube::new(size);
+    cube.scramble_deterministic(black_box(NONCE), black_box(HEADER));
+    cube
+}
+
+library_benchmark_group!(
+    name = consensus;
+    benchmarks = apply_move, verify, derive_challenge
+);
+
+main!(library_benchmark_groups = consensus);
@@ -0,0 +1,36 @@
+//! Compares the sticker layouts of `qbitcoin_core::layout` on the sizes
+//! miners use. Only one layout is compiled in at a time, so run the bench
+//! once per layout and let criterion compare the saved baselines:
+//!
+//! ```text
+//! cargo bench --bench layout -- --save-baseline face-major
+//! cargo bench --bench layout --features flat-stickers -- --baseline face-major
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use qbitcoin_core::{layout, Cube, Move};
+
+fn bench_layout(c: &mut Criterion) {
+    // Same group names for both layouts so baselines line up
+    let mut group = c.benchmark_group("layout");
+    println!("layout: {}", layout::NAME);
+    for size in 4..=7 {
+        let mut cube = Cube::new(size);
+        cube.scramble_deterministic(12345, b"layout");
+
+        group.bench_with_input(BenchmarkId::new("apply_move", size), &cube, |b, cube| {
+            let mut cube = cube.clone();
+            b.iter(|| cube.apply_move(black_box(&Move::R(1))))
+        });
+        group.bench_with_input(BenchmarkId::new("is_solved", size), &cube, |b, cube| {
+            b.iter(|| black_box(cube).is_solved())
+        });
+        group.bench_with_input(BenchmarkId::new("state_commitment", size), &cube, |b, cube| {
+            b.iter(|| black_box(cube).state_commitment())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_layout);
+criterion_main!(benches);
@@ -1,31 +1,21 @@
 use criterion::{criterion_group, criterion_main, Criterion};
-use qbitcoin_core::{Cube, Move, calculate_difficulty};
+use qbitcoin_core::quantum::grover_cost;
+use qbitcoin_core::difficulty::work_per_seal;
+use qbitcoin_core::{invert_sequence, Cube, Target};
 
 fn bench_rubikpow(c: &mut Criterion) {
     let mut group = c.benchmark_group("RubikPoW");
 
     // Benchmark for different cube sizes
     for size in [3, 4, 5].iter() {
-        let difficulty = calculate_difficulty(*size);
-        group.bench_function(format!("verify_{}x{}x{} (difficulty: {})", size, size, size, difficulty), |b| {
+        let work = work_per_seal(*size as u32, &Target::MAX);
+        group.bench_function(format!("verify_{}x{}x{} (work: {})", size, size, size, work), |b| {
             b.iter(|| {
                 let mut cube = Cube::new(*size);
                 let block_header = b"mock_block_header";
                 let scramble_moves = cube.scramble_deterministic(12345, block_header);
                 // Verify the scramble_moves solve the cube (reversing the scramble)
-                let mut solution = scramble_moves.clone();
-                solution.reverse();
-                for move_ref in solution.iter_mut() {
-                    // Invert each move (U -> U', U' -> U, U2 -> U2)
-                    match move_ref {
-                        Move::U(count) => *move_ref = Move::U((4 - count) % 4),
-                        Move::D(count) => *move_ref = Move::D((4 - count) % 4),
-                        Move::L(count) => *move_ref = Move::L((4 - count) % 4),
-                        Move::R(count) => *move_ref = Move::R((4 - count) % 4),
-                        Move::F(count) => *move_ref = Move::F((4 - count) % 4),
-                        Move::B(count) => *move_ref = Move::B((4 - count) % 4),
-                    }
-                }
+                let solution = invert_sequence(&scramble_moves);
                 assert!(cube.verify_solution(&solution));
             })
         });
@@ -33,8 +23,14 @@ fn bench_rubikpow(c: &mut Criterion) {
 
     group.finish();
 
-    // Add notes about quantum complexity
-    println!("NOTE: Grover's algorithm would require 2^89 - 2^193 quantum operations for 3x3x3 - 5x5x5 cubes.");
+    // Report quantum complexity from the cost model instead of quoting constants
+    for size in [3, 4, 5].iter() {
+        let estimate = grover_cost(*size, &Target::MAX);
+        println!(
+            "NOTE: {}x{}x{} Grover search needs ~2^{:.1} queries (circuit depth ~2^{:.1}, {} logical qubits).",
+            size, size, size, estimate.grover_queries_log2, estimate.circuit_depth_log2, estimate.logical_qubits
+        );
+    }
 }
 
 criterion_group!(benches, bench_rubikpow);
@@ -0,0 +1,15 @@
+//! The consensus test vectors as a Wasm module, for comparing the Wasm build
+//! of the crate against a native one; see `tests/wasm_vectors_tests.rs`.
+//!
+//! Build with
+//! `cargo build --release --example wasm_vectors --target wasm32-unknown-unknown --no-default-features`.
+
+use qbitcoin_core::vectors;
+
+/// Encoded vectors in the module's memory, as `pointer << 32 | length`. The
+/// buffer is never freed.
+#[no_mangle]
+pub extern "C" fn vectors() -> u64 {
+    let bytes = vectors::encode(&vectors::vectors()).leak();
+    ((bytes.as_ptr() as u64) << 32) | bytes.len() as u64
+}
@@ -0,0 +1,313 @@
+//! Indexer helpers for block explorers.
+//!
+//! Explorers feed finalized blocks in through a [`BlockSource`], the indexer
+//! pulls the RubikPoW seal out of each block's digest, pairs it with the
+//! pallet's events and hands a normalized [`SolutionRecord`] to a [`Sink`].
+//! Decoding the node's SCALE types is left to the source, which is the only
+//! part that depends on a particular client library.
+//!
+//! [`decode_submission`] reads a submission call straight from extrinsic
+//! bytes, so an explorer can show the solution without the runtime.
+
+use qbitcoin_core::spec::{decode_move, SpecVersion};
+use qbitcoin_core::{notation, Move, PowSeal, RUBIKPOW_ENGINE_ID};
+
+pub type AccountId = [u8; 32];
+pub type BlockHash = [u8; 32];
+
+/// A digest log entry, reduced to what the indexer needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DigestLog {
+    /// Author (32-byte account id) followed by the encoded seal.
+    PreRuntime([u8; 4], Vec<u8>),
+    Seal([u8; 4], Vec<u8>),
+    Other,
+}
+
+/// RubikPoW pallet events, with account ids already decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RubikPowEvent {
+    BlockMined { miner: AccountId, cube_size: u32 },
+    Reward { miner: AccountId, payee: AccountId, amount: u32 },
+    DifficultyAdjustment { new_difficulty: u32 },
+    SolutionRejected { author: AccountId, reason: u8 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinalizedBlock {
+    pub number: u64,
+    pub hash: BlockHash,
+    pub logs: Vec<DigestLog>,
+    pub events: Vec<RubikPowEvent>,
+}
+
+/// Yields finalized blocks in order, e.g. from a node subscription.
+pub trait BlockSource {
+    type Error;
+
+    /// `Ok(None)` once the source is exhausted.
+    fn next_finalized(&mut self) -> Result<Option<FinalizedBlock>, Self::Error>;
+}
+
+/// One solved block, normalized for storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolutionRecord {
+    pub block_number: u64,
+    pub block_hash: BlockHash,
+    pub miner: AccountId,
+    pub cube_size: u32,
+    pub nonce: u64,
+    pub moves: Vec<Move>,
+    pub metrics: SolutionMetrics,
+    /// Reward paid, if the block carried a `Reward` event for the miner.
+    pub reward: Option<u32>,
+    /// Difficulty after the block, if it changed.
+    pub difficulty: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SolutionMetrics {
+    /// Number of moves as submitted (half-turn metric).
+    pub move_count: usize,
+    /// Quarter turns, counting a half turn as two.
+    pub quarter_turns: usize,
+}
+
+impl SolutionMetrics {
+    pub fn of(moves: &[Move]) -> Self {
+        SolutionMetrics {
+            move_count: moves.len(),
+            quarter_turns: moves
+                .iter()
+                .map(|m| match m.count() % 4 {
+                    2 => 2,
+                    0 => 0,
+                    _ => 1,
+                })
+                .sum(),
+        }
+    }
+}
+
+/// Call index of `pallet_rubikpow::submit_solution`.
+pub const SUBMIT_SOLUTION_CALL: u8 = 0;
+/// Call index of `pallet_rubikpow::submit_solution_unsigned`.
+pub const SUBMIT_SOLUTION_UNSIGNED_CALL: u8 = 2;
+/// Version byte of an unsigned (bare) extrinsic.
+const UNSIGNED_EXTRINSIC_V4: u8 = 4;
+
+/// A submission call, decoded for display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmissionView {
+    /// Account credited by an unsigned submission, which it is signed by;
+    /// signed submissions credit the extrinsic's signer.
+    pub author: Option<AccountId>,
+    pub size: u32,
+    pub nonce: u64,
+    /// The moves in standard notation, e.g. `R U2 F'`.
+    pub algorithm_string: String,
+    pub metrics: SolutionMetrics,
+}
+
+/// Decodes a `pallet_rubikpow` submission call from the bytes following the
+/// pallet index: the call index, then the SCALE-encoded arguments. `None`
+/// for other calls, unknown move tags and malformed or trailing bytes.
+///
+/// The bytes in front of the call differ between signed extrinsics of
+/// different runtimes; take the call from the explorer's extrinsic decoder,
+/// or use [`decode_unsigned_extrinsic`] for bare extrinsics.
+pub fn decode_submission(bytes: &[u8]) -> Option<SubmissionView> {
+    let mut input = ScaleInput(bytes);
+    let author = match input.byte()? {
+        SUBMIT_SOLUTION_CALL => None,
+        SUBMIT_SOLUTION_UNSIGNED_CALL => Some(input.array()?),
+        _ => return None,
+    };
+    let size = u32::from_le_bytes(input.array()?);
+    let count = input.compact()?;
+    let moves = input
+        .take(usize::try_from(count).ok()?)?
+        .iter()
+        .map(|&tag| decode_move(SpecVersion::CURRENT, tag))
+        .collect::<Option<Vec<Move>>>()?;
+    let nonce = u64::from_le_bytes(input.array()?);
+    if author.is_some() {
+        // The mined block the seal is for and the author's signature
+        let _mined: [u8; 4] = input.array()?;
+        let _signature: [u8; 64] = input.array()?;
+    }
+    if !input.0.is_empty() {
+        return None;
+    }
+
+    Some(SubmissionView {
+        author,
+        size,
+        nonce,
+        algorithm_string: notation::format_algorithm(&moves),
+        metrics: SolutionMetrics::of(&moves),
+    })
+}
+
+/// [`decode_submission`] for a whole unsigned extrinsic as it appears in a
+/// block body: compact length, version byte, pallet index, call.
+pub fn decode_unsigned_extrinsic(bytes: &[u8], pallet_index: u8) -> Option<SubmissionView> {
+    let mut input = ScaleInput(bytes);
+    let len = usize::try_from(input.compact()?).ok()?;
+    if input.0.len() != len || input.byte()? != UNSIGNED_EXTRINSIC_V4 || input.byte()? != pallet_index {
+        return None;
+    }
+    decode_submission(input.0)
+}
+
+/// Just enough of a SCALE reader for submission calls.
+struct ScaleInput<'a>(&'a [u8]);
+
+impl<'a> ScaleInput<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(taken)
+    }
+
+    fn byte(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    /// Compact-encoded integer of up to 64 bits.
+    fn compact(&mut self) -> Option<u64> {
+        let first = self.byte()?;
+        Some(match first & 0b11 {
+            0b00 => u64::from(first >> 2),
+            0b01 => u64::from(u16::from_le_bytes([first, self.byte()?]) >> 2),
+            0b10 => {
+                let rest: [u8; 3] = self.array()?;
+                u64::from(u32::from_le_bytes([first, rest[0], rest[1], rest[2]]) >> 2)
+            }
+            _ => {
+                let len = usize::from(first >> 2) + 4;
+                if len > 8 {
+                    return None;
+                }
+                let mut bytes = [0u8; 8];
+                bytes[..len].copy_from_slice(self.take(len)?);
+                u64::from_le_bytes(bytes)
+            }
+        })
+    }
+}
+
+/// Destination for records. SQL-backed sinks can create their table from
+/// [`SOLUTIONS_SCHEMA`].
+pub trait Sink {
+    type Error;
+
+    fn write(&mut self, record: &SolutionRecord) -> Result<(), Self::Error>;
+}
+
+/// Table layout shared by the SQLite and Postgres sinks. `moves` holds the
+/// seal's move bytes.
+pub const SOLUTIONS_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS solutions (
+    block_number BIGINT PRIMARY KEY,
+    block_hash BLOB NOT NULL,
+    miner BLOB NOT NULL,
+    cube_size INTEGER NOT NULL,
+    nonce BIGINT NOT NULL,
+    moves BLOB NOT NULL,
+    move_count INTEGER NOT NULL,
+    quarter_turns INTEGER NOT NULL,
+    reward INTEGER,
+    difficulty INTEGER
+)";
+
+/// Keeps every record in memory; for tests and small tools.
+#[derive(Debug, Default)]
+pub struct MemorySink {
+    pub records: Vec<SolutionRecord>,
+}
+
+impl Sink for MemorySink {
+    type Error = std::convert::Infallible;
+
+    fn write(&mut self, record: &SolutionRecord) -> Result<(), Self::Error> {
+        self.records.push(record.clone());
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum IndexerError<S, K> {
+    Source(S),
+    Sink(K),
+}
+
+/// Extracts the solution record from a block, if it carries a RubikPoW seal.
+pub fn decode_block(block: &FinalizedBlock) -> Option<SolutionRecord> {
+    let (miner, seal) = block.logs.iter().find_map(|log| match log {
+        DigestLog::PreRuntime(id, data) if *id == RUBIKPOW_ENGINE_ID && data.len() >= 32 => {
+            let miner: AccountId = data[..32].try_into().ok()?;
+            Some((miner, PowSeal::decode(&data[32..])?))
+        }
+        _ => None,
+    })?;
+
+    let reward = block.events.iter().find_map(|event| match event {
+        RubikPowEvent::Reward {
+            miner: paid,
+            amount,
+            ..
+        } if *paid == miner => Some(*amount),
+        _ => None,
+    });
+    let difficulty = block.events.iter().rev().find_map(|event| match event {
+        RubikPowEvent::DifficultyAdjustment { new_difficulty } => Some(*new_difficulty),
+        _ => None,
+    });
+
+    Some(SolutionRecord {
+        block_number: block.number,
+        block_hash: block.hash,
+        miner,
+        cube_size: seal.cube_size,
+        nonce: seal.nonce,
+        metrics: SolutionMetrics::of(&seal.moves),
+        moves: seal.moves,
+        reward,
+        difficulty,
+    })
+}
+
+pub struct Indexer<S, K> {
+    source: S,
+    sink: K,
+}
+
+impl<S: BlockSource, K: Sink> Indexer<S, K> {
+    pub fn new(source: S, sink: K) -> Self {
+        Indexer { source, sink }
+    }
+
+    /// Indexes blocks until the source is exhausted. Returns how many
+    /// records were written.
+    pub fn run(&mut self) -> Result<usize, IndexerError<S::Error, K::Error>> {
+        let mut written = 0;
+        while let Some(block) = self.source.next_finalized().map_err(IndexerError::Source)? {
+            if let Some(record) = decode_block(&block) {
+                self.sink.write(&record).map_err(IndexerError::Sink)?;
+                written += 1;
+            }
+        }
+        Ok(written)
+    }
+
+    pub fn into_sink(self) -> K {
+        self.sink
+    }
+}
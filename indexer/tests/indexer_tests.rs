@@ -0,0 +1,135 @@
+use qbitcoin_core::spec::{encode_move, SpecVersion};
+use qbitcoin_core::{Axis, Face, Move, PowSeal, RUBIKPOW_ENGINE_ID};
+use qbitcoin_indexer::{
+    decode_submission, decode_unsigned_extrinsic, BlockSource, DigestLog, FinalizedBlock, Indexer, MemorySink,
+    RubikPowEvent, SolutionMetrics, SUBMIT_SOLUTION_CALL, SUBMIT_SOLUTION_UNSIGNED_CALL,
+};
+
+struct VecSource(Vec<FinalizedBlock>);
+
+impl BlockSource for VecSource {
+    type Error = ();
+
+    fn next_finalized(&mut self) -> Result<Option<FinalizedBlock>, ()> {
+        Ok(if self.0.is_empty() {
+            None
+        } else {
+            Some(self.0.remove(0))
+        })
+    }
+}
+
+#[test]
+fn test_indexes_sealed_blocks_only() {
+    let miner = [7u8; 32];
+    let seal = PowSeal {
+        cube_size: 3,
+        nonce: 9,
+        moves: vec![Move::R(1), Move::U(2), Move::F(3)],
+    };
+    let mut data = miner.to_vec();
+    data.extend(seal.encode());
+
+    let sealed = FinalizedBlock {
+        number: 1,
+        hash: [1; 32],
+        logs: vec![
+            DigestLog::Other,
+            DigestLog::PreRuntime(RUBIKPOW_ENGINE_ID, data),
+        ],
+        events: vec![
+            RubikPowEvent::BlockMined {
+                miner,
+                cube_size: 3,
+            },
+            RubikPowEvent::Reward {
+                miner,
+                payee: [8; 32],
+                amount: 3000,
+            },
+            RubikPowEvent::DifficultyAdjustment { new_difficulty: 12 },
+        ],
+    };
+    let empty = FinalizedBlock {
+        number: 2,
+        hash: [2; 32],
+        logs: vec![DigestLog::Other],
+        events: vec![],
+    };
+
+    let mut indexer = Indexer::new(VecSource(vec![sealed, empty]), MemorySink::default());
+    assert_eq!(indexer.run().unwrap(), 1);
+
+    let records = indexer.into_sink().records;
+    let record = &records[0];
+    assert_eq!(record.miner, miner);
+    assert_eq!(record.moves, seal.moves);
+    assert_eq!(record.reward, Some(3000));
+    assert_eq!(record.difficulty, Some(12));
+    assert_eq!(
+        record.metrics,
+        SolutionMetrics {
+            move_count: 3,
+            quarter_turns: 4
+        }
+    );
+}
+
+/// SCALE arguments of a submission call: size, moves, nonce.
+fn submission_args(size: u32, moves: &[Move], nonce: u64) -> Vec<u8> {
+    let mut bytes = size.to_le_bytes().to_vec();
+    bytes.push((moves.len() as u8) << 2);
+    bytes.extend(moves.iter().map(|m| encode_move(SpecVersion::CURRENT, m)));
+    bytes.extend(nonce.to_le_bytes());
+    bytes
+}
+
+#[test]
+fn test_decodes_submission_calls() {
+    let moves = [Move::R(1), Move::U(2), Move::F(3), Move::Wide(Face::Left, 1), Move::Rotation(Axis::Y, 3)];
+    let mut call = vec![SUBMIT_SOLUTION_CALL];
+    call.extend(submission_args(4, &moves, 77));
+
+    let view = decode_submission(&call).unwrap();
+    assert_eq!(view.author, None);
+    assert_eq!((view.size, view.nonce), (4, 77));
+    assert_eq!(view.algorithm_string, "R U2 F' Lw y'");
+    assert_eq!(view.metrics, SolutionMetrics::of(&moves));
+
+    let author = [9u8; 32];
+    let mut unsigned = vec![SUBMIT_SOLUTION_UNSIGNED_CALL];
+    unsigned.extend(author);
+    unsigned.extend(submission_args(3, &moves[..2], 5));
+    unsigned.extend(1u32.to_le_bytes());
+    unsigned.extend([3u8; 64]);
+    let mut extrinsic = (((unsigned.len() as u16 + 2) << 2) | 0b01).to_le_bytes().to_vec();
+    extrinsic.extend([4, 42]);
+    extrinsic.extend(&unsigned);
+
+    let view = decode_unsigned_extrinsic(&extrinsic, 42).unwrap();
+    assert_eq!(view.author, Some(author));
+    assert_eq!(view.algorithm_string, "R U2");
+    assert!(decode_unsigned_extrinsic(&extrinsic, 41).is_none());
+    // Without its signature
+    assert!(decode_submission(&unsigned[..unsigned.len() - 64]).is_none());
+}
+
+#[test]
+fn test_rejects_other_calls_and_malformed_bytes() {
+    let mut call = vec![SUBMIT_SOLUTION_CALL];
+    call.extend(submission_args(3, &[Move::R(1)], 1));
+
+    // set_difficulty
+    let mut other = call.clone();
+    other[0] = 1;
+    assert!(decode_submission(&other).is_none());
+    assert!(decode_submission(&call[..call.len() - 1]).is_none());
+
+    let mut trailing = call.clone();
+    trailing.push(0);
+    assert!(decode_submission(&trailing).is_none());
+
+    let mut unknown_move = call;
+    unknown_move[6] = 0xFF;
+    assert!(decode_submission(&unknown_move).is_none());
+}
@@ -0,0 +1,194 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Non-fungible records of notable seals.
+//!
+//! When `pallet_rubikpow` accepts a seal that is notable, the credited miner
+//! receives a collectible holding the seal's fingerprint. A seal is notable
+//! when it is the first on a cube larger than any mined before, or when it is
+//! provably optimal: on cubes up to 3x3x3, it is made of face turns only and
+//! no shorter sequence of them solves its challenge. The optimality search
+//! grows as `18^moves`, so only solutions of at most `MaxOptimalityDepth`
+//! moves are checked; longer ones would need the off-chain distance tables.
+//! The search is charged with the seal through [`OnSealAccepted::weight`].
+//!
+//! Collectibles live in a plain map rather than a full NFT pallet and can only
+//! be transferred.
+
+use frame_support::pallet_prelude::*;
+use frame_system::pallet_prelude::*;
+
+use pallet_rubikpow::OnSealAccepted;
+use qbitcoin_core::{
+    solver::{no_shorter_solution, search_bound},
+    weight::estimate_verification_weight,
+    Cube, MoveKind, PowSeal,
+};
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub type ItemId = u64;
+
+/// Largest cube whose moves are all face turns up to a rotation.
+pub const MAX_OPTIMALITY_SIZE: u32 = 3;
+
+/// Why a seal was minted.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum Notability {
+    /// First seal on a cube larger than any mined before.
+    RecordSize,
+    /// Made of face turns only, and no shorter face-turn solution exists.
+    OptimalLength,
+}
+
+/// `blake2_256` of the seal's encoding.
+pub fn seal_fingerprint(seal: &PowSeal) -> [u8; 32] {
+    sp_io::hashing::blake2_256(&seal.encode())
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+
+    pub type CollectibleOf<T> = Collectible<<T as frame_system::Config>::AccountId, BlockNumberFor<T>>;
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct Collectible<AccountId, BlockNumber> {
+        pub owner: AccountId,
+        pub fingerprint: [u8; 32],
+        pub cube_size: u32,
+        pub moves: u32,
+        pub notability: Notability,
+        pub minted_at: BlockNumber,
+    }
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        /// Longest solution checked for optimality.
+        #[pallet::constant]
+        type MaxOptimalityDepth: Get<u32>;
+    }
+
+    #[pallet::storage]
+    #[pallet::getter(fn next_item_id)]
+    pub type NextItemId<T: Config> = StorageValue<_, ItemId, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn items)]
+    pub type Items<T: Config> = StorageMap<_, Blake2_128Concat, ItemId, CollectibleOf<T>, OptionQuery>;
+
+    /// Largest cube size a seal was accepted for.
+    #[pallet::storage]
+    #[pallet::getter(fn largest_size)]
+    pub type LargestSize<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        Minted { item: ItemId, owner: T::AccountId, notability: Notability, fingerprint: [u8; 32] },
+        Transferred { item: ItemId, from: T::AccountId, to: T::AccountId },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        UnknownItem,
+        NotOwner,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        #[pallet::call_index(0)]
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn transfer(origin: OriginFor<T>, item: ItemId, to: T::AccountId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            <Items<T>>::try_mutate(item, |collectible| -> DispatchResult {
+                let collectible = collectible.as_mut().ok_or(Error::<T>::UnknownItem)?;
+                ensure!(collectible.owner == who, Error::<T>::NotOwner);
+                collectible.owner = to.clone();
+                Ok(())
+            })?;
+            Self::deposit_event(Event::Transferred { item, from: who, to });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Why `seal`, solving `scrambled`, deserves a collectible, if it does.
+        pub fn notability(seal: &PowSeal, scrambled: &Cube) -> Option<Notability> {
+            if seal.cube_size > Self::largest_size() {
+                return Some(Notability::RecordSize);
+            }
+            let moves = seal.moves.len();
+            let face_turns = seal.moves.iter().all(|m| m.kind() == MoveKind::Face);
+            if face_turns && Self::searched(seal.cube_size, moves) && no_shorter_solution(scrambled, moves) {
+                return Some(Notability::OptimalLength);
+            }
+            None
+        }
+
+        /// Whether solutions of `moves` moves on `cube_size` cubes are searched
+        /// for shorter ones.
+        fn searched(cube_size: u32, moves: usize) -> bool {
+            cube_size <= MAX_OPTIMALITY_SIZE && moves <= T::MaxOptimalityDepth::get() as usize
+        }
+
+        /// Most weight `note_seal` takes: the search `no_shorter_solution`
+        /// runs for an optimal solution, and the mint.
+        pub fn note_weight(cube_size: u32, moves: usize) -> Weight {
+            let mint = T::DbWeight::get().reads_writes(2, 3);
+            if !Self::searched(cube_size, moves) {
+                return mint;
+            }
+            // Every applied move first copies the cube; the copy and every
+            // solved check each cost about as much as a move
+            let bound = search_bound(moves);
+            let work = bound.moves_applied.saturating_mul(2).saturating_add(bound.states_checked);
+            let search = estimate_verification_weight(cube_size, usize::try_from(work).unwrap_or(usize::MAX));
+            mint.saturating_add(Weight::from_parts(search.ref_time, 0))
+        }
+
+        fn note_seal(who: &T::AccountId, seal: &PowSeal, scrambled: &Cube) {
+            let notability = Self::notability(seal, scrambled);
+            if seal.cube_size > Self::largest_size() {
+                <LargestSize<T>>::put(seal.cube_size);
+            }
+            let Some(notability) = notability else {
+                return;
+            };
+
+            let item = Self::next_item_id();
+            let fingerprint = seal_fingerprint(seal);
+            <Items<T>>::insert(
+                item,
+                Collectible {
+                    owner: who.clone(),
+                    fingerprint,
+                    cube_size: seal.cube_size,
+                    moves: seal.moves.len() as u32,
+                    notability,
+                    minted_at: <frame_system::Pallet<T>>::block_number(),
+                },
+            );
+            <NextItemId<T>>::put(item.saturating_add(1));
+            Self::deposit_event(Event::Minted { item, owner: who.clone(), notability, fingerprint });
+        }
+    }
+}
+
+impl<T: Config> OnSealAccepted<T::AccountId> for Pallet<T> {
+    fn on_seal_accepted(who: &T::AccountId, seal: &PowSeal, scrambled: &Cube) {
+        Self::note_seal(who, seal, scrambled);
+    }
+
+    fn weight(cube_size: u32, n_moves: usize) -> Weight {
+        Self::note_weight(cube_size, n_moves)
+    }
+}
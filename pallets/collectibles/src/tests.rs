@@ -0,0 +1,125 @@
+use crate::{mock::*, seal_fingerprint, Error, Event, Notability};
+use frame_support::{assert_noop, assert_ok, traits::Get};
+use pallet_rubikpow::OnSealAccepted;
+use qbitcoin_core::optimal::{distance, OptimalSolver};
+use qbitcoin_core::solver::{no_shorter_solution_with_stats, Solver};
+use qbitcoin_core::weight::estimate_verification_weight;
+use qbitcoin_core::{Axis, Cube, Move, PowSeal};
+
+fn seal(cube_size: u32, moves: Vec<Move>) -> PowSeal {
+    PowSeal { cube_size, nonce: 0, moves }
+}
+
+/// Feeds the pallet `seal`, solving `scrambled`, as accepted for `who`, and
+/// returns why it was minted.
+fn accept(who: AccountId, seal: &PowSeal, scrambled: &Cube) -> Option<Notability> {
+    let next = Collectibles::next_item_id();
+    <Collectibles as OnSealAccepted<AccountId>>::on_seal_accepted(&who, seal, scrambled);
+    (Collectibles::next_item_id() > next).then(|| Collectibles::items(next).unwrap().notability)
+}
+
+/// A 2x2x2 scramble `moves` face turns from solved, with an optimal solution.
+fn at_distance(moves: u8) -> (Cube, Vec<Move>) {
+    (0..)
+        .find_map(|nonce| {
+            let mut cube = Cube::new(2);
+            let scramble = cube.scramble_deterministic(nonce, b"collectibles");
+            (distance(&cube) == Some(moves)).then(|| {
+                let solution = OptimalSolver.solve(&cube, &scramble).unwrap();
+                (cube, solution)
+            })
+        })
+        .unwrap()
+}
+
+#[test]
+fn the_first_seal_on_a_larger_cube_sets_a_record() {
+    new_test_ext().execute_with(|| {
+        // Nothing was mined before, so even the smallest cube is a record
+        assert_eq!(Collectibles::largest_size(), 0);
+        let first = seal(2, vec![Move::R(1); 20]);
+        assert_eq!(accept(1, &first, &Cube::new(2)), Some(Notability::RecordSize));
+        System::assert_last_event(RuntimeEvent::Collectibles(Event::Minted {
+            item: 0,
+            owner: 1,
+            notability: Notability::RecordSize,
+            fingerprint: seal_fingerprint(&first),
+        }));
+        let item = Collectibles::items(0).unwrap();
+        assert_eq!((item.owner, item.cube_size, item.moves, item.minted_at), (1, 2, 20, 1));
+        assert_eq!(Collectibles::largest_size(), 2);
+
+        assert_eq!(accept(2, &seal(2, vec![Move::R(1); 20]), &Cube::new(2)), None);
+        assert_eq!(accept(2, &seal(5, vec![Move::R(1); 20]), &Cube::new(5)), Some(Notability::RecordSize));
+        // Sizes in between are not records any more
+        assert_eq!(accept(2, &seal(4, vec![Move::R(1); 20]), &Cube::new(4)), None);
+        assert_eq!(Collectibles::largest_size(), 5);
+        assert_eq!(Collectibles::next_item_id(), 2);
+    });
+}
+
+#[test]
+fn optimal_solutions_are_minted_within_the_search_bound() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(accept(1, &seal(5, vec![]), &Cube::new(5)), Some(Notability::RecordSize));
+
+        let (cube, solution) = at_distance(MAX_OPTIMALITY_DEPTH as u8);
+        assert_eq!(accept(1, &seal(2, solution.clone()), &cube), Some(Notability::OptimalLength));
+
+        // A solution with a shorter alternative
+        let (short_cube, short) = at_distance(MAX_OPTIMALITY_DEPTH as u8 - 2);
+        let mut detour = short.clone();
+        detour.extend([Move::R(2), Move::R(2)]);
+        assert_eq!(accept(1, &seal(2, detour), &short_cube), None);
+
+        // Past the search depth, optimal or not, nothing is searched
+        let (deep_cube, deep) = at_distance(MAX_OPTIMALITY_DEPTH as u8 + 1);
+        assert_eq!(accept(1, &seal(2, deep), &deep_cube), None);
+
+        // Only face turns count
+        let mut rotated = solution;
+        rotated.push(Move::Rotation(Axis::Y, 1));
+        assert_eq!(accept(1, &seal(2, rotated), &cube), None);
+
+        // Nor is anything searched on cubes past 3x3x3
+        let mut big = Cube::new(4);
+        big.apply_move(&Move::R(1));
+        assert_eq!(accept(1, &seal(4, vec![Move::R(3)]), &big), None);
+
+        assert_eq!(Collectibles::next_item_id(), 2);
+    });
+}
+
+#[test]
+fn the_seal_weight_covers_the_optimality_search() {
+    let mint = <Test as frame_system::Config>::DbWeight::get().reads_writes(2, 3);
+    let depth = MAX_OPTIMALITY_DEPTH as usize;
+    let (cube, solution) = at_distance(depth as u8);
+    let (optimal, stats) = no_shorter_solution_with_stats(&cube, solution.len());
+    assert!(optimal);
+
+    // An optimal solution searches the most, and each step costs at most a move
+    let work = 2 * stats.moves_applied + stats.states_checked;
+    let searched = estimate_verification_weight(2, work as usize).ref_time;
+    assert!(Collectibles::note_weight(2, depth).ref_time() >= mint.ref_time() + searched);
+    assert!(Collectibles::note_weight(3, depth).ref_time() > Collectibles::note_weight(2, depth).ref_time());
+
+    // Seals that are not searched only pay for the mint
+    assert_eq!(Collectibles::note_weight(2, depth + 1), mint);
+    assert_eq!(Collectibles::note_weight(4, 1), mint);
+    assert_eq!(<Collectibles as OnSealAccepted<AccountId>>::weight(2, depth), Collectibles::note_weight(2, depth));
+}
+
+#[test]
+fn only_owners_transfer_collectibles() {
+    new_test_ext().execute_with(|| {
+        accept(1, &seal(2, vec![]), &Cube::new(2));
+        assert_noop!(Collectibles::transfer(RuntimeOrigin::signed(1), 1, 2), Error::<Test>::UnknownItem);
+        assert_noop!(Collectibles::transfer(RuntimeOrigin::signed(2), 0, 3), Error::<Test>::NotOwner);
+
+        assert_ok!(Collectibles::transfer(RuntimeOrigin::signed(1), 0, 2));
+        System::assert_last_event(RuntimeEvent::Collectibles(Event::Transferred { item: 0, from: 1, to: 2 }));
+        assert_eq!(Collectibles::items(0).unwrap().owner, 2);
+        assert_noop!(Collectibles::transfer(RuntimeOrigin::signed(1), 0, 1), Error::<Test>::NotOwner);
+    });
+}
@@ -0,0 +1,158 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(not(test), deny(clippy::unwrap_used, clippy::expect_used))]
+
+//! Display names paid for with vanity proofs.
+//!
+//! A miner registers a name by submitting a 2x2x2 solution whose challenge
+//! commitment starts with the first `PrefixLen` bytes of the name's hash. The
+//! challenge is derived from the account, the name and the number of names
+//! the account registered before, so a proof only registers that name for
+//! that account once, and every extra prefix byte makes a name 256 times as
+//! expensive to claim. Names are unique; the owner can give one up, after
+//! which anyone, the owner included, needs a new proof to register it.
+
+use frame_support::pallet_prelude::*;
+use frame_system::pallet_prelude::*;
+use sp_std::vec::Vec;
+
+use qbitcoin_core::{verify_seal_matching, weight::estimate_verification_weight, Move, PowSeal, Prefix, VerifyError};
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// Cube size of vanity challenges.
+pub const CUBEID_CUBE_SIZE: u32 = 2;
+/// Domain separator of vanity challenge headers.
+pub const CUBEID_DOMAIN: &[u8] = b"qbitcoin/cubeid";
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+
+    pub type NameOf<T> = BoundedVec<u8, <T as Config>::MaxNameLen>;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        #[pallet::constant]
+        type MaxNameLen: Get<u32>;
+        /// Bytes of the name's hash the commitment has to start with.
+        #[pallet::constant]
+        type PrefixLen: Get<u32>;
+    }
+
+    #[pallet::storage]
+    #[pallet::getter(fn name_of)]
+    pub type Names<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, NameOf<T>, OptionQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn owner_of)]
+    pub type Owners<T: Config> = StorageMap<_, Blake2_128Concat, NameOf<T>, T::AccountId, OptionQuery>;
+
+    /// Names each account has registered so far, part of its next challenge.
+    #[pallet::storage]
+    #[pallet::getter(fn registrations)]
+    pub type Registrations<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        NameSet { who: T::AccountId, name: NameOf<T> },
+        NameCleared { who: T::AccountId, name: NameOf<T> },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        EmptyName,
+        /// Another account holds the name.
+        NameTaken,
+        NoName,
+        /// The moves do not solve the challenge.
+        InvalidSolution,
+        /// The commitment does not start with the required prefix.
+        PrefixNotMatched,
+        MalformedSeal,
+    }
+
+    impl<T> From<VerifyError> for Error<T> {
+        fn from(error: VerifyError) -> Self {
+            match error {
+                VerifyError::InvalidSolution
+                | VerifyError::CannedSolution
+                | VerifyError::EngineDisagreement
+                | VerifyError::SolutionTooLong => Error::InvalidSolution,
+                VerifyError::TargetNotMet => Error::PrefixNotMatched,
+                VerifyError::MalformedSeal
+                | VerifyError::CubeTooSmall
+                | VerifyError::CubeTooLarge
+                | VerifyError::StaleChallenge => Error::MalformedSeal,
+            }
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Sets the caller's name, replacing any name they held before.
+        #[pallet::call_index(0)]
+        #[pallet::weight(Pallet::<T>::register_weight(moves.len()))]
+        pub fn register_name(origin: OriginFor<T>, name: NameOf<T>, nonce: u64, moves: Vec<Move>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!name.is_empty(), Error::<T>::EmptyName);
+            if let Some(owner) = Self::owner_of(&name) {
+                ensure!(owner == who, Error::<T>::NameTaken);
+            }
+
+            let seal = PowSeal { cube_size: CUBEID_CUBE_SIZE, nonce, moves };
+            verify_seal_matching(&seal, &Self::challenge_header(&who, &name), &Self::required_prefix(&name))
+                .map_err(Error::<T>::from)?;
+
+            if let Some(old) = <Names<T>>::take(&who) {
+                <Owners<T>>::remove(&old);
+            }
+            <Owners<T>>::insert(&name, &who);
+            <Names<T>>::insert(&who, &name);
+            <Registrations<T>>::mutate(&who, |count| *count = count.saturating_add(1));
+            Self::deposit_event(Event::NameSet { who, name });
+            Ok(())
+        }
+
+        #[pallet::call_index(1)]
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(2))]
+        pub fn clear_name(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let name = <Names<T>>::take(&who).ok_or(Error::<T>::NoName)?;
+            <Owners<T>>::remove(&name);
+            Self::deposit_event(Event::NameCleared { who, name });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Weight of `register_name` with `n_moves` moves, which are replayed.
+        pub fn register_weight(n_moves: usize) -> Weight {
+            let verification = estimate_verification_weight(CUBEID_CUBE_SIZE, n_moves);
+            Weight::from_parts(verification.ref_time, 0).saturating_add(T::DbWeight::get().reads_writes(3, 4))
+        }
+
+        /// Header bytes of the challenge for `who`'s next registration of `name`.
+        pub fn challenge_header(who: &T::AccountId, name: &[u8]) -> Vec<u8> {
+            let mut header = CUBEID_DOMAIN.to_vec();
+            who.encode_to(&mut header);
+            name.encode_to(&mut header);
+            Self::registrations(who).encode_to(&mut header);
+            header
+        }
+
+        /// Prefix the commitment of a proof for `name` has to start with.
+        pub fn required_prefix(name: &[u8]) -> Prefix {
+            Prefix::truncated(&sp_io::hashing::blake2_256(name), T::PrefixLen::get() as usize)
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{mock::*, Error, Event, NameOf, CUBEID_CUBE_SIZE};
+use frame_support::{assert_noop, assert_ok};
+use qbitcoin_core::miner::{attempt, BlockTemplate};
+use qbitcoin_core::solver::ReverseScramble;
+use qbitcoin_core::{verify_seal_matching, PowSeal, Target};
+use sp_runtime::DispatchResult;
+
+fn name(bytes: &[u8]) -> NameOf<Test> {
+    bytes.to_vec().try_into().unwrap()
+}
+
+/// Grinds nonces until one solves `who`'s next challenge for `name` with a
+/// commitment that does or does not start with the required prefix.
+fn grind(who: AccountId, name: &[u8], matching: bool) -> PowSeal {
+    let header = CubeId::challenge_header(&who, name);
+    let prefix = CubeId::required_prefix(name);
+    let template =
+        BlockTemplate { parent_hash: [0; 32], header: header.clone(), cube_size: CUBEID_CUBE_SIZE, target: Target::MAX };
+    (0..)
+        .filter_map(|nonce| attempt(&ReverseScramble, &template, nonce))
+        .find(|seal| verify_seal_matching(seal, &header, &prefix).is_ok() == matching)
+        .unwrap()
+}
+
+fn prove(who: AccountId, name: &[u8]) -> PowSeal {
+    grind(who, name, true)
+}
+
+fn register(who: AccountId, bytes: &[u8], seal: PowSeal) -> DispatchResult {
+    CubeId::register_name(RuntimeOrigin::signed(who), name(bytes), seal.nonce, seal.moves)
+}
+
+#[test]
+fn names_are_registered_with_a_matching_proof() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(register(1, b"", prove(1, b"")), Error::<Test>::EmptyName);
+        assert_noop!(register(1, b"alice", grind(1, b"alice", false)), Error::<Test>::PrefixNotMatched);
+        // The proof is bound to the account
+        assert_noop!(register(2, b"alice", prove(1, b"alice")), Error::<Test>::InvalidSolution);
+
+        assert_ok!(register(1, b"alice", prove(1, b"alice")));
+        System::assert_last_event(RuntimeEvent::CubeId(Event::NameSet { who: 1, name: name(b"alice") }));
+        assert_eq!(CubeId::name_of(1), Some(name(b"alice")));
+        assert_eq!(CubeId::owner_of(name(b"alice")), Some(1));
+        assert_eq!(CubeId::registrations(1), 1);
+
+        assert_noop!(register(2, b"alice", prove(2, b"alice")), Error::<Test>::NameTaken);
+    });
+}
+
+#[test]
+fn registering_a_new_name_releases_the_old_one() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(register(1, b"alice", prove(1, b"alice")));
+        assert_ok!(register(1, b"alicia", prove(1, b"alicia")));
+        assert_eq!(CubeId::name_of(1), Some(name(b"alicia")));
+        assert_eq!(CubeId::owner_of(name(b"alice")), None);
+        assert_eq!(CubeId::owner_of(name(b"alicia")), Some(1));
+        assert_eq!(CubeId::registrations(1), 2);
+
+        assert_ok!(register(2, b"alice", prove(2, b"alice")));
+        assert_eq!(CubeId::owner_of(name(b"alice")), Some(2));
+    });
+}
+
+#[test]
+fn cleared_names_cannot_be_reclaimed_with_an_old_proof() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(CubeId::clear_name(RuntimeOrigin::signed(1)), Error::<Test>::NoName);
+
+        let seal = prove(1, b"alice");
+        assert_ok!(register(1, b"alice", seal.clone()));
+        assert_ok!(CubeId::clear_name(RuntimeOrigin::signed(1)));
+        System::assert_last_event(RuntimeEvent::CubeId(Event::NameCleared { who: 1, name: name(b"alice") }));
+        assert_eq!(CubeId::name_of(1), None);
+        assert_eq!(CubeId::owner_of(name(b"alice")), None);
+        assert_noop!(CubeId::clear_name(RuntimeOrigin::signed(1)), Error::<Test>::NoName);
+
+        // Every registration is a new challenge, so the first proof is spent
+        assert_noop!(register(1, b"alice", seal), Error::<Test>::InvalidSolution);
+        assert_ok!(register(1, b"alice", prove(1, b"alice")));
+        assert_eq!(CubeId::owner_of(name(b"alice")), Some(1));
+    });
+}
@@ -0,0 +1,186 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Testnet faucet gated by 2x2x2 cube puzzles.
+//!
+//! Anyone can ask for `DripAmount` from the faucet account, but only with a
+//! solution to a small challenge derived from their account and the hash of a
+//! recent block, checked by the same verifier as block seals. The block is
+//! named by the requester and must be at most `ChallengeWindow` blocks old, so
+//! a solution stays usable while the transaction waits in the pool but cannot
+//! be prepared far in advance. Each account can draw again only after
+//! `Cooldown` blocks, after which its last drip is forgotten.
+
+use frame_support::{
+    pallet_prelude::*,
+    traits::{Currency, ExistenceRequirement},
+    PalletId,
+};
+use frame_system::pallet_prelude::*;
+use sp_runtime::traits::{AccountIdConversion, CheckedSub, Saturating};
+use sp_std::vec::Vec;
+
+use qbitcoin_core::{verify_seal, weight::estimate_verification_weight, Move, PowSeal, Target, VerifyError};
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// Cube size of faucet challenges.
+pub const FAUCET_CUBE_SIZE: u32 = 2;
+/// Domain separator of faucet challenge headers.
+pub const FAUCET_DOMAIN: &[u8] = b"qbitcoin/faucet";
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        type Currency: Currency<Self::AccountId>;
+        /// Derives the account drips are paid from.
+        #[pallet::constant]
+        type PalletId: Get<PalletId>;
+        /// Amount paid per request.
+        #[pallet::constant]
+        type DripAmount: Get<BalanceOf<Self>>;
+        /// Blocks an account has to wait between two drips.
+        #[pallet::constant]
+        type Cooldown: Get<BlockNumberFor<Self>>;
+        /// How many blocks back the challenge block may be. Must not exceed
+        /// `frame_system::Config::BlockHashCount`.
+        #[pallet::constant]
+        type ChallengeWindow: Get<BlockNumberFor<Self>>;
+        /// Difficulty of faucet challenges.
+        #[pallet::constant]
+        type FaucetDifficulty: Get<u32>;
+    }
+
+    /// Block of each account's last drip.
+    #[pallet::storage]
+    #[pallet::getter(fn last_drip)]
+    pub type LastDrip<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, BlockNumberFor<T>, OptionQuery>;
+
+    /// Accounts that drew at each block, so their `LastDrip` entries can be
+    /// dropped once the cooldown is over.
+    #[pallet::storage]
+    pub type DripsAt<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, BlockNumberFor<T>, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        Dripped { who: T::AccountId, amount: BalanceOf<T> },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The account drew from the faucet less than `Cooldown` blocks ago.
+        CoolingDown,
+        /// The challenge block is in the future or older than `ChallengeWindow`.
+        StaleChallenge,
+        /// The solution does not solve the challenge.
+        InvalidSolution,
+        TargetNotMet,
+        MalformedSeal,
+    }
+
+    impl<T> From<VerifyError> for Error<T> {
+        fn from(error: VerifyError) -> Self {
+            match error {
+                VerifyError::InvalidSolution
+                | VerifyError::CannedSolution
+                | VerifyError::EngineDisagreement
+                | VerifyError::SolutionTooLong => Error::InvalidSolution,
+                VerifyError::TargetNotMet => Error::TargetNotMet,
+                VerifyError::StaleChallenge => Error::StaleChallenge,
+                VerifyError::MalformedSeal | VerifyError::CubeTooSmall | VerifyError::CubeTooLarge => {
+                    Error::MalformedSeal
+                }
+            }
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        /// Forgets the drips whose cooldown ends with this block. Their accounts
+        /// may draw again from now on, so they cannot have drawn since.
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            let Some(expired) = n.checked_sub(&T::Cooldown::get()) else {
+                return Weight::zero();
+            };
+            let mut forgotten = 0u64;
+            for (who, ()) in <DripsAt<T>>::drain_prefix(expired) {
+                <LastDrip<T>>::remove(&who);
+                forgotten += 1;
+            }
+            T::DbWeight::get().reads_writes(forgotten.saturating_add(1), forgotten.saturating_mul(2))
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Pays `DripAmount` to the caller for a solution of their challenge
+        /// at block `at`.
+        #[pallet::call_index(0)]
+        #[pallet::weight(Pallet::<T>::request_weight(moves.len()))]
+        pub fn request_funds(
+            origin: OriginFor<T>,
+            at: BlockNumberFor<T>,
+            nonce: u64,
+            moves: Vec<Move>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let now = <frame_system::Pallet<T>>::block_number();
+
+            if let Some(last) = Self::last_drip(&who) {
+                ensure!(now >= last.saturating_add(T::Cooldown::get()), Error::<T>::CoolingDown);
+            }
+            // The hash of the current block is not known until it is finalized
+            ensure!(at < now && now.saturating_sub(at) <= T::ChallengeWindow::get(), Error::<T>::StaleChallenge);
+
+            let header = Self::challenge_header(&who, at);
+            let seal = PowSeal { cube_size: FAUCET_CUBE_SIZE, nonce, moves };
+            let target = Target::from_difficulty(T::FaucetDifficulty::get());
+            verify_seal(&seal, &header, &target).map_err(Error::<T>::from)?;
+
+            let amount = T::DripAmount::get();
+            T::Currency::transfer(&Self::account_id(), &who, amount, ExistenceRequirement::KeepAlive)?;
+            <LastDrip<T>>::insert(&who, now);
+            <DripsAt<T>>::insert(now, &who, ());
+            Self::deposit_event(Event::Dripped { who, amount });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Weight of `request_funds` with `n_moves` moves, which are replayed.
+        pub fn request_weight(n_moves: usize) -> Weight {
+            let verification = estimate_verification_weight(FAUCET_CUBE_SIZE, n_moves);
+            Weight::from_parts(verification.ref_time, 0).saturating_add(T::DbWeight::get().reads_writes(2, 2))
+        }
+
+        /// Account drips are paid from.
+        pub fn account_id() -> T::AccountId {
+            T::PalletId::get().into_account_truncating()
+        }
+
+        /// Header bytes of `who`'s challenge at block `at`.
+        pub fn challenge_header(who: &T::AccountId, at: BlockNumberFor<T>) -> Vec<u8> {
+            let mut header = FAUCET_DOMAIN.to_vec();
+            who.encode_to(&mut header);
+            at.encode_to(&mut header);
+            <frame_system::Pallet<T>>::block_hash(at).encode_to(&mut header);
+            header
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate as pallet_faucet;
+use frame_support::traits::{ConstU16, ConstU32, ConstU64, Everything, Hooks};
+use frame_support::{parameter_types, PalletId};
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, IdentityLookup},
+    BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+pub type AccountId = u64;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        Balances: pallet_balances,
+        Faucet: pallet_faucet,
+    }
+);
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Block = Block;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ConstU16<42>;
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+    type MaxLocks = ();
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+    type Balance = u64;
+    type RuntimeEvent = RuntimeEvent;
+    type DustRemoval = ();
+    type ExistentialDeposit = ConstU64<1>;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type FreezeIdentifier = ();
+    type MaxFreezes = ();
+    type RuntimeHoldReason = ();
+    type MaxHolds = ();
+}
+
+pub const DRIP_AMOUNT: u64 = 100;
+pub const COOLDOWN: u64 = 5;
+pub const CHALLENGE_WINDOW: u64 = 3;
+pub const FAUCET_DIFFICULTY: u32 = 4;
+
+parameter_types! {
+    pub const FaucetPalletId: PalletId = PalletId(*b"qb/fauct");
+}
+
+impl pallet_faucet::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type PalletId = FaucetPalletId;
+    type DripAmount = ConstU64<DRIP_AMOUNT>;
+    type Cooldown = ConstU64<COOLDOWN>;
+    type ChallengeWindow = ConstU64<CHALLENGE_WINDOW>;
+    type FaucetDifficulty = ConstU32<FAUCET_DIFFICULTY>;
+}
+
+/// Funds of the faucet account at genesis.
+pub const FAUCET_FUNDS: u64 = 1_000_000;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+    pallet_balances::GenesisConfig::<Test> { balances: vec![(Faucet::account_id(), FAUCET_FUNDS)] }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+/// Advances to block `n`, giving every block passed a distinct hash.
+pub fn run_to(n: u64) {
+    while System::block_number() < n {
+        let parent = System::block_number();
+        frame_system::BlockHash::<Test>::insert(parent, H256::repeat_byte(parent as u8));
+        System::set_block_number(parent + 1);
+        Faucet::on_initialize(parent + 1);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{mock::*, DripsAt, Error, Event, FAUCET_CUBE_SIZE};
+use frame_support::{assert_noop, assert_ok};
+use qbitcoin_core::miner::{attempt, BlockTemplate};
+use qbitcoin_core::solver::ReverseScramble;
+use qbitcoin_core::{verify_seal, PowSeal, Target};
+use sp_runtime::DispatchResult;
+
+/// Grinds nonces until one solves `who`'s challenge at block `at`.
+fn solve(who: AccountId, at: u64) -> PowSeal {
+    let template = BlockTemplate {
+        parent_hash: [0; 32],
+        header: Faucet::challenge_header(&who, at),
+        cube_size: FAUCET_CUBE_SIZE,
+        target: Target::from_difficulty(FAUCET_DIFFICULTY),
+    };
+    (0..).find_map(|nonce| attempt(&ReverseScramble, &template, nonce)).unwrap()
+}
+
+fn request(who: AccountId, at: u64, seal: PowSeal) -> DispatchResult {
+    Faucet::request_funds(RuntimeOrigin::signed(who), at, seal.nonce, seal.moves)
+}
+
+#[test]
+fn challenges_are_bound_to_the_account_and_block() {
+    new_test_ext().execute_with(|| {
+        run_to(3);
+        assert_ne!(Faucet::challenge_header(&2, 2), Faucet::challenge_header(&3, 2));
+        assert_ne!(Faucet::challenge_header(&2, 1), Faucet::challenge_header(&2, 2));
+
+        let seal = solve(2, 2);
+        assert_noop!(request(3, 2, seal.clone()), Error::<Test>::InvalidSolution);
+        assert_noop!(request(2, 1, seal.clone()), Error::<Test>::InvalidSolution);
+
+        assert_ok!(request(2, 2, seal));
+        System::assert_last_event(RuntimeEvent::Faucet(Event::Dripped { who: 2, amount: DRIP_AMOUNT }));
+        assert_eq!(Balances::free_balance(2), DRIP_AMOUNT);
+        assert_eq!(Balances::free_balance(Faucet::account_id()), FAUCET_FUNDS - DRIP_AMOUNT);
+        assert_eq!(Faucet::last_drip(2), Some(3));
+    });
+}
+
+#[test]
+fn wrong_solutions_are_rejected() {
+    new_test_ext().execute_with(|| {
+        run_to(3);
+        let unsolved = PowSeal { cube_size: FAUCET_CUBE_SIZE, nonce: 0, moves: vec![] };
+        assert_noop!(request(2, 2, unsolved), Error::<Test>::InvalidSolution);
+
+        let mut seal = solve(2, 2);
+        seal.moves.pop();
+        assert_noop!(request(2, 2, seal), Error::<Test>::InvalidSolution);
+
+        // A solution that misses the faucet's target
+        let header = Faucet::challenge_header(&2, 2);
+        let target = Target::from_difficulty(FAUCET_DIFFICULTY);
+        let template =
+            BlockTemplate { parent_hash: [0; 32], header: header.clone(), cube_size: FAUCET_CUBE_SIZE, target: Target::MAX };
+        let easy = (0..)
+            .filter_map(|nonce| attempt(&ReverseScramble, &template, nonce))
+            .find(|seal| verify_seal(seal, &header, &target).is_err())
+            .unwrap();
+        assert_noop!(request(2, 2, easy), Error::<Test>::TargetNotMet);
+    });
+}
+
+#[test]
+fn accounts_wait_out_the_cooldown_and_are_then_forgotten() {
+    new_test_ext().execute_with(|| {
+        run_to(3);
+        assert_ok!(request(2, 2, solve(2, 2)));
+
+        run_to(3 + COOLDOWN - 1);
+        let now = System::block_number();
+        assert_noop!(request(2, now - 1, solve(2, now - 1)), Error::<Test>::CoolingDown);
+        assert_eq!(Faucet::last_drip(2), Some(3));
+
+        // The drip is forgotten as soon as the account may draw again
+        run_to(3 + COOLDOWN);
+        assert_eq!(Faucet::last_drip(2), None);
+        assert_eq!(DripsAt::<Test>::iter_prefix(3).count(), 0);
+
+        let now = System::block_number();
+        assert_ok!(request(2, now - 1, solve(2, now - 1)));
+        assert_eq!(Faucet::last_drip(2), Some(now));
+        assert!(DripsAt::<Test>::contains_key(now, 2));
+        assert_eq!(Balances::free_balance(2), 2 * DRIP_AMOUNT);
+    });
+}
+
+#[test]
+fn challenges_expire_after_the_window() {
+    new_test_ext().execute_with(|| {
+        run_to(10);
+        let unsolved = || PowSeal { cube_size: FAUCET_CUBE_SIZE, nonce: 0, moves: vec![] };
+        // The current block's hash is not known yet, nor are later ones
+        assert_noop!(request(2, 10, unsolved()), Error::<Test>::StaleChallenge);
+        assert_noop!(request(2, 11, unsolved()), Error::<Test>::StaleChallenge);
+        assert_noop!(request(2, 10 - CHALLENGE_WINDOW - 1, unsolved()), Error::<Test>::StaleChallenge);
+
+        let oldest = 10 - CHALLENGE_WINDOW;
+        let seal = solve(2, oldest);
+        assert_ok!(request(2, oldest, seal.clone()));
+
+        // The same solution goes stale once the block leaves the window
+        run_to(10 + COOLDOWN);
+        assert_noop!(request(2, oldest, seal), Error::<Test>::StaleChallenge);
+    });
+}
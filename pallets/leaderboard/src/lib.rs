@@ -0,0 +1,226 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Per-epoch records of accepted seals.
+//!
+//! For every cube size the leaderboard keeps the shortest solution and the
+//! fastest submission of each epoch of `EpochLength` blocks. Lengths are
+//! counted in the metric `SolutionMetric` names when the seal is accepted, so
+//! after governance switches metrics the epoch's record may still be in the
+//! old one until it is beaten. Submission time
+//! is the wall-clock time since the previous accepted seal of any size, i.e.
+//! how long the challenge stood before it was solved. Breaking a record emits
+//! an event and, if `RecordBonus` is not zero, mints that bonus to the new
+//! holder; setting the first record of an epoch earns no bonus. Only the last
+//! `EpochsKept` epochs are stored.
+//!
+//! The pallet is fed by `pallet_rubikpow` through its `OnSealAccepted` hook.
+
+use frame_support::{
+    pallet_prelude::*,
+    traits::{Currency, UnixTime},
+};
+use frame_system::pallet_prelude::*;
+use sp_runtime::traits::{UniqueSaturatedInto, Zero};
+
+use pallet_rubikpow::OnSealAccepted;
+use qbitcoin_core::{Cube, Metric, PowSeal};
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub type EpochIndex = u32;
+
+/// Which record a seal set.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum RecordKind {
+    /// Fewest moves; the value is the length in the solution metric.
+    ShortestSolution,
+    /// Least time since the previous seal; the value is in milliseconds.
+    FastestSubmission,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    pub type RecordOf<T> = Record<<T as frame_system::Config>::AccountId, BlockNumberFor<T>>;
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct Record<AccountId, BlockNumber> {
+        pub holder: AccountId,
+        /// Solution length or milliseconds, depending on the record.
+        pub value: u64,
+        /// Block the record was set in.
+        pub set_at: BlockNumber,
+    }
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        type Currency: Currency<Self::AccountId>;
+        /// Wall clock used to measure submission times.
+        type UnixTime: UnixTime;
+        /// Blocks per epoch.
+        #[pallet::constant]
+        type EpochLength: Get<BlockNumberFor<Self>>;
+        /// Epochs whose records are kept, including the current one.
+        #[pallet::constant]
+        type EpochsKept: Get<u32>;
+        /// Metric solution lengths are compared in, usually
+        /// `pallet_rubikpow::CurrentMetric`.
+        type SolutionMetric: Get<Metric>;
+        /// Minted to whoever breaks a record; zero disables bonuses.
+        #[pallet::constant]
+        type RecordBonus: Get<BalanceOf<Self>>;
+    }
+
+    /// Shortest solution per epoch and cube size.
+    #[pallet::storage]
+    #[pallet::getter(fn shortest_solution)]
+    pub type ShortestSolutions<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, EpochIndex, Twox64Concat, u32, RecordOf<T>, OptionQuery>;
+
+    /// Fastest submission per epoch and cube size.
+    #[pallet::storage]
+    #[pallet::getter(fn fastest_submission)]
+    pub type FastestSubmissions<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, EpochIndex, Twox64Concat, u32, RecordOf<T>, OptionQuery>;
+
+    /// Epoch of the last recorded seal.
+    #[pallet::storage]
+    #[pallet::getter(fn current_epoch)]
+    pub type CurrentEpoch<T: Config> = StorageValue<_, EpochIndex, ValueQuery>;
+
+    /// Unix time in milliseconds of the last accepted seal.
+    #[pallet::storage]
+    #[pallet::getter(fn last_seal_at)]
+    pub type LastSealAt<T: Config> = StorageValue<_, u64, OptionQuery>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// `previous` is the value of the record that was broken, if the
+        /// epoch had one.
+        RecordSet {
+            epoch: EpochIndex,
+            cube_size: u32,
+            kind: RecordKind,
+            holder: T::AccountId,
+            value: u64,
+            previous: Option<u64>,
+        },
+        BonusPaid { holder: T::AccountId, amount: BalanceOf<T> },
+    }
+
+    impl<T: Config> Pallet<T> {
+        pub fn epoch_at(block: BlockNumberFor<T>) -> EpochIndex {
+            let length = T::EpochLength::get();
+            if length.is_zero() {
+                return 0;
+            }
+            (block / length).unique_saturated_into()
+        }
+
+        fn note_seal(who: &T::AccountId, seal: &PowSeal) {
+            let now = <frame_system::Pallet<T>>::block_number();
+            let epoch = Self::epoch_at(now);
+            Self::advance_epoch(epoch);
+
+            let millis = T::UnixTime::now().as_millis() as u64;
+            let elapsed = Self::last_seal_at().map(|last| millis.saturating_sub(last));
+            <LastSealAt<T>>::put(millis);
+
+            let length = T::SolutionMetric::get().length(&seal.moves, seal.cube_size as usize);
+            Self::try_record(epoch, seal.cube_size, RecordKind::ShortestSolution, who, u64::from(length), now);
+            if let Some(elapsed) = elapsed {
+                Self::try_record(epoch, seal.cube_size, RecordKind::FastestSubmission, who, elapsed, now);
+            }
+        }
+
+        pub fn record(epoch: EpochIndex, cube_size: u32, kind: RecordKind) -> Option<RecordOf<T>> {
+            match kind {
+                RecordKind::ShortestSolution => Self::shortest_solution(epoch, cube_size),
+                RecordKind::FastestSubmission => Self::fastest_submission(epoch, cube_size),
+            }
+        }
+
+        /// Stores `value` if it beats the epoch's record.
+        fn try_record(
+            epoch: EpochIndex,
+            cube_size: u32,
+            kind: RecordKind,
+            holder: &T::AccountId,
+            value: u64,
+            now: BlockNumberFor<T>,
+        ) {
+            let previous = Self::record(epoch, cube_size, kind).map(|record| record.value);
+            if previous.map_or(false, |previous| value >= previous) {
+                return;
+            }
+            let record = Record { holder: holder.clone(), value, set_at: now };
+            match kind {
+                RecordKind::ShortestSolution => <ShortestSolutions<T>>::insert(epoch, cube_size, record),
+                RecordKind::FastestSubmission => <FastestSubmissions<T>>::insert(epoch, cube_size, record),
+            }
+            Self::record_set(epoch, cube_size, kind, holder, value, previous);
+        }
+
+        fn record_set(
+            epoch: EpochIndex,
+            cube_size: u32,
+            kind: RecordKind,
+            holder: &T::AccountId,
+            value: u64,
+            previous: Option<u64>,
+        ) {
+            Self::deposit_event(Event::RecordSet { epoch, cube_size, kind, holder: holder.clone(), value, previous });
+
+            let bonus = T::RecordBonus::get();
+            if previous.is_some() && !bonus.is_zero() {
+                let _ = T::Currency::deposit_creating(holder, bonus);
+                Self::deposit_event(Event::BonusPaid { holder: holder.clone(), amount: bonus });
+            }
+        }
+
+        /// Drops the epochs that fell out of `EpochsKept` when a new one starts.
+        fn advance_epoch(epoch: EpochIndex) {
+            let previous = Self::current_epoch();
+            if epoch == previous {
+                return;
+            }
+            <CurrentEpoch<T>>::put(epoch);
+
+            // Records only exist for the kept epochs up to the previous one
+            let Some(last_expired) = epoch.checked_sub(T::EpochsKept::get()) else {
+                return;
+            };
+            let first_kept = previous.saturating_add(1).saturating_sub(T::EpochsKept::get());
+            for expired in first_kept..=last_expired.min(previous) {
+                // At most one entry per cube size
+                let _ = <ShortestSolutions<T>>::clear_prefix(expired, u32::MAX, None);
+                let _ = <FastestSubmissions<T>>::clear_prefix(expired, u32::MAX, None);
+            }
+        }
+    }
+}
+
+impl<T: Config> OnSealAccepted<T::AccountId> for Pallet<T> {
+    fn on_seal_accepted(who: &T::AccountId, seal: &PowSeal, _scrambled: &Cube) {
+        Self::note_seal(who, seal);
+    }
+
+    fn weight(_cube_size: u32, _n_moves: usize) -> Weight {
+        // Epoch and clock, and both records with their bonuses
+        T::DbWeight::get().reads_writes(6, 6)
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{mock::*, Event, Record, RecordKind};
+use pallet_rubikpow::OnSealAccepted;
+use qbitcoin_core::{Cube, Move, PowSeal};
+
+/// Feeds the leaderboard a seal of `cube_size` with `length` moves by `who`,
+/// accepted at unix time `millis`.
+fn accept(who: AccountId, cube_size: u32, length: usize, millis: u64) {
+    Timestamp::set_timestamp(millis);
+    let seal = PowSeal { cube_size, nonce: 0, moves: vec![Move::R(1); length] };
+    <Leaderboard as OnSealAccepted<AccountId>>::on_seal_accepted(&who, &seal, &Cube::new(cube_size as usize));
+}
+
+fn records_set() -> Vec<(RecordKind, AccountId, u64, Option<u64>)> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            RuntimeEvent::Leaderboard(Event::RecordSet { kind, holder, value, previous, .. }) => {
+                Some((kind, holder, value, previous))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn holder(epoch: u32, cube_size: u32, kind: RecordKind) -> Option<(AccountId, u64)> {
+    Leaderboard::record(epoch, cube_size, kind).map(|record| (record.holder, record.value))
+}
+
+#[test]
+fn accepted_seals_set_and_break_records() {
+    new_test_ext().execute_with(|| {
+        use RecordKind::*;
+
+        // The first seal has nothing to be faster than
+        accept(1, 3, 10, 1_000);
+        assert_eq!(records_set(), vec![(ShortestSolution, 1, 10, None)]);
+        assert_eq!(Leaderboard::shortest_solution(0, 3), Some(Record { holder: 1, value: 10, set_at: 1 }));
+
+        System::reset_events();
+        accept(2, 3, 8, 4_000);
+        assert_eq!(records_set(), vec![(ShortestSolution, 2, 8, Some(10)), (FastestSubmission, 2, 3_000, None)]);
+        // Only breaking a record earns the bonus
+        System::assert_has_event(RuntimeEvent::Leaderboard(Event::BonusPaid { holder: 2, amount: RECORD_BONUS }));
+        assert_eq!(Balances::free_balance(2), RECORD_BONUS);
+
+        System::reset_events();
+        accept(3, 3, 9, 5_000);
+        assert_eq!(records_set(), vec![(FastestSubmission, 3, 1_000, Some(3_000))]);
+        assert_eq!(Balances::free_balance(3), RECORD_BONUS);
+        assert_eq!(holder(0, 3, ShortestSolution), Some((2, 8)));
+        assert_eq!(holder(0, 3, FastestSubmission), Some((3, 1_000)));
+
+        // Every cube size has its own records, timed against seals of any size
+        System::reset_events();
+        accept(1, 4, 30, 5_500);
+        assert_eq!(records_set(), vec![(ShortestSolution, 1, 30, None), (FastestSubmission, 1, 500, None)]);
+        assert_eq!(holder(0, 3, ShortestSolution), Some((2, 8)));
+        assert_eq!(Balances::free_balance(1), 0);
+    });
+}
+
+#[test]
+fn ties_keep_the_earlier_holder() {
+    new_test_ext().execute_with(|| {
+        accept(1, 3, 8, 1_000);
+        accept(2, 3, 9, 2_000);
+        System::reset_events();
+
+        // Equal to both records: neither changes hands
+        accept(3, 3, 8, 3_000);
+        assert_eq!(records_set(), vec![]);
+        assert_eq!(Leaderboard::shortest_solution(0, 3), Some(Record { holder: 1, value: 8, set_at: 1 }));
+        assert_eq!(holder(0, 3, RecordKind::FastestSubmission), Some((2, 1_000)));
+        assert_eq!(Balances::free_balance(3), 0);
+
+        // The same order across blocks
+        System::set_block_number(2);
+        accept(4, 3, 8, 4_000);
+        assert_eq!(holder(0, 3, RecordKind::ShortestSolution), Some((1, 8)));
+        assert_eq!(holder(0, 3, RecordKind::FastestSubmission), Some((2, 1_000)));
+    });
+}
+
+#[test]
+fn records_are_kept_per_epoch_for_the_last_epochs() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(Leaderboard::epoch_at(EPOCH_LENGTH - 1), 0);
+        assert_eq!(Leaderboard::epoch_at(EPOCH_LENGTH), 1);
+
+        accept(1, 3, 8, 1_000);
+
+        // A new epoch starts from scratch, so a worse seal still sets its record
+        System::set_block_number(EPOCH_LENGTH + 5);
+        System::reset_events();
+        accept(2, 3, 12, 2_000);
+        assert_eq!(Leaderboard::current_epoch(), 1);
+        assert!(records_set().contains(&(RecordKind::ShortestSolution, 2, 12, None)));
+        assert_eq!(Balances::free_balance(2), 0);
+        assert_eq!(holder(0, 3, RecordKind::ShortestSolution), Some((1, 8)));
+
+        // Epoch 0 falls out of the last `EPOCHS_KEPT`
+        System::set_block_number(2 * EPOCH_LENGTH);
+        accept(3, 3, 12, 3_000);
+        assert_eq!(Leaderboard::record(0, 3, RecordKind::ShortestSolution), None);
+        assert_eq!(Leaderboard::record(0, 3, RecordKind::FastestSubmission), None);
+        assert_eq!(holder(1, 3, RecordKind::ShortestSolution), Some((2, 12)));
+        assert_eq!(holder(2, 3, RecordKind::ShortestSolution), Some((3, 12)));
+    });
+}
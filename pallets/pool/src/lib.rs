@@ -0,0 +1,337 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Mining pools on top of RubikPoW.
+//!
+//! An operator opens a pool, records the shares its members contribute and,
+//! when the pool finds a block, closes the round by paying the block reward
+//! out of its own account according to the pool's payout scheme.
+//!
+//! Operators bond `PoolBond` when opening a pool. The bond can be slashed by
+//! `SlashOrigin` (governance, or a fraud-proof verifier configured as that
+//! origin) and is only returned `UnbondingPeriod` blocks after the operator
+//! asks to close the pool.
+
+use frame_support::{
+    pallet_prelude::*,
+    traits::{Currency, ExistenceRequirement, ReservableCurrency},
+};
+use frame_system::pallet_prelude::*;
+use sp_runtime::{
+    helpers_128bit::multiply_by_rational_with_rounding,
+    traits::{Saturating, UniqueSaturatedInto, Zero},
+    Rounding,
+};
+use sp_std::vec::Vec;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub type PoolId = u32;
+
+/// How a round's reward is split between members.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum PayoutScheme {
+    /// Pay-per-last-N-shares: the reward is split over the last `window`
+    /// shares, regardless of which round they were submitted in.
+    Pplns { window: u32 },
+    /// The reward is split over the shares of the round that found the block.
+    Proportional,
+}
+
+/// Why an operator's bond was slashed.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum Misbehavior {
+    /// Closed a round without paying members their share.
+    WithheldPayouts,
+    /// Recorded shares or claimed rewards that do not correspond to valid work.
+    InvalidClaim,
+}
+
+/// Splits `reward` over `shares` in proportion to their weight, merging
+/// shares of the same account. Rounding dust is not distributed.
+pub fn split_reward<AccountId: PartialEq + Clone>(
+    shares: &[(AccountId, u32)],
+    reward: u128,
+) -> Vec<(AccountId, u128)> {
+    let total: u128 = shares.iter().map(|(_, weight)| u128::from(*weight)).sum();
+    if total == 0 {
+        return Vec::new();
+    }
+
+    let mut weights: Vec<(AccountId, u128)> = Vec::new();
+    for (who, weight) in shares {
+        match weights.iter_mut().find(|(account, _)| account == who) {
+            Some((_, sum)) => *sum += u128::from(*weight),
+            None => weights.push((who.clone(), u128::from(*weight))),
+        }
+    }
+    weights
+        .into_iter()
+        .map(|(who, weight)| {
+            // `weight <= total`, so the share never exceeds `reward`
+            let share = multiply_by_rational_with_rounding(reward, weight, total, Rounding::Down).unwrap_or(0);
+            (who, share)
+        })
+        .collect()
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    pub type PoolInfoOf<T> = PoolInfo<<T as frame_system::Config>::AccountId, BalanceOf<T>, BlockNumberFor<T>>;
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct PoolInfo<AccountId, Balance, BlockNumber> {
+        pub operator: AccountId,
+        pub scheme: PayoutScheme,
+        /// Rounds closed so far.
+        pub round: u32,
+        /// Amount reserved from the operator.
+        pub bond: Balance,
+        /// Block from which the bond can be withdrawn, once unbonding started.
+        pub unbonding_until: Option<BlockNumber>,
+    }
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        type Currency: ReservableCurrency<Self::AccountId>;
+        /// Origin allowed to slash operator bonds.
+        type SlashOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+        /// Deposit reserved from an operator for each pool.
+        #[pallet::constant]
+        type PoolBond: Get<BalanceOf<Self>>;
+        /// Blocks between `unbond` and `withdraw_bond`.
+        #[pallet::constant]
+        type UnbondingPeriod: Get<BlockNumberFor<Self>>;
+        /// Shares kept per pool; also the largest allowed PPLNS window.
+        #[pallet::constant]
+        type MaxShares: Get<u32>;
+    }
+
+    #[pallet::storage]
+    #[pallet::getter(fn next_pool_id)]
+    pub type NextPoolId<T: Config> = StorageValue<_, PoolId, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn pools)]
+    pub type Pools<T: Config> = StorageMap<_, Blake2_128Concat, PoolId, PoolInfoOf<T>, OptionQuery>;
+
+    /// Shares recorded for each pool, oldest first.
+    #[pallet::storage]
+    #[pallet::getter(fn shares)]
+    pub type Shares<T: Config> =
+        StorageMap<_, Blake2_128Concat, PoolId, BoundedVec<(T::AccountId, u32), T::MaxShares>, ValueQuery>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        PoolCreated { pool: PoolId, operator: T::AccountId, scheme: PayoutScheme },
+        SchemeChanged { pool: PoolId, scheme: PayoutScheme },
+        ShareRecorded { pool: PoolId, miner: T::AccountId, weight: u32 },
+        PaidOut { pool: PoolId, round: u32, miner: T::AccountId, amount: BalanceOf<T> },
+        RoundClosed { pool: PoolId, round: u32, reward: BalanceOf<T> },
+        Bonded { pool: PoolId, operator: T::AccountId, amount: BalanceOf<T> },
+        Unbonding { pool: PoolId, until: BlockNumberFor<T> },
+        BondWithdrawn { pool: PoolId, operator: T::AccountId, amount: BalanceOf<T> },
+        Slashed { pool: PoolId, operator: T::AccountId, amount: BalanceOf<T>, reason: Misbehavior },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        UnknownPool,
+        NotOperator,
+        /// PPLNS window is zero or larger than `MaxShares`.
+        InvalidWindow,
+        ZeroWeight,
+        /// The pool is unbonding and no longer accepts shares or rounds.
+        PoolClosing,
+        AlreadyUnbonding,
+        NotUnbonding,
+        /// The unbonding period has not elapsed yet.
+        BondLocked,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        #[pallet::call_index(0)]
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(2))]
+        pub fn create_pool(origin: OriginFor<T>, scheme: PayoutScheme) -> DispatchResult {
+            let operator = ensure_signed(origin)?;
+            Self::ensure_valid_scheme(scheme)?;
+
+            let bond = T::PoolBond::get();
+            T::Currency::reserve(&operator, bond)?;
+
+            let pool = Self::next_pool_id();
+            <Pools<T>>::insert(
+                pool,
+                PoolInfo { operator: operator.clone(), scheme, round: 0, bond, unbonding_until: None },
+            );
+            <NextPoolId<T>>::put(pool.saturating_add(1));
+            Self::deposit_event(Event::PoolCreated { pool, operator: operator.clone(), scheme });
+            Self::deposit_event(Event::Bonded { pool, operator, amount: bond });
+            Ok(())
+        }
+
+        /// Takes effect from the next round that is closed.
+        #[pallet::call_index(1)]
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        pub fn set_scheme(origin: OriginFor<T>, pool: PoolId, scheme: PayoutScheme) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::ensure_valid_scheme(scheme)?;
+            <Pools<T>>::try_mutate(pool, |info| -> DispatchResult {
+                let info = info.as_mut().ok_or(Error::<T>::UnknownPool)?;
+                ensure!(info.operator == who, Error::<T>::NotOperator);
+                info.scheme = scheme;
+                Ok(())
+            })?;
+            Self::deposit_event(Event::SchemeChanged { pool, scheme });
+            Ok(())
+        }
+
+        /// Records a share the operator has verified off chain. Once
+        /// `MaxShares` is reached the oldest share is dropped.
+        #[pallet::call_index(2)]
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        pub fn record_share(origin: OriginFor<T>, pool: PoolId, miner: T::AccountId, weight: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(weight > 0, Error::<T>::ZeroWeight);
+            Self::ensure_operator(pool, &who)?;
+
+            <Shares<T>>::mutate(pool, |shares| {
+                if shares.is_full() && !shares.is_empty() {
+                    shares.remove(0);
+                }
+                let _ = shares.try_push((miner.clone(), weight));
+            });
+            Self::deposit_event(Event::ShareRecorded { pool, miner, weight });
+            Ok(())
+        }
+
+        /// Pays `reward` from the operator's account to the members and starts
+        /// a new round.
+        #[pallet::call_index(3)]
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(T::MaxShares::get().into()))]
+        pub fn close_round(origin: OriginFor<T>, pool: PoolId, reward: BalanceOf<T>) -> DispatchResult {
+            let operator = ensure_signed(origin)?;
+            let info = Self::ensure_operator(pool, &operator)?;
+
+            let shares = Self::shares(pool);
+            let counted: &[(T::AccountId, u32)] = match info.scheme {
+                PayoutScheme::Pplns { window } => {
+                    let window = (window as usize).min(shares.len());
+                    &shares[shares.len() - window..]
+                }
+                PayoutScheme::Proportional => &shares[..],
+            };
+
+            let round = info.round;
+            for (miner, amount) in split_reward(counted, reward.unique_saturated_into()) {
+                let amount: BalanceOf<T> = amount.unique_saturated_into();
+                if amount.is_zero() || miner == operator {
+                    continue;
+                }
+                T::Currency::transfer(&operator, &miner, amount, ExistenceRequirement::KeepAlive)?;
+                Self::deposit_event(Event::PaidOut { pool, round, miner, amount });
+            }
+
+            // Proportional rounds start from scratch; PPLNS keeps its window
+            if info.scheme == PayoutScheme::Proportional {
+                <Shares<T>>::remove(pool);
+            }
+            <Pools<T>>::mutate(pool, |info| {
+                if let Some(info) = info {
+                    info.round = info.round.saturating_add(1);
+                }
+            });
+            Self::deposit_event(Event::RoundClosed { pool, round, reward });
+            Ok(())
+        }
+
+        /// Stops the pool and starts the unbonding period. The bond stays
+        /// slashable until it is withdrawn.
+        #[pallet::call_index(4)]
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        pub fn unbond(origin: OriginFor<T>, pool: PoolId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let until = <frame_system::Pallet<T>>::block_number().saturating_add(T::UnbondingPeriod::get());
+            <Pools<T>>::try_mutate(pool, |info| -> DispatchResult {
+                let info = info.as_mut().ok_or(Error::<T>::UnknownPool)?;
+                ensure!(info.operator == who, Error::<T>::NotOperator);
+                ensure!(info.unbonding_until.is_none(), Error::<T>::AlreadyUnbonding);
+                info.unbonding_until = Some(until);
+                Ok(())
+            })?;
+            Self::deposit_event(Event::Unbonding { pool, until });
+            Ok(())
+        }
+
+        /// Returns what is left of the bond and removes the pool.
+        #[pallet::call_index(5)]
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(2))]
+        pub fn withdraw_bond(origin: OriginFor<T>, pool: PoolId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let info = Self::pools(pool).ok_or(Error::<T>::UnknownPool)?;
+            ensure!(info.operator == who, Error::<T>::NotOperator);
+            let until = info.unbonding_until.ok_or(Error::<T>::NotUnbonding)?;
+            ensure!(<frame_system::Pallet<T>>::block_number() >= until, Error::<T>::BondLocked);
+
+            T::Currency::unreserve(&who, info.bond);
+            <Pools<T>>::remove(pool);
+            <Shares<T>>::remove(pool);
+            Self::deposit_event(Event::BondWithdrawn { pool, operator: who, amount: info.bond });
+            Ok(())
+        }
+
+        /// Slashes up to `amount` of the operator's bond.
+        #[pallet::call_index(6)]
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        pub fn slash(
+            origin: OriginFor<T>,
+            pool: PoolId,
+            amount: BalanceOf<T>,
+            reason: Misbehavior,
+        ) -> DispatchResult {
+            T::SlashOrigin::ensure_origin(origin)?;
+            let mut info = Self::pools(pool).ok_or(Error::<T>::UnknownPool)?;
+
+            let amount = amount.min(info.bond);
+            let (_imbalance, unslashed) = T::Currency::slash_reserved(&info.operator, amount);
+            let slashed = amount.saturating_sub(unslashed);
+            info.bond = info.bond.saturating_sub(slashed);
+            let operator = info.operator.clone();
+            <Pools<T>>::insert(pool, info);
+
+            Self::deposit_event(Event::Slashed { pool, operator, amount: slashed, reason });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        fn ensure_operator(pool: PoolId, who: &T::AccountId) -> Result<PoolInfoOf<T>, DispatchError> {
+            let info = Self::pools(pool).ok_or(Error::<T>::UnknownPool)?;
+            ensure!(&info.operator == who, Error::<T>::NotOperator);
+            ensure!(info.unbonding_until.is_none(), Error::<T>::PoolClosing);
+            Ok(info)
+        }
+
+        fn ensure_valid_scheme(scheme: PayoutScheme) -> DispatchResult {
+            if let PayoutScheme::Pplns { window } = scheme {
+                ensure!(window > 0 && window <= T::MaxShares::get(), Error::<T>::InvalidWindow);
+            }
+            Ok(())
+        }
+    }
+}
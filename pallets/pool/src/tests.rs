@@ -0,0 +1,210 @@
+use crate::{mock::*, split_reward, Error, Event, Misbehavior, PayoutScheme};
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::DispatchError;
+
+#[test]
+fn rewards_are_split_by_share_weight() {
+    let shares = [(1u8, 3), (2, 1), (1, 2), (3, 4)];
+    assert_eq!(split_reward(&shares, 1_000), vec![(1, 500), (2, 100), (3, 400)]);
+    // Dust stays with the pool
+    assert_eq!(split_reward(&[(1u8, 1), (2, 2)], 100), vec![(1, 33), (2, 66)]);
+    assert_eq!(split_reward(&[(1u8, 0)], 100), vec![]);
+}
+
+#[test]
+fn large_rewards_are_split_without_overflow() {
+    let shares = [(1u8, u32::MAX), (2, u32::MAX), (3, 2 * (u32::MAX / 2))];
+    let reward = u128::MAX - 7;
+    let split = split_reward(&shares, reward);
+    let total = 3 * u128::from(u32::MAX) - 1;
+    for (who, share) in &split {
+        let weight = u128::from(shares[usize::from(*who) - 1].1);
+        // `floor(reward * weight / total)`, split so nothing overflows
+        let expected = reward / total * weight + reward % total * weight / total;
+        assert_eq!(*share, expected);
+    }
+    let paid: u128 = split.iter().map(|(_, share)| share).sum();
+    assert!(paid <= reward && reward - paid < 3);
+}
+
+fn record(pool: u32, miner: AccountId, weight: u32) {
+    assert_ok!(Pool::record_share(RuntimeOrigin::signed(OPERATOR), pool, miner, weight));
+}
+
+fn paid_out() -> Vec<(AccountId, u64)> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            RuntimeEvent::Pool(Event::PaidOut { miner, amount, .. }) => Some((miner, amount)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn pplns_rounds_pay_the_last_window_of_shares() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Pool::create_pool(RuntimeOrigin::signed(OPERATOR), PayoutScheme::Pplns { window: 3 }));
+        System::assert_has_event(RuntimeEvent::Pool(Event::PoolCreated {
+            pool: 0,
+            operator: OPERATOR,
+            scheme: PayoutScheme::Pplns { window: 3 },
+        }));
+        assert_eq!(Balances::reserved_balance(OPERATOR), POOL_BOND);
+
+        // Share 2 falls out of the window of the last three
+        for (miner, weight) in [(2, 5), (3, 1), (4, 2), (3, 1)] {
+            record(0, miner, weight);
+        }
+        assert_eq!(Pool::shares(0).len(), 4);
+        assert_ok!(Pool::close_round(RuntimeOrigin::signed(OPERATOR), 0, 1_000));
+        assert_eq!(paid_out(), vec![(3, 500), (4, 500)]);
+        System::assert_last_event(RuntimeEvent::Pool(Event::RoundClosed { pool: 0, round: 0, reward: 1_000 }));
+        assert_eq!(Balances::free_balance(2), BALANCE);
+        assert_eq!(Balances::free_balance(3), BALANCE + 500);
+        assert_eq!(Balances::free_balance(OPERATOR), BALANCE - POOL_BOND - 1_000);
+
+        // The window carries over into the next round, and past `MaxShares` the
+        // oldest share is dropped
+        System::reset_events();
+        record(0, 5, 4);
+        record(0, 5, 2);
+        assert_eq!(Pool::shares(0).to_vec(), vec![(4, 2), (3, 1), (5, 4), (5, 2)]);
+        assert_ok!(Pool::close_round(RuntimeOrigin::signed(OPERATOR), 0, 700));
+        assert_eq!(paid_out(), vec![(3, 100), (5, 600)]);
+        assert_eq!(Pool::pools(0).unwrap().round, 2);
+    });
+}
+
+#[test]
+fn proportional_rounds_start_from_scratch() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Pool::create_pool(RuntimeOrigin::signed(OPERATOR), PayoutScheme::Proportional));
+        record(0, 2, 1);
+        record(0, 3, 2);
+        // The operator keeps its own share; nothing is transferred for it
+        record(0, OPERATOR, 1);
+        assert_ok!(Pool::close_round(RuntimeOrigin::signed(OPERATOR), 0, 1_001));
+        assert_eq!(paid_out(), vec![(2, 250), (3, 500)]);
+        assert!(Pool::shares(0).is_empty());
+
+        System::reset_events();
+        assert_ok!(Pool::close_round(RuntimeOrigin::signed(OPERATOR), 0, 1_000));
+        assert_eq!(paid_out(), vec![]);
+        System::assert_last_event(RuntimeEvent::Pool(Event::RoundClosed { pool: 0, round: 1, reward: 1_000 }));
+    });
+}
+
+#[test]
+fn only_the_operator_records_shares_and_closes_rounds() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Pool::create_pool(RuntimeOrigin::signed(OPERATOR), PayoutScheme::Pplns { window: 0 }),
+            Error::<Test>::InvalidWindow
+        );
+        assert_noop!(
+            Pool::create_pool(RuntimeOrigin::signed(OPERATOR), PayoutScheme::Pplns { window: MAX_SHARES + 1 }),
+            Error::<Test>::InvalidWindow
+        );
+        assert_ok!(Pool::create_pool(RuntimeOrigin::signed(OPERATOR), PayoutScheme::Proportional));
+
+        assert_noop!(Pool::record_share(RuntimeOrigin::signed(2), 0, 2, 1), Error::<Test>::NotOperator);
+        assert_noop!(Pool::record_share(RuntimeOrigin::signed(OPERATOR), 0, 2, 0), Error::<Test>::ZeroWeight);
+        assert_noop!(Pool::record_share(RuntimeOrigin::signed(OPERATOR), 1, 2, 1), Error::<Test>::UnknownPool);
+        assert_noop!(Pool::close_round(RuntimeOrigin::signed(2), 0, 10), Error::<Test>::NotOperator);
+
+        // A reward the operator cannot pay fails the whole round
+        record(0, 2, 1);
+        let unpayable = BALANCE;
+        assert!(Pool::close_round(RuntimeOrigin::signed(OPERATOR), 0, unpayable).is_err());
+        assert_eq!(Pool::pools(0).unwrap().round, 0);
+    });
+}
+
+#[test]
+fn bonds_are_returned_only_after_the_unbonding_period() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Pool::create_pool(RuntimeOrigin::signed(OPERATOR), PayoutScheme::Proportional));
+        assert_noop!(Pool::withdraw_bond(RuntimeOrigin::signed(OPERATOR), 0), Error::<Test>::NotUnbonding);
+        assert_noop!(Pool::unbond(RuntimeOrigin::signed(2), 0), Error::<Test>::NotOperator);
+
+        assert_ok!(Pool::unbond(RuntimeOrigin::signed(OPERATOR), 0));
+        let until = 1 + UNBONDING_PERIOD;
+        System::assert_last_event(RuntimeEvent::Pool(Event::Unbonding { pool: 0, until }));
+        assert_noop!(Pool::unbond(RuntimeOrigin::signed(OPERATOR), 0), Error::<Test>::AlreadyUnbonding);
+        // A closing pool takes no more work
+        assert_noop!(Pool::record_share(RuntimeOrigin::signed(OPERATOR), 0, 2, 1), Error::<Test>::PoolClosing);
+        assert_noop!(Pool::close_round(RuntimeOrigin::signed(OPERATOR), 0, 10), Error::<Test>::PoolClosing);
+
+        System::set_block_number(until - 1);
+        assert_noop!(Pool::withdraw_bond(RuntimeOrigin::signed(OPERATOR), 0), Error::<Test>::BondLocked);
+        assert_eq!(Balances::reserved_balance(OPERATOR), POOL_BOND);
+
+        System::set_block_number(until);
+        assert_noop!(Pool::withdraw_bond(RuntimeOrigin::signed(2), 0), Error::<Test>::NotOperator);
+        assert_ok!(Pool::withdraw_bond(RuntimeOrigin::signed(OPERATOR), 0));
+        System::assert_last_event(RuntimeEvent::Pool(Event::BondWithdrawn {
+            pool: 0,
+            operator: OPERATOR,
+            amount: POOL_BOND,
+        }));
+        assert_eq!(Balances::reserved_balance(OPERATOR), 0);
+        assert_eq!(Balances::free_balance(OPERATOR), BALANCE);
+        assert_eq!(Pool::pools(0), None);
+        assert_noop!(Pool::withdraw_bond(RuntimeOrigin::signed(OPERATOR), 0), Error::<Test>::UnknownPool);
+    });
+}
+
+#[test]
+fn unbonding_bonds_can_still_be_slashed() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Pool::create_pool(RuntimeOrigin::signed(OPERATOR), PayoutScheme::Proportional));
+        assert_noop!(
+            Pool::slash(RuntimeOrigin::signed(2), 0, 10, Misbehavior::InvalidClaim),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Pool::slash(RuntimeOrigin::root(), 1, 10, Misbehavior::InvalidClaim),
+            Error::<Test>::UnknownPool
+        );
+
+        assert_ok!(Pool::unbond(RuntimeOrigin::signed(OPERATOR), 0));
+        assert_ok!(Pool::slash(RuntimeOrigin::root(), 0, 30, Misbehavior::WithheldPayouts));
+        System::assert_last_event(RuntimeEvent::Pool(Event::Slashed {
+            pool: 0,
+            operator: OPERATOR,
+            amount: 30,
+            reason: Misbehavior::WithheldPayouts,
+        }));
+        assert_eq!(Pool::pools(0).unwrap().bond, POOL_BOND - 30);
+        assert_eq!(Balances::reserved_balance(OPERATOR), POOL_BOND - 30);
+
+        // Only the remainder is returned
+        System::set_block_number(1 + UNBONDING_PERIOD);
+        assert_ok!(Pool::withdraw_bond(RuntimeOrigin::signed(OPERATOR), 0));
+        assert_eq!(Balances::reserved_balance(OPERATOR), 0);
+        assert_eq!(Balances::free_balance(OPERATOR), BALANCE - 30);
+    });
+}
+
+#[test]
+fn slashes_are_capped_at_the_pool_bond() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Pool::create_pool(RuntimeOrigin::signed(OPERATOR), PayoutScheme::Proportional));
+        assert_ok!(Pool::create_pool(RuntimeOrigin::signed(OPERATOR), PayoutScheme::Proportional));
+        assert_eq!(Balances::reserved_balance(OPERATOR), 2 * POOL_BOND);
+
+        // The bond of another pool by the same operator is left alone
+        assert_ok!(Pool::slash(RuntimeOrigin::root(), 0, 10 * POOL_BOND, Misbehavior::InvalidClaim));
+        System::assert_last_event(RuntimeEvent::Pool(Event::Slashed {
+            pool: 0,
+            operator: OPERATOR,
+            amount: POOL_BOND,
+            reason: Misbehavior::InvalidClaim,
+        }));
+        assert_eq!(Pool::pools(0).unwrap().bond, 0);
+        assert_eq!(Pool::pools(1).unwrap().bond, POOL_BOND);
+        assert_eq!(Balances::reserved_balance(OPERATOR), POOL_BOND);
+        assert_eq!(Balances::free_balance(OPERATOR), BALANCE - 2 * POOL_BOND);
+    });
+}
@@ -0,0 +1,368 @@
+//! JSON-RPC interface of the RubikPoW pallet.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use jsonrpsee::{
+    core::RpcResult,
+    proc_macros::rpc,
+    types::error::{ErrorObject, ErrorObjectOwned},
+};
+use codec::{Decode, Encode};
+use pallet_rubikpow::offchain::{submission_key, SubmissionOutcome, SubmissionRecord};
+use pallet_rubikpow::{MAX_CUBE_SIZE, MIN_CUBE_SIZE};
+use qbitcoin_core::{difficulty::REFERENCE_SIZE, notation, render, Cube, SpecVersion, VerifyError};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{offchain::OffchainStorage, Bytes};
+use sp_runtime::traits::{Block as BlockT, NumberFor, One, Saturating};
+
+pub use pallet_rubikpow::{RetargetPoint, TimeToBlock};
+pub use pallet_rubikpow_runtime_api::RubikPowApi as RubikPowRuntimeApi;
+
+#[cfg(test)]
+mod tests;
+
+/// A block named by hash or by number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BlockHashOrNumber<BlockHash> {
+    Hash(BlockHash),
+    Number(u64),
+}
+
+/// The challenge of a block, ready to show on a web page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScramblePreview<BlockHash> {
+    pub block_hash: BlockHash,
+    pub cube_size: u32,
+    /// Lowest nonce a seal on top of the block can use; the scramble is the
+    /// one for this nonce.
+    pub nonce: u64,
+    /// Scramble in standard notation, e.g. `R U2 F'`.
+    pub scramble: String,
+    /// Unfolded net of the scrambled cube.
+    pub svg: String,
+}
+
+/// What became of a seal the node recorded for its author.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "fate")]
+pub enum SubmissionFate<AccountId> {
+    /// Mined a block that is still on the best chain.
+    #[serde(rename_all = "camelCase")]
+    Included { payee: AccountId, reward: u32 },
+    /// Mined a block that has since left the best chain.
+    Orphaned,
+    #[serde(rename_all = "camelCase")]
+    Rejected { code: u8, reason: String },
+}
+
+/// A seal from the node's offchain index of submissions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnSubmission<BlockHash, AccountId> {
+    /// Mined block (`BlockNumber`) the seal was submitted for.
+    pub height: u32,
+    pub parent_hash: BlockHash,
+    pub cube_size: u32,
+    pub nonce: u64,
+    #[serde(flatten)]
+    pub fate: SubmissionFate<AccountId>,
+}
+
+/// Longest span of heights one `rubikpow_ownSubmissions` call looks up.
+pub const MAX_SUBMISSION_RANGE: u32 = 4096;
+
+#[rpc(client, server)]
+pub trait RubikPowApi<BlockHash, BlockNumber> {
+    /// Validates an encoded `PowSeal` exactly as the pallet would on submission
+    /// (bounds, nonce, solution, target) without submitting anything.
+    #[method(name = "rubikpow_checkSeal")]
+    fn check_seal(&self, seal: Bytes, at: Option<BlockHash>) -> RpcResult<()>;
+
+    /// The most recent difficulty retarget points, oldest first.
+    #[method(name = "rubikpow_difficultyHistory")]
+    fn difficulty_history(
+        &self,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<RetargetPoint<BlockNumber>>>;
+
+    /// Expected time to find a block, and earnings, at the current difficulty
+    /// for a miner making `solve_rate` attempts per second on `cube_size`
+    /// challenges. `null` for a zero rate or a size the chain rejects.
+    #[method(name = "rubikpow_estimateTimeToBlock")]
+    fn estimate_time_to_block(
+        &self,
+        cube_size: u32,
+        solve_rate: u64,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<TimeToBlock>>;
+
+    /// Reward a seal for `cube_size` whose solution is `solution_len` moves
+    /// long in the chain's metric would earn if included at `at_block`, by
+    /// default the block after `at`. `null` for a size the chain rejects.
+    #[method(name = "rubikpow_estimateReward")]
+    fn estimate_reward(
+        &self,
+        cube_size: u32,
+        solution_len: u32,
+        at_block: Option<BlockNumber>,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<u32>>;
+
+    /// The scramble miners building on `block` face, in standard notation and
+    /// as an SVG, for a `cube_size` cube (3x3x3 if omitted).
+    #[method(name = "rubikpow_previewScramble")]
+    fn preview_scramble(
+        &self,
+        block: BlockHashOrNumber<BlockHash>,
+        cube_size: Option<u32>,
+    ) -> RpcResult<ScramblePreview<BlockHash>>;
+}
+
+#[rpc(client, server)]
+pub trait OwnSubmissionsApi<BlockHash, AccountId> {
+    /// Seals `author` submitted for mined heights `from..=to` that this node
+    /// recorded, by height. Needs the node to run with offchain indexing
+    /// enabled; a node without it returns nothing.
+    #[method(name = "rubikpow_ownSubmissions")]
+    fn own_submissions(
+        &self,
+        author: AccountId,
+        from: u32,
+        to: u32,
+    ) -> RpcResult<Vec<OwnSubmission<BlockHash, AccountId>>>;
+}
+
+/// Error codes returned by this module.
+pub mod error_code {
+    /// The runtime API call itself failed.
+    pub const RUNTIME_ERROR: i32 = 1;
+    /// The seal was checked and would be rejected.
+    pub const SEAL_REJECTED: i32 = 2;
+    /// The requested block is not known to the node.
+    pub const UNKNOWN_BLOCK: i32 = 3;
+    /// The chain does not accept seals of the requested cube size.
+    pub const INVALID_CUBE_SIZE: i32 = 4;
+    /// The range of heights is reversed or longer than
+    /// `MAX_SUBMISSION_RANGE`.
+    pub const INVALID_RANGE: i32 = 5;
+}
+
+pub struct RubikPow<C, Block> {
+    client: Arc<C>,
+    _marker: PhantomData<Block>,
+}
+
+impl<C, Block> RubikPow<C, Block> {
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<C, Block> RubikPowApiServer<<Block as BlockT>::Hash, NumberFor<Block>> for RubikPow<C, Block>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: RubikPowRuntimeApi<Block, <Block as BlockT>::Hash, NumberFor<Block>>,
+{
+    fn check_seal(&self, seal: Bytes, at: Option<<Block as BlockT>::Hash>) -> RpcResult<()> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        let result = self
+            .client
+            .runtime_api()
+            .check_seal(at, seal.to_vec())
+            .map_err(|e| runtime_error("Unable to check seal", e))?;
+
+        result.map_err(|code| {
+            // Data carries the numeric code plus its name, e.g.
+            // `{"code": 4, "reason": "StaleChallenge"}`
+            let reason = VerifyError::from_code(code).map_or("Unknown", VerifyError::name);
+            ErrorObject::owned(
+                error_code::SEAL_REJECTED,
+                "Seal would be rejected",
+                Some(serde_json::json!({ "code": code, "reason": reason })),
+            )
+        })
+    }
+
+    fn difficulty_history(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<RetargetPoint<NumberFor<Block>>>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        self.client
+            .runtime_api()
+            .difficulty_history(at)
+            .map_err(|e| runtime_error("Unable to query difficulty history", e))
+    }
+
+    fn estimate_time_to_block(
+        &self,
+        cube_size: u32,
+        solve_rate: u64,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<TimeToBlock>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        self.client
+            .runtime_api()
+            .estimate_time_to_block(at, cube_size, solve_rate)
+            .map_err(|e| runtime_error("Unable to estimate time to block", e))
+    }
+
+    fn estimate_reward(
+        &self,
+        cube_size: u32,
+        solution_len: u32,
+        at_block: Option<NumberFor<Block>>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<u32>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        let at_block = match at_block {
+            Some(number) => number,
+            None => self
+                .client
+                .number(at)
+                .ok()
+                .flatten()
+                .ok_or_else(|| ErrorObject::owned(error_code::UNKNOWN_BLOCK, "Unknown block", Some(format!("{:?}", at))))?
+                .saturating_add(One::one()),
+        };
+        self.client
+            .runtime_api()
+            .estimate_reward(at, cube_size, solution_len, at_block)
+            .map_err(|e| runtime_error("Unable to estimate reward", e))
+    }
+
+    fn preview_scramble(
+        &self,
+        block: BlockHashOrNumber<<Block as BlockT>::Hash>,
+        cube_size: Option<u32>,
+    ) -> RpcResult<ScramblePreview<<Block as BlockT>::Hash>> {
+        let cube_size = cube_size.unwrap_or(REFERENCE_SIZE);
+        if !(MIN_CUBE_SIZE..=MAX_CUBE_SIZE).contains(&cube_size) {
+            return Err(ErrorObject::owned(
+                error_code::INVALID_CUBE_SIZE,
+                "Cube size out of range",
+                Some(serde_json::json!({ "min": MIN_CUBE_SIZE, "max": MAX_CUBE_SIZE })),
+            ));
+        }
+        let block_hash = match block {
+            BlockHashOrNumber::Hash(hash) => hash,
+            BlockHashOrNumber::Number(number) => NumberFor::<Block>::try_from(number)
+                .ok()
+                .and_then(|number| self.client.hash(number).ok().flatten())
+                .ok_or_else(|| ErrorObject::owned(error_code::UNKNOWN_BLOCK, "Unknown block", Some(number)))?,
+        };
+
+        let api = self.client.runtime_api();
+        let (header, nonce) = api
+            .preview_challenge(block_hash)
+            .map_err(|e| runtime_error("Unable to derive the challenge", e))?;
+        let scramble_spec = api
+            .scramble_spec(block_hash, cube_size)
+            .map_err(|e| runtime_error("Unable to derive the challenge", e))?;
+        let mut cube = Cube::new(cube_size as usize);
+        let scramble = cube.scramble_with(SpecVersion::CURRENT, &scramble_spec, nonce, &header);
+        Ok(ScramblePreview {
+            block_hash,
+            cube_size,
+            nonce,
+            scramble: notation::format_algorithm(&scramble),
+            svg: render::svg(&cube),
+        })
+    }
+}
+
+/// Serves `rubikpow_ownSubmissions` from the node's offchain storage.
+pub struct OwnSubmissions<C, Block, S> {
+    client: Arc<C>,
+    storage: S,
+    _marker: PhantomData<Block>,
+}
+
+impl<C, Block, S> OwnSubmissions<C, Block, S> {
+    pub fn new(client: Arc<C>, storage: S) -> Self {
+        Self {
+            client,
+            storage,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<C, Block, S> OwnSubmissions<C, Block, S>
+where
+    Block: BlockT,
+    C: HeaderBackend<Block>,
+{
+    /// Whether the block built on `parent_hash` that processed a seal is on
+    /// the best chain. Sibling blocks on the same parent are not told apart.
+    fn is_canonical(&self, parent_hash: <Block as BlockT>::Hash) -> bool {
+        match self.client.number(parent_hash) {
+            Ok(Some(number)) => self.client.hash(number).ok().flatten() == Some(parent_hash),
+            _ => false,
+        }
+    }
+}
+
+impl<C, Block, S, AccountId> OwnSubmissionsApiServer<<Block as BlockT>::Hash, AccountId> for OwnSubmissions<C, Block, S>
+where
+    Block: BlockT,
+    C: HeaderBackend<Block> + Send + Sync + 'static,
+    S: OffchainStorage + 'static,
+    AccountId: Encode + Decode + Clone + Serialize + for<'de> Deserialize<'de> + Send + Sync + 'static,
+{
+    fn own_submissions(
+        &self,
+        author: AccountId,
+        from: u32,
+        to: u32,
+    ) -> RpcResult<Vec<OwnSubmission<<Block as BlockT>::Hash, AccountId>>> {
+        if from > to || to - from >= MAX_SUBMISSION_RANGE {
+            return Err(ErrorObject::owned(
+                error_code::INVALID_RANGE,
+                "Invalid range of heights",
+                Some(serde_json::json!({ "maxRange": MAX_SUBMISSION_RANGE })),
+            ));
+        }
+
+        let mut submissions = Vec::new();
+        for height in from..=to {
+            let Some(encoded) = self.storage.get(sp_offchain::STORAGE_PREFIX, &submission_key(&author, height)) else {
+                continue;
+            };
+            // Entries that do not decode were written by another runtime
+            let Ok(record) = SubmissionRecord::<AccountId, <Block as BlockT>::Hash>::decode(&mut &encoded[..]) else {
+                continue;
+            };
+            let fate = match record.outcome {
+                SubmissionOutcome::Mined { payee, reward } if self.is_canonical(record.parent_hash) => {
+                    SubmissionFate::Included { payee, reward }
+                }
+                SubmissionOutcome::Mined { .. } => SubmissionFate::Orphaned,
+                SubmissionOutcome::Rejected { reason: code } => SubmissionFate::Rejected {
+                    code,
+                    reason: VerifyError::from_code(code).map_or("Unknown", VerifyError::name).into(),
+                },
+            };
+            submissions.push(OwnSubmission {
+                height,
+                parent_hash: record.parent_hash,
+                cube_size: record.cube_size,
+                nonce: record.nonce,
+                fate,
+            });
+        }
+        Ok(submissions)
+    }
+}
+
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> ErrorObjectOwned {
+    ErrorObject::owned(error_code::RUNTIME_ERROR, message, Some(format!("{:?}", e)))
+}
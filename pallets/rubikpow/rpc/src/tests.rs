@@ -0,0 +1,79 @@
+use super::*;
+use sp_api::ApiRef;
+use sp_blockchain::{BlockStatus, Info};
+use substrate_test_runtime_client::runtime::{Block, Hash, Header};
+use substrate_test_runtime_client::{DefaultTestClientBuilderExt, TestClient, TestClientBuilder, TestClientBuilderExt};
+
+/// Runtime API standing in for the pallet: the reward halves every ten
+/// blocks and drops by one per move, so tests can tell which arguments the
+/// RPC passed.
+#[derive(Clone)]
+struct MockApi;
+
+sp_api::mock_impl_runtime_apis! {
+    impl RubikPowRuntimeApi<Block, Hash, u64> for MockApi {
+        fn estimate_reward(cube_size: u32, solution_len: u32, at_block: u64) -> Option<u32> {
+            (MIN_CUBE_SIZE..=MAX_CUBE_SIZE)
+                .contains(&cube_size)
+                .then(|| (3000 >> (at_block / 10)) - solution_len)
+        }
+    }
+}
+
+/// A real test chain for headers, with the mock runtime API.
+struct MockClient {
+    chain: TestClient,
+}
+
+impl ProvideRuntimeApi<Block> for MockClient {
+    type Api = MockApi;
+
+    fn runtime_api(&self) -> ApiRef<'_, Self::Api> {
+        MockApi.into()
+    }
+}
+
+impl HeaderBackend<Block> for MockClient {
+    fn header(&self, hash: Hash) -> sp_blockchain::Result<Option<Header>> {
+        self.chain.header(hash)
+    }
+
+    fn info(&self) -> Info<Block> {
+        self.chain.info()
+    }
+
+    fn status(&self, hash: Hash) -> sp_blockchain::Result<BlockStatus> {
+        self.chain.status(hash)
+    }
+
+    fn number(&self, hash: Hash) -> sp_blockchain::Result<Option<u64>> {
+        self.chain.number(hash)
+    }
+
+    fn hash(&self, number: u64) -> sp_blockchain::Result<Option<Hash>> {
+        self.chain.hash(number)
+    }
+}
+
+fn rpc() -> RubikPow<MockClient, Block> {
+    RubikPow::new(Arc::new(MockClient { chain: TestClientBuilder::new().build() }))
+}
+
+#[test]
+fn estimate_reward_defaults_to_the_next_block() {
+    let rpc = rpc();
+    // The best block is genesis, so the estimate is for block 1
+    assert_eq!(rpc.estimate_reward(3, 20, None, None).unwrap(), Some(2980));
+    assert_eq!(rpc.estimate_reward(3, 20, Some(9), None).unwrap(), Some(2980));
+    assert_eq!(rpc.estimate_reward(3, 20, Some(10), None).unwrap(), Some(1480));
+    assert_eq!(rpc.estimate_reward(3, 25, Some(25), None).unwrap(), Some(725));
+    assert_eq!(rpc.estimate_reward(MAX_CUBE_SIZE + 1, 20, None, None).unwrap(), None);
+}
+
+#[test]
+fn estimate_reward_at_an_unknown_block_is_an_error() {
+    let error = rpc().estimate_reward(3, 20, None, Some(Hash::repeat_byte(7))).unwrap_err();
+    assert_eq!(error.code(), error_code::UNKNOWN_BLOCK);
+    // An explicit height needs no lookup
+    assert_eq!(rpc().estimate_reward(3, 20, Some(1), Some(Hash::repeat_byte(7))).unwrap(), Some(2980));
+}
@@ -0,0 +1,53 @@
+//! Runtime API of the RubikPoW pallet, for wallets, exchanges and bridges.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use pallet_rubikpow::{MinerProfile, RetargetPoint, TimeToBlock};
+use qbitcoin_core::ScrambleSpec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    pub trait RubikPowApi<Hash, BlockNumber> where Hash: Codec, BlockNumber: Codec {
+        /// Blocks' worth of work, at the current difficulty, built on top of `block_hash`.
+        fn confirmations(block_hash: Hash) -> Option<u32>;
+
+        /// Whether at least `depth` blocks' worth of work has been built on `block_hash`.
+        fn is_confirmed(block_hash: Hash, depth: u32) -> bool;
+
+        /// Runs every check `submit_solution` would on an encoded `PowSeal`,
+        /// against the state at the call's block, without submitting it.
+        /// The error is a `qbitcoin_core::VerifyError` code.
+        fn check_seal(seal: Vec<u8>) -> Result<(), u8>;
+
+        /// Header bytes and lowest accepted nonce of the next challenge, as of
+        /// the call's block.
+        fn preview_challenge() -> (Vec<u8>, u64);
+
+        /// Header bytes of the next challenge for a block whose transactions
+        /// have root `template_root`. Differs from `preview_challenge` once
+        /// V2 rules, which bind seals to their block, are in force.
+        fn challenge_header_for(template_root: [u8; 32]) -> Vec<u8>;
+
+        /// How challenges on `cube_size` cubes are scrambled.
+        fn scramble_spec(cube_size: u32) -> ScrambleSpec;
+
+        /// The most recent retarget points, oldest first.
+        fn difficulty_history() -> Vec<RetargetPoint<BlockNumber>>;
+
+        /// Expected payout for a candidate solution, using the pallet's reward
+        /// schedule. `None` if a seal of that size would be rejected.
+        fn estimate_reward(cube_size: u32, solution_len: u32, at_block: BlockNumber) -> Option<u32>;
+
+        /// Expected time to block and earnings at the current difficulty for a
+        /// miner making `attempts_per_second` attempts on `cube_size`
+        /// challenges. `None` for a zero rate or a rejected size.
+        fn estimate_time_to_block(cube_size: u32, attempts_per_second: u64) -> Option<TimeToBlock>;
+    }
+
+    /// Miner statistics for wallets showing miner profiles.
+    pub trait MinerStatsApi<AccountId> where AccountId: Codec {
+        /// Lifetime statistics of `who`, if the pallet still tracks the account.
+        fn miner_profile(who: AccountId) -> Option<MinerProfile>;
+    }
+}
@@ -0,0 +1,165 @@
+//! Paying for a transaction with a cube solution instead of fees.
+//!
+//! [`ChargeByPow`] wraps the runtime's fee extension. A transaction that
+//! carries a [`PowProof`] for a call accepted by `Config::PowPayableCalls`
+//! skips the wrapped extension, and so pays no fee; any other transaction is
+//! charged by it as usual. The proof solves a 2x2x2 challenge derived from the
+//! signer, the signer's account nonce and the call, at the much easier
+//! `Config::PowFeeDifficulty`, so a proof cannot be reused for a different
+//! transaction and spamming calls costs CPU time.
+//!
+//! Place `ChargeByPow` before `CheckNonce` in the runtime's extensions: the
+//! challenge uses the account nonce the transaction is signed with, which
+//! `CheckNonce` increments during dispatch.
+
+use codec::{Decode, Encode};
+use frame_support::traits::Contains;
+use scale_info::TypeInfo;
+use sp_runtime::{
+    traits::{DispatchInfoOf, PostDispatchInfoOf, SignedExtension},
+    transaction_validity::{
+        InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+    },
+    DispatchResult,
+};
+use sp_std::{fmt, marker::PhantomData, vec::Vec};
+
+use qbitcoin_core::{verify_seal_with_versions, Move, PowSeal, Target};
+
+use crate::{Config, Pallet};
+
+/// Cube size of fee challenges.
+pub const POW_FEE_CUBE_SIZE: u32 = 2;
+/// Domain separator of fee challenge headers.
+pub const POW_FEE_DOMAIN: &[u8] = b"qbitcoin/pow-fee";
+
+/// Solution of a transaction's fee challenge.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+pub struct PowProof {
+    pub nonce: u64,
+    pub moves: Vec<Move>,
+}
+
+impl fmt::Debug for PowProof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PowProof {{ nonce: {}, moves: {} }}", self.nonce, self.moves.len())
+    }
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct ChargeByPow<T, S> {
+    proof: Option<PowProof>,
+    inner: S,
+    _marker: PhantomData<T>,
+}
+
+impl<T, S> ChargeByPow<T, S> {
+    /// Pays with `proof` if given, otherwise through `inner`.
+    pub fn new(proof: Option<PowProof>, inner: S) -> Self {
+        ChargeByPow { proof, inner, _marker: PhantomData }
+    }
+}
+
+impl<T, S: fmt::Debug> fmt::Debug for ChargeByPow<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.proof {
+            Some(proof) => write!(f, "ChargeByPow({:?})", proof),
+            None => write!(f, "ChargeByPow({:?})", self.inner),
+        }
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Header bytes of the fee challenge for `who` sending `call` at
+    /// `account_nonce`.
+    pub fn pow_fee_header(who: &T::AccountId, account_nonce: T::Nonce, call: &T::RuntimeCall) -> Vec<u8> {
+        let mut header = POW_FEE_DOMAIN.to_vec();
+        who.encode_to(&mut header);
+        account_nonce.encode_to(&mut header);
+        header.extend_from_slice(&sp_io::hashing::blake2_256(&call.encode()));
+        header
+    }
+
+    /// Checks `proof` against the fee challenge of `who` sending `call` now.
+    pub fn check_pow_fee(
+        who: &T::AccountId,
+        call: &T::RuntimeCall,
+        proof: &PowProof,
+    ) -> Result<(), TransactionValidityError> {
+        if !T::PowPayableCalls::contains(call) {
+            return Err(InvalidTransaction::Call.into());
+        }
+        let header = Self::pow_fee_header(who, <frame_system::Pallet<T>>::account_nonce(who), call);
+        let seal = PowSeal { cube_size: POW_FEE_CUBE_SIZE, nonce: proof.nonce, moves: proof.moves.clone() };
+        let target = Target::from_difficulty(T::PowFeeDifficulty::get());
+        let (spec, commitment) = Self::current_rules();
+        verify_seal_with_versions(spec, commitment, &seal, &header, &target)
+            .map(|_| ())
+            .map_err(|error| InvalidTransaction::Custom(error.code()).into())
+    }
+}
+
+impl<T, S> SignedExtension for ChargeByPow<T, S>
+where
+    T: Config + Send + Sync,
+    S: SignedExtension<AccountId = T::AccountId, Call = T::RuntimeCall>,
+{
+    const IDENTIFIER: &'static str = "ChargeByPow";
+    type AccountId = T::AccountId;
+    type Call = T::RuntimeCall;
+    type AdditionalSigned = S::AdditionalSigned;
+    /// `None` when paid by proof.
+    type Pre = Option<S::Pre>;
+
+    fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+        self.inner.additional_signed()
+    }
+
+    fn validate(
+        &self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> TransactionValidity {
+        match &self.proof {
+            Some(proof) => {
+                Pallet::<T>::check_pow_fee(who, call, proof)?;
+                Ok(ValidTransaction::default())
+            }
+            None => self.inner.validate(who, call, info, len),
+        }
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        match &self.proof {
+            Some(proof) => {
+                Pallet::<T>::check_pow_fee(who, call, proof)?;
+                Ok(None)
+            }
+            None => self.inner.pre_dispatch(who, call, info, len).map(Some),
+        }
+    }
+
+    fn post_dispatch(
+        pre: Option<Self::Pre>,
+        info: &DispatchInfoOf<Self::Call>,
+        post_info: &PostDispatchInfoOf<Self::Call>,
+        len: usize,
+        result: &DispatchResult,
+    ) -> Result<(), TransactionValidityError> {
+        match pre {
+            Some(Some(pre)) => S::post_dispatch(Some(pre), info, post_info, len, result),
+            // Paid by proof: the wrapped extension never saw the transaction
+            Some(None) => Ok(()),
+            None => S::post_dispatch(None, info, post_info, len, result),
+        }
+    }
+}
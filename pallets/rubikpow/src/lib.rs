@@ -1,28 +1,416 @@
 #![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(not(test), deny(clippy::unwrap_used, clippy::expect_used))]
 
 use frame_support::{
     dispatch,
     pallet_prelude::*,
-    traits::{Currency, ExistenceRequirement},
+    traits::{Contains, Currency, ExistenceRequirement, UnixTime},
 };
+use frame_system::offchain::{SignedPayload, SigningTypes};
 use frame_system::pallet_prelude::*;
-use sp_runtime::traits::Hash;
+use sp_runtime::{
+    traits::{Hash, IdentifyAccount, One, SaturatedConversion, Saturating, Zero},
+    Digest, DigestItem,
+};
 use sp_std::vec::Vec;
 
 pub use pallet::*;
 
+pub mod extension;
+pub mod offchain;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// A difficulty change, as returned by `difficulty_history`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct RetargetPoint<BlockNumber> {
+    pub block_number: BlockNumber,
+    pub difficulty: u32,
+    /// Big-endian target derived from `difficulty`.
+    pub target: [u8; 32],
+    /// Blocks since the previous retarget point (zero for the first one).
+    pub observed_interval: BlockNumber,
+}
+
+/// Expected time to find a block and earnings for a given solve rate, as
+/// returned by `estimate_time_to_block`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeToBlock {
+    /// Difficulty the estimate was made at.
+    pub difficulty: u32,
+    pub expected_millis: u64,
+    /// Reward for each block found.
+    pub reward: u32,
+    pub reward_per_day: u128,
+}
+
+/// Lifetime mining record of one account, as returned by `miner_profile`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct MinerProfile {
+    pub blocks_mined: u32,
+    /// Sum of solution lengths, each in the metric in force when it was mined.
+    pub total_solution_length: u64,
+    /// Most work per solution move of any of the account's seals.
+    pub best_efficiency: u128,
+    /// Mined block (`BlockNumber`) the account last mined.
+    pub last_mined: u32,
+}
+
+impl MinerProfile {
+    /// Mean solution length, rounded down; `None` before the first block.
+    pub fn average_solution_length(&self) -> Option<u64> {
+        self.total_solution_length.checked_div(u64::from(self.blocks_mined))
+    }
+}
+
+/// One solution in a `submit_solutions_batch` call, with the same fields as
+/// `submit_solution` takes.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct Submission {
+    pub cube_size: u32,
+    pub moves: Vec<qbitcoin_core::Move>,
+    pub nonce: u64,
+}
+
+impl From<qbitcoin_core::PowSeal> for Submission {
+    fn from(seal: qbitcoin_core::PowSeal) -> Self {
+        Submission { cube_size: seal.cube_size, moves: seal.moves, nonce: seal.nonce }
+    }
+}
+
+impl From<Submission> for qbitcoin_core::PowSeal {
+    fn from(submission: Submission) -> Self {
+        qbitcoin_core::PowSeal { cube_size: submission.cube_size, nonce: submission.nonce, moves: submission.moves }
+    }
+}
+
+/// A seal submitted without a transaction fee, signed by the author it
+/// credits so that the nodes relaying it cannot claim it.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct UnsignedSubmission<Public> {
+    /// Key of the account credited with the seal.
+    pub author: Public,
+    pub cube_size: u32,
+    pub moves: Vec<qbitcoin_core::Move>,
+    pub nonce: u64,
+    /// Mined block (`BlockNumber`) the seal is submitted for.
+    pub mined: u32,
+}
+
+impl<T: SigningTypes> SignedPayload<T> for UnsignedSubmission<T::Public> {
+    fn public(&self) -> T::Public {
+        self.author.clone()
+    }
+}
+
+/// Consensus rules that take effect from a mined height, as scheduled by
+/// `schedule_activation`.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct Activation {
+    /// First mined block (`BlockNumber`) whose seal is checked under these
+    /// rules.
+    pub height: u32,
+    /// Challenge derivation: seed, scramble and challenge commitment.
+    pub spec: qbitcoin_core::SpecVersion,
+    /// State commitment compared with the target.
+    pub commitment: qbitcoin_core::CommitmentVersion,
+}
+
+/// The metric governance chose, for pallets that score solutions without
+/// depending on this one's storage directly.
+pub struct CurrentMetric<T>(core::marker::PhantomData<T>);
+
+impl<T: Config> Get<qbitcoin_core::Metric> for CurrentMetric<T> {
+    fn get() -> qbitcoin_core::Metric {
+        <SolutionMetric<T>>::get()
+    }
+}
+
+/// Notified of every seal the pallet accepts, after the reward was issued.
+pub trait OnSealAccepted<AccountId> {
+    /// `who` is the account credited with `seal`, after delegation;
+    /// `scrambled` is the challenge cube `seal` solves.
+    fn on_seal_accepted(who: &AccountId, seal: &qbitcoin_core::PowSeal, scrambled: &qbitcoin_core::Cube);
+
+    /// Most weight `on_seal_accepted` takes for a seal of `n_moves` moves on
+    /// a `cube_size` cube, charged with the call carrying the seal.
+    fn weight(cube_size: u32, n_moves: usize) -> Weight;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(5)]
+impl<AccountId> OnSealAccepted<AccountId> for Tuple {
+    fn on_seal_accepted(who: &AccountId, seal: &qbitcoin_core::PowSeal, scrambled: &qbitcoin_core::Cube) {
+        for_tuples!( #( Tuple::on_seal_accepted(who, seal, scrambled); )* );
+    }
+
+    fn weight(cube_size: u32, n_moves: usize) -> Weight {
+        let mut weight = Weight::zero();
+        for_tuples!( #( weight = weight.saturating_add(Tuple::weight(cube_size, n_moves)); )* );
+        weight
+    }
+}
+
+/// Notified of every solution the pallet accepts, right after
+/// [`OnSealAccepted`], with only the outcome: enough for pools, randomness
+/// beacons or treasuries, which have no use for the moves.
+#[impl_trait_for_tuples::impl_for_tuples(5)]
+pub trait OnSolutionAccepted<AccountId> {
+    /// `miner` is the account credited, after delegation; `quality` is the
+    /// solution's length in the metric `SolutionMetric` names, lower being
+    /// better; `commitment` is the challenge commitment the block records.
+    fn on_solution_accepted(miner: &AccountId, cube_size: u32, quality: u32, commitment: &[u8; 32]);
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
-    use qbitcoin_core::{Cube, Move, calculate_difficulty};
+    use qbitcoin_core::{
+        difficulty::{
+            efficiency_reward, emission, expected_reward_per_day, expected_time_ms, scale_reward, work_per_seal,
+            REFERENCE_SIZE,
+        },
+        policy::{MinQuality, SolutionPolicy},
+        retarget::retarget,
+        spec::{binds_template, challenge_commitment, challenge_header, challenge_seed, next_challenge_seed},
+        verify::MIN_SEAL_SIZE,
+        weight::{estimate_verification_weight, WeightHint},
+        verify_seal_with_rules, CommitmentVersion, Cube, Metric, Move, PowSeal, Rules, ScrambleSpec, SpecVersion,
+        Target, VerifyError, RUBIKPOW_ENGINE_ID, TEMPLATE_ENGINE_ID,
+    };
+    #[cfg(feature = "std")]
+    use qbitcoin_core::import::ImportState;
+    use crate::offchain::{SubmissionOutcome, SubmissionRecord};
+
+    /// Same as the core rule: smaller cubes prove no work.
+    pub const MIN_CUBE_SIZE: u32 = MIN_SEAL_SIZE;
+    /// Limit cube size for performance
+    pub const MAX_CUBE_SIZE: u32 = 16;
+    /// Reward for a seal on a `REFERENCE_SIZE` cube before any halving; other
+    /// sizes are scaled by their work.
+    pub const BASE_REWARD: u32 = 3000;
 
     #[pallet::pallet]
     pub struct Pallet<T>(_);
 
+    /// Size limits and nonce ordering, checked before the seal itself.
+    pub fn check_seal_policy(seal: &PowSeal, last_nonce: u64) -> Result<(), VerifyError> {
+        ensure!(seal.cube_size >= MIN_CUBE_SIZE, VerifyError::CubeTooSmall);
+        ensure!(seal.cube_size <= MAX_CUBE_SIZE, VerifyError::CubeTooLarge);
+
+        // Ensure nonce is unique and increasing; a used nonce means the
+        // challenge it was mined for is gone
+        ensure!(seal.nonce > last_nonce, VerifyError::StaleChallenge);
+        Ok(())
+    }
+
+    /// New difficulty if the `mined`th block closes a retarget interval of
+    /// `interval` blocks that began at `interval_start`, the time of the block
+    /// before it. The first interval begins at the first mined block, so it
+    /// spans one block time less.
+    pub fn retarget_due(
+        mined: u32,
+        difficulty: u32,
+        interval_start: u64,
+        now: u64,
+        interval: u32,
+        target_block_time: u64,
+    ) -> Option<u32> {
+        let interval = interval.max(1);
+        if mined % interval != 0 {
+            return None;
+        }
+        let block_times = if mined == interval { interval - 1 } else { interval };
+        if block_times == 0 {
+            return None;
+        }
+        let observed = now.saturating_sub(interval_start);
+        let expected = target_block_time.saturating_mul(u64::from(block_times));
+        Some(retarget(difficulty, observed, expected))
+    }
+
+    /// Spec and commitment versions `schedule` puts in force at mined height
+    /// `height`.
+    pub fn rules_in(schedule: &[Activation], height: u32) -> (SpecVersion, CommitmentVersion) {
+        schedule
+            .iter()
+            .take_while(|activation| activation.height <= height)
+            .last()
+            .map_or((SpecVersion::CURRENT, CommitmentVersion::CURRENT), |activation| {
+                (activation.spec, activation.commitment)
+            })
+    }
+
+    /// A `Config::SolutionPolicy`, compared by identity.
+    #[cfg(feature = "std")]
+    #[derive(Clone, Copy)]
+    pub struct SnapshotPolicy(pub Option<&'static dyn SolutionPolicy>);
+
+    #[cfg(feature = "std")]
+    impl core::fmt::Debug for SnapshotPolicy {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str(if self.0.is_some() { "SnapshotPolicy(Some(..))" } else { "SnapshotPolicy(None)" })
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl PartialEq for SnapshotPolicy {
+        fn eq(&self, other: &Self) -> bool {
+            match (self.0, other.0) {
+                (Some(a), Some(b)) => core::ptr::addr_eq(a, b),
+                (a, b) => a.is_none() && b.is_none(),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl Eq for SnapshotPolicy {}
+
+    /// The state the seals of a block are checked against, for verifying
+    /// them in parallel at import with `qbitcoin_core::import` while getting
+    /// the results in-order dispatch will produce.
+    #[cfg(feature = "std")]
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ImportSnapshot {
+        pub mined: u32,
+        pub difficulty: u32,
+        pub last_nonce: u64,
+        pub retarget_interval_start: u64,
+        /// Wall clock the block is imported at, in milliseconds.
+        pub now: u64,
+        pub retarget_interval: u32,
+        pub target_block_time: u64,
+        pub committed_seed: [u8; 32],
+        /// Sizes scrambled other than by `ScrambleSpec::V1`.
+        pub scramble_specs: Vec<(u32, ScrambleSpec)>,
+        pub min_quality: MinQuality,
+        /// `ActivationSchedule`, for the rules each seal of the block is
+        /// checked under.
+        pub activations: Vec<Activation>,
+        pub policy: SnapshotPolicy,
+    }
+
+    #[cfg(feature = "std")]
+    impl ImportState for ImportSnapshot {
+        fn header(&self) -> Vec<u8> {
+            // Same bytes as `get_current_block_header`: seals in transactions
+            // are not bound to a template
+            challenge_header(self.spec(), self.mined, &self.committed_seed, &[0; 32])
+        }
+
+        fn target(&self) -> Target {
+            Target::from_difficulty(self.difficulty)
+        }
+
+        fn spec(&self) -> SpecVersion {
+            rules_in(&self.activations, self.mined.saturating_add(1)).0
+        }
+
+        fn commitment(&self) -> CommitmentVersion {
+            rules_in(&self.activations, self.mined.saturating_add(1)).1
+        }
+
+        fn scramble_spec(&self, cube_size: u32) -> ScrambleSpec {
+            self.scramble_specs
+                .iter()
+                .find(|(size, _)| *size == cube_size)
+                .map_or(ScrambleSpec::V1, |(_, scramble)| *scramble)
+        }
+
+        fn check_policy(&self, seal: &PowSeal) -> Result<(), VerifyError> {
+            check_seal_policy(seal, self.last_nonce)
+        }
+
+        fn min_quality(&self) -> MinQuality {
+            self.min_quality
+        }
+
+        fn policy(&self) -> &dyn SolutionPolicy {
+            self.policy.0.unwrap_or(&())
+        }
+
+        fn accept(&mut self, seal: &PowSeal) {
+            // Mirrors `apply_seal` and `maybe_retarget`
+            let spec = self.spec();
+            let seed = challenge_seed(spec, seal.nonce, &self.header());
+            let commitment = challenge_commitment(spec, &seed, seal.cube_size, &self.target());
+            self.committed_seed = next_challenge_seed(spec, &commitment);
+            self.last_nonce = seal.nonce;
+            self.mined += 1;
+            if self.mined == 1 {
+                self.retarget_interval_start = self.now;
+            }
+            if let Some(difficulty) = retarget_due(
+                self.mined,
+                self.difficulty,
+                self.retarget_interval_start,
+                self.now,
+                self.retarget_interval,
+                self.target_block_time,
+            ) {
+                self.difficulty = difficulty;
+                self.retarget_interval_start = self.now;
+            }
+        }
+    }
+
     #[pallet::config]
-    pub trait Config: frame_system::Config {
+    pub trait Config: frame_system::Config + frame_system::offchain::SigningTypes {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
         type Currency: Currency<Self::AccountId>;
+        /// How many blocks back `is_confirmed` can answer for. Must not exceed
+        /// `frame_system::Config::BlockHashCount`, which is used for pruning.
+        #[pallet::constant]
+        type ConfirmationHistory: Get<u32>;
+        /// Unsigned submissions are only gossiped if the challenge commitment is
+        /// within this factor of the current target.
+        #[pallet::constant]
+        type ProximityFactor: Get<u32>;
+        /// Number of retarget points kept for `difficulty_history`.
+        #[pallet::constant]
+        type DifficultyHistoryLength: Get<u32>;
+        /// Wall clock used to measure retarget intervals.
+        type UnixTime: UnixTime;
+        /// Intended time between mined blocks, in milliseconds.
+        #[pallet::constant]
+        type TargetBlockTime: Get<u64>;
+        /// Mined blocks between difficulty retargets.
+        #[pallet::constant]
+        type RetargetInterval: Get<u32>;
+        /// Key the offchain worker signs pool shares with.
+        type PoolShareKey: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>;
+        /// Crypto the authors of unsigned submissions sign them with.
+        type SubmissionKey: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>;
+        /// Calls `extension::ChargeByPow` lets signers pay for with a cube
+        /// solution instead of fees.
+        type PowPayableCalls: Contains<<Self as frame_system::Config>::RuntimeCall>;
+        /// Difficulty of the fee challenges `extension::ChargeByPow` accepts;
+        /// meant to be far below the mining difficulty.
+        #[pallet::constant]
+        type PowFeeDifficulty: Get<u32>;
+        /// Consumers of accepted seals, such as a leaderboard.
+        type OnSealAccepted: OnSealAccepted<Self::AccountId>;
+        /// Consumers of accepted solutions that need no more than their
+        /// outcome, such as a pool or a randomness beacon.
+        type OnSolutionAccepted: OnSolutionAccepted<Self::AccountId>;
+        /// Most rule activations `ActivationSchedule` holds.
+        #[pallet::constant]
+        type MaxActivations: Get<u32>;
+        /// Refuses solutions that were not found for their challenge, such as
+        /// canned answers; `None` accepts every solution.
+        type SolutionPolicy: Get<Option<&'static dyn SolutionPolicy>>;
+        /// Accounts `MinerProfiles` keeps statistics for. Past it, the
+        /// account that mined least recently is dropped.
+        #[pallet::constant]
+        type MaxTrackedMiners: Get<u32>;
+        /// Most solutions one `submit_solutions_batch` call carries.
+        #[pallet::constant]
+        type MaxBatchSize: Get<u32>;
     }
 
     #[pallet::storage]
@@ -37,12 +425,135 @@ pub mod pallet {
     #[pallet::getter(fn block_number)]
     pub type BlockNumber<T: Config> = StorageValue<_, u32, ValueQuery>;
 
+    /// `hash(seed || size || target)` of the challenge solved in each block.
+    #[pallet::storage]
+    #[pallet::getter(fn challenge_commitment_at)]
+    pub type ChallengeCommitments<T: Config> =
+        StorageMap<_, Blake2_128Concat, BlockNumberFor<T>, [u8; 32], OptionQuery>;
+
+    /// Unix time in milliseconds at which the current retarget interval began.
+    #[pallet::storage]
+    #[pallet::getter(fn retarget_interval_start)]
+    pub type RetargetIntervalStart<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+    /// Sum of the work of every accepted seal.
+    #[pallet::storage]
+    #[pallet::getter(fn total_work)]
+    pub type TotalWork<T: Config> = StorageValue<_, u128, ValueQuery>;
+
+    /// Block number and accumulated work at the end of each recent block, keyed by hash.
+    #[pallet::storage]
+    pub type WorkAt<T: Config> =
+        StorageMap<_, Identity, T::Hash, (BlockNumberFor<T>, u128), OptionQuery>;
+
+    /// Block in which each author last had an unsigned submission included,
+    /// by the account of the key that signed it.
+    #[pallet::storage]
+    #[pallet::getter(fn last_unsigned_submission)]
+    pub type LastUnsignedSubmission<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BlockNumberFor<T>, OptionQuery>;
+
+    /// The most recent retarget points, oldest first.
+    #[pallet::storage]
+    #[pallet::getter(fn difficulty_history)]
+    pub type DifficultyHistory<T: Config> = StorageValue<
+        _,
+        BoundedVec<RetargetPoint<BlockNumberFor<T>>, T::DifficultyHistoryLength>,
+        ValueQuery,
+    >;
+
+    /// Metric solution lengths are scored in, chosen by governance.
+    #[pallet::storage]
+    #[pallet::getter(fn solution_metric)]
+    pub type SolutionMetric<T: Config> = StorageValue<_, Metric, ValueQuery>;
+
+    /// Scramble length and filters per cube size, set by root through
+    /// `set_scramble_spec`; unlike the difficulty they are never retargeted.
+    /// Sizes without an entry use `ScrambleSpec::V1`.
+    #[pallet::storage]
+    #[pallet::getter(fn scramble_spec)]
+    pub type ScrambleSpecs<T: Config> = StorageMap<_, Blake2_128Concat, u32, ScrambleSpec, ValueQuery>;
+
+    /// Longest solutions accepted relative to their scramble, set by
+    /// governance. `MinQuality::NONE` until set.
+    #[pallet::storage]
+    #[pallet::getter(fn min_quality)]
+    pub type MinSolutionQuality<T: Config> = StorageValue<_, MinQuality, ValueQuery>;
+
+    /// Seed of the next challenge, committed to by the last mined block.
+    /// Zeros until the first block is mined.
+    #[pallet::storage]
+    #[pallet::getter(fn committed_seed)]
+    pub type CommittedSeed<T: Config> = StorageValue<_, [u8; 32], ValueQuery>;
+
+    /// Root of the transactions the seal in the current block's digest was
+    /// mined with, for `on_finalize` to check against the block's extrinsics
+    /// before applying the seal. Only set while a block bound to its template
+    /// executes.
+    #[pallet::storage]
+    pub type TemplateRoot<T: Config> = StorageValue<_, [u8; 32], OptionQuery>;
+
+    /// Scheduled rule changes by ascending height. Heights before the first
+    /// entry use the core's current versions.
+    #[pallet::storage]
+    #[pallet::getter(fn activation_schedule)]
+    pub type ActivationSchedule<T: Config> = StorageValue<_, BoundedVec<Activation, T::MaxActivations>, ValueQuery>;
+
+    /// Statistics of the accounts in `TrackedMiners`.
+    #[pallet::storage]
+    #[pallet::getter(fn miner_profile)]
+    pub type MinerProfiles<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, MinerProfile, OptionQuery>;
+
+    /// Accounts with a profile, least recently mined first.
+    #[pallet::storage]
+    #[pallet::getter(fn tracked_miners)]
+    pub type TrackedMiners<T: Config> = StorageValue<_, BoundedVec<T::AccountId, T::MaxTrackedMiners>, ValueQuery>;
+
+    /// Submission key -> solver account that receives its rewards.
+    #[pallet::storage]
+    #[pallet::getter(fn delegator_of)]
+    pub type Delegations<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId, OptionQuery>;
+
+    /// Submission key -> solvers offering to take its rewards, until the key
+    /// accepts one of them with `accept_delegate`.
+    #[pallet::storage]
+    pub type DelegateOffers<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+    /// Solver account -> account its rewards are minted to, such as a cold
+    /// wallet that never signs submissions.
+    #[pallet::storage]
+    #[pallet::getter(fn payout_account_of)]
+    pub type PayoutAccounts<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, T::AccountId, OptionQuery>;
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         BlockMined { miner: T::AccountId, cube_size: u32 },
-        Reward { miner: T::AccountId, amount: u32 },
+        /// `payee` is the miner's payout account, or the miner itself.
+        Reward { miner: T::AccountId, payee: T::AccountId, amount: u32 },
         DifficultyAdjustment { new_difficulty: u32 },
+        /// A seal carried in a block's pre-runtime digest was rejected.
+        /// `reason` is a `VerifyError` code.
+        SolutionRejected { author: T::AccountId, reason: u8 },
+        /// The block's transactions differ from the template its digest seal
+        /// was mined with, so the seal was skipped.
+        TemplateMismatch { author: T::AccountId },
+        /// `solver` offered to take the rewards of `delegate`'s seals.
+        DelegateOffered { solver: T::AccountId, delegate: T::AccountId },
+        DelegateAdded { solver: T::AccountId, delegate: T::AccountId },
+        DelegateRemoved { solver: T::AccountId, delegate: T::AccountId },
+        SolutionMetricChanged { old: Metric, new: Metric },
+        ActivationScheduled { activation: Activation },
+        ScrambleSpecChanged { cube_size: u32, scramble: ScrambleSpec },
+        /// `payout` is `None` once rewards go to the miner again.
+        PayoutAccountSet { miner: T::AccountId, payout: Option<T::AccountId> },
+        /// `accepted` of a batch's solutions were mined.
+        BatchSubmitted { submitter: T::AccountId, accepted: u32 },
+        /// The solution at `index` of a non-atomic batch was skipped.
+        /// `reason` is a `VerifyError` code.
+        BatchItemRejected { index: u32, reason: u8 },
+        MinQualityChanged { old: MinQuality, new: MinQuality },
     }
 
     #[pallet::error]
@@ -52,12 +563,58 @@ pub mod pallet {
         CubeTooLarge,
         InvalidNonce,
         DifficultyTooLow,
+        /// The seal bytes could not be decoded.
+        MalformedSeal,
+        TargetNotMet,
+        /// The solution policy refused the solution as not specific to its
+        /// challenge.
+        CannedSolution,
+        /// The author already had an unsigned submission in this block.
+        RateLimited,
+        /// The key already submits on behalf of another solver.
+        AlreadyDelegated,
+        NotDelegated,
+        /// The solver has not offered to take the key's rewards.
+        NoDelegateOffer,
+        /// Activations must come after the block being mined, so that
+        /// templates already handed out stay valid.
+        ActivationNotInFuture,
+        /// Activations must be scheduled in order of height.
+        ActivationOutOfOrder,
+        TooManyActivations,
+        /// Scramble lengths must be ordered, non-zero and at most
+        /// `MAX_SCRAMBLE_LENGTH`.
+        InvalidScrambleSpec,
+        EmptyBatch,
+        /// The two verification engines disagreed on the seal.
+        EngineDisagreement,
+        /// The solution is longer than `MinSolutionQuality` allows.
+        SolutionTooLong,
+        /// A quality floor must allow at least the scramble undone move
+        /// for move.
+        QualityFloorTooStrict,
+    }
+
+    impl<T> From<VerifyError> for Error<T> {
+        fn from(error: VerifyError) -> Self {
+            match error {
+                VerifyError::MalformedSeal => Error::MalformedSeal,
+                VerifyError::CubeTooSmall => Error::CubeTooSmall,
+                VerifyError::CubeTooLarge => Error::CubeTooLarge,
+                VerifyError::StaleChallenge => Error::InvalidNonce,
+                VerifyError::InvalidSolution => Error::InvalidSolution,
+                VerifyError::TargetNotMet => Error::TargetNotMet,
+                VerifyError::CannedSolution => Error::CannedSolution,
+                VerifyError::EngineDisagreement => Error::EngineDisagreement,
+                VerifyError::SolutionTooLong => Error::SolutionTooLong,
+            }
+        }
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         #[pallet::call_index(0)]
-        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))] // Adjust weight based on complexity
+        #[pallet::weight(Pallet::<T>::seal_weight(*cube_size, moves.len()))]
         pub fn submit_solution(
             origin: OriginFor<T>,
             cube_size: u32,
@@ -66,39 +623,79 @@ pub mod pallet {
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
-            ensure!(cube_size >= 2, Error::<T>::CubeTooSmall);
-            ensure!(cube_size <= 16, Error::<T>::CubeTooLarge); // Limit cube size for performance
+            let seal = PowSeal { cube_size, nonce, moves };
+            let verified = Self::check_seal(&seal).map_err(Error::<T>::from)?;
+            Self::apply_seal(who, &seal, verified);
 
-            // Ensure nonce is unique and increasing
-            let last_nonce = Self::last_nonce();
-            ensure!(nonce > last_nonce, Error::<T>::InvalidNonce);
-            <LastNonce<T>>::put(nonce);
+            Ok(())
+        }
 
-            // Create cube and scramble it with the nonce
-            let mut cube = Cube::new(cube_size as usize);
-            let block_header = Self::get_current_block_header();
-            let scramble = cube.scramble_deterministic(nonce, &block_header);
+        /// Unsigned variant of `submit_solution`, gossiped by miners without an
+        /// account balance. `signature` is the author's over `submission`.
+        /// Filtered by `validate_unsigned`, which checks the signature.
+        #[pallet::call_index(2)]
+        #[pallet::weight(
+            Pallet::<T>::seal_weight(submission.cube_size, submission.moves.len())
+                .saturating_add(T::DbWeight::get().writes(1))
+        )]
+        pub fn submit_solution_unsigned(
+            origin: OriginFor<T>,
+            submission: UnsignedSubmission<T::Public>,
+            _signature: T::Signature,
+        ) -> DispatchResult {
+            ensure_none(origin)?;
 
-            // Verify solution
-            ensure!(cube.verify_solution(&moves), Error::<T>::InvalidSolution);
+            let author = submission.author.into_account();
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(Self::last_unsigned_submission(&author) != Some(now), Error::<T>::RateLimited);
 
-            // Check if the cube state meets the current difficulty target
-            let difficulty = Self::difficulty();
-            let target_hash = Self::calculate_target_hash(difficulty);
-            ensure!(cube.meets_difficulty(target_hash), Error::<T>::InvalidSolution);
+            let seal = PowSeal { cube_size: submission.cube_size, nonce: submission.nonce, moves: submission.moves };
+            let verified = Self::check_seal(&seal).map_err(Error::<T>::from)?;
+            <LastUnsignedSubmission<T>>::insert(&author, now);
+            Self::apply_seal(author, &seal, verified);
 
-            let reward = Self::calculate_reward(cube_size);
-            let new_difficulty = Self::adjust_difficulty(difficulty, cube_size);
+            Ok(())
+        }
 
-            <Difficulty<T>>::put(new_difficulty);
-            <BlockNumber<T>>::put(Self::block_number() + 1);
+        /// Offers to take the rewards of `delegate`'s seals. Nothing changes
+        /// until `delegate` accepts with `accept_delegate`, so no account can
+        /// claim another's rewards.
+        #[pallet::call_index(3)]
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        pub fn add_delegate(origin: OriginFor<T>, delegate: T::AccountId) -> DispatchResult {
+            let solver = ensure_signed(origin)?;
+            ensure!(!<Delegations<T>>::contains_key(&delegate), Error::<T>::AlreadyDelegated);
+            <DelegateOffers<T>>::insert(&delegate, &solver, ());
+            Self::deposit_event(Event::DelegateOffered { solver, delegate });
+            Ok(())
+        }
 
-            // Issue reward (simplified - in reality, would use T::Currency)
-            // For now, we just deposit an event.
-            Self::deposit_event(Event::BlockMined { miner: who.clone(), cube_size });
-            Self::deposit_event(Event::Reward { miner: who, amount: reward });
-            Self::deposit_event(Event::DifficultyAdjustment { new_difficulty });
+        /// Ends a delegation, or withdraws an offer not yet accepted. Either
+        /// the solver or the delegate may call it.
+        #[pallet::call_index(4)]
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(2))]
+        pub fn remove_delegate(origin: OriginFor<T>, delegate: T::AccountId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            if <DelegateOffers<T>>::take(&delegate, &who).is_some() {
+                return Ok(());
+            }
+            let solver = Self::delegator_of(&delegate).ok_or(Error::<T>::NotDelegated)?;
+            ensure!(who == solver || who == delegate, Error::<T>::NotDelegated);
+            <Delegations<T>>::remove(&delegate);
+            Self::deposit_event(Event::DelegateRemoved { solver, delegate });
+            Ok(())
+        }
 
+        /// Accepts `solver`'s offer: the caller's seals count as `solver`'s
+        /// and their rewards go to it.
+        #[pallet::call_index(11)]
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(2))]
+        pub fn accept_delegate(origin: OriginFor<T>, solver: T::AccountId) -> DispatchResult {
+            let delegate = ensure_signed(origin)?;
+            ensure!(!<Delegations<T>>::contains_key(&delegate), Error::<T>::AlreadyDelegated);
+            ensure!(<DelegateOffers<T>>::take(&delegate, &solver).is_some(), Error::<T>::NoDelegateOffer);
+            <Delegations<T>>::insert(&delegate, &solver);
+            Self::deposit_event(Event::DelegateAdded { solver, delegate });
             Ok(())
         }
 
@@ -108,51 +705,725 @@ pub mod pallet {
             ensure_root(origin)?;
             ensure!(new_difficulty > 0, Error::<T>::DifficultyTooLow);
             <Difficulty<T>>::put(new_difficulty);
+            Self::record_retarget(new_difficulty);
             Self::deposit_event(Event::DifficultyAdjustment { new_difficulty });
             Ok(())
         }
+
+        /// Switches the metric solution lengths are scored in. Records and
+        /// estimates use the new metric from the next seal on.
+        #[pallet::call_index(5)]
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_solution_metric(origin: OriginFor<T>, metric: Metric) -> DispatchResult {
+            ensure_root(origin)?;
+            let old = <SolutionMetric<T>>::get();
+            if old != metric {
+                <SolutionMetric<T>>::put(metric);
+                Self::deposit_event(Event::SolutionMetricChanged { old, new: metric });
+            }
+            Ok(())
+        }
+
+        /// Schedules seals from `activation.height` on to be checked under
+        /// new spec and commitment versions. Activations already superseded
+        /// by a later one in force are dropped to make room.
+        #[pallet::call_index(6)]
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(2, 1))]
+        pub fn schedule_activation(origin: OriginFor<T>, activation: Activation) -> DispatchResult {
+            ensure_root(origin)?;
+            let next = Self::block_number().saturating_add(1);
+            ensure!(activation.height > next, Error::<T>::ActivationNotInFuture);
+
+            <ActivationSchedule<T>>::try_mutate(|schedule| {
+                if let Some(last) = schedule.last() {
+                    ensure!(activation.height > last.height, Error::<T>::ActivationOutOfOrder);
+                }
+                let in_force = schedule.iter().take_while(|scheduled| scheduled.height <= next).count();
+                if in_force > 1 {
+                    let current = schedule[in_force - 1].height;
+                    schedule.retain(|scheduled| scheduled.height >= current);
+                }
+                schedule.try_push(activation).map_err(|_| Error::<T>::TooManyActivations)
+            })?;
+            Self::deposit_event(Event::ActivationScheduled { activation });
+            Ok(())
+        }
+
+        /// Sets how challenges on `cube_size` cubes are scrambled, from the
+        /// next seal on. Longer scrambles raise the solving work per attempt
+        /// without moving the hash target. The length stays as set until the
+        /// next call: `maybe_retarget` only adjusts the difficulty.
+        #[pallet::call_index(7)]
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        pub fn set_scramble_spec(origin: OriginFor<T>, cube_size: u32, scramble: ScrambleSpec) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(cube_size >= MIN_CUBE_SIZE, Error::<T>::CubeTooSmall);
+            ensure!(cube_size <= MAX_CUBE_SIZE, Error::<T>::CubeTooLarge);
+            ensure!(scramble.is_valid(), Error::<T>::InvalidScrambleSpec);
+            if scramble == ScrambleSpec::V1 {
+                <ScrambleSpecs<T>>::remove(cube_size);
+            } else {
+                <ScrambleSpecs<T>>::insert(cube_size, scramble);
+            }
+            Self::deposit_event(Event::ScrambleSpecChanged { cube_size, scramble });
+            Ok(())
+        }
+
+        /// Mints the caller's rewards to `payout` from the next seal on, or to
+        /// the caller again for `None`. Delegates' seals count as the caller's.
+        #[pallet::call_index(8)]
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+        pub fn set_payout_account(origin: OriginFor<T>, payout: Option<T::AccountId>) -> DispatchResult {
+            let miner = ensure_signed(origin)?;
+            let payout = payout.filter(|payout| *payout != miner);
+            match &payout {
+                Some(payout) => <PayoutAccounts<T>>::insert(&miner, payout),
+                None => <PayoutAccounts<T>>::remove(&miner),
+            }
+            Self::deposit_event(Event::PayoutAccountSet { miner, payout });
+            Ok(())
+        }
+
+        /// Submits several solutions in one transaction, for pools that
+        /// aggregate their members' finds. Each solution mines a block, so
+        /// each must solve the challenge left by the ones before it. If
+        /// `atomic`, the first invalid solution fails the whole call and
+        /// nothing is mined; otherwise invalid solutions are skipped and
+        /// reported with `BatchItemRejected`.
+        #[pallet::call_index(9)]
+        #[pallet::weight(Pallet::<T>::batch_weight(seals))]
+        pub fn submit_solutions_batch(
+            origin: OriginFor<T>,
+            seals: BoundedVec<Submission, T::MaxBatchSize>,
+            atomic: bool,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(!seals.is_empty(), Error::<T>::EmptyBatch);
+
+            let mut accepted = 0u32;
+            for (index, submission) in seals.into_iter().enumerate() {
+                let seal = PowSeal::from(submission);
+                match Self::check_seal(&seal) {
+                    Ok(verified) => {
+                        Self::apply_seal(who.clone(), &seal, verified);
+                        accepted += 1;
+                    }
+                    // Returning the error discards everything mined so far
+                    Err(error) if atomic => return Err(Error::<T>::from(error).into()),
+                    Err(error) => {
+                        Self::index_rejection(&who, &seal, error);
+                        Self::deposit_event(Event::BatchItemRejected { index: index as u32, reason: error.code() })
+                    }
+                }
+            }
+            Self::deposit_event(Event::BatchSubmitted { submitter: who, accepted });
+            Ok(())
+        }
+
+        /// Caps solution length at `quality.max_percent` percent of the
+        /// scramble's, from the next seal on, so that padded replays of the
+        /// scramble are refused outright.
+        #[pallet::call_index(10)]
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_min_quality(origin: OriginFor<T>, quality: MinQuality) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(quality.max_percent >= 100, Error::<T>::QualityFloorTooStrict);
+            let old = <MinSolutionQuality<T>>::get();
+            if old != quality {
+                <MinSolutionQuality<T>>::put(quality);
+                Self::deposit_event(Event::MinQualityChanged { old, new: quality });
+            }
+            Ok(())
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            let mut weight = Self::record_parent_work(n);
+
+            // Blocks authored through the standard flow carry their seal as a pre-runtime
+            // digest. From V2 it comes with the root of the transactions it was mined
+            // with, so it is checked and applied in `on_finalize`, once the block's
+            // extrinsics are known; a seal without one is rejected like any other
+            // invalid seal.
+            let digest = <frame_system::Pallet<T>>::digest();
+            match Self::find_pre_runtime_seal(&digest) {
+                Some((author, seal)) => {
+                    weight.saturating_accrue(Self::seal_weight(seal.cube_size, seal.moves.len()));
+                    let (spec, _) = Self::current_rules();
+                    if !binds_template(spec) {
+                        match Self::check_seal(&seal) {
+                            Ok(verified) => Self::apply_seal(author, &seal, verified),
+                            Err(error) => Self::reject_digest_seal(author, &seal, error),
+                        }
+                    } else if let Some(template_root) = Self::find_template_root(&digest) {
+                        <TemplateRoot<T>>::put(template_root);
+                    } else {
+                        Self::reject_digest_seal(author, &seal, VerifyError::MalformedSeal);
+                    }
+                    weight.saturating_accrue(T::DbWeight::get().reads_writes(5, 7));
+                }
+                None => weight.saturating_accrue(T::DbWeight::get().reads(1)),
+            }
+            weight
+        }
+
+        fn on_finalize(_n: BlockNumberFor<T>) {
+            let Some(template_root) = <TemplateRoot<T>>::take() else {
+                return;
+            };
+            let Some((author, seal)) = Self::find_pre_runtime_seal(&<frame_system::Pallet<T>>::digest()) else {
+                return;
+            };
+            // A seal mined for other transactions than the block carries says
+            // nothing about this block: it is skipped, so the block mines
+            // nothing and adds no work
+            if Self::extrinsics_root().as_ref() != &template_root[..] {
+                log::warn!(target: "rubikpow", "block transactions differ from the template its seal was mined with");
+                Self::deposit_event(Event::TemplateMismatch { author });
+                return;
+            }
+            match Self::check_bound_seal(&seal, &template_root) {
+                Ok(verified) => Self::apply_seal(author, &seal, verified),
+                Err(error) => Self::reject_digest_seal(author, &seal, error),
+            }
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_n: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+            Self::do_try_state()
+        }
+
+        fn offchain_worker(_n: BlockNumberFor<T>) {
+            // Nodes mining to a pool forward the shares their miner left in
+            // offchain storage; nodes without a pool URL do nothing
+            if let Err(e) = offchain::submit_pending_shares::<T>() {
+                log::warn!(target: "rubikpow", "pool share submission failed: {:?}", e);
+            }
+        }
+
+    }
+
+    #[pallet::validate_unsigned]
+    impl<T: Config> ValidateUnsigned for Pallet<T> {
+        type Call = Call<T>;
+
+        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            let Call::submit_solution_unsigned { submission, signature } = call else {
+                return InvalidTransaction::Call.into();
+            };
+
+            // Only the author can have signed the seal over to itself, so a
+            // relay that swaps in its own account invalidates the signature
+            if !SignedPayload::<T>::verify::<T::SubmissionKey>(submission, signature.clone()) {
+                return InvalidTransaction::BadProof.into();
+            }
+            if submission.mined != Self::block_number().saturating_add(1) {
+                return InvalidTransaction::Stale.into();
+            }
+
+            // One unsigned submission per author and block keeps gossip bounded
+            let author = submission.author.clone().into_account();
+            let now = <frame_system::Pallet<T>>::block_number();
+            if Self::last_unsigned_submission(&author) == Some(now) {
+                return InvalidTransaction::ExhaustsResources.into();
+            }
+
+            let seal = PowSeal {
+                cube_size: submission.cube_size,
+                nonce: submission.nonce,
+                moves: submission.moves.clone(),
+            };
+            if let Err(error) = Self::check_proximity(&seal) {
+                return InvalidTransaction::Custom(error.code()).into();
+            }
+
+            ValidTransaction::with_tag_prefix("RubikPow")
+                .and_provides((author, now))
+                .longevity(1)
+                .propagate(true)
+                .build()
+        }
+    }
+
+    /// Values derived while checking a seal, reused when applying it.
+    pub struct VerifiedSeal {
+        pub difficulty: u32,
+        /// The challenge cube before the solution was applied.
+        pub scrambled: Cube,
+        /// Fork-choice work of the seal, from `work_per_seal`.
+        pub work: u128,
+        pub challenge_commitment: [u8; 32],
+        /// Seed the block commits to for the next challenge.
+        pub next_seed: [u8; 32],
     }
 
     impl<T: Config> Pallet<T> {
-        fn calculate_reward(cube_size: u32) -> u32 {
-            // Reward based on cube size and difficulty
-            let base_reward = 1000;
-            base_reward * cube_size
+        /// Full validation of a seal against current state, without writing anything.
+        pub fn check_seal(seal: &PowSeal) -> Result<VerifiedSeal, VerifyError> {
+            Self::check_bound_seal(seal, &[0; 32])
+        }
+
+        /// [`Pallet::check_seal`] for a seal mined for the block whose
+        /// transactions have root `template_root`. Only V2 challenges depend
+        /// on the root.
+        pub fn check_bound_seal(seal: &PowSeal, template_root: &[u8; 32]) -> Result<VerifiedSeal, VerifyError> {
+            let cube_size = seal.cube_size;
+            Self::check_bounds_and_nonce(seal)?;
+
+            // Scramble with the nonce, replay the solution and check the target
+            let block_header = Self::challenge_header_for(template_root);
+            let difficulty = Self::difficulty();
+            let target = Self::calculate_target(difficulty);
+            let (spec, commitment) = Self::current_rules();
+            let rules = Rules {
+                spec,
+                commitment,
+                scramble: Self::scramble_spec(cube_size),
+                policy: T::SolutionPolicy::get().unwrap_or(&()),
+                // Double-checking is for authoring nodes, not consensus
+                double_check: false,
+                min_quality: Self::min_quality(),
+            };
+            let scrambled = verify_seal_with_rules(&rules, seal, &block_header, &target)?;
+
+            let seed = challenge_seed(spec, seal.nonce, &block_header);
+            let commitment = challenge_commitment(spec, &seed, cube_size, &target);
+            Ok(VerifiedSeal {
+                difficulty,
+                scrambled,
+                work: work_per_seal(cube_size, &target),
+                challenge_commitment: commitment,
+                next_seed: next_challenge_seed(spec, &commitment),
+            })
         }
 
-        fn adjust_difficulty(current_difficulty: u32, cube_size: u32) -> u32 {
-            // Difficulty adjustment based on cube size and target block time
-            // This is a simplified implementation
-            let adjustment_factor = (cube_size * 100) / (current_difficulty.max(1));
-            current_difficulty.saturating_add(adjustment_factor)
+        /// Spec and commitment versions in force at mined height `height`.
+        pub fn rules_at(height: u32) -> (SpecVersion, CommitmentVersion) {
+            rules_in(&Self::activation_schedule(), height)
         }
 
-        fn calculate_target_hash(difficulty: u32) -> [u8; 32] {
-            // Calculate the target hash based on the difficulty
-            // This is a simplified implementation
-            let mut target = [0u8; 32];
-            let difficulty_bytes = difficulty.to_le_bytes();
-            target[..4].copy_from_slice(&difficulty_bytes);
-            target
+        fn reject_digest_seal(author: T::AccountId, seal: &PowSeal, error: VerifyError) {
+            Self::index_rejection(&author, seal, error);
+            Self::deposit_event(Event::SolutionRejected { author, reason: error.code() });
         }
 
-        fn get_current_block_header() -> Vec<u8> {
-            // Get the current block header as a byte vector
-            // This is a simplified implementation
-            Self::block_number().to_le_bytes().to_vec()
-        }
-
-        #[pallet::hooks]
-        impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-            fn on_finalize(_n: BlockNumberFor<T>) {
-                // Adjust difficulty every 2016 blocks (similar to Bitcoin)
-                if Self::block_number() % 2016 == 0 {
-                    let current_difficulty = Self::difficulty();
-                    let new_difficulty = Self::adjust_difficulty(current_difficulty, 3); // Using 3 as a default cube size for adjustment
-                    <Difficulty<T>>::put(new_difficulty);
-                    Self::deposit_event(Event::DifficultyAdjustment { new_difficulty });
+        /// Root of the extrinsics applied so far, computed the way
+        /// `frame_system` computes the header's once the block is finalized.
+        fn extrinsics_root() -> T::Hash {
+            let extrinsics = (0..<frame_system::Pallet<T>>::extrinsic_count())
+                .map(<frame_system::Pallet<T>>::extrinsic_data)
+                .collect();
+            frame_system::extrinsics_data_root::<T::Hashing>(extrinsics)
+        }
+
+        /// Rules the seal of the block being mined is checked under.
+        pub fn current_rules() -> (SpecVersion, CommitmentVersion) {
+            Self::rules_at(Self::block_number().saturating_add(1))
+        }
+
+        fn check_bounds_and_nonce(seal: &PowSeal) -> Result<(), VerifyError> {
+            check_seal_policy(seal, Self::last_nonce())
+        }
+
+        /// Gossip pre-filter: the challenge commitment must be within
+        /// `ProximityFactor` of the target. The moves are only replayed when the
+        /// call is dispatched.
+        fn check_proximity(seal: &PowSeal) -> Result<(), VerifyError> {
+            Self::check_bounds_and_nonce(seal)?;
+
+            let (spec, commitment) = Self::current_rules();
+            let mut cube = Cube::try_new(seal.cube_size as usize)?;
+            let scramble = Self::scramble_spec(seal.cube_size);
+            cube.scramble_with(spec, &scramble, seal.nonce, &Self::get_current_block_header());
+
+            let target = Self::calculate_target(Self::difficulty());
+            let relaxed = target.saturating_mul(T::ProximityFactor::get());
+            ensure!(relaxed.is_met_by(&cube.state_commitment_with(commitment)), VerifyError::TargetNotMet);
+            Ok(())
+        }
+
+        /// Dry run of [`Pallet::check_seal`] on an encoded seal, for the
+        /// `rubikpow_checkSeal` RPC. A nonce that was already used (including a
+        /// resubmitted duplicate) is reported as `StaleChallenge`.
+        pub fn check_encoded_seal(seal: &[u8]) -> Result<(), VerifyError> {
+            let seal = PowSeal::try_decode(seal)?;
+            Self::check_seal(&seal).map(|_| ())
+        }
+
+        /// Weight of checking and applying one seal, as predicted by
+        /// `estimate_verification_weight`.
+        pub fn seal_weight(cube_size: u32, n_moves: usize) -> Weight {
+            Self::hint_weight(estimate_verification_weight(cube_size, n_moves))
+                .saturating_add(T::OnSealAccepted::weight(cube_size, n_moves))
+        }
+
+        /// Weight of `submit_solutions_batch`: that of each of its seals.
+        pub fn batch_weight(seals: &[Submission]) -> Weight {
+            seals.iter().fold(Weight::zero(), |weight, seal| {
+                weight.saturating_add(Self::seal_weight(seal.cube_size, seal.moves.len()))
+            })
+        }
+
+        fn hint_weight(hint: WeightHint) -> Weight {
+            Weight::from_parts(hint.ref_time, 0).saturating_add(T::DbWeight::get().writes(hint.writes))
+        }
+
+        fn apply_seal(submitter: T::AccountId, seal: &PowSeal, verified: VerifiedSeal) {
+            // Delegated submission keys mine on behalf of their solver
+            let who = Self::delegator_of(&submitter).unwrap_or_else(|| submitter.clone());
+            let cube_size = seal.cube_size;
+            <LastNonce<T>>::put(seal.nonce);
+
+            // Commit to the solved challenge so light clients can check the seal
+            // against it, and to the seed of the next one
+            Self::record_challenge_commitment(verified.challenge_commitment, verified.next_seed);
+
+            let quality = Self::solution_metric().length(&seal.moves, cube_size as usize);
+            let now = <frame_system::Pallet<T>>::block_number();
+            let reward = Self::calculate_reward(cube_size, verified.difficulty, quality, now);
+            let mined = Self::block_number().saturating_add(One::one());
+            <BlockNumber<T>>::put(mined);
+            <TotalWork<T>>::mutate(|total| *total = total.saturating_add(verified.work));
+
+            Self::deposit_event(Event::BlockMined { miner: who.clone(), cube_size });
+            let payee = Self::payout_account_of(&who).unwrap_or_else(|| who.clone());
+            let _ = T::Currency::deposit_creating(&payee, reward.into());
+            Self::index_submission(&submitter, seal, mined, SubmissionOutcome::Mined { payee: payee.clone(), reward });
+            Self::deposit_event(Event::Reward { miner: who.clone(), payee, amount: reward });
+            Self::record_miner_stats(&who, seal, verified.work, mined);
+            T::OnSealAccepted::on_seal_accepted(&who, seal, &verified.scrambled);
+            T::OnSolutionAccepted::on_solution_accepted(&who, cube_size, quality, &verified.challenge_commitment);
+
+            Self::maybe_retarget(mined, verified.difficulty);
+        }
+
+        /// Records a seal `author` submitted for the next block that was
+        /// turned down.
+        fn index_rejection(author: &T::AccountId, seal: &PowSeal, error: VerifyError) {
+            let mined = Self::block_number().saturating_add(1);
+            Self::index_submission(author, seal, mined, SubmissionOutcome::Rejected { reason: error.code() });
+        }
+
+        fn index_submission(
+            author: &T::AccountId,
+            seal: &PowSeal,
+            mined: u32,
+            outcome: SubmissionOutcome<T::AccountId>,
+        ) {
+            let record = SubmissionRecord {
+                parent_hash: <frame_system::Pallet<T>>::parent_hash(),
+                cube_size: seal.cube_size,
+                nonce: seal.nonce,
+                outcome,
+            };
+            offchain::index_submission(author, mined, &record);
+        }
+
+        /// Updates `who`'s profile, making room by dropping the least recently
+        /// active account if `who` is not tracked yet.
+        fn record_miner_stats(who: &T::AccountId, seal: &PowSeal, work: u128, mined: u32) {
+            let tracked = <TrackedMiners<T>>::mutate(|tracked| {
+                if let Some(position) = tracked.iter().position(|miner| miner == who) {
+                    tracked.remove(position);
+                } else if tracked.is_full() && !tracked.is_empty() {
+                    let evicted = tracked.remove(0);
+                    <MinerProfiles<T>>::remove(&evicted);
                 }
+                tracked.try_push(who.clone()).is_ok()
+            });
+            if !tracked {
+                return;
             }
+
+            let length = Self::solution_length(seal);
+            let efficiency = work / u128::from(length.max(1));
+            <MinerProfiles<T>>::mutate(who, |profile| {
+                let profile = profile.get_or_insert_with(MinerProfile::default);
+                profile.blocks_mined = profile.blocks_mined.saturating_add(1);
+                profile.total_solution_length = profile.total_solution_length.saturating_add(u64::from(length));
+                profile.best_efficiency = profile.best_efficiency.max(efficiency);
+                profile.last_mined = mined;
+            });
+        }
+
+        /// Retargets once every `RetargetInterval` mined blocks, scaling the
+        /// difficulty by how long the interval took against `TargetBlockTime`.
+        fn maybe_retarget(mined: u32, difficulty: u32) {
+            let now = T::UnixTime::now().as_millis() as u64;
+            if mined == 1 {
+                // The first interval starts with the first mined block
+                <RetargetIntervalStart<T>>::put(now);
+            }
+            let Some(new_difficulty) = retarget_due(
+                mined,
+                difficulty,
+                Self::retarget_interval_start(),
+                now,
+                T::RetargetInterval::get(),
+                T::TargetBlockTime::get(),
+            ) else {
+                return;
+            };
+
+            <Difficulty<T>>::put(new_difficulty);
+            <RetargetIntervalStart<T>>::put(now);
+            Self::record_retarget(new_difficulty);
+            Self::deposit_event(Event::DifficultyAdjustment { new_difficulty });
+        }
+
+        /// Number of `REFERENCE_SIZE` blocks' worth of work, at the current
+        /// difficulty, accumulated on top of `block_hash`. `None` if the block
+        /// is unknown or too old.
+        pub fn confirmations(block_hash: T::Hash) -> Option<u32> {
+            let (_, work_at_block) = <WorkAt<T>>::get(block_hash)?;
+            let work_since = Self::total_work().saturating_sub(work_at_block);
+            let target = Self::calculate_target(Self::difficulty());
+            let per_block = work_per_seal(REFERENCE_SIZE, &target).max(1);
+            Some((work_since / per_block).min(u128::from(u32::MAX)) as u32)
+        }
+
+        pub fn is_confirmed(block_hash: T::Hash, depth: u32) -> bool {
+            Self::confirmations(block_hash).map_or(false, |confirmations| confirmations >= depth)
+        }
+
+        /// Stores the parent's accumulated work (its hash is only known once the
+        /// child starts executing) and prunes the entry that fell out of history.
+        fn record_parent_work(n: BlockNumberFor<T>) -> Weight {
+            if n.is_zero() {
+                return Weight::zero();
+            }
+            let parent_number = n.saturating_sub(One::one());
+            let parent_hash = <frame_system::Pallet<T>>::parent_hash();
+            <WorkAt<T>>::insert(parent_hash, (parent_number, Self::total_work()));
+
+            let history: BlockNumberFor<T> = T::ConfirmationHistory::get().into();
+            if parent_number >= history {
+                let expired = <frame_system::Pallet<T>>::block_hash(parent_number - history);
+                <WorkAt<T>>::remove(expired);
+            }
+            T::DbWeight::get().reads_writes(3, 2)
+        }
+
+        /// `Seal` digest item carrying the encoded seal, appended by the block author.
+        pub fn seal_digest(seal: &PowSeal) -> DigestItem {
+            DigestItem::Seal(RUBIKPOW_ENGINE_ID, seal.encode())
+        }
+
+        /// `PreRuntime` digest item carrying the author followed by the encoded seal,
+        /// so the runtime can credit the author while executing the block.
+        pub fn pre_runtime_digest(author: &T::AccountId, seal: &PowSeal) -> DigestItem {
+            let mut data = author.encode();
+            data.extend(seal.encode());
+            DigestItem::PreRuntime(RUBIKPOW_ENGINE_ID, data)
+        }
+
+        pub fn find_seal(digest: &Digest) -> Option<PowSeal> {
+            digest.logs().iter().find_map(|item| match item {
+                DigestItem::Seal(id, data) if *id == RUBIKPOW_ENGINE_ID => PowSeal::decode(data),
+                _ => None,
+            })
+        }
+
+        pub fn find_pre_runtime_seal(digest: &Digest) -> Option<(T::AccountId, PowSeal)> {
+            digest.logs().iter().find_map(|item| match item {
+                DigestItem::PreRuntime(id, data) if *id == RUBIKPOW_ENGINE_ID => {
+                    let mut input = &data[..];
+                    let author = T::AccountId::decode(&mut input).ok()?;
+                    Some((author, PowSeal::decode(input)?))
+                }
+                _ => None,
+            })
+        }
+
+        /// `PreRuntime` digest item carrying the root of the transactions the
+        /// block's seal was mined with.
+        pub fn template_digest(template_root: &[u8; 32]) -> DigestItem {
+            DigestItem::PreRuntime(TEMPLATE_ENGINE_ID, template_root.to_vec())
+        }
+
+        pub fn find_template_root(digest: &Digest) -> Option<[u8; 32]> {
+            digest.logs().iter().find_map(|item| match item {
+                DigestItem::PreRuntime(id, data) if *id == TEMPLATE_ENGINE_ID => <[u8; 32]>::try_from(&data[..]).ok(),
+                _ => None,
+            })
+        }
+
+        /// Header bytes and lowest nonce of the challenges a seal built on the
+        /// current state can solve.
+        pub fn preview_challenge() -> (Vec<u8>, u64) {
+            (Self::get_current_block_header(), Self::last_nonce().saturating_add(1))
+        }
+
+        /// Length of `seal`'s solution in the current [`SolutionMetric`].
+        pub fn solution_length(seal: &PowSeal) -> u32 {
+            Self::solution_metric().length(&seal.moves, seal.cube_size as usize)
+        }
+
+        /// Reward a seal for `cube_size` whose solution is `solution_len` long in
+        /// the current metric would earn if included at `at_block` at the
+        /// current difficulty, or `None` if the size would be rejected. This is
+        /// what `apply_seal` pays.
+        pub fn estimate_reward(cube_size: u32, solution_len: u32, at_block: BlockNumberFor<T>) -> Option<u32> {
+            if !(MIN_CUBE_SIZE..=MAX_CUBE_SIZE).contains(&cube_size) {
+                return None;
+            }
+            Some(Self::calculate_reward(cube_size, Self::difficulty(), solution_len, at_block))
+        }
+
+        /// Expected time for a miner making `attempts_per_second` attempts on
+        /// `cube_size` challenges to find a block at the current difficulty, and
+        /// what it would earn at the current emission with solutions as long
+        /// as the longest scramble. `None` for a zero rate or a rejected size.
+        pub fn estimate_time_to_block(cube_size: u32, attempts_per_second: u64) -> Option<TimeToBlock> {
+            if !(MIN_CUBE_SIZE..=MAX_CUBE_SIZE).contains(&cube_size) {
+                return None;
+            }
+            let difficulty = Self::difficulty();
+            let target = Self::calculate_target(difficulty);
+            let base = emission(BASE_REWARD, <frame_system::Pallet<T>>::block_number().saturated_into());
+            Some(TimeToBlock {
+                difficulty,
+                expected_millis: expected_time_ms(&target, attempts_per_second)?,
+                reward: scale_reward(base, cube_size, &target),
+                reward_per_day: expected_reward_per_day(base, cube_size, &target, attempts_per_second),
+            })
+        }
+
+        /// Current state as an [`ImportSnapshot`].
+        #[cfg(feature = "std")]
+        pub fn import_snapshot() -> ImportSnapshot {
+            ImportSnapshot {
+                mined: Self::block_number(),
+                difficulty: Self::difficulty(),
+                last_nonce: Self::last_nonce(),
+                retarget_interval_start: Self::retarget_interval_start(),
+                now: T::UnixTime::now().as_millis() as u64,
+                retarget_interval: T::RetargetInterval::get(),
+                target_block_time: T::TargetBlockTime::get(),
+                committed_seed: Self::committed_seed(),
+                scramble_specs: <ScrambleSpecs<T>>::iter().collect(),
+                min_quality: Self::min_quality(),
+                activations: Self::activation_schedule().into_inner(),
+                policy: SnapshotPolicy(T::SolutionPolicy::get()),
+            }
+        }
+
+        /// Storage invariants, checked by try-runtime and after every step of
+        /// the pallet's fuzz tests.
+        #[cfg(any(feature = "try-runtime", test))]
+        pub fn do_try_state() -> Result<(), sp_runtime::TryRuntimeError> {
+            // Nonces start above zero, so a mined block always leaves one behind
+            ensure!(
+                (Self::block_number() == 0) == (Self::last_nonce() == 0),
+                "LastNonce and the mined block counter disagree"
+            );
+            ensure!(
+                (Self::block_number() == 0) == (Self::committed_seed() == [0; 32]),
+                "CommittedSeed and the mined block counter disagree"
+            );
+
+            let tracked = Self::tracked_miners();
+            ensure!(
+                tracked.iter().all(|miner| <MinerProfiles<T>>::contains_key(miner)),
+                "TrackedMiners holds an account without a profile"
+            );
+            ensure!(
+                <MinerProfiles<T>>::iter_keys().count() == tracked.len(),
+                "MinerProfiles holds an untracked account"
+            );
+            ensure!(
+                tracked.windows(2).all(|pair| {
+                    let last_mined =
+                        |miner: &T::AccountId| Self::miner_profile(miner).map(|profile| profile.last_mined);
+                    last_mined(&pair[0]) < last_mined(&pair[1])
+                }),
+                "TrackedMiners is not ordered by last mined block"
+            );
+
+            let history = Self::difficulty_history();
+            ensure!(
+                history.windows(2).all(|pair| pair[0].block_number <= pair[1].block_number),
+                "DifficultyHistory is not ordered by block number"
+            );
+            for point in history.iter() {
+                ensure!(point.difficulty > 0, "DifficultyHistory holds a zero difficulty");
+                ensure!(
+                    point.target == *Self::calculate_target(point.difficulty).as_bytes(),
+                    "DifficultyHistory target does not match its difficulty"
+                );
+            }
+            if let Some(last) = history.last() {
+                ensure!(
+                    last.difficulty == Self::difficulty(),
+                    "Difficulty changed without a retarget point"
+                );
+            }
+
+            ensure!(
+                Self::retarget_interval_start() <= T::UnixTime::now().as_millis() as u64,
+                "retarget interval starts in the future"
+            );
+            Ok(())
+        }
+
+        /// Appends a retarget point, dropping the oldest once the history is full.
+        fn record_retarget(difficulty: u32) {
+            let now = <frame_system::Pallet<T>>::block_number();
+            <DifficultyHistory<T>>::mutate(|history| {
+                let observed_interval =
+                    history.last().map_or(Zero::zero(), |last| now.saturating_sub(last.block_number));
+                if history.is_full() && !history.is_empty() {
+                    history.remove(0);
+                }
+                let _ = history.try_push(RetargetPoint {
+                    block_number: now,
+                    difficulty,
+                    target: *Self::calculate_target(difficulty).as_bytes(),
+                    observed_interval,
+                });
+            });
+        }
+
+        /// Reward of a seal for `cube_size` whose solution is `solution_len`
+        /// long in the current metric, included at `height`.
+        fn calculate_reward(cube_size: u32, difficulty: u32, solution_len: u32, height: BlockNumberFor<T>) -> u32 {
+            // Emission at the height, the same size scaling the miner ranks
+            // templates by, then the solution's efficiency against the
+            // longest scramble of its size
+            let base = emission(BASE_REWARD, height.saturated_into());
+            let reward = scale_reward(base, cube_size, &Self::calculate_target(difficulty));
+            efficiency_reward(reward, solution_len, Self::scramble_spec(cube_size).length.1)
+        }
+
+        fn calculate_target(difficulty: u32) -> Target {
+            // Same big-endian target rule as every off-chain verifier
+            Target::from_difficulty(difficulty)
+        }
+
+        /// Logs the challenge commitment followed by the next seed, so the
+        /// first 32 bytes of the log stay the commitment.
+        fn record_challenge_commitment(commitment: [u8; 32], next_seed: [u8; 32]) {
+            let now = <frame_system::Pallet<T>>::block_number();
+            <ChallengeCommitments<T>>::insert(now, commitment);
+            <CommittedSeed<T>>::put(next_seed);
+            let mut log = commitment.to_vec();
+            log.extend_from_slice(&next_seed);
+            <frame_system::Pallet<T>>::deposit_log(DigestItem::Consensus(RUBIKPOW_ENGINE_ID, log));
+        }
+
+        /// The mined count and the seed the last mined block committed to.
+        fn get_current_block_header() -> Vec<u8> {
+            Self::challenge_header_for(&[0; 32])
+        }
+
+        /// Header the challenge of the block being mined is derived from, for
+        /// a block whose transactions have root `template_root`. Authors
+        /// mining under V2 rules build their templates from it.
+        pub fn challenge_header_for(template_root: &[u8; 32]) -> Vec<u8> {
+            let (spec, _) = Self::current_rules();
+            challenge_header(spec, Self::block_number(), &Self::committed_seed(), template_root)
         }
     }
-}
\ No newline at end of file
+}
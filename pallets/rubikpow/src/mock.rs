@@ -0,0 +1,160 @@
+use crate as pallet_rubikpow;
+use core::cell::RefCell;
+use frame_support::traits::{ConstU16, ConstU32, ConstU64, Everything, Get};
+use qbitcoin_core::policy::SolutionPolicy;
+use sp_core::{sr25519::Signature, H256};
+use sp_runtime::{
+    traits::{BlakeTwo256, IdentifyAccount, IdentityLookup, Verify},
+    BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        Balances: pallet_balances,
+        Timestamp: pallet_timestamp,
+        RubikPow: pallet_rubikpow,
+    }
+);
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Block = Block;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ConstU16<42>;
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+impl frame_system::offchain::SigningTypes for Test {
+    type Public = <Signature as Verify>::Signer;
+    type Signature = Signature;
+}
+
+impl pallet_balances::Config for Test {
+    type MaxLocks = ();
+    type MaxReserves = ();
+    type ReserveIdentifier = [u8; 8];
+    type Balance = u64;
+    type RuntimeEvent = RuntimeEvent;
+    type DustRemoval = ();
+    type ExistentialDeposit = ConstU64<1>;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type FreezeIdentifier = ();
+    type MaxFreezes = ();
+    type RuntimeHoldReason = ();
+    type MaxHolds = ();
+}
+
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ConstU64<1>;
+    type WeightInfo = ();
+}
+
+pub const TARGET_BLOCK_TIME_MS: u64 = 6_000;
+pub const RETARGET_INTERVAL: u32 = 10;
+
+impl pallet_rubikpow::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type ConfirmationHistory = ConstU32<100>;
+    type ProximityFactor = ConstU32<4>;
+    type DifficultyHistoryLength = ConstU32<16>;
+    type UnixTime = Timestamp;
+    type TargetBlockTime = ConstU64<TARGET_BLOCK_TIME_MS>;
+    type RetargetInterval = ConstU32<RETARGET_INTERVAL>;
+    type PoolShareKey = crate::offchain::crypto::PoolShareId;
+    type SubmissionKey = crate::offchain::crypto::PoolShareId;
+    type PowPayableCalls = PowPayableCalls;
+    type PowFeeDifficulty = ConstU32<4>;
+    type OnSealAccepted = ();
+    type OnSolutionAccepted = AcceptedSolutions;
+    type MaxActivations = ConstU32<4>;
+    type SolutionPolicy = TestPolicy;
+    type MaxTrackedMiners = ConstU32<2>;
+    type MaxBatchSize = ConstU32<4>;
+}
+
+thread_local! {
+    static POLICY: RefCell<Option<&'static dyn SolutionPolicy>> = RefCell::new(None);
+    static ACCEPTED: RefCell<Vec<(AccountId, u32, u32, [u8; 32])>> = RefCell::new(Vec::new());
+}
+
+/// Records every accepted solution for tests to read with `take`.
+pub struct AcceptedSolutions;
+
+impl AcceptedSolutions {
+    /// The solutions accepted since the last call, oldest first.
+    pub fn take() -> Vec<(AccountId, u32, u32, [u8; 32])> {
+        ACCEPTED.with(|accepted| accepted.take())
+    }
+}
+
+impl pallet_rubikpow::OnSolutionAccepted<AccountId> for AcceptedSolutions {
+    fn on_solution_accepted(miner: &AccountId, cube_size: u32, quality: u32, commitment: &[u8; 32]) {
+        ACCEPTED.with(|accepted| accepted.borrow_mut().push((miner.clone(), cube_size, quality, *commitment)));
+    }
+}
+
+/// Solution policy tests can swap with `set_policy`; none by default.
+pub struct TestPolicy;
+
+impl TestPolicy {
+    pub fn set_policy(policy: Option<&'static dyn SolutionPolicy>) {
+        POLICY.with(|current| *current.borrow_mut() = policy);
+    }
+}
+
+impl Get<Option<&'static dyn SolutionPolicy>> for TestPolicy {
+    fn get() -> Option<&'static dyn SolutionPolicy> {
+        POLICY.with(|current| *current.borrow())
+    }
+}
+
+/// Only delegation management can be paid for with a cube solution.
+pub struct PowPayableCalls;
+
+impl frame_support::traits::Contains<RuntimeCall> for PowPayableCalls {
+    fn contains(call: &RuntimeCall) -> bool {
+        matches!(
+            call,
+            RuntimeCall::RubikPow(pallet_rubikpow::Call::add_delegate { .. })
+                | RuntimeCall::RubikPow(pallet_rubikpow::Call::accept_delegate { .. })
+                | RuntimeCall::RubikPow(pallet_rubikpow::Call::remove_delegate { .. })
+        )
+    }
+}
+
+pub fn account(seed: u8) -> AccountId {
+    sp_core::sr25519::Public::from_raw([seed; 32])
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let storage = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
@@ -0,0 +1,205 @@
+//! Offchain-worker forwarding of pool shares, and the offchain index of
+//! submissions.
+//!
+//! The local miner appends encoded `PowSeal`s that meet the pool's share
+//! target to the [`PENDING_SHARES_KEY`] offchain storage entry. Each block the
+//! offchain worker posts them, signed with the node's `rbkp` key, to the URL
+//! stored under [`POOL_URL_KEY`]. Shares stay pending until the pool
+//! acknowledges them, so a failed post is retried on the next block. Only the
+//! shares a post carried are then removed: the miner keeps appending while the
+//! request is in flight.
+//!
+//! On nodes started with offchain indexing enabled, every block also records
+//! the seals it mined or rejected in the node's offchain storage, one
+//! [`SubmissionRecord`] under [`submission_key`] per author and mined height,
+//! so miners can reconcile payouts against their own node. Seals in failed
+//! extrinsics are not recorded: their offchain writes are discarded along
+//! with their storage changes. Blocks on every imported fork write records,
+//! and a later fork at the same height overwrites an earlier one's.
+
+use codec::{Decode, Encode};
+use frame_system::offchain::{SignMessage, Signer};
+use sp_runtime::{
+    offchain::{
+        http,
+        storage::{MutateStorageError, StorageValueRef},
+        Duration,
+    },
+    RuntimeDebug,
+};
+use sp_std::{vec, vec::Vec};
+
+use crate::Config;
+
+/// Offchain storage key holding the pool endpoint as UTF-8.
+pub const POOL_URL_KEY: &[u8] = b"rubikpow::pool-url";
+/// Offchain storage key holding the SCALE-encoded `Vec<Vec<u8>>` of pending shares.
+pub const PENDING_SHARES_KEY: &[u8] = b"rubikpow::pending-shares";
+/// Prefix of the offchain index keys of submissions.
+pub const SUBMISSION_INDEX_PREFIX: &[u8] = b"rubikpow::submission::";
+
+const HTTP_TIMEOUT_MS: u64 = 5_000;
+/// Attempts at removing posted shares while the miner keeps appending.
+const DRAIN_ATTEMPTS: u32 = 8;
+
+pub mod crypto {
+    use sp_core::sr25519::Signature as Sr25519Signature;
+    use sp_runtime::{
+        app_crypto::{app_crypto, sr25519},
+        traits::Verify,
+        KeyTypeId, MultiSignature, MultiSigner,
+    };
+
+    pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"rbkp");
+
+    app_crypto!(sr25519, KEY_TYPE);
+
+    pub struct PoolShareId;
+
+    impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for PoolShareId {
+        type RuntimeAppPublic = Public;
+        type GenericSignature = sp_core::sr25519::Signature;
+        type GenericPublic = sp_core::sr25519::Public;
+    }
+
+    impl frame_system::offchain::AppCrypto<<Sr25519Signature as Verify>::Signer, Sr25519Signature>
+        for PoolShareId
+    {
+        type RuntimeAppPublic = Public;
+        type GenericSignature = sp_core::sr25519::Signature;
+        type GenericPublic = sp_core::sr25519::Public;
+    }
+}
+
+/// What became of a seal, as seen by the block that processed it.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum SubmissionOutcome<AccountId> {
+    /// The seal mined the block; `payee` was credited `reward`.
+    Mined { payee: AccountId, reward: u32 },
+    /// `reason` is a `VerifyError` code.
+    Rejected { reason: u8 },
+}
+
+/// One seal an author submitted, as kept in the offchain index.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct SubmissionRecord<AccountId, Hash> {
+    /// Parent of the block that processed the seal; whether that block is
+    /// still canonical can only be told from the node's view of the chain.
+    pub parent_hash: Hash,
+    pub cube_size: u32,
+    pub nonce: u64,
+    pub outcome: SubmissionOutcome<AccountId>,
+}
+
+/// Offchain index key of `author`'s seal for the `mined`th block
+/// (`BlockNumber`).
+pub fn submission_key<AccountId: Encode>(author: &AccountId, mined: u32) -> Vec<u8> {
+    let mut key = SUBMISSION_INDEX_PREFIX.to_vec();
+    author.encode_to(&mut key);
+    key.extend_from_slice(&mined.to_le_bytes());
+    key
+}
+
+/// Records `record` in the offchain index; a no-op on nodes without offchain
+/// indexing.
+pub fn index_submission<AccountId: Encode, Hash: Encode>(
+    author: &AccountId,
+    mined: u32,
+    record: &SubmissionRecord<AccountId, Hash>,
+) {
+    sp_io::offchain_index::set(&submission_key(author, mined), &record.encode());
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ShareError {
+    NoSigningKey,
+    Http,
+    /// The pool answered with a non-2xx status.
+    Rejected(u16),
+    /// The pool took the shares but they could not be removed from the
+    /// pending ones, which kept changing; they are posted again.
+    Storage,
+}
+
+/// Body posted to the pool: the encoded shares, the signer's public key and
+/// its signature over the shares.
+#[derive(Encode, Decode)]
+pub struct ShareSubmission<Public, Signature> {
+    pub shares: Vec<Vec<u8>>,
+    pub public: Public,
+    pub signature: Signature,
+}
+
+/// Posts all pending shares; a no-op when no pool URL is configured or
+/// nothing is pending.
+pub fn submit_pending_shares<T: Config>() -> Result<(), ShareError> {
+    post_pending_shares(|shares| {
+        let signer = Signer::<T, T::PoolShareKey>::any_account();
+        let (account, signature) = signer
+            .sign_message(&shares.encode())
+            .ok_or(ShareError::NoSigningKey)?;
+        Ok(ShareSubmission {
+            shares: shares.to_vec(),
+            public: account.public,
+            signature,
+        }
+        .encode())
+    })
+}
+
+/// Posts the pending shares as the body `encode` makes of them and, once the
+/// pool acknowledges them, removes them from the pending ones.
+pub(crate) fn post_pending_shares(
+    encode: impl FnOnce(&[Vec<u8>]) -> Result<Vec<u8>, ShareError>,
+) -> Result<(), ShareError> {
+    let url = match StorageValueRef::persistent(POOL_URL_KEY).get::<Vec<u8>>() {
+        Ok(Some(url)) => url,
+        _ => return Ok(()),
+    };
+    let shares: Vec<Vec<u8>> = StorageValueRef::persistent(PENDING_SHARES_KEY).get().ok().flatten().unwrap_or_default();
+    if shares.is_empty() {
+        return Ok(());
+    }
+    let body = encode(&shares)?;
+
+    let url = core::str::from_utf8(&url).map_err(|_| ShareError::Http)?;
+    let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(HTTP_TIMEOUT_MS));
+    let request = http::Request::post(url, vec![body])
+        .add_header("Content-Type", "application/octet-stream")
+        .deadline(deadline)
+        .send()
+        .map_err(|_| ShareError::Http)?;
+    let response = request
+        .try_wait(deadline)
+        .map_err(|_| ShareError::Http)?
+        .map_err(|_| ShareError::Http)?;
+    if !(200..300).contains(&response.code) {
+        return Err(ShareError::Rejected(response.code));
+    }
+
+    forget_shares(&shares)
+}
+
+/// Removes one pending copy of each of `posted`, keeping shares appended
+/// since they were read. Retries when an append lands between reading and
+/// writing the entry.
+fn forget_shares(posted: &[Vec<u8>]) -> Result<(), ShareError> {
+    let pending = StorageValueRef::persistent(PENDING_SHARES_KEY);
+    for _ in 0..DRAIN_ATTEMPTS {
+        let drained = pending.mutate(|current: Result<Option<Vec<Vec<u8>>>, _>| {
+            let mut current = current.ok().flatten().unwrap_or_default();
+            for share in posted {
+                if let Some(index) = current.iter().position(|pending| pending == share) {
+                    current.remove(index);
+                }
+            }
+            Ok::<_, ()>(current)
+        });
+        match drained {
+            Ok(_) => return Ok(()),
+            Err(MutateStorageError::ConcurrentModification(_)) => continue,
+            Err(MutateStorageError::ValueFunctionFailed(())) => break,
+        }
+    }
+    Err(ShareError::Storage)
+}
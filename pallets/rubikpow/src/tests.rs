@@ -0,0 +1,1410 @@
+use crate::offchain::{
+    post_pending_shares, submission_key, ShareError, SubmissionOutcome, SubmissionRecord, PENDING_SHARES_KEY, POOL_URL_KEY,
+};
+use crate::{mock::*, Activation, Error, Event, Submission};
+use frame_support::storage::{with_transaction, TransactionOutcome};
+use frame_support::{assert_noop, assert_ok, BoundedVec};
+use sp_runtime::DispatchError;
+use qbitcoin_core::miner::{attempt, attempt_with_scramble, BlockTemplate};
+use qbitcoin_core::solver::ReverseScramble;
+use qbitcoin_core::policy::{Blacklist, MinQuality};
+use qbitcoin_core::{
+    spec, verify_seal_with_versions, CommitmentVersion, Metric, Move, PowSeal, ScrambleSpec, SpecVersion, Target,
+    VerifyError,
+};
+
+const CUBE_SIZE: u32 = 2;
+
+/// Grinds nonces until one yields a seal meeting the current target.
+fn mine() -> PowSeal {
+    let template = BlockTemplate {
+        parent_hash: [0; 32],
+        header: RubikPow::preview_challenge().0,
+        cube_size: CUBE_SIZE,
+        target: Target::from_difficulty(RubikPow::difficulty()),
+    };
+    (RubikPow::last_nonce() + 1..)
+        .find_map(|nonce| attempt(&ReverseScramble, &template, nonce))
+        .unwrap()
+}
+
+/// Grinds nonces until one yields a seal whose state meets the current target
+/// under `commitment` but not under `other`.
+fn mine_only_under(commitment: CommitmentVersion, other: CommitmentVersion) -> PowSeal {
+    let header = RubikPow::preview_challenge().0;
+    let target = Target::from_difficulty(RubikPow::difficulty());
+    let template = BlockTemplate { parent_hash: [0; 32], header: header.clone(), cube_size: CUBE_SIZE, target: Target::MAX };
+    let meets = |seal: &PowSeal, version| verify_seal_with_versions(SpecVersion::V1, version, seal, &header, &target).is_ok();
+    (RubikPow::last_nonce() + 1..)
+        .filter_map(|nonce| attempt(&ReverseScramble, &template, nonce))
+        .find(|seal| meets(seal, commitment) && !meets(seal, other))
+        .unwrap()
+}
+
+fn submit(seal: PowSeal) {
+    assert_ok!(RubikPow::submit_solution(
+        RuntimeOrigin::signed(account(1)),
+        seal.cube_size,
+        seal.moves,
+        seal.nonce
+    ));
+}
+
+#[test]
+fn difficulty_converges_to_target_block_time() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(RubikPow::set_difficulty(RuntimeOrigin::root(), 20));
+
+        // The network solves 50 challenges per second at difficulty 1, so the
+        // block time is `difficulty / 50` seconds and the steady state is 300.
+        let solve_rate = 50;
+        let mut now = 1_000_000u64;
+        let mut block_times = Vec::new();
+
+        for block in 2..=400u64 {
+            let block_time = u64::from(RubikPow::difficulty()) * 1000 / solve_rate;
+            now += block_time;
+            block_times.push(block_time);
+            System::set_block_number(block);
+            Timestamp::set_timestamp(now);
+            submit(mine());
+        }
+
+        let tail = &block_times[block_times.len() - 100..];
+        let mean = tail.iter().sum::<u64>() / tail.len() as u64;
+        assert!(mean.abs_diff(TARGET_BLOCK_TIME_MS) < TARGET_BLOCK_TIME_MS / 10, "mean block time {}", mean);
+
+        // No oscillation: the last retargets stay within 10% of each other
+        let history = RubikPow::difficulty_history();
+        let recent: Vec<u32> = history.iter().rev().take(8).map(|point| point.difficulty).collect();
+        let (min, max) = (*recent.iter().min().unwrap(), *recent.iter().max().unwrap());
+        assert!(max - min <= max / 10, "recent difficulties {:?}", recent);
+    });
+}
+
+#[test]
+fn retarget_emits_event_once_per_interval() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(RubikPow::set_difficulty(RuntimeOrigin::root(), 5));
+        System::reset_events();
+
+        for block in 2..2 + u64::from(RETARGET_INTERVAL) {
+            System::set_block_number(block);
+            Timestamp::set_timestamp(block * TARGET_BLOCK_TIME_MS / 2);
+            submit(mine());
+        }
+
+        let adjustments = System::events()
+            .into_iter()
+            .filter(|record| {
+                matches!(record.event, RuntimeEvent::RubikPow(Event::DifficultyAdjustment { .. }))
+            })
+            .count();
+        assert_eq!(adjustments, 1);
+        // Blocks came twice as fast as targeted
+        assert_eq!(RubikPow::difficulty(), 10);
+    });
+}
+
+#[test]
+fn time_to_block_estimate_follows_difficulty() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(RubikPow::set_difficulty(RuntimeOrigin::root(), 300));
+
+        let estimate = RubikPow::estimate_time_to_block(3, 50).unwrap();
+        assert_eq!(estimate.difficulty, 300);
+        assert_eq!(estimate.expected_millis, 6_000);
+        // Time-to-block estimates assume solutions as long as the longest scramble
+        assert_eq!(Some(estimate.reward), RubikPow::estimate_reward(3, ScrambleSpec::V1.length.1, System::block_number()));
+        assert_eq!(estimate.reward_per_day, 14_400 * u128::from(estimate.reward));
+
+        assert_ok!(RubikPow::set_difficulty(RuntimeOrigin::root(), 600));
+        assert_eq!(RubikPow::estimate_time_to_block(3, 50).unwrap().expected_millis, 12_000);
+
+        assert_eq!(RubikPow::estimate_time_to_block(3, 0), None);
+        assert_eq!(RubikPow::estimate_time_to_block(1, 50), None);
+    });
+}
+
+#[test]
+fn parallel_import_matches_dispatch_order() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(RubikPow::set_difficulty(RuntimeOrigin::root(), 1));
+        Timestamp::set_timestamp(1_000);
+        // The third challenge is the first under V2, whose headers are longer
+        let activation = Activation { height: 3, spec: SpecVersion::V2, commitment: CommitmentVersion::CURRENT };
+        assert_ok!(RubikPow::schedule_activation(RuntimeOrigin::root(), activation));
+
+        // Seals for the next three challenges, a duplicate of the first
+        // challenge and a replayed nonce, in one block
+        let seal_for = |header: &[u8], nonce: u64| {
+            let template = BlockTemplate {
+                parent_hash: [0; 32],
+                header: header.to_vec(),
+                cube_size: CUBE_SIZE,
+                target: Target::MAX,
+            };
+            attempt(&ReverseScramble, &template, nonce).unwrap()
+        };
+        // Each challenge's header carries the seed the seal before committed to
+        let after = |mined: u32, header: &[u8], seal: &PowSeal, next: SpecVersion| {
+            let seed = spec::challenge_seed(SpecVersion::V1, seal.nonce, header);
+            let commitment = spec::challenge_commitment(SpecVersion::V1, &seed, seal.cube_size, &Target::from_difficulty(1));
+            spec::challenge_header(next, mined, &spec::next_challenge_seed(SpecVersion::V1, &commitment), &[0; 32])
+        };
+        let first = RubikPow::preview_challenge().0;
+        let (a, b) = (seal_for(&first, 3), seal_for(&first, 5));
+        let second = after(1, &first, &a, SpecVersion::V1);
+        let c = seal_for(&second, 4);
+        let third = after(2, &second, &c, SpecVersion::V2);
+        let seals = vec![a, b, c, seal_for(&third, 4), seal_for(&third, 7)];
+
+        let mut snapshot = RubikPow::import_snapshot();
+        let planned = qbitcoin_core::import::verify_block_seals(&mut snapshot, &seals);
+
+        let dispatched: Vec<bool> = seals
+            .iter()
+            .map(|seal| {
+                RubikPow::submit_solution(
+                    RuntimeOrigin::signed(account(1)),
+                    seal.cube_size,
+                    seal.moves.clone(),
+                    seal.nonce,
+                )
+                .is_ok()
+            })
+            .collect();
+        assert_eq!(planned.iter().map(Result::is_ok).collect::<Vec<_>>(), dispatched);
+        assert_eq!(dispatched, [true, false, true, false, true]);
+        assert_eq!(RubikPow::current_rules().0, SpecVersion::V2);
+        assert_eq!(snapshot, RubikPow::import_snapshot());
+    });
+}
+
+#[test]
+fn unsigned_submissions_credit_the_key_that_signed_them() {
+    use crate::UnsignedSubmission;
+    use codec::Encode;
+    use sp_core::{sr25519, Pair};
+    use sp_runtime::traits::ValidateUnsigned;
+    use sp_runtime::transaction_validity::{InvalidTransaction, TransactionSource};
+
+    new_test_ext().execute_with(|| {
+        let key = sr25519::Pair::from_seed(&[7; 32]);
+        let seal = mine();
+        let submission = UnsignedSubmission {
+            author: key.public(),
+            cube_size: seal.cube_size,
+            moves: seal.moves,
+            nonce: seal.nonce,
+            mined: RubikPow::block_number() + 1,
+        };
+        let signature = key.sign(&submission.encode());
+        let validate = |submission: &UnsignedSubmission<AccountId>| {
+            let call = crate::Call::submit_solution_unsigned { submission: submission.clone(), signature: signature.clone() };
+            RubikPow::validate_unsigned(TransactionSource::External, &call)
+        };
+        assert!(validate(&submission).is_ok());
+
+        // A relay naming itself as the author cannot reuse the signature
+        let relayed = UnsignedSubmission { author: account(2), ..submission.clone() };
+        assert_eq!(validate(&relayed), Err(InvalidTransaction::BadProof.into()));
+        let later = UnsignedSubmission { mined: submission.mined + 1, ..submission.clone() };
+        assert_eq!(validate(&later), Err(InvalidTransaction::BadProof.into()));
+
+        assert_ok!(RubikPow::submit_solution_unsigned(RuntimeOrigin::none(), submission, signature.clone()));
+        System::assert_has_event(RuntimeEvent::RubikPow(Event::BlockMined { miner: key.public(), cube_size: CUBE_SIZE }));
+        assert_eq!(RubikPow::last_unsigned_submission(key.public()), Some(System::block_number()));
+    });
+}
+
+mod pow_fee {
+    use super::*;
+    use crate::extension::{ChargeByPow, PowProof, POW_FEE_CUBE_SIZE};
+    use codec::{Decode, Encode};
+    use frame_support::dispatch::DispatchInfo;
+    use scale_info::TypeInfo;
+    use sp_runtime::traits::SignedExtension;
+    use sp_runtime::transaction_validity::{
+        InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+    };
+
+    /// Stands in for the fee extension `ChargeByPow` wraps.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo)]
+    struct ChargeFee;
+
+    impl SignedExtension for ChargeFee {
+        const IDENTIFIER: &'static str = "ChargeFee";
+        type AccountId = AccountId;
+        type Call = RuntimeCall;
+        type AdditionalSigned = ();
+        type Pre = ();
+
+        fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+            Ok(())
+        }
+
+        fn validate(&self, _: &AccountId, _: &RuntimeCall, _: &DispatchInfo, _: usize) -> TransactionValidity {
+            Ok(ValidTransaction { priority: 7, ..Default::default() })
+        }
+
+        fn pre_dispatch(
+            self,
+            _: &AccountId,
+            _: &RuntimeCall,
+            _: &DispatchInfo,
+            _: usize,
+        ) -> Result<(), TransactionValidityError> {
+            Ok(())
+        }
+    }
+
+    fn payable_call() -> RuntimeCall {
+        RuntimeCall::RubikPow(crate::Call::add_delegate { delegate: account(9) })
+    }
+
+    fn solve_fee(who: &AccountId, call: &RuntimeCall) -> PowProof {
+        let template = BlockTemplate {
+            parent_hash: [0; 32],
+            header: RubikPow::pow_fee_header(who, System::account_nonce(who), call),
+            cube_size: POW_FEE_CUBE_SIZE,
+            target: Target::from_difficulty(4),
+        };
+        let seal = (0..).find_map(|nonce| attempt(&ReverseScramble, &template, nonce)).unwrap();
+        PowProof { nonce: seal.nonce, moves: seal.moves }
+    }
+
+    fn extension(proof: Option<PowProof>) -> ChargeByPow<Test, ChargeFee> {
+        ChargeByPow::new(proof, ChargeFee)
+    }
+
+    #[test]
+    fn proof_replaces_the_fee() {
+        new_test_ext().execute_with(|| {
+            let (who, call, info) = (account(1), payable_call(), DispatchInfo::default());
+            let proof = solve_fee(&who, &call);
+
+            assert_eq!(extension(Some(proof.clone())).validate(&who, &call, &info, 0).unwrap().priority, 0);
+            assert_eq!(extension(Some(proof)).pre_dispatch(&who, &call, &info, 0), Ok(None));
+
+            // Without a proof the wrapped extension charges as usual
+            assert_eq!(extension(None).validate(&who, &call, &info, 0).unwrap().priority, 7);
+            assert_eq!(extension(None).pre_dispatch(&who, &call, &info, 0), Ok(Some(())));
+        });
+    }
+
+    #[test]
+    fn proof_is_bound_to_signer_nonce_and_call() {
+        new_test_ext().execute_with(|| {
+            let (who, call, info) = (account(1), payable_call(), DispatchInfo::default());
+            let proof = solve_fee(&who, &call);
+            let rejected = |who: &AccountId, call: &RuntimeCall| {
+                extension(Some(proof.clone())).validate(who, call, &info, 0).is_err()
+            };
+
+            assert!(rejected(&account(2), &call));
+            assert!(rejected(&who, &RuntimeCall::RubikPow(crate::Call::add_delegate { delegate: account(8) })));
+            System::inc_account_nonce(&who);
+            assert!(rejected(&who, &call));
+        });
+    }
+
+    #[test]
+    fn only_payable_calls_accept_proofs() {
+        new_test_ext().execute_with(|| {
+            let (who, info) = (account(1), DispatchInfo::default());
+            let call = RuntimeCall::RubikPow(crate::Call::set_difficulty { new_difficulty: 1 });
+            let proof = solve_fee(&who, &call);
+            assert_eq!(
+                extension(Some(proof)).validate(&who, &call, &info, 0),
+                Err(InvalidTransaction::Call.into())
+            );
+        });
+    }
+}
+
+/// Random call sequences against the mock runtime, checking `do_try_state`
+/// after every step.
+mod fuzz {
+    use super::*;
+    use frame_support::assert_noop;
+    use qbitcoin_core::Move;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    /// Difficulty above which the harness stops grinding valid seals.
+    const MAX_GRIND_DIFFICULTY: u32 = 200;
+
+    fn step(rng: &mut StdRng, now: &mut u64) {
+        match rng.gen_range(0..6) {
+            0 | 1 if RubikPow::difficulty() <= MAX_GRIND_DIFFICULTY => submit(mine()),
+            2 if RubikPow::difficulty() <= MAX_GRIND_DIFFICULTY => {
+                // Corrupt a valid seal or reuse an old nonce: must be rejected without side effects
+                let mut seal = mine();
+                if rng.gen_bool(0.5) {
+                    seal.moves.push(Move::F(1));
+                    seal.moves.push(Move::R(1));
+                } else {
+                    seal.nonce = rng.gen_range(0..=RubikPow::last_nonce());
+                }
+                assert!(RubikPow::submit_solution(
+                    RuntimeOrigin::signed(account(rng.gen())),
+                    seal.cube_size,
+                    seal.moves,
+                    seal.nonce
+                )
+                .is_err());
+            }
+            3 => {
+                let difficulty = rng.gen_range(0..50);
+                if difficulty == 0 {
+                    assert_noop!(
+                        RubikPow::set_difficulty(RuntimeOrigin::root(), 0),
+                        crate::Error::<Test>::DifficultyTooLow
+                    );
+                } else {
+                    assert_ok!(RubikPow::set_difficulty(RuntimeOrigin::root(), difficulty));
+                }
+            }
+            4 => {
+                *now += rng.gen_range(0..4 * TARGET_BLOCK_TIME_MS);
+                Timestamp::set_timestamp(*now);
+            }
+            _ => System::set_block_number(System::block_number() + 1),
+        }
+    }
+
+    #[test]
+    fn random_calls_preserve_invariants() {
+        for seed in 0..8u64 {
+            new_test_ext().execute_with(|| {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let mut now = 1_000u64;
+                Timestamp::set_timestamp(now);
+                assert_ok!(RubikPow::set_difficulty(RuntimeOrigin::root(), 10));
+
+                for _ in 0..150 {
+                    step(&mut rng, &mut now);
+                    RubikPow::do_try_state().unwrap_or_else(|e| panic!("seed {}: {:?}", seed, e));
+                }
+            });
+        }
+    }
+}
+
+#[test]
+fn governance_switches_solution_metric() {
+    new_test_ext().execute_with(|| {
+        let seal = PowSeal { cube_size: 3, nonce: 1, moves: vec![Move::R(1), Move::U(2), Move::F(3)] };
+        assert_eq!(RubikPow::solution_metric(), Metric::Htm);
+        assert_eq!(RubikPow::solution_length(&seal), 3);
+
+        assert!(RubikPow::set_solution_metric(RuntimeOrigin::signed(account(1)), Metric::Qtm).is_err());
+        System::reset_events();
+        assert_ok!(RubikPow::set_solution_metric(RuntimeOrigin::root(), Metric::Qtm));
+        assert_eq!(RubikPow::solution_length(&seal), 4);
+        System::assert_last_event(RuntimeEvent::RubikPow(Event::SolutionMetricChanged {
+            old: Metric::Htm,
+            new: Metric::Qtm,
+        }));
+
+        // Setting the same metric again is not a change
+        assert_ok!(RubikPow::set_solution_metric(RuntimeOrigin::root(), Metric::Qtm));
+        assert_eq!(System::events().len(), 1);
+    });
+}
+
+#[test]
+fn preview_challenge_is_the_next_one_to_mine() {
+    new_test_ext().execute_with(|| {
+        let (header, nonce) = RubikPow::preview_challenge();
+        assert_eq!(header, spec::challenge_header(SpecVersion::V1, 0, &[0; 32], &[0; 32]));
+        assert_eq!(nonce, 1);
+
+        let seal = mine();
+        submit(seal.clone());
+        let (header, nonce) = RubikPow::preview_challenge();
+        assert_eq!(header, spec::challenge_header(SpecVersion::V1, 1, &RubikPow::committed_seed(), &[0; 32]));
+        assert_eq!(nonce, seal.nonce + 1);
+    });
+}
+
+#[test]
+fn activation_switches_rules_at_its_height() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(RubikPow::set_difficulty(RuntimeOrigin::root(), 16));
+        let activation = Activation { height: 2, spec: SpecVersion::V1, commitment: CommitmentVersion::V1FaceIds };
+        assert_ok!(RubikPow::schedule_activation(RuntimeOrigin::root(), activation));
+        System::assert_last_event(RuntimeEvent::RubikPow(Event::ActivationScheduled { activation }));
+        assert_eq!(RubikPow::current_rules(), (SpecVersion::V1, CommitmentVersion::V2Packed));
+        assert_eq!(RubikPow::rules_at(2), (SpecVersion::V1, CommitmentVersion::V1FaceIds));
+
+        // Block 1 is still checked under the old commitment
+        let old_only = mine_only_under(CommitmentVersion::V2Packed, CommitmentVersion::V1FaceIds);
+        let new_only = mine_only_under(CommitmentVersion::V1FaceIds, CommitmentVersion::V2Packed);
+        let submit_as = |seal: PowSeal| {
+            RubikPow::submit_solution(RuntimeOrigin::signed(account(1)), seal.cube_size, seal.moves, seal.nonce)
+        };
+        assert_noop!(submit_as(new_only), Error::<Test>::TargetNotMet);
+        assert_ok!(submit_as(old_only));
+
+        // Block 2 onwards only under the new one
+        assert_eq!(RubikPow::current_rules(), (SpecVersion::V1, CommitmentVersion::V1FaceIds));
+        let old_only = mine_only_under(CommitmentVersion::V2Packed, CommitmentVersion::V1FaceIds);
+        let new_only = mine_only_under(CommitmentVersion::V1FaceIds, CommitmentVersion::V2Packed);
+        assert_noop!(submit_as(old_only), Error::<Test>::TargetNotMet);
+        assert_ok!(submit_as(new_only));
+        assert_eq!(RubikPow::block_number(), 2);
+    });
+}
+
+#[test]
+fn v2_seals_are_bound_to_their_template() {
+    new_test_ext().execute_with(|| {
+        let activation = Activation { height: 2, spec: SpecVersion::V2, commitment: CommitmentVersion::CURRENT };
+        assert_ok!(RubikPow::schedule_activation(RuntimeOrigin::root(), activation));
+
+        // Under V1 the root changes nothing
+        let root = [1; 32];
+        assert_eq!(RubikPow::challenge_header_for(&root), RubikPow::preview_challenge().0);
+        submit(mine());
+
+        assert_ne!(RubikPow::challenge_header_for(&root), RubikPow::preview_challenge().0);
+        let seal = mine_for(&root);
+        assert!(RubikPow::check_bound_seal(&seal, &root).is_ok());
+
+        // Swapping the transactions after solving voids the seal
+        assert_eq!(RubikPow::check_bound_seal(&seal, &[2; 32]).err(), Some(VerifyError::InvalidSolution));
+        assert_eq!(RubikPow::check_seal(&seal).err(), Some(VerifyError::InvalidSolution));
+
+        let digest = sp_runtime::Digest { logs: vec![RubikPow::template_digest(&root)] };
+        assert_eq!(RubikPow::find_template_root(&digest), Some(root));
+    });
+}
+
+/// Grinds nonces until one yields a seal for the block whose transactions
+/// have root `template_root`.
+fn mine_for(template_root: &[u8; 32]) -> PowSeal {
+    let template = BlockTemplate {
+        parent_hash: [0; 32],
+        header: RubikPow::challenge_header_for(template_root),
+        cube_size: CUBE_SIZE,
+        target: Target::from_difficulty(RubikPow::difficulty()),
+    };
+    (RubikPow::last_nonce() + 1..)
+        .find_map(|nonce| attempt(&ReverseScramble, &template, nonce))
+        .unwrap()
+}
+
+/// Executes an empty block whose digest carries `seal` and, if given, the
+/// template root it was mined with.
+fn author_block(seal: &PowSeal, template_root: Option<[u8; 32]>) {
+    use frame_support::traits::Hooks;
+
+    let number = System::block_number() + 1;
+    let mut logs = vec![RubikPow::pre_runtime_digest(&account(1), seal)];
+    logs.extend(template_root.map(|root| RubikPow::template_digest(&root)));
+    System::initialize(&number, &Default::default(), &sp_runtime::Digest { logs });
+    RubikPow::on_initialize(number);
+    RubikPow::on_finalize(number);
+}
+
+/// Root of the transactions of an empty block.
+fn empty_root() -> [u8; 32] {
+    frame_system::extrinsics_data_root::<sp_runtime::traits::BlakeTwo256>(vec![]).0
+}
+
+#[test]
+fn v2_blocks_carry_the_template_their_seal_was_mined_with() {
+    new_test_ext().execute_with(|| {
+        let activation = Activation { height: 2, spec: SpecVersion::V2, commitment: CommitmentVersion::CURRENT };
+        assert_ok!(RubikPow::schedule_activation(RuntimeOrigin::root(), activation));
+        submit(mine());
+
+        // Leaving the root out does not fall back to zeros
+        let seal = mine_for(&[0; 32]);
+        author_block(&seal, None);
+        System::assert_last_event(RuntimeEvent::RubikPow(Event::SolutionRejected {
+            author: account(1),
+            reason: VerifyError::MalformedSeal.code(),
+        }));
+        assert_eq!(RubikPow::block_number(), 1);
+
+        let root = empty_root();
+        author_block(&mine_for(&root), Some(root));
+        assert_eq!(RubikPow::block_number(), 2);
+        assert_eq!(crate::TemplateRoot::<Test>::get(), None);
+    });
+}
+
+#[test]
+fn v2_seals_mined_for_other_transactions_are_skipped() {
+    new_test_ext().execute_with(|| {
+        let activation = Activation { height: 2, spec: SpecVersion::V2, commitment: CommitmentVersion::CURRENT };
+        assert_ok!(RubikPow::schedule_activation(RuntimeOrigin::root(), activation));
+        submit(mine());
+        let balance = Balances::free_balance(account(1));
+        let work = RubikPow::total_work();
+
+        // The seal checks out against the root it names, which is not the block's
+        let root = [1; 32];
+        assert_ne!(root, empty_root());
+        author_block(&mine_for(&root), Some(root));
+        System::assert_last_event(RuntimeEvent::RubikPow(Event::TemplateMismatch { author: account(1) }));
+        assert_eq!(RubikPow::block_number(), 1);
+        assert_eq!((Balances::free_balance(account(1)), RubikPow::total_work()), (balance, work));
+        assert_eq!(crate::TemplateRoot::<Test>::get(), None);
+    });
+}
+
+#[test]
+fn min_quality_refuses_padded_solutions() {
+    new_test_ext().execute_with(|| {
+        assert!(RubikPow::set_min_quality(RuntimeOrigin::signed(account(1)), MinQuality::NONE).is_err());
+        assert_noop!(
+            RubikPow::set_min_quality(RuntimeOrigin::root(), MinQuality { max_percent: 99 }),
+            Error::<Test>::QualityFloorTooStrict
+        );
+        let quality = MinQuality { max_percent: 100 };
+        assert_ok!(RubikPow::set_min_quality(RuntimeOrigin::root(), quality));
+        System::assert_last_event(RuntimeEvent::RubikPow(Event::MinQualityChanged { old: MinQuality::NONE, new: quality }));
+        assert_eq!(RubikPow::import_snapshot().min_quality, quality);
+
+        // The scramble undone and then a turn back and forth
+        let mut padded = mine();
+        padded.moves.extend([Move::U(1), Move::U(3)]);
+        assert_noop!(
+            RubikPow::submit_solution(RuntimeOrigin::signed(account(1)), padded.cube_size, padded.moves, padded.nonce),
+            Error::<Test>::SolutionTooLong
+        );
+        submit(mine());
+        assert_eq!(RubikPow::block_number(), 1);
+    });
+}
+
+#[test]
+fn activations_are_scheduled_in_order_and_ahead() {
+    new_test_ext().execute_with(|| {
+        let at = |height| Activation { height, spec: SpecVersion::V1, commitment: CommitmentVersion::V2Packed };
+        assert!(RubikPow::schedule_activation(RuntimeOrigin::signed(account(1)), at(5)).is_err());
+        // Block 1 is the one being mined
+        assert_noop!(RubikPow::schedule_activation(RuntimeOrigin::root(), at(1)), Error::<Test>::ActivationNotInFuture);
+
+        assert_ok!(RubikPow::schedule_activation(RuntimeOrigin::root(), at(2)));
+        assert_ok!(RubikPow::schedule_activation(RuntimeOrigin::root(), at(3)));
+        assert_noop!(RubikPow::schedule_activation(RuntimeOrigin::root(), at(3)), Error::<Test>::ActivationOutOfOrder);
+        assert_ok!(RubikPow::schedule_activation(RuntimeOrigin::root(), at(4)));
+        assert_ok!(RubikPow::schedule_activation(RuntimeOrigin::root(), at(5)));
+        assert_noop!(RubikPow::schedule_activation(RuntimeOrigin::root(), at(6)), Error::<Test>::TooManyActivations);
+
+        // Once later activations are in force the earlier ones make room
+        for _ in 0..3 {
+            submit(mine());
+        }
+        assert_ok!(RubikPow::schedule_activation(RuntimeOrigin::root(), at(6)));
+        let heights: Vec<u32> = RubikPow::activation_schedule().iter().map(|activation| activation.height).collect();
+        assert_eq!(heights, vec![4, 5, 6]);
+    });
+}
+
+#[test]
+fn each_block_commits_to_the_next_challenge_seed() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(RubikPow::committed_seed(), [0; 32]);
+        let seal = mine();
+        let (header, _) = RubikPow::preview_challenge();
+        let target = Target::from_difficulty(RubikPow::difficulty());
+        submit(seal.clone());
+
+        let seed = spec::challenge_seed(SpecVersion::V1, seal.nonce, &header);
+        let commitment = spec::challenge_commitment(SpecVersion::V1, &seed, CUBE_SIZE, &target);
+        let next = spec::next_challenge_seed(SpecVersion::V1, &commitment);
+        assert_eq!(RubikPow::committed_seed(), next);
+        assert_eq!(RubikPow::preview_challenge().0, spec::challenge_header(SpecVersion::V1, 1, &next, &[0; 32]));
+
+        // The block's log carries both, the commitment first
+        let logs = System::digest().logs;
+        let mut expected = commitment.to_vec();
+        expected.extend_from_slice(&next);
+        assert!(logs.contains(&sp_runtime::DigestItem::Consensus(qbitcoin_core::RUBIKPOW_ENGINE_ID, expected)));
+
+        // A seal for the uncommitted header of the same height is stale work
+        let template = BlockTemplate {
+            parent_hash: [0; 32],
+            header: 1u32.to_le_bytes().to_vec(),
+            cube_size: CUBE_SIZE,
+            target: Target::MAX,
+        };
+        let stale = attempt(&ReverseScramble, &template, seal.nonce + 1).unwrap();
+        assert_noop!(
+            RubikPow::submit_solution(RuntimeOrigin::signed(account(1)), stale.cube_size, stale.moves, stale.nonce),
+            Error::<Test>::InvalidSolution
+        );
+    });
+}
+
+#[test]
+fn accepted_solutions_are_reported_to_hooks() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(RubikPow::set_solution_metric(RuntimeOrigin::root(), Metric::Qtm));
+        let seal = mine();
+        submit(seal.clone());
+
+        let commitment = RubikPow::challenge_commitment_at(System::block_number()).unwrap();
+        let quality = Metric::Qtm.length(&seal.moves, CUBE_SIZE as usize);
+        assert_eq!(AcceptedSolutions::take(), vec![(account(1), CUBE_SIZE, quality, commitment)]);
+
+        // Rejected seals are not reported
+        assert_noop!(
+            RubikPow::submit_solution(RuntimeOrigin::signed(account(1)), seal.cube_size, seal.moves, seal.nonce),
+            Error::<Test>::InvalidNonce
+        );
+        assert!(AcceptedSolutions::take().is_empty());
+    });
+}
+
+#[test]
+fn scramble_length_is_set_per_size() {
+    new_test_ext().execute_with(|| {
+        let long = ScrambleSpec { length: (40, 40), ..ScrambleSpec::V1 };
+        assert!(RubikPow::set_scramble_spec(RuntimeOrigin::signed(account(1)), CUBE_SIZE, long).is_err());
+        assert_noop!(
+            RubikPow::set_scramble_spec(RuntimeOrigin::root(), CUBE_SIZE, ScrambleSpec { length: (41, 40), ..long }),
+            Error::<Test>::InvalidScrambleSpec
+        );
+        assert_noop!(RubikPow::set_scramble_spec(RuntimeOrigin::root(), 1, long), Error::<Test>::CubeTooSmall);
+
+        assert_ok!(RubikPow::set_scramble_spec(RuntimeOrigin::root(), CUBE_SIZE, long));
+        System::assert_last_event(RuntimeEvent::RubikPow(Event::ScrambleSpecChanged { cube_size: CUBE_SIZE, scramble: long }));
+        assert_eq!(RubikPow::scramble_spec(CUBE_SIZE), long);
+        assert_eq!(RubikPow::scramble_spec(3), ScrambleSpec::V1);
+
+        // Seals for the default scramble no longer solve the challenge
+        let template = BlockTemplate {
+            parent_hash: [0; 32],
+            header: RubikPow::preview_challenge().0,
+            cube_size: CUBE_SIZE,
+            target: Target::from_difficulty(RubikPow::difficulty()),
+        };
+        let default = attempt(&ReverseScramble, &template, 1).unwrap();
+        assert_noop!(
+            RubikPow::submit_solution(RuntimeOrigin::signed(account(1)), CUBE_SIZE, default.moves, 1),
+            Error::<Test>::InvalidSolution
+        );
+        let seal = attempt_with_scramble(&ReverseScramble, &template, &long, 1).unwrap();
+        assert_eq!(seal.moves.len(), 40);
+        submit(seal);
+        assert_eq!(RubikPow::block_number(), 1);
+
+        // Setting the default again drops the entry
+        assert_ok!(RubikPow::set_scramble_spec(RuntimeOrigin::root(), CUBE_SIZE, ScrambleSpec::V1));
+        assert_eq!(crate::ScrambleSpecs::<Test>::iter().count(), 0);
+    });
+}
+
+#[test]
+fn solution_policy_is_enforced_when_configured() {
+    new_test_ext().execute_with(|| {
+        let seal = mine();
+        let blacklist: &'static Blacklist = Box::leak(Box::new(Blacklist::new(vec![seal.moves.clone()])));
+        TestPolicy::set_policy(Some(blacklist));
+        assert_noop!(
+            RubikPow::submit_solution(RuntimeOrigin::signed(account(1)), seal.cube_size, seal.moves.clone(), seal.nonce),
+            Error::<Test>::CannedSolution
+        );
+        assert_eq!(RubikPow::check_encoded_seal(&seal.encode()), Err(VerifyError::CannedSolution));
+        let mut snapshot = RubikPow::import_snapshot();
+        let planned = qbitcoin_core::import::verify_block_seals(&mut snapshot, core::slice::from_ref(&seal));
+        assert_eq!(planned, [Err(VerifyError::CannedSolution)]);
+
+        TestPolicy::set_policy(None);
+        submit(seal);
+        assert_eq!(RubikPow::block_number(), 1);
+    });
+}
+
+#[test]
+fn miner_profiles_track_the_most_recent_miners() {
+    new_test_ext().execute_with(|| {
+        let mine_as = |who: u8| {
+            let seal = mine();
+            let length = RubikPow::solution_length(&seal);
+            assert_ok!(RubikPow::submit_solution(
+                RuntimeOrigin::signed(account(who)),
+                seal.cube_size,
+                seal.moves,
+                seal.nonce
+            ));
+            u64::from(length)
+        };
+
+        let first = mine_as(1);
+        mine_as(2);
+        let third = mine_as(1);
+        let profile = RubikPow::miner_profile(account(1)).unwrap();
+        assert_eq!(profile.blocks_mined, 2);
+        assert_eq!(profile.total_solution_length, first + third);
+        assert_eq!(profile.average_solution_length(), Some((first + third) / 2));
+        assert_eq!(profile.last_mined, 3);
+        assert!(profile.best_efficiency > 0);
+        assert_eq!(RubikPow::tracked_miners().into_inner(), vec![account(2), account(1)]);
+
+        // Only two accounts fit; the one that mined least recently goes
+        mine_as(3);
+        assert_eq!(RubikPow::miner_profile(account(2)), None);
+        assert_eq!(RubikPow::tracked_miners().into_inner(), vec![account(1), account(3)]);
+        assert_eq!(RubikPow::miner_profile(account(3)).unwrap().blocks_mined, 1);
+        RubikPow::do_try_state().unwrap();
+    });
+}
+
+#[test]
+fn rewards_are_minted_to_the_payout_account() {
+    new_test_ext().execute_with(|| {
+        let reward_payee = || {
+            System::events().into_iter().rev().find_map(|record| match record.event {
+                RuntimeEvent::RubikPow(Event::Reward { miner, payee, .. }) => Some((miner, payee)),
+                _ => None,
+            })
+        };
+        let last_reward = || {
+            System::events().into_iter().rev().find_map(|record| match record.event {
+                RuntimeEvent::RubikPow(Event::Reward { amount, .. }) => Some(u64::from(amount)),
+                _ => None,
+            })
+        };
+
+        submit(mine());
+        assert_eq!(reward_payee(), Some((account(1), account(1))));
+        let first = last_reward().unwrap();
+        assert!(first > 0);
+        assert_eq!(Balances::free_balance(account(1)), first);
+
+        assert_ok!(RubikPow::set_payout_account(RuntimeOrigin::signed(account(1)), Some(account(5))));
+        System::assert_last_event(RuntimeEvent::RubikPow(Event::PayoutAccountSet {
+            miner: account(1),
+            payout: Some(account(5)),
+        }));
+        assert_eq!(RubikPow::payout_account_of(account(1)), Some(account(5)));
+        submit(mine());
+        assert_eq!(reward_payee(), Some((account(1), account(5))));
+        assert_eq!(Balances::free_balance(account(5)), last_reward().unwrap());
+        assert_eq!(Balances::free_balance(account(1)), first);
+        // Statistics stay with the miner
+        assert_eq!(RubikPow::miner_profile(account(1)).unwrap().blocks_mined, 2);
+        assert_eq!(RubikPow::miner_profile(account(5)), None);
+
+        // Naming the miner itself clears the redirect
+        assert_ok!(RubikPow::set_payout_account(RuntimeOrigin::signed(account(1)), Some(account(1))));
+        assert_eq!(RubikPow::payout_account_of(account(1)), None);
+        submit(mine());
+        assert_eq!(reward_payee(), Some((account(1), account(1))));
+        assert_eq!(Balances::free_balance(account(1)), first + last_reward().unwrap());
+    });
+}
+
+#[test]
+fn delegates_must_accept_before_their_rewards_are_redirected() {
+    new_test_ext().execute_with(|| {
+        let (solver, key, thief) = (account(1), account(2), account(3));
+        let mined_by = |key: AccountId| {
+            let seal = mine();
+            assert_ok!(RubikPow::submit_solution(RuntimeOrigin::signed(key), seal.cube_size, seal.moves, seal.nonce));
+            System::events().into_iter().rev().find_map(|record| match record.event {
+                RuntimeEvent::RubikPow(Event::Reward { miner, payee, .. }) => Some((miner, payee)),
+                _ => None,
+            })
+        };
+
+        // An offer alone redirects nothing, so no one can claim another key's rewards
+        assert_ok!(RubikPow::add_delegate(RuntimeOrigin::signed(thief.clone()), key.clone()));
+        System::assert_last_event(RuntimeEvent::RubikPow(Event::DelegateOffered {
+            solver: thief.clone(),
+            delegate: key.clone(),
+        }));
+        assert_eq!(RubikPow::delegator_of(&key), None);
+        assert_eq!(mined_by(key.clone()), Some((key.clone(), key.clone())));
+
+        // The key accepts the offer it wants; the other stays pending
+        assert_noop!(
+            RubikPow::accept_delegate(RuntimeOrigin::signed(key.clone()), solver.clone()),
+            Error::<Test>::NoDelegateOffer
+        );
+        assert_ok!(RubikPow::add_delegate(RuntimeOrigin::signed(solver.clone()), key.clone()));
+        assert_ok!(RubikPow::accept_delegate(RuntimeOrigin::signed(key.clone()), solver.clone()));
+        System::assert_last_event(RuntimeEvent::RubikPow(Event::DelegateAdded {
+            solver: solver.clone(),
+            delegate: key.clone(),
+        }));
+        assert_eq!(RubikPow::delegator_of(&key), Some(solver.clone()));
+        assert_noop!(
+            RubikPow::accept_delegate(RuntimeOrigin::signed(key.clone()), thief.clone()),
+            Error::<Test>::AlreadyDelegated
+        );
+        assert_noop!(
+            RubikPow::add_delegate(RuntimeOrigin::signed(thief.clone()), key.clone()),
+            Error::<Test>::AlreadyDelegated
+        );
+
+        // The key's seals count as the solver's and pay its payout account
+        assert_eq!(mined_by(key.clone()), Some((solver.clone(), solver.clone())));
+        assert_ok!(RubikPow::set_payout_account(RuntimeOrigin::signed(solver.clone()), Some(account(5))));
+        assert_eq!(mined_by(key.clone()), Some((solver.clone(), account(5))));
+        assert!(Balances::free_balance(account(5)) > 0);
+        assert_eq!(RubikPow::miner_profile(&solver).unwrap().blocks_mined, 2);
+
+        // Only the solver or the key can end the delegation
+        assert_noop!(
+            RubikPow::remove_delegate(RuntimeOrigin::signed(thief.clone()), key.clone()),
+            Error::<Test>::NotDelegated
+        );
+        assert_ok!(RubikPow::remove_delegate(RuntimeOrigin::signed(key.clone()), key.clone()));
+        System::assert_last_event(RuntimeEvent::RubikPow(Event::DelegateRemoved {
+            solver: solver.clone(),
+            delegate: key.clone(),
+        }));
+        assert_eq!(mined_by(key.clone()), Some((key.clone(), key.clone())));
+
+        // A solver withdraws an offer not yet accepted
+        assert_ok!(RubikPow::add_delegate(RuntimeOrigin::signed(solver.clone()), key.clone()));
+        assert_ok!(RubikPow::remove_delegate(RuntimeOrigin::signed(solver.clone()), key.clone()));
+        assert_noop!(
+            RubikPow::accept_delegate(RuntimeOrigin::signed(key.clone()), solver.clone()),
+            Error::<Test>::NoDelegateOffer
+        );
+        assert!(crate::DelegateOffers::<Test>::contains_key(&key, &thief));
+        assert_noop!(
+            RubikPow::remove_delegate(RuntimeOrigin::signed(solver), key.clone()),
+            Error::<Test>::NotDelegated
+        );
+    });
+}
+
+#[test]
+fn shorter_solutions_earn_more() {
+    new_test_ext().execute_with(|| {
+        let seal = mine();
+        let reward_of = |moves: Vec<Move>| {
+            with_transaction(|| {
+                assert_ok!(RubikPow::submit_solution(RuntimeOrigin::signed(account(1)), seal.cube_size, moves, seal.nonce));
+                let reward = System::events().into_iter().rev().find_map(|record| match record.event {
+                    RuntimeEvent::RubikPow(Event::Reward { amount, .. }) => Some(amount),
+                    _ => None,
+                });
+                TransactionOutcome::Rollback(Ok::<_, DispatchError>(reward.unwrap()))
+            })
+            .unwrap()
+        };
+
+        // The same solution padded with moves that undo each other
+        let mut padded = seal.moves.clone();
+        padded.extend([Move::U(1), Move::U(3), Move::F(1), Move::F(3)]);
+        let (short, long) = (reward_of(seal.moves.clone()), reward_of(padded.clone()));
+        assert!(short > long, "{short} <= {long}");
+
+        let length = |moves: &[Move]| RubikPow::solution_length(&PowSeal { moves: moves.to_vec(), ..seal.clone() });
+        assert_eq!(RubikPow::estimate_reward(CUBE_SIZE, length(&seal.moves), 0), Some(short));
+        assert_eq!(RubikPow::estimate_reward(CUBE_SIZE, length(&padded), 0), Some(long));
+    });
+}
+
+#[test]
+fn reward_estimates_match_payouts_across_halvings() {
+    use qbitcoin_core::difficulty::HALVING_INTERVAL;
+
+    new_test_ext().execute_with(|| {
+        let interval = u64::from(HALVING_INTERVAL);
+        let reference = ScrambleSpec::V1.length.1;
+        let at = |height| RubikPow::estimate_reward(CUBE_SIZE, reference, height).unwrap();
+        assert_eq!(at(interval - 1), at(0));
+        assert_eq!(at(interval), at(0) / 2);
+        assert_eq!(at(2 * interval), at(0) / 4);
+        assert!(RubikPow::estimate_reward(CUBE_SIZE, reference / 2, 0) > Some(at(0)));
+        assert_eq!(RubikPow::estimate_reward(crate::MAX_CUBE_SIZE + 1, reference, 0), None);
+
+        // A seal pays what was estimated for its block, after the first halving too
+        for height in [2, interval + 3] {
+            System::set_block_number(height);
+            let seal = mine();
+            let estimate = RubikPow::estimate_reward(CUBE_SIZE, RubikPow::solution_length(&seal), height);
+            submit(seal);
+            let paid = System::events().into_iter().rev().find_map(|record| match record.event {
+                RuntimeEvent::RubikPow(Event::Reward { amount, .. }) => Some(amount),
+                _ => None,
+            });
+            assert_eq!(paid, estimate, "height {height}");
+        }
+    });
+}
+
+/// Seals solving the next `count` challenges in turn, found without mining
+/// any of them.
+fn mine_chain(count: usize) -> Vec<Submission> {
+    with_transaction(|| {
+        let seals = (0..count)
+            .map(|_| {
+                let seal = mine();
+                submit(seal.clone());
+                Submission::from(seal)
+            })
+            .collect();
+        TransactionOutcome::Rollback(Ok::<_, DispatchError>(seals))
+    })
+    .unwrap()
+}
+
+fn batch(seals: Vec<Submission>) -> BoundedVec<Submission, <Test as crate::Config>::MaxBatchSize> {
+    seals.try_into().unwrap()
+}
+
+#[test]
+fn batches_mine_each_solution_in_turn() {
+    new_test_ext().execute_with(|| {
+        let who = RuntimeOrigin::signed(account(1));
+        assert_noop!(RubikPow::submit_solutions_batch(who.clone(), batch(vec![]), true), Error::<Test>::EmptyBatch);
+
+        let seals = mine_chain(2);
+        assert_ok!(RubikPow::submit_solutions_batch(who.clone(), batch(seals.clone()), true));
+        assert_eq!(RubikPow::block_number(), 2);
+        System::assert_last_event(RuntimeEvent::RubikPow(Event::BatchSubmitted { submitter: account(1), accepted: 2 }));
+
+        // An atomic batch with a spent solution mines nothing
+        let fresh = mine_chain(1).remove(0);
+        assert_noop!(
+            RubikPow::submit_solutions_batch(who.clone(), batch(vec![fresh.clone(), seals[0].clone()]), true),
+            Error::<Test>::InvalidNonce
+        );
+
+        // Otherwise the spent one is skipped
+        assert_ok!(RubikPow::submit_solutions_batch(who, batch(vec![seals[1].clone(), fresh]), false));
+        assert_eq!(RubikPow::block_number(), 3);
+        System::assert_has_event(RuntimeEvent::RubikPow(Event::BatchItemRejected {
+            index: 0,
+            reason: VerifyError::StaleChallenge.code(),
+        }));
+        System::assert_last_event(RuntimeEvent::RubikPow(Event::BatchSubmitted { submitter: account(1), accepted: 1 }));
+    });
+}
+
+#[test]
+fn submissions_are_recorded_in_the_offchain_index() {
+    use codec::Decode;
+
+    let mut ext = new_test_ext();
+    let (seal, reward) = ext.execute_with(|| {
+        let seal = mine();
+        submit(seal.clone());
+        let reward = System::events()
+            .into_iter()
+            .find_map(|record| match record.event {
+                RuntimeEvent::RubikPow(Event::Reward { amount, .. }) => Some(amount),
+                _ => None,
+            })
+            .unwrap();
+        // A spent seal, submitted again for the next block
+        assert_ok!(RubikPow::submit_solutions_batch(
+            RuntimeOrigin::signed(account(1)),
+            batch(vec![Submission::from(seal.clone())]),
+            false
+        ));
+        (seal, reward)
+    });
+    ext.persist_offchain_overlay();
+
+    let record = |mined| {
+        let encoded = ext.offchain_db().get(&submission_key(&account(1), mined))?;
+        Some(SubmissionRecord::<AccountId, sp_core::H256>::decode(&mut &encoded[..]).unwrap())
+    };
+    let mined = record(1).unwrap();
+    assert_eq!((mined.cube_size, mined.nonce), (seal.cube_size, seal.nonce));
+    assert_eq!(mined.outcome, SubmissionOutcome::Mined { payee: account(1), reward });
+    assert_eq!(
+        record(2).unwrap().outcome,
+        SubmissionOutcome::Rejected { reason: VerifyError::StaleChallenge.code() }
+    );
+    assert_eq!(record(3), None);
+}
+
+/// Feeds the same random seals to the node's standalone verifier service and
+/// to the pallet, which must agree on every decision and rejection reason.
+#[test]
+fn verifier_service_and_pallet_agree_on_random_seals() {
+    use qbitcoin_core::verifier::{VerifierConfig, VerifierService, VerifyJob};
+    use qbitcoin_core::{Axis, Face};
+    use std::collections::BTreeMap;
+
+    new_test_ext().execute_with(|| {
+        assert_ok!(RubikPow::set_difficulty(RuntimeOrigin::root(), 4));
+        let header = RubikPow::preview_challenge().0;
+        let target = Target::from_difficulty(RubikPow::difficulty());
+
+        let mut state = 0x5eed_u64;
+        let mut next = move |bound: u64| {
+            // splitmix64
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            (z ^ (z >> 31)) % bound
+        };
+        let solved = |cube_size: u32, nonce: u64| {
+            let template = BlockTemplate { parent_hash: [0; 32], header: header.clone(), cube_size, target: Target::MAX };
+            attempt(&ReverseScramble, &template, nonce).unwrap()
+        };
+
+        let mut seals = Vec::new();
+        for _ in 0..120 {
+            let cube_size = 2 + next(3) as u32;
+            let nonce = 1 + next(1 << 20);
+            let seal = match next(5) {
+                // Solves its challenge; meets the target about once in four
+                0 | 1 => solved(cube_size, nonce),
+                2 => {
+                    let mut seal = solved(cube_size, nonce);
+                    seal.moves.pop();
+                    seal
+                }
+                3 => {
+                    let moves = (0..next(12))
+                        .map(|_| match next(8) {
+                            0 => Move::Wide(Face::ALL[next(6) as usize], 1 + next(3) as usize),
+                            1 => Move::Rotation(Axis::ALL[next(3) as usize], 1 + next(3) as usize),
+                            _ => Move::from_face_and_count(Face::ALL[next(6) as usize], 1 + next(3) as usize),
+                        })
+                        .collect();
+                    PowSeal { cube_size, nonce, moves }
+                }
+                _ => PowSeal { cube_size: next(2) as u32, nonce, moves: vec![] },
+            };
+            seals.push(seal);
+        }
+
+        let (service, outcomes) = VerifierService::start(VerifierConfig { workers: 4, per_peer_queue: 128, max_queued: 128 });
+        let ids: Vec<u64> = seals
+            .iter()
+            .map(|seal| {
+                let job = VerifyJob { seal: seal.clone(), header: header.clone(), target };
+                service.submit(0, job).unwrap()
+            })
+            .collect();
+        let mut results = BTreeMap::new();
+        for _ in 0..seals.len() {
+            let outcome = outcomes.recv().unwrap();
+            results.insert(outcome.id, outcome.result);
+        }
+
+        let mut seen = BTreeMap::new();
+        for (seal, id) in seals.iter().zip(ids) {
+            let pallet = RubikPow::check_seal(seal).map(|_| ());
+            assert_eq!(results[&id], pallet, "{:?}", seal);
+            *seen.entry(pallet.err().map(VerifyError::code)).or_insert(0) += 1;
+        }
+        // Every kind of decision came up
+        for expected in [None, Some(VerifyError::CubeTooSmall), Some(VerifyError::InvalidSolution), Some(VerifyError::TargetNotMet)] {
+            assert!(seen.contains_key(&expected.map(VerifyError::code)), "{:?} never seen", expected);
+        }
+    });
+}
+
+#[test]
+fn adversarial_seals_are_rejected_without_panicking() {
+    use qbitcoin_core::{Axis, Face};
+
+    new_test_ext().execute_with(|| {
+        let mut state = 0xbad_5ea1_u64;
+        let mut next = move |bound: u64| {
+            // splitmix64
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            (z ^ (z >> 31)) % bound
+        };
+
+        assert_eq!(RubikPow::check_encoded_seal(&[]), Err(VerifyError::MalformedSeal));
+        for _ in 0..500 {
+            let bytes: Vec<u8> = (0..next(40)).map(|_| next(256) as u8).collect();
+            let _ = RubikPow::check_encoded_seal(&bytes);
+        }
+
+        let extreme_moves = vec![
+            Move::U(usize::MAX),
+            Move::Wide(Face::Back, usize::MAX),
+            Move::Rotation(Axis::Z, usize::MAX),
+        ];
+        for (cube_size, error) in [
+            (0, Error::<Test>::CubeTooSmall),
+            (u32::MAX, Error::<Test>::CubeTooLarge),
+            (CUBE_SIZE, Error::<Test>::InvalidSolution),
+        ] {
+            assert_noop!(
+                RubikPow::submit_solution(
+                    RuntimeOrigin::signed(account(1)),
+                    cube_size,
+                    extreme_moves.clone(),
+                    RubikPow::last_nonce() + 1
+                ),
+                error
+            );
+        }
+    });
+}
+
+const POOL_URL: &str = "http://pool.example/shares";
+
+/// Externalities with offchain worker and storage extensions, a pool URL and
+/// `pending` shares, where the pool acknowledges one post of `posted` unless
+/// it is empty.
+fn offchain_ext(pending: &[&[u8]], posted: &[&[u8]]) -> sp_io::TestExternalities {
+    use codec::Encode;
+    use sp_core::offchain::testing::{PendingRequest, TestOffchainExt};
+    use sp_core::offchain::{OffchainDbExt, OffchainWorkerExt};
+    use sp_runtime::offchain::storage::StorageValueRef;
+
+    let mut ext = new_test_ext();
+    let (offchain, state) = TestOffchainExt::new();
+    if !posted.is_empty() {
+        let shares: Vec<Vec<u8>> = posted.iter().map(|share| share.to_vec()).collect();
+        state.write().expect_request(PendingRequest {
+            method: "POST".into(),
+            uri: POOL_URL.into(),
+            headers: vec![("Content-Type".into(), "application/octet-stream".into())],
+            body: shares.encode(),
+            response: Some(Vec::new()),
+            sent: true,
+            ..Default::default()
+        });
+    }
+    ext.register_extension(OffchainDbExt::new(offchain.clone()));
+    ext.register_extension(OffchainWorkerExt::new(offchain));
+    ext.execute_with(|| {
+        StorageValueRef::persistent(POOL_URL_KEY).set(&POOL_URL.as_bytes().to_vec());
+        StorageValueRef::persistent(PENDING_SHARES_KEY).set(&pending.iter().map(|share| share.to_vec()).collect::<Vec<_>>());
+    });
+    ext
+}
+
+fn pending_shares() -> Vec<Vec<u8>> {
+    sp_runtime::offchain::storage::StorageValueRef::persistent(PENDING_SHARES_KEY).get().unwrap().unwrap()
+}
+
+#[test]
+fn acknowledged_shares_are_removed_from_the_pending_ones() {
+    use codec::Encode;
+
+    let mut ext = offchain_ext(&[b"a", b"b"], &[b"a", b"b"]);
+    ext.execute_with(|| {
+        assert_eq!(post_pending_shares(|shares| Ok(shares.encode())), Ok(()));
+        assert_eq!(pending_shares(), Vec::<Vec<u8>>::new());
+        // Nothing pending, nothing posted
+        assert_eq!(post_pending_shares(|_| panic!("nothing to post")), Ok(()));
+    });
+}
+
+#[test]
+fn failed_posts_keep_their_shares() {
+    use sp_runtime::offchain::storage::StorageValueRef;
+
+    let mut ext = offchain_ext(&[b"a", b"b"], &[]);
+    ext.execute_with(|| {
+        assert_eq!(post_pending_shares(|_| Err(ShareError::NoSigningKey)), Err(ShareError::NoSigningKey));
+        assert_eq!(pending_shares(), vec![b"a".to_vec(), b"b".to_vec()]);
+
+        // A URL the request cannot be made to
+        StorageValueRef::persistent(POOL_URL_KEY).set(&vec![0xffu8, 0xfe]);
+        assert_eq!(post_pending_shares(|shares| Ok(shares.concat())), Err(ShareError::Http));
+        assert_eq!(pending_shares(), vec![b"a".to_vec(), b"b".to_vec()]);
+    });
+}
+
+#[test]
+fn shares_appended_during_a_post_stay_pending() {
+    use codec::Encode;
+    use sp_runtime::offchain::storage::StorageValueRef;
+
+    let mut ext = offchain_ext(&[b"a", b"b"], &[b"a", b"b"]);
+    ext.execute_with(|| {
+        // The miner appends a share after the worker read the pending ones
+        let posted = post_pending_shares(|shares| {
+            let mut pending = pending_shares();
+            pending.push(b"c".to_vec());
+            StorageValueRef::persistent(PENDING_SHARES_KEY).set(&pending);
+            Ok(shares.encode())
+        });
+        assert_eq!(posted, Ok(()));
+        assert_eq!(pending_shares(), vec![b"c".to_vec()]);
+    });
+}
+
+/// Starts block `number` on top of a parent with hash `parent`, as importing
+/// it would.
+fn start_block(number: u64, parent: sp_core::H256) {
+    use frame_support::traits::Hooks;
+
+    System::initialize(&number, &parent, &Default::default());
+    RubikPow::on_initialize(number);
+}
+
+#[test]
+fn confirmations_count_the_work_mined_on_top_of_a_block() {
+    use qbitcoin_core::difficulty::{work_per_seal, REFERENCE_SIZE};
+    use sp_core::H256;
+
+    new_test_ext().execute_with(|| {
+        submit(mine());
+        let first = H256::repeat_byte(1);
+        assert_eq!(RubikPow::confirmations(first), None);
+        assert!(!RubikPow::is_confirmed(first, 0));
+
+        // The block's own seal does not confirm it
+        start_block(2, first);
+        assert_eq!(RubikPow::confirmations(first), Some(0));
+        assert!(RubikPow::is_confirmed(first, 0));
+        assert!(!RubikPow::is_confirmed(first, 1));
+
+        let work_at_first = RubikPow::total_work();
+        for number in 2..6u64 {
+            submit(mine());
+            start_block(number + 1, H256::repeat_byte(number as u8));
+        }
+        // Counted in reference-size blocks at the current difficulty; 2x2x2
+        // seals are worth less than one each
+        let per_block = work_per_seal(REFERENCE_SIZE, &Target::from_difficulty(RubikPow::difficulty()));
+        let expected = ((RubikPow::total_work() - work_at_first) / per_block) as u32;
+        assert!(expected > 0 && expected < 4);
+        assert_eq!(RubikPow::confirmations(first), Some(expected));
+        assert!(RubikPow::is_confirmed(first, expected));
+        assert!(!RubikPow::is_confirmed(first, expected + 1));
+        // Later blocks have less on top of them
+        assert!(RubikPow::confirmations(H256::repeat_byte(4)).unwrap() < expected);
+    });
+}
+
+#[test]
+fn confirmations_are_forgotten_past_the_history() {
+    use frame_support::traits::Get;
+    use sp_core::H256;
+
+    new_test_ext().execute_with(|| {
+        let history = u64::from(<Test as crate::Config>::ConfirmationHistory::get());
+        for number in 2..=history + 1 {
+            start_block(number, H256::repeat_byte(number as u8 - 1));
+        }
+        assert_eq!(RubikPow::confirmations(H256::repeat_byte(1)), Some(0));
+
+        // Starting block `history + 2` records its parent and drops block 1
+        start_block(history + 2, H256::repeat_byte(history as u8 + 1));
+        assert_eq!(RubikPow::confirmations(H256::repeat_byte(1)), None);
+        assert!(!RubikPow::is_confirmed(H256::repeat_byte(1), 0));
+        assert_eq!(RubikPow::confirmations(H256::repeat_byte(2)), Some(0));
+    });
+}
+
+#[test]
+fn seals_can_be_checked_without_being_applied() {
+    use qbitcoin_core::difficulty::work_per_seal;
+
+    new_test_ext().execute_with(|| {
+        let seal = mine();
+        let state = sp_io::storage::root(sp_runtime::StateVersion::V1);
+        let verified = RubikPow::check_seal(&seal).unwrap();
+        assert_eq!(verified.difficulty, RubikPow::difficulty());
+        assert_eq!(verified.work, work_per_seal(CUBE_SIZE, &Target::from_difficulty(RubikPow::difficulty())));
+        assert_eq!(RubikPow::check_encoded_seal(&seal.encode()), Ok(()));
+        // Nothing was written
+        assert_eq!(sp_io::storage::root(sp_runtime::StateVersion::V1), state);
+        assert_eq!((RubikPow::last_nonce(), RubikPow::total_work()), (0, 0));
+
+        // The seal is only spent once submitted; a resubmission is stale
+        submit(seal.clone());
+        assert_eq!(RubikPow::check_seal(&seal).err(), Some(VerifyError::StaleChallenge));
+        assert_eq!(RubikPow::check_encoded_seal(&seal.encode()), Err(VerifyError::StaleChallenge));
+    });
+}
+
+#[test]
+fn checked_seals_report_why_they_would_be_rejected() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(RubikPow::set_difficulty(RuntimeOrigin::root(), 16));
+        let seal = mine();
+
+        let sized = |cube_size| PowSeal { cube_size, ..seal.clone() };
+        assert_eq!(RubikPow::check_seal(&sized(1)).err(), Some(VerifyError::CubeTooSmall));
+        assert_eq!(RubikPow::check_seal(&sized(crate::MAX_CUBE_SIZE + 1)).err(), Some(VerifyError::CubeTooLarge));
+
+        let mut unsolved = seal.clone();
+        unsolved.moves.pop();
+        assert_eq!(RubikPow::check_seal(&unsolved).err(), Some(VerifyError::InvalidSolution));
+
+        // Solved, but not within the target
+        let template = BlockTemplate {
+            parent_hash: [0; 32],
+            header: RubikPow::preview_challenge().0,
+            cube_size: CUBE_SIZE,
+            target: Target::MAX,
+        };
+        let easy = (1..)
+            .filter_map(|nonce| attempt(&ReverseScramble, &template, nonce))
+            .find(|seal| RubikPow::check_seal(seal).is_err())
+            .unwrap();
+        assert_eq!(RubikPow::check_seal(&easy).err(), Some(VerifyError::TargetNotMet));
+
+        let mut truncated = seal.encode();
+        truncated.pop();
+        assert_eq!(RubikPow::check_encoded_seal(&truncated), Err(VerifyError::MalformedSeal));
+        assert_eq!(RubikPow::check_encoded_seal(&seal.encode()), Ok(()));
+    });
+}
+
+#[test]
+fn difficulty_history_records_every_retarget_point() {
+    use crate::RetargetPoint;
+
+    new_test_ext().execute_with(|| {
+        assert!(RubikPow::difficulty_history().is_empty());
+        let point = |block_number, difficulty, observed_interval| RetargetPoint {
+            block_number,
+            difficulty,
+            target: *Target::from_difficulty(difficulty).as_bytes(),
+            observed_interval,
+        };
+
+        assert_ok!(RubikPow::set_difficulty(RuntimeOrigin::root(), 5));
+        assert_eq!(RubikPow::difficulty_history().to_vec(), vec![point(1, 5, 0)]);
+
+        // A retarget at the end of an interval mined twice as fast as targeted
+        let mut retargeted_at = 0;
+        for block in 2..2 + u64::from(RETARGET_INTERVAL) {
+            System::set_block_number(block);
+            Timestamp::set_timestamp(block * TARGET_BLOCK_TIME_MS / 2);
+            let before = RubikPow::difficulty();
+            submit(mine());
+            if RubikPow::difficulty() != before {
+                retargeted_at = block;
+            }
+        }
+        assert_eq!(
+            RubikPow::difficulty_history().to_vec(),
+            vec![point(1, 5, 0), point(retargeted_at, 10, retargeted_at - 1)]
+        );
+    });
+}
+
+#[test]
+fn difficulty_history_keeps_the_latest_points() {
+    use frame_support::traits::Get;
+
+    new_test_ext().execute_with(|| {
+        let length = <Test as crate::Config>::DifficultyHistoryLength::get();
+        for block in 1..=u64::from(length) + 3 {
+            System::set_block_number(block);
+            assert_ok!(RubikPow::set_difficulty(RuntimeOrigin::root(), block as u32));
+        }
+
+        let history = RubikPow::difficulty_history();
+        assert_eq!(history.len() as u32, length);
+        // The three oldest points were dropped, and intervals still count from them
+        assert_eq!(history.first().map(|point| (point.block_number, point.observed_interval)), Some((4, 1)));
+        assert_eq!(history.last().map(|point| point.difficulty), Some(length + 3));
+        assert!(history.windows(2).all(|pair| pair[1].block_number - pair[0].block_number == pair[1].observed_interval));
+    });
+}
@@ -0,0 +1,292 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Scheduled competitions on a fixed scramble.
+//!
+//! `ScheduleOrigin` announces a tournament: a seed, a cube size, a submission
+//! window and the prizes for the best ranks. Every participant solves the same
+//! scramble, derived from the tournament id and seed. Since the scramble is
+//! public, solutions are submitted in two steps so they cannot be copied from
+//! the transaction pool: during the window a participant commits to
+//! `blake2_256((account, moves, salt))`, and within `RevealPeriod` blocks after
+//! it ends reveals the moves. Revealed solutions are checked with the core
+//! verifier and ranked by move count, ties going to the earlier commitment.
+//! Once the reveal period is over anyone can settle the tournament, which mints
+//! the prizes to the best entries.
+
+use frame_support::{
+    pallet_prelude::*,
+    traits::{Currency, Get},
+};
+use frame_system::pallet_prelude::*;
+use sp_runtime::traits::Saturating;
+use sp_std::vec::Vec;
+
+use pallet_rubikpow::{MAX_CUBE_SIZE, MIN_CUBE_SIZE};
+use qbitcoin_core::{verify_seal, weight::estimate_verification_weight, Move, PowSeal, Target};
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub type TournamentId = u32;
+
+/// Domain separator of tournament challenge headers.
+pub const TOURNAMENT_DOMAIN: &[u8] = b"qbitcoin/tournament";
+
+/// Header bytes the scramble of tournament `id` is derived from, with nonce 0.
+pub fn challenge_header(id: TournamentId, seed: &[u8; 32]) -> Vec<u8> {
+    let mut header = TOURNAMENT_DOMAIN.to_vec();
+    header.extend_from_slice(&id.to_le_bytes());
+    header.extend_from_slice(seed);
+    header
+}
+
+/// What a participant commits to during the window.
+pub fn solution_commitment<AccountId: Encode>(who: &AccountId, moves: &[Move], salt: &[u8; 32]) -> [u8; 32] {
+    sp_io::hashing::blake2_256(&(who, moves, salt).encode())
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    pub type TournamentOf<T> = Tournament<BlockNumberFor<T>, BalanceOf<T>, <T as Config>::MaxPrizes>;
+
+    pub type EntryOf<T> = Entry<<T as frame_system::Config>::AccountId, BlockNumberFor<T>>;
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(MaxPrizes))]
+    pub struct Tournament<BlockNumber, Balance, MaxPrizes: Get<u32>> {
+        pub seed: [u8; 32],
+        pub cube_size: u32,
+        /// First block commitments are accepted in.
+        pub start: BlockNumber,
+        /// Last block commitments are accepted in.
+        pub end: BlockNumber,
+        /// Prize for each rank, best first.
+        pub prizes: BoundedVec<Balance, MaxPrizes>,
+    }
+
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct Commitment<BlockNumber> {
+        pub hash: [u8; 32],
+        pub committed_at: BlockNumber,
+        /// Order of the commitment within the tournament, to break ties
+        /// between commitments of the same block.
+        pub index: u32,
+    }
+
+    /// A revealed and verified solution.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct Entry<AccountId, BlockNumber> {
+        pub who: AccountId,
+        pub moves: u32,
+        pub committed_at: BlockNumber,
+        pub index: u32,
+    }
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        type Currency: Currency<Self::AccountId>;
+        /// Origin allowed to schedule tournaments.
+        type ScheduleOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+        /// Blocks after the window during which solutions can be revealed.
+        #[pallet::constant]
+        type RevealPeriod: Get<BlockNumberFor<Self>>;
+        /// Participants per tournament.
+        #[pallet::constant]
+        type MaxEntries: Get<u32>;
+        /// Ranks that can win a prize.
+        #[pallet::constant]
+        type MaxPrizes: Get<u32>;
+    }
+
+    #[pallet::storage]
+    #[pallet::getter(fn next_tournament_id)]
+    pub type NextTournamentId<T: Config> = StorageValue<_, TournamentId, ValueQuery>;
+
+    /// Tournaments that have not been settled yet.
+    #[pallet::storage]
+    #[pallet::getter(fn tournaments)]
+    pub type Tournaments<T: Config> = StorageMap<_, Blake2_128Concat, TournamentId, TournamentOf<T>, OptionQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn commitment)]
+    pub type Commitments<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        TournamentId,
+        Blake2_128Concat,
+        T::AccountId,
+        Commitment<BlockNumberFor<T>>,
+        OptionQuery,
+    >;
+
+    /// Commitments made so far per tournament.
+    #[pallet::storage]
+    #[pallet::getter(fn commitment_count)]
+    pub type CommitmentCount<T: Config> = StorageMap<_, Blake2_128Concat, TournamentId, u32, ValueQuery>;
+
+    /// Verified entries, best first.
+    #[pallet::storage]
+    #[pallet::getter(fn ranking)]
+    pub type Rankings<T: Config> =
+        StorageMap<_, Blake2_128Concat, TournamentId, BoundedVec<EntryOf<T>, T::MaxEntries>, ValueQuery>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        Scheduled { tournament: TournamentId, cube_size: u32, start: BlockNumberFor<T>, end: BlockNumberFor<T> },
+        Committed { tournament: TournamentId, who: T::AccountId },
+        Revealed { tournament: TournamentId, who: T::AccountId, moves: u32 },
+        PrizePaid { tournament: TournamentId, rank: u32, who: T::AccountId, amount: BalanceOf<T> },
+        Settled { tournament: TournamentId, entries: u32 },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        UnknownTournament,
+        /// The cube size is outside the sizes the chain mines.
+        InvalidCubeSize,
+        /// The window ends before it starts or has already started.
+        InvalidWindow,
+        /// The submission window is not open.
+        NotOpen,
+        /// The reveal period is not running.
+        NotRevealing,
+        /// The reveal period has not ended yet.
+        NotFinished,
+        TooManyEntries,
+        AlreadyCommitted,
+        NoCommitment,
+        AlreadyRevealed,
+        /// The moves and salt do not match the commitment.
+        CommitmentMismatch,
+        /// The moves do not solve the tournament scramble.
+        InvalidSolution,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        #[pallet::call_index(0)]
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(2))]
+        pub fn schedule(
+            origin: OriginFor<T>,
+            seed: [u8; 32],
+            cube_size: u32,
+            start: BlockNumberFor<T>,
+            end: BlockNumberFor<T>,
+            prizes: BoundedVec<BalanceOf<T>, T::MaxPrizes>,
+        ) -> DispatchResult {
+            T::ScheduleOrigin::ensure_origin(origin)?;
+            ensure!((MIN_CUBE_SIZE..=MAX_CUBE_SIZE).contains(&cube_size), Error::<T>::InvalidCubeSize);
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(start > now && end >= start, Error::<T>::InvalidWindow);
+
+            let tournament = Self::next_tournament_id();
+            <Tournaments<T>>::insert(tournament, Tournament { seed, cube_size, start, end, prizes });
+            <NextTournamentId<T>>::put(tournament.saturating_add(1));
+            Self::deposit_event(Event::Scheduled { tournament, cube_size, start, end });
+            Ok(())
+        }
+
+        /// Commits to a solution; see [`solution_commitment`].
+        #[pallet::call_index(1)]
+        #[pallet::weight(10_000 + T::DbWeight::get().reads_writes(3, 2))]
+        pub fn commit(origin: OriginFor<T>, tournament: TournamentId, hash: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let info = Self::tournaments(tournament).ok_or(Error::<T>::UnknownTournament)?;
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(now >= info.start && now <= info.end, Error::<T>::NotOpen);
+            ensure!(!<Commitments<T>>::contains_key(tournament, &who), Error::<T>::AlreadyCommitted);
+            let index = Self::commitment_count(tournament);
+            ensure!(index < T::MaxEntries::get(), Error::<T>::TooManyEntries);
+
+            <Commitments<T>>::insert(tournament, &who, Commitment { hash, committed_at: now, index });
+            <CommitmentCount<T>>::insert(tournament, index + 1);
+            Self::deposit_event(Event::Committed { tournament, who });
+            Ok(())
+        }
+
+        #[pallet::call_index(2)]
+        #[pallet::weight(Pallet::<T>::reveal_weight(moves.len()))]
+        pub fn reveal(origin: OriginFor<T>, tournament: TournamentId, moves: Vec<Move>, salt: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let info = Self::tournaments(tournament).ok_or(Error::<T>::UnknownTournament)?;
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                now > info.end && now <= info.end.saturating_add(T::RevealPeriod::get()),
+                Error::<T>::NotRevealing
+            );
+            let commitment = Self::commitment(tournament, &who).ok_or(Error::<T>::NoCommitment)?;
+            ensure!(commitment.hash == solution_commitment(&who, &moves, &salt), Error::<T>::CommitmentMismatch);
+
+            let mut ranking = Self::ranking(tournament);
+            ensure!(ranking.iter().all(|entry| entry.who != who), Error::<T>::AlreadyRevealed);
+
+            let seal = PowSeal { cube_size: info.cube_size, nonce: 0, moves };
+            verify_seal(&seal, &challenge_header(tournament, &info.seed), &Target::MAX)
+                .map_err(|_| Error::<T>::InvalidSolution)?;
+
+            let entry = Entry {
+                who: who.clone(),
+                moves: seal.moves.len() as u32,
+                committed_at: commitment.committed_at,
+                index: commitment.index,
+            };
+            let key = |entry: &EntryOf<T>| (entry.moves, entry.committed_at, entry.index);
+            let position = ranking.partition_point(|other| key(other) < key(&entry));
+            // Bounded by `MaxEntries` through the commitment count
+            ranking.try_insert(position, entry).map_err(|_| Error::<T>::TooManyEntries)?;
+            <Rankings<T>>::insert(tournament, ranking);
+            Self::deposit_event(Event::Revealed { tournament, who, moves: seal.moves.len() as u32 });
+            Ok(())
+        }
+
+        /// Pays the prizes and removes the tournament.
+        #[pallet::call_index(3)]
+        #[pallet::weight(10_000 + T::DbWeight::get().writes(T::MaxEntries::get().saturating_add(3).into()))]
+        pub fn settle(origin: OriginFor<T>, tournament: TournamentId) -> DispatchResult {
+            ensure_signed(origin)?;
+            let info = Self::tournaments(tournament).ok_or(Error::<T>::UnknownTournament)?;
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(now > info.end.saturating_add(T::RevealPeriod::get()), Error::<T>::NotFinished);
+
+            let ranking = <Rankings<T>>::take(tournament);
+            for (rank, (entry, amount)) in ranking.iter().zip(info.prizes.iter()).enumerate() {
+                let _ = T::Currency::deposit_creating(&entry.who, *amount);
+                Self::deposit_event(Event::PrizePaid {
+                    tournament,
+                    rank: rank as u32,
+                    who: entry.who.clone(),
+                    amount: *amount,
+                });
+            }
+
+            <Tournaments<T>>::remove(tournament);
+            <CommitmentCount<T>>::remove(tournament);
+            let _ = <Commitments<T>>::clear_prefix(tournament, T::MaxEntries::get(), None);
+            Self::deposit_event(Event::Settled { tournament, entries: ranking.len() as u32 });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Weight of `reveal` with `n_moves` moves, which are replayed on a
+        /// cube of the largest size a tournament can be held on.
+        pub fn reveal_weight(n_moves: usize) -> Weight {
+            let verification = estimate_verification_weight(MAX_CUBE_SIZE, n_moves);
+            Weight::from_parts(verification.ref_time, 0).saturating_add(T::DbWeight::get().reads_writes(3, 1))
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use crate::{challenge_header, mock::*, solution_commitment, Error, Event};
+use frame_support::{assert_noop, assert_ok};
+use qbitcoin_core::miner::{attempt, BlockTemplate};
+use qbitcoin_core::solver::ReverseScramble;
+use qbitcoin_core::{Move, Target};
+use sp_runtime::{DispatchError, DispatchResult};
+
+const SEED: [u8; 32] = [7; 32];
+const CUBE_SIZE: u32 = 3;
+const START: u64 = 5;
+const END: u64 = 10;
+
+fn schedule(prizes: Vec<u64>) -> u32 {
+    let id = Tournament::next_tournament_id();
+    assert_ok!(Tournament::schedule(RuntimeOrigin::root(), SEED, CUBE_SIZE, START, END, prizes.try_into().unwrap()));
+    id
+}
+
+/// Moves solving the scramble of tournament `id`, padded with `padding` pairs
+/// of half turns that cancel out.
+fn solution(id: u32, padding: usize) -> Vec<Move> {
+    let template =
+        BlockTemplate { parent_hash: [0; 32], header: challenge_header(id, &SEED), cube_size: CUBE_SIZE, target: Target::MAX };
+    let mut moves = attempt(&ReverseScramble, &template, 0).unwrap().moves;
+    moves.extend(std::iter::repeat(Move::R(2)).take(2 * padding));
+    moves
+}
+
+fn salt(who: AccountId) -> [u8; 32] {
+    [who as u8; 32]
+}
+
+fn commit(who: AccountId, id: u32, moves: &[Move]) -> DispatchResult {
+    Tournament::commit(RuntimeOrigin::signed(who), id, solution_commitment(&who, moves, &salt(who)))
+}
+
+fn reveal(who: AccountId, id: u32, moves: Vec<Move>) -> DispatchResult {
+    Tournament::reveal(RuntimeOrigin::signed(who), id, moves, salt(who))
+}
+
+fn tournament_events() -> Vec<Event<Test>> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            RuntimeEvent::Tournament(event) => Some(event),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn tournaments_are_scheduled_ahead_for_mined_sizes() {
+    new_test_ext().execute_with(|| {
+        let prizes = || vec![100].try_into().unwrap();
+        assert_noop!(
+            Tournament::schedule(RuntimeOrigin::signed(1), SEED, CUBE_SIZE, START, END, prizes()),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Tournament::schedule(RuntimeOrigin::root(), SEED, 1, START, END, prizes()),
+            Error::<Test>::InvalidCubeSize
+        );
+        assert_noop!(
+            Tournament::schedule(RuntimeOrigin::root(), SEED, 17, START, END, prizes()),
+            Error::<Test>::InvalidCubeSize
+        );
+        assert_noop!(
+            Tournament::schedule(RuntimeOrigin::root(), SEED, CUBE_SIZE, 1, END, prizes()),
+            Error::<Test>::InvalidWindow
+        );
+        assert_noop!(
+            Tournament::schedule(RuntimeOrigin::root(), SEED, CUBE_SIZE, START, START - 1, prizes()),
+            Error::<Test>::InvalidWindow
+        );
+
+        assert_eq!(schedule(vec![100]), 0);
+        System::assert_last_event(RuntimeEvent::Tournament(Event::Scheduled {
+            tournament: 0,
+            cube_size: CUBE_SIZE,
+            start: START,
+            end: END,
+        }));
+        assert_eq!(schedule(vec![100]), 1);
+    });
+}
+
+#[test]
+fn commitments_are_taken_up_to_and_including_the_last_block() {
+    new_test_ext().execute_with(|| {
+        let id = schedule(vec![100]);
+        let moves = solution(id, 0);
+        assert_noop!(commit(1, 1, &moves), Error::<Test>::UnknownTournament);
+
+        System::set_block_number(START - 1);
+        assert_noop!(commit(1, id, &moves), Error::<Test>::NotOpen);
+        System::set_block_number(START);
+        assert_ok!(commit(1, id, &moves));
+        System::assert_last_event(RuntimeEvent::Tournament(Event::Committed { tournament: id, who: 1 }));
+        assert_noop!(commit(1, id, &moves), Error::<Test>::AlreadyCommitted);
+
+        System::set_block_number(END);
+        assert_ok!(commit(2, id, &moves));
+        // The window is still open, so nothing can be revealed yet
+        assert_noop!(reveal(1, id, moves.clone()), Error::<Test>::NotRevealing);
+        assert_ok!(commit(3, id, &moves));
+        assert_ok!(commit(4, id, &moves));
+        assert_noop!(commit(5, id, &moves), Error::<Test>::TooManyEntries);
+        assert_eq!(Tournament::commitment(id, 4).unwrap().index, MAX_ENTRIES - 1);
+
+        System::set_block_number(END + 1);
+        assert_noop!(commit(6, id, &moves), Error::<Test>::NotOpen);
+    });
+}
+
+#[test]
+fn reveals_must_open_the_commitment_with_a_solution() {
+    new_test_ext().execute_with(|| {
+        let id = schedule(vec![100]);
+        let moves = solution(id, 0);
+        let mut unsolved = moves.clone();
+        unsolved.pop();
+
+        System::set_block_number(START);
+        assert_ok!(commit(1, id, &moves));
+        assert_ok!(commit(2, id, &unsolved));
+        assert_ok!(commit(3, id, &moves));
+
+        System::set_block_number(END + 1);
+        assert_noop!(reveal(1, id, unsolved.clone()), Error::<Test>::CommitmentMismatch);
+        assert_noop!(
+            Tournament::reveal(RuntimeOrigin::signed(1), id, moves.clone(), salt(2)),
+            Error::<Test>::CommitmentMismatch
+        );
+        // Someone else's commitment does not open for a copied solution
+        assert_noop!(
+            Tournament::reveal(RuntimeOrigin::signed(4), id, moves.clone(), salt(1)),
+            Error::<Test>::NoCommitment
+        );
+        assert_noop!(reveal(2, id, unsolved), Error::<Test>::InvalidSolution);
+
+        assert_ok!(reveal(1, id, moves.clone()));
+        System::assert_last_event(RuntimeEvent::Tournament(Event::Revealed {
+            tournament: id,
+            who: 1,
+            moves: moves.len() as u32,
+        }));
+        assert_noop!(reveal(1, id, moves.clone()), Error::<Test>::AlreadyRevealed);
+
+        // The last block of the reveal period still counts
+        System::set_block_number(END + REVEAL_PERIOD);
+        assert_ok!(reveal(3, id, moves.clone()));
+        System::set_block_number(END + REVEAL_PERIOD + 1);
+        assert_noop!(reveal(3, id, moves), Error::<Test>::NotRevealing);
+    });
+}
+
+#[test]
+fn rankings_order_by_moves_then_commitment_block_then_commitment_order() {
+    new_test_ext().execute_with(|| {
+        let id = schedule(vec![100, 50]);
+        let short = solution(id, 0);
+        let long = solution(id, 1);
+
+        System::set_block_number(START);
+        assert_ok!(commit(1, id, &long));
+        assert_ok!(commit(3, id, &short));
+        assert_ok!(commit(4, id, &short));
+        System::set_block_number(START + 1);
+        assert_ok!(commit(2, id, &short));
+
+        System::set_block_number(END + 1);
+        for (who, moves) in [(2, &short), (1, &long), (4, &short), (3, &short)] {
+            assert_ok!(reveal(who, id, moves.clone()));
+        }
+        let ranking: Vec<_> = Tournament::ranking(id)
+            .iter()
+            .map(|entry| (entry.who, entry.moves, entry.committed_at, entry.index))
+            .collect();
+        let (short, long) = (short.len() as u32, long.len() as u32);
+        assert_eq!(
+            ranking,
+            vec![(3, short, START, 1), (4, short, START, 2), (2, short, START + 1, 3), (1, long, START, 0)]
+        );
+    });
+}
+
+#[test]
+fn settling_pays_the_best_ranks_once_reveals_are_over() {
+    new_test_ext().execute_with(|| {
+        let id = schedule(vec![100, 50]);
+        let moves = solution(id, 0);
+        System::set_block_number(START);
+        for who in 1..=3 {
+            assert_ok!(commit(who, id, &moves));
+        }
+        System::set_block_number(END + 1);
+        for who in [2, 1, 3] {
+            assert_ok!(reveal(who, id, moves.clone()));
+        }
+
+        System::set_block_number(END + REVEAL_PERIOD);
+        assert_noop!(Tournament::settle(RuntimeOrigin::signed(5), id), Error::<Test>::NotFinished);
+
+        System::set_block_number(END + REVEAL_PERIOD + 1);
+        System::reset_events();
+        assert_ok!(Tournament::settle(RuntimeOrigin::signed(5), id));
+        assert_eq!(
+            tournament_events(),
+            vec![
+                Event::PrizePaid { tournament: id, rank: 0, who: 1, amount: 100 },
+                Event::PrizePaid { tournament: id, rank: 1, who: 2, amount: 50 },
+                Event::Settled { tournament: id, entries: 3 },
+            ]
+        );
+        assert_eq!(Balances::free_balance(1), 100);
+        assert_eq!(Balances::free_balance(2), 50);
+        assert_eq!(Balances::free_balance(3), 0);
+
+        assert_eq!(Tournament::tournaments(id), None);
+        assert!(Tournament::ranking(id).is_empty());
+        assert_eq!(Tournament::commitment(id, 1), None);
+        assert_eq!(Tournament::commitment_count(id), 0);
+        assert_noop!(Tournament::settle(RuntimeOrigin::signed(5), id), Error::<Test>::UnknownTournament);
+    });
+}
@@ -0,0 +1,245 @@
+//! Append-only archive of accepted seals.
+//!
+//! Pools and auditors keep every accepted solution in an archive so the whole
+//! history can be re-verified offline. An archive starts with [`MAGIC`],
+//! followed by records of
+//! `payload length: u32 | payload | first 4 bytes of Sha3-256(payload)`,
+//! where the payload is
+//! `height: u64 | header hash: 32 bytes | target: 32 bytes | header length: u32 | header | PowSeal encoding`
+//! (integers little endian). Records are only ever appended; a write cut short
+//! by a crash shows up as a truncated last record and damage anywhere else as
+//! a checksum mismatch.
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use sha3::{Digest, Sha3_256};
+
+use crate::{PowSeal, Target};
+
+/// First bytes of every archive.
+pub const MAGIC: &[u8; 8] = b"QBARCHV1";
+
+const CHECKSUM_LEN: usize = 4;
+/// Largest payload a reader accepts, so a corrupt length cannot make it
+/// allocate without bound.
+pub const MAX_PAYLOAD_LEN: u32 = 1 << 24;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveRecord {
+    pub height: u64,
+    pub header_hash: [u8; 32],
+    /// Header bytes the challenge was derived from.
+    pub header: Vec<u8>,
+    /// Target the seal was checked against.
+    pub target: Target,
+    pub seal: PowSeal,
+}
+
+impl ArchiveRecord {
+    fn encode_payload(&self) -> Vec<u8> {
+        let seal = self.seal.encode();
+        let mut payload = Vec::with_capacity(8 + 32 + 32 + 4 + self.header.len() + seal.len());
+        payload.extend_from_slice(&self.height.to_le_bytes());
+        payload.extend_from_slice(&self.header_hash);
+        payload.extend_from_slice(self.target.as_bytes());
+        payload.extend_from_slice(&(self.header.len() as u32).to_le_bytes());
+        payload.extend_from_slice(&self.header);
+        payload.extend_from_slice(&seal);
+        payload
+    }
+
+    fn decode_payload(payload: &[u8]) -> Option<ArchiveRecord> {
+        let height = u64::from_le_bytes(payload.get(0..8)?.try_into().ok()?);
+        let header_hash = payload.get(8..40)?.try_into().ok()?;
+        let target = Target::from_bytes(payload.get(40..72)?.try_into().ok()?);
+        let header_len = u32::from_le_bytes(payload.get(72..76)?.try_into().ok()?) as usize;
+        let header_end = 76usize.checked_add(header_len)?;
+        let header = payload.get(76..header_end)?.to_vec();
+        let seal = PowSeal::decode(payload.get(header_end..)?)?;
+        Some(ArchiveRecord {
+            height,
+            header_hash,
+            header,
+            target,
+            seal,
+        })
+    }
+}
+
+fn checksum(payload: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let hash = Sha3_256::digest(payload);
+    let mut checksum = [0; CHECKSUM_LEN];
+    checksum.copy_from_slice(&hash[..CHECKSUM_LEN]);
+    checksum
+}
+
+#[derive(Debug)]
+pub enum ArchiveError {
+    Io(io::Error),
+    /// The input does not start with [`MAGIC`].
+    BadMagic,
+    /// The input ends inside the record starting at this byte offset.
+    Truncated { offset: u64 },
+    /// The record at this byte offset fails its checksum.
+    Checksum { offset: u64 },
+    /// The record at this byte offset has a valid checksum but does not
+    /// decode, or declares a payload above [`MAX_PAYLOAD_LEN`].
+    Malformed { offset: u64 },
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveError::Io(e) => write!(f, "archive I/O error: {}", e),
+            ArchiveError::BadMagic => f.write_str("not a seal archive"),
+            ArchiveError::Truncated { offset } => write!(f, "archive truncated in the record at byte {}", offset),
+            ArchiveError::Checksum { offset } => write!(f, "checksum mismatch in the record at byte {}", offset),
+            ArchiveError::Malformed { offset } => write!(f, "malformed record at byte {}", offset),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+impl From<io::Error> for ArchiveError {
+    fn from(e: io::Error) -> Self {
+        ArchiveError::Io(e)
+    }
+}
+
+/// Appends records to an archive.
+pub struct ArchiveWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    /// Starts a new archive on `inner`, writing [`MAGIC`].
+    pub fn new(mut inner: W) -> Result<Self, ArchiveError> {
+        inner.write_all(MAGIC)?;
+        Ok(ArchiveWriter { inner })
+    }
+
+    pub fn append(&mut self, record: &ArchiveRecord) -> Result<(), ArchiveError> {
+        let payload = record.encode_payload();
+        let mut bytes = Vec::with_capacity(4 + payload.len() + CHECKSUM_LEN);
+        bytes.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&payload);
+        bytes.extend_from_slice(&checksum(&payload));
+        // One write per record keeps a crash from interleaving partial records
+        self.inner.write_all(&bytes)?;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), ArchiveError> {
+        self.inner.flush()?;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl ArchiveWriter<File> {
+    /// Opens the archive at `path` for appending, creating it if it does not
+    /// exist. An existing file must start with [`MAGIC`].
+    pub fn open(path: &Path) -> Result<Self, ArchiveError> {
+        let mut file = OpenOptions::new().read(true).append(true).create(true).open(path)?;
+        if file.metadata()?.len() == 0 {
+            return ArchiveWriter::new(file);
+        }
+        let mut magic = [0u8; 8];
+        file.read_exact(&mut magic).map_err(|_| ArchiveError::BadMagic)?;
+        if &magic != MAGIC {
+            return Err(ArchiveError::BadMagic);
+        }
+        Ok(ArchiveWriter { inner: file })
+    }
+}
+
+/// Reads records back in the order they were appended.
+pub struct ArchiveReader<R: Read> {
+    inner: R,
+    offset: u64,
+    failed: bool,
+}
+
+impl<R: Read> ArchiveReader<R> {
+    /// Checks [`MAGIC`] at the start of `inner`.
+    pub fn new(mut inner: R) -> Result<Self, ArchiveError> {
+        let mut magic = [0u8; 8];
+        inner.read_exact(&mut magic).map_err(|_| ArchiveError::BadMagic)?;
+        if &magic != MAGIC {
+            return Err(ArchiveError::BadMagic);
+        }
+        Ok(ArchiveReader {
+            inner,
+            offset: MAGIC.len() as u64,
+            failed: false,
+        })
+    }
+
+    /// Byte offset of the next record.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    fn read_record(&mut self) -> Result<Option<ArchiveRecord>, ArchiveError> {
+        let offset = self.offset;
+        let mut len = [0u8; 4];
+        match read_full(&mut self.inner, &mut len)? {
+            0 => return Ok(None),
+            4 => {}
+            _ => return Err(ArchiveError::Truncated { offset }),
+        }
+        let len = u32::from_le_bytes(len);
+        if len > MAX_PAYLOAD_LEN {
+            return Err(ArchiveError::Malformed { offset });
+        }
+
+        let mut rest = vec![0u8; len as usize + CHECKSUM_LEN];
+        if read_full(&mut self.inner, &mut rest)? != rest.len() {
+            return Err(ArchiveError::Truncated { offset });
+        }
+        let (payload, stored) = rest.split_at(len as usize);
+        if checksum(payload) != stored {
+            return Err(ArchiveError::Checksum { offset });
+        }
+        let record = ArchiveRecord::decode_payload(payload).ok_or(ArchiveError::Malformed { offset })?;
+
+        self.offset += 4 + rest.len() as u64;
+        Ok(Some(record))
+    }
+}
+
+/// Stops at the first error: nothing after a damaged record can be located.
+impl<R: Read> Iterator for ArchiveReader<R> {
+    type Item = Result<ArchiveRecord, ArchiveError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = self.read_record();
+        self.failed = result.is_err();
+        result.transpose()
+    }
+}
+
+/// Like `read_exact`, but returns how many bytes were read before the end of
+/// the input instead of failing.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
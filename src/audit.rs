@@ -0,0 +1,67 @@
+//! Re-verification of archived seals.
+//!
+//! [`audit`] replays every record of an [`archive`](crate::archive) through
+//! [`verify_seal_with_spec`] under the spec version active at the record's
+//! height, so a verifier refactor can be checked against the full history
+//! before it ships.
+
+use std::io::Read;
+
+use crate::archive::{ArchiveError, ArchiveReader};
+use crate::spec::{self, SpecVersion};
+use crate::verify::verify_seal_with_spec;
+use crate::VerifyError;
+
+/// An archived seal that no longer verifies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Discrepancy {
+    pub height: u64,
+    pub header_hash: [u8; 32],
+    pub version: SpecVersion,
+    pub error: VerifyError,
+}
+
+#[derive(Debug, Default)]
+pub struct AuditReport {
+    /// Records read and re-verified.
+    pub checked: u64,
+    pub discrepancies: Vec<Discrepancy>,
+    /// Why reading stopped before the end of the archive, if it did.
+    pub archive_error: Option<ArchiveError>,
+}
+
+impl AuditReport {
+    /// True if the whole archive was read and every seal verified.
+    pub fn is_clean(&self) -> bool {
+        self.discrepancies.is_empty() && self.archive_error.is_none()
+    }
+}
+
+/// Re-verifies every record from `from_height` on.
+pub fn audit<R: Read>(archive: ArchiveReader<R>, from_height: u64) -> AuditReport {
+    let mut report = AuditReport::default();
+    for record in archive {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                report.archive_error = Some(error);
+                break;
+            }
+        };
+        if record.height < from_height {
+            continue;
+        }
+
+        let version = spec::version_at(record.height);
+        if let Err(error) = verify_seal_with_spec(version, &record.seal, &record.header, &record.target) {
+            report.discrepancies.push(Discrepancy {
+                height: record.height,
+                header_hash: record.header_hash,
+                version,
+                error,
+            });
+        }
+        report.checked += 1;
+    }
+    report
+}
@@ -0,0 +1,82 @@
+//! Enumerates distance-to-solved distributions for research.
+//!
+//! ```text
+//! qbit-distances 2 [--out FILE]
+//! qbit-distances <size> <subgroup> [--max-states N] [--out FILE]
+//! ```
+//!
+//! The first form enumerates every 2x2x2 state; the second a named subgroup
+//! (`square`, `r2u2`, `ru2`) of a larger cube. The distribution is written as
+//! `size,distance,count` CSV to stdout or `FILE`, with a summary on stderr.
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use qbitcoin_core::research::{distance_distribution, named_subgroup, subgroup_distance_distribution};
+
+fn usage() -> ExitCode {
+    eprintln!("usage: qbit-distances <size> [subgroup] [--max-states N] [--out FILE]");
+    ExitCode::from(2)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(Ok(size)) = args.first().map(|arg| arg.parse::<usize>()) else {
+        return usage();
+    };
+
+    let mut subgroup = None;
+    let mut max_states = 10_000_000;
+    let mut out = None;
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--max-states" => match rest.next().map(|value| value.parse()) {
+                Some(Ok(value)) => max_states = value,
+                _ => return usage(),
+            },
+            "--out" => match rest.next() {
+                Some(path) => out = Some(path.clone()),
+                None => return usage(),
+            },
+            name if subgroup.is_none() => subgroup = Some(name.to_string()),
+            _ => return usage(),
+        }
+    }
+
+    let distribution = match subgroup {
+        None => match distance_distribution(size) {
+            Some(distribution) => distribution,
+            None => {
+                eprintln!("only the 2x2x2 can be enumerated fully; name a subgroup");
+                return usage();
+            }
+        },
+        Some(name) => match named_subgroup(&name) {
+            Some(generators) => subgroup_distance_distribution(size, &generators, max_states),
+            None => {
+                eprintln!("unknown subgroup {}", name);
+                return usage();
+            }
+        },
+    };
+
+    let written = match out {
+        Some(path) => File::create(&path).and_then(|file| distribution.write_csv(io::BufWriter::new(file))),
+        None => distribution.write_csv(io::stdout().lock()),
+    };
+    if let Err(err) = written {
+        eprintln!("cannot write distribution: {}", err);
+        return ExitCode::FAILURE;
+    }
+
+    let _ = writeln!(
+        io::stderr(),
+        "{} states, diameter {}, mean distance {:.3}",
+        distribution.states(),
+        distribution.diameter(),
+        distribution.mean()
+    );
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,91 @@
+//! Replays derivation and verification of a seal step by step.
+//!
+//! ```text
+//! qbit-replay <header-hex> <seal-hex> [--difficulty N] [--expect TRACE]
+//! ```
+//!
+//! `seal-hex` is the `PowSeal` encoding (cube size, nonce and moves). The
+//! trace is printed one `label: value` line per step; with `--expect`, it is
+//! compared against a trace dumped by another node version and the first
+//! divergent step is reported.
+
+use std::process::ExitCode;
+
+use qbitcoin_core::replay::{first_divergence, replay};
+use qbitcoin_core::{PowSeal, Target};
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim().trim_start_matches("0x");
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn usage() -> ExitCode {
+    eprintln!("usage: qbit-replay <header-hex> <seal-hex> [--difficulty N] [--expect TRACE]");
+    ExitCode::from(2)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() < 2 {
+        return usage();
+    }
+    let (Some(header), Some(seal_bytes)) = (from_hex(&args[0]), from_hex(&args[1])) else {
+        eprintln!("header and seal must be hex");
+        return usage();
+    };
+    let Some(seal) = PowSeal::decode(&seal_bytes) else {
+        eprintln!("seal does not decode");
+        return ExitCode::FAILURE;
+    };
+
+    let mut target = Target::MAX;
+    let mut expect = None;
+    let mut rest = args[2..].iter();
+    while let Some(flag) = rest.next() {
+        match (flag.as_str(), rest.next()) {
+            ("--difficulty", Some(value)) => match value.parse() {
+                Ok(difficulty) => target = Target::from_difficulty(difficulty),
+                Err(_) => return usage(),
+            },
+            ("--expect", Some(path)) => expect = Some(path.clone()),
+            _ => return usage(),
+        }
+    }
+
+    let trace = replay(&seal, &header, &target);
+    for step in &trace {
+        println!("{}", step);
+    }
+
+    if let Some(path) = expect {
+        let theirs: Vec<String> = match std::fs::read_to_string(&path) {
+            Ok(text) => text.lines().map(str::to_string).collect(),
+            Err(e) => {
+                eprintln!("cannot read {}: {}", path, e);
+                return ExitCode::FAILURE;
+            }
+        };
+        if let Some(index) = first_divergence(&trace, &theirs) {
+            eprintln!("diverges at step {}:", index);
+            eprintln!(
+                "  ours:   {}",
+                trace
+                    .get(index)
+                    .map_or("<end>".to_string(), |s| s.to_string())
+            );
+            eprintln!(
+                "  theirs: {}",
+                theirs.get(index).map_or("<end>", String::as_str)
+            );
+            return ExitCode::FAILURE;
+        }
+        eprintln!("traces match");
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,48 @@
+//! SCALE encoding of moves for runtime calls and storage.
+//!
+//! A move encodes as its one-byte tag from [`spec::encode_move`], not as a
+//! derived enum index, so the wire format does not depend on the order of
+//! `Move`'s variants. Decoding rejects reserved and unassigned tags.
+
+use codec::{Decode, Encode, EncodeLike, Error, Input, MaxEncodedLen, Output};
+use scale_info::build::Fields;
+use scale_info::{Path, Type, TypeInfo};
+
+use crate::spec::{self, SpecVersion};
+use crate::Move;
+
+impl Encode for Move {
+    fn size_hint(&self) -> usize {
+        1
+    }
+
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        dest.push_byte(spec::encode_move(SpecVersion::CURRENT, self));
+    }
+}
+
+impl EncodeLike for Move {}
+
+impl Decode for Move {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        let tag = input.read_byte()?;
+        spec::decode_move(SpecVersion::CURRENT, tag).ok_or_else(|| Error::from("unknown move tag"))
+    }
+}
+
+impl MaxEncodedLen for Move {
+    fn max_encoded_len() -> usize {
+        1
+    }
+}
+
+impl TypeInfo for Move {
+    type Identity = Self;
+
+    fn type_info() -> Type {
+        Type::builder()
+            .path(Path::new("Move", module_path!()))
+            .docs(&["One-byte move tag, see `spec::encode_move`."])
+            .composite(Fields::unnamed().field(|f| f.ty::<u8>().type_name("u8")))
+    }
+}
@@ -0,0 +1,114 @@
+//! State commitments checked against the proof-of-work target.
+//!
+//! The commitment hashes which face each sticker belongs to in the solved
+//! state, never the `Color` values themselves, so colour schemes, per-challenge
+//! recolouring and any reordering of the `Color` enum leave it unchanged.
+
+#![deny(clippy::indexing_slicing, clippy::panic, clippy::unreachable)]
+
+use alloc::vec::Vec;
+
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::spec::{STATE_DOMAIN_V1, STATE_DOMAIN_V2};
+use crate::{Cube, Move};
+
+/// Domain separator for the links of [`solution_transcript`].
+const TRANSCRIPT_DOMAIN: &[u8] = b"qbitcoin/transcript";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "scale-codec",
+    derive(codec::Encode, codec::Decode, codec::MaxEncodedLen, scale_info::TypeInfo)
+)]
+pub enum CommitmentVersion {
+    /// Keccak-256 over a domain tag, the size (u32, little endian) and one
+    /// face index byte per sticker in `Face::ALL`, row-major order.
+    #[cfg_attr(feature = "scale-codec", codec(index = 1))]
+    V1FaceIds,
+    /// Keccak-256 over a domain tag and `Cube::to_packed_bytes`.
+    #[cfg_attr(feature = "scale-codec", codec(index = 2))]
+    V2Packed,
+}
+
+impl CommitmentVersion {
+    pub const CURRENT: CommitmentVersion = CommitmentVersion::V2Packed;
+    pub const ALL: [CommitmentVersion; 2] = [CommitmentVersion::V1FaceIds, CommitmentVersion::V2Packed];
+}
+
+impl Cube {
+    /// Face index (position in `Face::ALL`) of every sticker's solved face,
+    /// in `Face::ALL`, row-major order.
+    pub fn face_ids(&self) -> Vec<u8> {
+        self.stickers()
+            .iter()
+            .map(|&color| self.scheme.face_of(color).index() as u8)
+            .collect()
+    }
+
+    /// Commitment to the sticker state under [`CommitmentVersion::CURRENT`].
+    pub fn state_commitment(&self) -> [u8; 32] {
+        self.state_commitment_with(CommitmentVersion::CURRENT)
+    }
+
+    pub fn state_commitment_with(&self, version: CommitmentVersion) -> [u8; 32] {
+        let mut hasher = Keccak::v256();
+        let mut result = [0u8; 32];
+
+        match version {
+            CommitmentVersion::V1FaceIds => {
+                hasher.update(STATE_DOMAIN_V1);
+                hasher.update(&(self.size as u32).to_le_bytes());
+                hasher.update(&self.face_ids());
+            }
+            CommitmentVersion::V2Packed => {
+                hasher.update(STATE_DOMAIN_V2);
+                hasher.update(&self.to_packed_bytes());
+            }
+        }
+
+        hasher.finalize(&mut result);
+        result
+    }
+}
+
+/// Hash chain over the state commitments of `cube` and of every state a
+/// solution passes through: link `i` is Keccak-256 of a domain tag, link
+/// `i - 1` (zeros for the first) and the commitment after `i` moves. The
+/// result has `moves.len() + 1` links.
+///
+/// Not consensus data; the chain is an audit trail for explorers and lets
+/// pools deduplicate shares by their final link.
+pub fn solution_transcript(cube: &Cube, moves: &[Move]) -> Vec<[u8; 32]> {
+    let mut state = cube.clone();
+    let mut links = Vec::with_capacity(moves.len() + 1);
+    let mut link = transcript_link(&[0u8; 32], &state);
+    links.push(link);
+    for m in moves {
+        state.apply_move(m);
+        link = transcript_link(&link, &state);
+        links.push(link);
+    }
+    links
+}
+
+/// Final link of [`solution_transcript`].
+pub fn solution_fingerprint(cube: &Cube, moves: &[Move]) -> [u8; 32] {
+    let mut state = cube.clone();
+    let mut link = transcript_link(&[0u8; 32], &state);
+    for m in moves {
+        state.apply_move(m);
+        link = transcript_link(&link, &state);
+    }
+    link
+}
+
+fn transcript_link(previous: &[u8; 32], state: &Cube) -> [u8; 32] {
+    let mut link = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(TRANSCRIPT_DOMAIN);
+    hasher.update(previous);
+    hasher.update(&state.state_commitment());
+    hasher.finalize(&mut link);
+    link
+}
@@ -0,0 +1,163 @@
+//! A second cube engine, for double-checking seals.
+//!
+//! [`Cube`](crate::Cube) moves stickers through permutations built once per
+//! move. This engine tracks cubies instead: each keeps its position and the
+//! images of the three axes under its rotation, and a move rotates the cubies
+//! of the turned layers. It shares no geometry with the sticker engine, not
+//! even the sticker coordinates, so a bug in either shows up as the two
+//! disagreeing. It is slower, and only used when [`Rules::double_check`]
+//! asks for it.
+//!
+//! [`Rules::double_check`]: crate::Rules::double_check
+
+#![deny(clippy::indexing_slicing, clippy::panic, clippy::unreachable)]
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use crate::{Face, Move};
+
+type Vec3 = [i32; 3];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cubie {
+    /// Doubled, centred position, like [`sticker`] returns.
+    position: Vec3,
+    /// Where the cubie's rotation takes the x, y and z axes.
+    frame: [Vec3; 3],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CubieCube {
+    size: usize,
+    cubies: Vec<Cubie>,
+}
+
+impl CubieCube {
+    /// A solved cube of `size`; the caller checks the size.
+    pub fn new(size: usize) -> Self {
+        let m = size as i32 - 1;
+        let coords: Vec<i32> = (0..size as i32).map(|i| 2 * i - m).collect();
+        let mut cubies = Vec::new();
+        for &x in &coords {
+            for &y in &coords {
+                for &z in &coords {
+                    let position = [x, y, z];
+                    if position.iter().any(|c| c.abs() == m) {
+                        cubies.push(Cubie {
+                            position,
+                            frame: [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+                        });
+                    }
+                }
+            }
+        }
+        CubieCube { size, cubies }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn apply_move(&mut self, m: &Move) {
+        let axis = normal(m.face());
+        let layers = m.layers(self.size);
+        let last = self.size as i32 - 1;
+        for _ in 0..m.count() % 4 {
+            for cubie in &mut self.cubies {
+                let layer = (last - dot(cubie.position, axis)) / 2;
+                if usize::try_from(layer).is_ok_and(|layer| layers.contains(&layer)) {
+                    cubie.position = quarter_turn(cubie.position, axis);
+                    cubie.frame = cubie.frame.map(|v| quarter_turn(v, axis));
+                }
+            }
+        }
+    }
+
+    /// Every face shows stickers from a single face of the solved cube.
+    pub fn is_solved(&self) -> bool {
+        let m = self.size as i32 - 1;
+        Face::ALL.iter().all(|&face| {
+            let outward = normal(face);
+            let mut homes = self
+                .cubies
+                .iter()
+                .filter(|cubie| dot(cubie.position, outward) == m)
+                .map(|cubie| home_normal(cubie, outward));
+            match homes.next() {
+                Some(first) => homes.all(|home| home == first),
+                None => true,
+            }
+        })
+    }
+
+    /// The solved face of every sticker, in the order and encoding of
+    /// [`Cube::face_ids`](crate::Cube::face_ids). A sticker no cubie carries,
+    /// which a correct engine never has, reads `u8::MAX`.
+    pub fn face_ids(&self) -> Vec<u8> {
+        let at: BTreeMap<Vec3, &Cubie> = self.cubies.iter().map(|cubie| (cubie.position, cubie)).collect();
+        let mut ids = Vec::with_capacity(6 * self.size * self.size);
+        for face in Face::ALL {
+            for row in 0..self.size {
+                for col in 0..self.size {
+                    let (position, outward) = sticker(self.size, face, row, col);
+                    let id = at
+                        .get(&position)
+                        .map(|cubie| home_normal(cubie, outward))
+                        .and_then(|home| Face::ALL.iter().position(|&face| normal(face) == home))
+                        .map_or(u8::MAX, |index| index as u8);
+                    ids.push(id);
+                }
+            }
+        }
+        ids
+    }
+}
+
+/// Position and outward normal of the sticker at `row`, `col` of `face`, with
+/// faces laid out as seen from outside: the side faces have row 0 at the top
+/// and go round F, R, B, L; Up has row 0 at the back and Down at the front,
+/// both with column 0 on the left.
+fn sticker(size: usize, face: Face, row: usize, col: usize) -> (Vec3, Vec3) {
+    let m = size as i32 - 1;
+    let (r, c) = (2 * row as i32 - m, 2 * col as i32 - m);
+    let position = match face {
+        Face::Up => [c, m, r],
+        Face::Down => [c, -m, -r],
+        Face::Front => [c, -r, m],
+        Face::Back => [-c, -r, -m],
+        Face::Right => [m, -r, -c],
+        Face::Left => [-m, -r, c],
+    };
+    (position, normal(face))
+}
+
+fn normal(face: Face) -> Vec3 {
+    match face {
+        Face::Up => [0, 1, 0],
+        Face::Down => [0, -1, 0],
+        Face::Left => [-1, 0, 0],
+        Face::Right => [1, 0, 0],
+        Face::Front => [0, 0, 1],
+        Face::Back => [0, 0, -1],
+    }
+}
+
+/// Which way the sticker now facing `outward` faced on the solved cube: the
+/// inverse rotation, i.e. the transposed frame, applied to `outward`.
+fn home_normal(cubie: &Cubie, outward: Vec3) -> Vec3 {
+    cubie.frame.map(|axis| dot(axis, outward))
+}
+
+fn dot(a: Vec3, b: Vec3) -> i32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Quarter turn of `v` clockwise as seen from the tip of the unit vector
+/// `axis`: `(v · axis) axis + v × axis`.
+fn quarter_turn(v: Vec3, axis: Vec3) -> Vec3 {
+    let [x, y, z] = v;
+    let [a, b, c] = axis;
+    let along = dot(v, axis);
+    [along * a + y * c - z * b, along * b + z * a - x * c, along * c + x * b - y * a]
+}
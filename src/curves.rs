@@ -0,0 +1,127 @@
+//! Integer reward and efficiency curves.
+//!
+//! Fractions are per-mille: [`ONE`] is 1000 and stands for 100%. Curves are
+//! piecewise linear through a fixed set of points and are evaluated with
+//! integer arithmetic only, rounding down and saturating instead of
+//! overflowing, so the runtime and the miner get the same answer on every
+//! platform.
+
+/// 100% in per-mille.
+pub const ONE: u32 = 1000;
+
+/// `amount · per_mille / 1000`, rounded down. Saturates at `u128::MAX`.
+pub fn apply(amount: u128, per_mille: u32) -> u128 {
+    let (whole, rest) = (amount / u128::from(ONE), amount % u128::from(ONE));
+    // `rest · per_mille` stays far below `u128::MAX`
+    whole
+        .saturating_mul(u128::from(per_mille))
+        .saturating_add(rest * u128::from(per_mille) / u128::from(ONE))
+}
+
+/// `numerator / denominator` in per-mille, rounded down and saturating at
+/// `u32::MAX`; `None` for a zero denominator.
+pub fn ratio(numerator: u128, denominator: u128) -> Option<u32> {
+    if denominator == 0 {
+        return None;
+    }
+    let (whole, rest) = (numerator / denominator, numerator % denominator);
+    let fraction = match rest.checked_mul(u128::from(ONE)) {
+        Some(scaled) => scaled / denominator,
+        // Only for denominators above `u128::MAX / 1000`
+        None => (rest / (denominator / u128::from(ONE))).min(u128::from(ONE - 1)),
+    };
+    let per_mille = whole.saturating_mul(u128::from(ONE)).saturating_add(fraction);
+    Some(u32::try_from(per_mille).unwrap_or(u32::MAX))
+}
+
+/// What is left of 100% after `steps` steps that each keep `retained`
+/// per-mille of the previous value, rounding down every step. `retained`
+/// above [`ONE`] counts as `ONE`.
+pub fn compound(retained: u32, steps: u32) -> u32 {
+    let retained = retained.min(ONE);
+    let mut value = ONE;
+    for _ in 0..steps {
+        if value == 0 || retained == ONE {
+            break;
+        }
+        // Strictly decreasing, so this runs at most 1000 times
+        value = value * retained / ONE;
+    }
+    value
+}
+
+/// Piecewise-linear curve through `N` points `(x, per-mille)` with strictly
+/// increasing `x`. Below the first point it is flat at the first value, above
+/// the last at the last value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Curve<const N: usize> {
+    points: [(u32, u32); N],
+}
+
+impl<const N: usize> Curve<N> {
+    /// Panics, at compile time for constants, unless `N > 0` and the `x`
+    /// coordinates strictly increase.
+    pub const fn new(points: [(u32, u32); N]) -> Self {
+        match Curve::try_new(points) {
+            Some(curve) => curve,
+            None => panic!("curve points need strictly increasing x"),
+        }
+    }
+
+    pub const fn try_new(points: [(u32, u32); N]) -> Option<Self> {
+        if N == 0 {
+            return None;
+        }
+        let mut i = 1;
+        while i < N {
+            if points[i - 1].0 >= points[i].0 {
+                return None;
+            }
+            i += 1;
+        }
+        Some(Curve { points })
+    }
+
+    pub fn points(&self) -> &[(u32, u32); N] {
+        &self.points
+    }
+
+    /// Per-mille value at `x`, always between the neighbouring points' values.
+    pub fn eval(&self, x: u32) -> u32 {
+        let (first_x, first_y) = self.points[0];
+        if x <= first_x {
+            return first_y;
+        }
+        for pair in self.points.windows(2) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            if x > x1 {
+                continue;
+            }
+            let (dx, span) = (u64::from(x - x0), u64::from(x1 - x0));
+            // The step is at most |y1 - y0| as dx <= span
+            return if y1 >= y0 {
+                y0 + (u64::from(y1 - y0) * dx / span) as u32
+            } else {
+                y0 - (u64::from(y0 - y1) * dx / span) as u32
+            };
+        }
+        self.points[N - 1].1
+    }
+
+    /// `amount` scaled by the curve's value at `x`.
+    pub fn scale(&self, x: u32, amount: u128) -> u128 {
+        apply(amount, self.eval(x))
+    }
+
+    /// True if no point is lower than the one before, so `eval` never
+    /// decreases.
+    pub fn is_non_decreasing(&self) -> bool {
+        self.points.windows(2).all(|pair| pair[0].1 <= pair[1].1)
+    }
+
+    /// True if no point is higher than the one before, so `eval` never
+    /// increases.
+    pub fn is_non_increasing(&self) -> bool {
+        self.points.windows(2).all(|pair| pair[0].1 >= pair[1].1)
+    }
+}
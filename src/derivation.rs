@@ -0,0 +1,117 @@
+//! Step-by-step account of how a challenge is derived, for comparing
+//! implementations.
+//!
+//! [`derive_challenge_trace`] records every value [`Cube::scramble_with`]
+//! computes on the way from a header and nonce to a scramble: the bytes
+//! hashed into the seed, the colour permutation hash and swaps, each value
+//! drawn from the scramble RNG with the filter that rejected it, if any, and
+//! the moves that result. The draws come from the consensus code itself, so
+//! the trace cannot drift from it. Printing a trace and one from another
+//! implementation side by side shows the first value they disagree on.
+//!
+//! [`Cube::scramble_with`]: crate::Cube::scramble_with
+
+use std::fmt;
+
+use crate::replay::to_hex;
+use crate::spec::{self, ColorDraw, ScrambleDraw, ScrambleFilter, COLOR_PERMUTATION_DOMAIN};
+use crate::{notation, Color, Move, ScrambleSpec, SpecVersion};
+
+/// Everything derived for one challenge, in derivation order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChallengeTrace {
+    pub version: SpecVersion,
+    pub scramble_spec: ScrambleSpec,
+    /// Bytes hashed into the seed: the nonce (u64, little endian), then the header.
+    pub seed_input: Vec<u8>,
+    pub seed: [u8; 32],
+    /// Bytes hashed for the colour permutation: its domain tag, then the seed.
+    pub color_input: Vec<u8>,
+    pub color_hash: [u8; 32],
+    /// The Fisher-Yates swaps, as `(position, draw, swapped with)`.
+    pub color_swaps: Vec<(usize, u16, usize)>,
+    pub colors: [Color; 6],
+    /// Whether challenges are recoloured with `colors`, which only builds
+    /// with the `color-shuffle` feature do.
+    pub recolored: bool,
+    pub draws: Vec<ScrambleDraw>,
+    /// The scramble, as applied to cubes of size 2 and up. Smaller cubes
+    /// stay solved.
+    pub moves: Vec<Move>,
+}
+
+/// Trace of the challenge for `nonce` on `header` under the current spec
+/// version and [`ScrambleSpec::V1`].
+pub fn derive_challenge_trace(header: &[u8], nonce: u64) -> ChallengeTrace {
+    derive_challenge_trace_with(SpecVersion::CURRENT, &ScrambleSpec::V1, header, nonce)
+}
+
+/// [`derive_challenge_trace`] under an explicit version and scramble spec,
+/// which must be valid.
+pub fn derive_challenge_trace_with(
+    version: SpecVersion,
+    scramble: &ScrambleSpec,
+    header: &[u8],
+    nonce: u64,
+) -> ChallengeTrace {
+    let mut seed_input = nonce.to_le_bytes().to_vec();
+    seed_input.extend_from_slice(header);
+    let seed = spec::challenge_seed(version, nonce, header);
+
+    let mut color_input = COLOR_PERMUTATION_DOMAIN.to_vec();
+    color_input.extend_from_slice(&seed);
+    let mut color_hash = [0; 32];
+    let mut color_swaps = Vec::new();
+    let colors = spec::color_permutation_observed(version, &seed, |draw| match draw {
+        ColorDraw::Hash(hash) => color_hash = hash,
+        ColorDraw::Swap { position, draw, with } => color_swaps.push((position, draw, with)),
+    });
+
+    let mut draws = Vec::new();
+    let moves = spec::scramble_moves_observed(version, scramble, &seed, |draw| draws.push(draw));
+
+    ChallengeTrace {
+        version,
+        scramble_spec: *scramble,
+        seed_input,
+        seed,
+        color_input,
+        color_hash,
+        color_swaps,
+        colors,
+        recolored: cfg!(feature = "color-shuffle"),
+        draws,
+        moves,
+    }
+}
+
+impl fmt::Display for ChallengeTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "version      {:?}", self.version)?;
+        writeln!(f, "seed input   {}", to_hex(&self.seed_input))?;
+        writeln!(f, "seed         {}", to_hex(&self.seed))?;
+        writeln!(f, "color input  {}", to_hex(&self.color_input))?;
+        writeln!(f, "color hash   {}", to_hex(&self.color_hash))?;
+        for &(position, draw, with) in &self.color_swaps {
+            writeln!(f, "color swap   {position} <-> {draw} % {} = {with}", position + 1)?;
+        }
+        let colors: Vec<String> = self.colors.iter().map(Color::to_string).collect();
+        let applied = if self.recolored { "" } else { " (not applied)" };
+        writeln!(f, "colors       {}{applied}", colors.join(" "))?;
+        for draw in &self.draws {
+            match draw {
+                ScrambleDraw::Length(length) => writeln!(f, "length       {length}")?,
+                ScrambleDraw::Face { face, rejected_by: None } => writeln!(f, "face         {face}")?,
+                ScrambleDraw::Face { face, rejected_by: Some(filter) } => {
+                    let filter = match filter {
+                        ScrambleFilter::DistinctFaces => "distinct faces",
+                        ScrambleFilter::NoAxisRuns => "no axis runs",
+                    };
+                    writeln!(f, "face         {face} rejected by {filter}")?
+                }
+                ScrambleDraw::Count(count) => writeln!(f, "count        {count}")?,
+            }
+        }
+        write!(f, "moves        {}", notation::format_algorithm(&self.moves))
+    }
+}
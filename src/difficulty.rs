@@ -0,0 +1,102 @@
+//! Work and reward scaling across cube sizes.
+//!
+//! A seal's work is the expected number of attempts its target asks for,
+//! weighted by what one attempt costs on its cube size: scrambling and
+//! committing to an n×n×n cube touches `6n²` stickers per step, so the weight
+//! is `n²`. The pallet's block reward and fork-choice work, the miner's
+//! choice between templates and wallets' time-to-block estimates all go
+//! through the functions below. The base reward halves every
+//! [`HALVING_INTERVAL`] blocks, and the block reward scales with the
+//! solution's length along [`EFFICIENCY_CURVE`], so shorter solutions earn
+//! more.
+//!
+//! Everything here only uses `core` and integer arithmetic, so it compiles
+//! unchanged for the Wasm runtime.
+
+use crate::curves::{self, Curve};
+use crate::Target;
+
+/// Cube size whose seals earn exactly the base reward.
+pub const REFERENCE_SIZE: u32 = 3;
+
+/// Relative cost of one attempt on an n×n×n cube, `n²`.
+pub fn size_weight(size: u32) -> u128 {
+    u128::from(size) * u128::from(size)
+}
+
+/// Expected number of hashes per success, `2^256 / (target + 1)`, from the
+/// target's upper 128 bits. Exact for every [`Target::from_difficulty`]
+/// value; saturates at `u128::MAX` for targets below `2^128`.
+pub fn expected_hashes(target: &Target) -> u128 {
+    let mut high = [0u8; 16];
+    high.copy_from_slice(&target.as_bytes()[..16]);
+    match u128::from_be_bytes(high) {
+        0 => u128::MAX,
+        high => u128::MAX / high,
+    }
+}
+
+/// Work a seal of `size` contributes when checked against `target`. Fork
+/// choice adds this up; saturates at `u128::MAX`.
+pub fn work_per_seal(size: u32, target: &Target) -> u128 {
+    expected_hashes(target).saturating_mul(size_weight(size))
+}
+
+/// Reward for a seal of `size` checked against `target`: `base` for a
+/// [`REFERENCE_SIZE`] seal, scaled by the seal's work relative to one. A
+/// retarget changes both by the same factor, so the reward follows the cube
+/// size but not the difficulty. Saturates at `u32::MAX`.
+pub fn scale_reward(base: u32, size: u32, target: &Target) -> u32 {
+    let work = work_per_seal(size, target);
+    let reference = work_per_seal(REFERENCE_SIZE, target);
+    let reward = match work.checked_mul(u128::from(base)) {
+        Some(product) => product / reference,
+        None => (work / reference).saturating_mul(u128::from(base)),
+    };
+    u32::try_from(reward).unwrap_or(u32::MAX)
+}
+
+/// Blocks between two halvings of the base reward, four years of six-second
+/// blocks.
+pub const HALVING_INTERVAL: u32 = 21_024_000;
+
+/// Base reward paid at `height`: `base` halved once for every
+/// [`HALVING_INTERVAL`] blocks before it, rounding down in per-mille steps.
+pub fn emission(base: u32, height: u32) -> u32 {
+    let remaining = curves::compound(curves::ONE / 2, height / HALVING_INTERVAL);
+    u32::try_from(curves::apply(u128::from(base), remaining)).unwrap_or(u32::MAX)
+}
+
+/// Reward per-mille by solution length, itself per-mille of a reference
+/// length: a quarter more at half the reference, the full reward at it and a
+/// quarter less from one and a half times it.
+pub const EFFICIENCY_CURVE: Curve<3> = Curve::new([(500, 1250), (1000, 1000), (1500, 750)]);
+
+/// `reward` scaled along [`EFFICIENCY_CURVE`] by a solution `length` moves
+/// long, relative to `reference` moves. A zero reference leaves the reward
+/// unchanged. Saturates at `u32::MAX`.
+pub fn efficiency_reward(reward: u32, length: u32, reference: u32) -> u32 {
+    let Some(relative) = curves::ratio(u128::from(length), u128::from(reference)) else {
+        return reward;
+    };
+    u32::try_from(EFFICIENCY_CURVE.scale(relative, u128::from(reward))).unwrap_or(u32::MAX)
+}
+
+/// Milliseconds a miner making `attempts_per_second` attempts is expected to
+/// take to meet `target`; `None` for a zero rate. Saturates at `u64::MAX`.
+pub fn expected_time_ms(target: &Target, attempts_per_second: u64) -> Option<u64> {
+    if attempts_per_second == 0 {
+        return None;
+    }
+    let millis = expected_hashes(target).saturating_mul(1000) / u128::from(attempts_per_second);
+    Some(u64::try_from(millis).unwrap_or(u64::MAX))
+}
+
+/// Expected earnings per day of a miner making `attempts_per_second` attempts
+/// on `size` challenges against `target`, under a schedule paying `base` per
+/// [`REFERENCE_SIZE`] seal.
+pub fn expected_reward_per_day(base: u32, size: u32, target: &Target, attempts_per_second: u64) -> u128 {
+    const SECONDS_PER_DAY: u128 = 86_400;
+    let attempts = u128::from(attempts_per_second).saturating_mul(SECONDS_PER_DAY);
+    u128::from(scale_reward(base, size, target)).saturating_mul(attempts) / expected_hashes(target)
+}
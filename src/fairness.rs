@@ -0,0 +1,130 @@
+//! Solver-agnostic fairness checks for the scramble generator.
+//!
+//! Challenges are sorted into easy, medium and hard buckets by their optimal
+//! solution length, which no solver can improve on. Suites of challenges per
+//! bucket are derived from consecutive nonces over a fixed header, so every
+//! release sees the same sets, and the share of scrambles falling into each
+//! bucket is compared with the share of all states in it: a generator that
+//! drifts towards easy states shows up here whatever solver miners use.
+//!
+//! Only the 2x2x2 has an optimal solver, so only size 2 is supported.
+
+use crate::optimal::{self, OptimalSolver};
+use crate::solver::Solver;
+use crate::Cube;
+
+/// Block header every suite and sample is derived from.
+pub const SUITE_HEADER: &[u8] = b"qbitcoin/fairness-suite";
+
+/// Largest allowed difference between a sampled and the expected bucket share.
+pub const SHARE_TOLERANCE: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Bucket {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Bucket {
+    pub const ALL: [Bucket; 3] = [Bucket::Easy, Bucket::Medium, Bucket::Hard];
+
+    /// Bucket of a 2x2x2 challenge with the given optimal length: up to 8
+    /// moves is easy, 9 medium and 10 or more hard.
+    pub fn of_length(size: usize, optimal_length: u8) -> Option<Bucket> {
+        if size != 2 {
+            return None;
+        }
+        Some(match optimal_length {
+            0..=8 => Bucket::Easy,
+            9 => Bucket::Medium,
+            _ => Bucket::Hard,
+        })
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuiteEntry {
+    pub nonce: u64,
+    pub optimal_length: u8,
+    pub bucket: Bucket,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChallengeSuite {
+    pub size: usize,
+    /// Entries grouped by bucket, easy first, each group in nonce order.
+    pub entries: Vec<SuiteEntry>,
+}
+
+impl ChallengeSuite {
+    pub fn bucket(&self, bucket: Bucket) -> impl Iterator<Item = &SuiteEntry> {
+        self.entries.iter().filter(move |entry| entry.bucket == bucket)
+    }
+}
+
+/// Optimal length of the challenge for `nonce` over `header`.
+fn optimal_length(size: usize, nonce: u64, header: &[u8]) -> Option<u8> {
+    let mut cube = Cube::new(size);
+    let scramble = cube.scramble_deterministic(nonce, header);
+    OptimalSolver
+        .solve(&cube, &scramble)
+        .map(|solution| solution.len() as u8)
+}
+
+/// The first `per_bucket` challenges of each bucket, scanning nonces from 0
+/// over [`SUITE_HEADER`]. `None` for sizes without an optimal solver.
+pub fn challenge_suite(size: usize, per_bucket: usize) -> Option<ChallengeSuite> {
+    Bucket::of_length(size, 0)?;
+
+    let mut groups: [Vec<SuiteEntry>; 3] = Default::default();
+    let mut nonce = 0;
+    while groups.iter().any(|group| group.len() < per_bucket) {
+        let length = optimal_length(size, nonce, SUITE_HEADER)?;
+        let bucket = Bucket::of_length(size, length)?;
+        let group = &mut groups[bucket.index()];
+        if group.len() < per_bucket {
+            group.push(SuiteEntry {
+                nonce,
+                optimal_length: length,
+                bucket,
+            });
+        }
+        nonce += 1;
+    }
+
+    Some(ChallengeSuite {
+        size,
+        entries: groups.into_iter().flatten().collect(),
+    })
+}
+
+/// Share of all reachable states in each bucket, which a well-mixed scramble
+/// generator should reproduce.
+pub fn expected_shares(size: usize) -> Option<[f64; 3]> {
+    Bucket::of_length(size, 0)?;
+
+    let counts = optimal::distances();
+    let total: u64 = counts.iter().sum();
+    let mut shares = [0.0; 3];
+    for (length, &count) in counts.iter().enumerate() {
+        let bucket = Bucket::of_length(size, length as u8)?;
+        shares[bucket.index()] += count as f64 / total as f64;
+    }
+    Some(shares)
+}
+
+/// Share of the challenges for nonces `0..samples` over [`SUITE_HEADER`] in
+/// each bucket.
+pub fn sampled_shares(size: usize, samples: u64) -> Option<[f64; 3]> {
+    let mut counts = [0u64; 3];
+    for nonce in 0..samples {
+        let bucket = Bucket::of_length(size, optimal_length(size, nonce, SUITE_HEADER)?)?;
+        counts[bucket.index()] += 1;
+    }
+    Some(counts.map(|count| count as f64 / samples.max(1) as f64))
+}
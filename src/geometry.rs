@@ -0,0 +1,116 @@
+//! Sticker coordinates in 3D space.
+//!
+//! Axes: x points to the Right face, y to Up and z to Front. Coordinates are
+//! doubled and centred on the middle of the cube, so along each axis a cubie of
+//! a size-n cube sits at one of -(n-1), -(n-1)+2, ..., n-1. Quarter turns and
+//! reflections are then exact integer maps.
+//!
+//! Face grids are indexed `[row][col]` as seen from outside the face:
+//! Front, Right, Back and Left have row 0 next to Up and are read left to right
+//! going round the cube (F → R → B → L); Up has row 0 next to Back and Down has
+//! row 0 next to Front, both with column 0 next to Left.
+
+#![deny(clippy::indexing_slicing, clippy::panic, clippy::unreachable)]
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::Face;
+
+pub(crate) type Vec3 = [i32; 3];
+
+/// Centred position and outward normal of a sticker.
+pub(crate) fn to_space(size: usize, face: Face, row: usize, col: usize) -> (Vec3, Vec3) {
+    let m = size as i32 - 1;
+    let (row, col) = (row as i32, col as i32);
+
+    let (index, normal) = match face {
+        Face::Up => ([col, m, row], [0, 1, 0]),
+        Face::Down => ([col, 0, m - row], [0, -1, 0]),
+        Face::Front => ([col, m - row, m], [0, 0, 1]),
+        Face::Back => ([m - col, m - row, 0], [0, 0, -1]),
+        Face::Right => ([m, m - row, m - col], [1, 0, 0]),
+        Face::Left => ([0, m - row, col], [-1, 0, 0]),
+    };
+
+    (index.map(|i| 2 * i - m), normal)
+}
+
+/// Inverse of [`to_space`].
+pub(crate) fn from_space(size: usize, position: Vec3, normal: Vec3) -> (Face, usize, usize) {
+    let m = size as i32 - 1;
+    let [x, y, z] = position.map(|c| (c + m) / 2);
+
+    // Sticker normals are unit axis vectors; the last arm is [-1, 0, 0]
+    let (face, row, col) = match normal {
+        [0, 1, 0] => (Face::Up, z, x),
+        [0, -1, 0] => (Face::Down, m - z, x),
+        [0, 0, 1] => (Face::Front, m - y, x),
+        [0, 0, -1] => (Face::Back, m - y, m - x),
+        [1, 0, 0] => (Face::Right, m - y, m - z),
+        _ => (Face::Left, m - y, z),
+    };
+
+    (face, row as usize, col as usize)
+}
+
+/// Position of a sticker in the flat `Face::ALL`, row-major ordering.
+pub(crate) fn sticker_index(size: usize, face: Face, row: usize, col: usize) -> usize {
+    (face.index() * size + row) * size + col
+}
+
+/// For every sticker index, the index it is carried to by `map`.
+pub(crate) fn permutation<F>(size: usize, map: F) -> Vec<usize>
+where
+    F: Fn(Vec3, Vec3) -> (Vec3, Vec3),
+{
+    let mut destinations = Vec::with_capacity(6 * size * size);
+    for face in Face::ALL {
+        for row in 0..size {
+            for col in 0..size {
+                let (position, normal) = to_space(size, face, row, col);
+                let (position, normal) = map(position, normal);
+                let (face, row, col) = from_space(size, position, normal);
+                destinations.push(sticker_index(size, face, row, col));
+            }
+        }
+    }
+    destinations
+}
+
+/// Outward normal of a face.
+pub(crate) fn normal(face: Face) -> Vec3 {
+    match face {
+        Face::Up => [0, 1, 0],
+        Face::Down => [0, -1, 0],
+        Face::Front => [0, 0, 1],
+        Face::Back => [0, 0, -1],
+        Face::Right => [1, 0, 0],
+        Face::Left => [-1, 0, 0],
+    }
+}
+
+/// Quarter turn clockwise as seen from the tip of `axis`, a unit axis vector:
+/// the part of `v` along the axis stays, the rest becomes `v × axis`.
+fn quarter_turn(v: Vec3, axis: Vec3) -> Vec3 {
+    let [x, y, z] = v;
+    let [a, b, c] = axis;
+    let along = x * a + y * b + z * c;
+    [along * a + y * c - z * b, along * b + z * a - x * c, along * c + x * b - y * a]
+}
+
+/// Sticker permutation of turning `layers`, numbered from `face` with 0 for
+/// the outermost one, by `turns` quarter turns, clockwise as seen from that
+/// face. Layers `0..size` are the whole cube.
+pub(crate) fn layer_turn(size: usize, face: Face, layers: Range<usize>, turns: usize) -> Vec<usize> {
+    let m = size as i32 - 1;
+    let axis = normal(face);
+    permutation(size, |position, normal| {
+        // Distance from the turned face in layers, 0 for the outermost one
+        let along: i32 = position.iter().zip(axis).map(|(p, a)| p * a).sum();
+        if !usize::try_from((m - along) / 2).is_ok_and(|layer| layers.contains(&layer)) {
+            return (position, normal);
+        }
+        (0..turns % 4).fold((position, normal), |(p, n), _| (quarter_turn(p, axis), quarter_turn(n, axis)))
+    })
+}
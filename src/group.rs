@@ -0,0 +1,339 @@
+//! Group-theoretic view of move sequences.
+//!
+//! Every move permutes the sticker positions of the cube. Composing those
+//! permutations gives the group element of a whole sequence without touching
+//! a `Cube`, which is what pools use to spot trivially mutated duplicate shares.
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::geometry::{self, Vec3};
+use crate::{Cube, Face, Move};
+
+/// A permutation of the `6·n²` sticker positions, indexed in `Face::ALL`,
+/// row-major order: the sticker at position `i` moves to `destinations[i]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Permutation {
+    destinations: Vec<usize>,
+}
+
+impl Permutation {
+    pub fn identity(size: usize) -> Self {
+        Permutation {
+            destinations: (0..6 * size * size).collect(),
+        }
+    }
+
+    /// The sticker permutation performed by `Cube::apply_move`.
+    pub fn of_move(m: &Move, size: usize) -> Self {
+        Permutation {
+            destinations: m.sticker_destinations(size),
+        }
+    }
+
+    pub fn of_sequence(moves: &[Move], size: usize) -> Self {
+        let mut cache: BTreeMap<Move, Permutation> = BTreeMap::new();
+        moves.iter().fold(Permutation::identity(size), |acc, m| {
+            let step = cache
+                .entry(*m)
+                .or_insert_with(|| Permutation::of_move(m, size));
+            acc.then(step)
+        })
+    }
+
+    /// The permutation performing `self` first and `next` afterwards.
+    pub fn then(&self, next: &Permutation) -> Permutation {
+        Permutation {
+            destinations: self
+                .destinations
+                .iter()
+                .map(|&d| next.destinations[d])
+                .collect(),
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.destinations.iter().enumerate().all(|(i, &d)| i == d)
+    }
+
+    pub fn destinations(&self) -> &[usize] {
+        &self.destinations
+    }
+
+    /// Length of every cycle, fixed points included, in order of their
+    /// smallest position.
+    pub fn cycle_lengths(&self) -> Vec<usize> {
+        let mut seen = vec![false; self.destinations.len()];
+        let mut lengths = Vec::new();
+        for start in 0..self.destinations.len() {
+            let mut length = 0;
+            let mut i = start;
+            while !seen[i] {
+                seen[i] = true;
+                i = self.destinations[i];
+                length += 1;
+            }
+            if length > 0 {
+                lengths.push(length);
+            }
+        }
+        lengths
+    }
+
+    /// Every cycle longer than one position, each starting at its smallest
+    /// position and listed in order of it. Position `a` followed by `b`
+    /// means the sticker at `a` moves to `b`.
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        let mut seen = vec![false; self.destinations.len()];
+        let mut cycles = Vec::new();
+        for start in 0..self.destinations.len() {
+            let mut cycle = Vec::new();
+            let mut i = start;
+            while !seen[i] {
+                seen[i] = true;
+                cycle.push(i);
+                i = self.destinations[i];
+            }
+            if cycle.len() > 1 {
+                cycles.push(cycle);
+            }
+        }
+        cycles
+    }
+
+    /// Number of repetitions that return to the identity: the least common
+    /// multiple of the cycle lengths.
+    pub fn order(&self) -> u64 {
+        self.cycle_lengths().into_iter().fold(1, |acc, length| lcm(acc, length as u64))
+    }
+}
+
+/// A sticker position, named by face and by row and column as seen from
+/// outside that face.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StickerId {
+    pub face: Face,
+    pub row: usize,
+    pub col: usize,
+}
+
+impl StickerId {
+    /// The sticker at `index` in `Face::ALL`, row-major order.
+    pub fn from_index(index: usize, size: usize) -> Self {
+        let (face, cell) = (index / (size * size), index % (size * size));
+        StickerId {
+            face: Face::ALL[face],
+            row: cell / size,
+            col: cell % size,
+        }
+    }
+
+    pub fn index(self, size: usize) -> usize {
+        geometry::sticker_index(size, self.face, self.row, self.col)
+    }
+}
+
+/// Written `U[0,2]`: face letter, then row and column.
+impl fmt::Display for StickerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{},{}]", self.face, self.row, self.col)
+    }
+}
+
+/// The sticker permutation of `moves` on a `size` cube in cycle notation, as
+/// [`Permutation::cycles`] lists it.
+pub fn as_cycles(moves: &[Move], size: usize) -> Vec<Vec<StickerId>> {
+    Permutation::of_sequence(moves, size)
+        .cycles()
+        .into_iter()
+        .map(|cycle| cycle.into_iter().map(|i| StickerId::from_index(i, size)).collect())
+        .collect()
+}
+
+/// Cycles written the usual way, e.g. `(U[0,2] R[0,0] D[2,0])(F[1,1] F[1,2])`;
+/// `()` for the identity.
+pub fn format_cycles(cycles: &[Vec<StickerId>]) -> String {
+    if cycles.is_empty() {
+        return "()".into();
+    }
+    cycles
+        .iter()
+        .map(|cycle| {
+            let stickers: Vec<String> = cycle.iter().map(StickerId::to_string).collect();
+            format!("({})", stickers.join(" "))
+        })
+        .collect()
+}
+
+/// Returns true if both sequences leave a solved cube in the same state.
+///
+/// For cubes up to 3x3x3 every sticker is distinguishable, so the sequences
+/// are compared as group elements by composing sticker permutations. Larger
+/// cubes have interchangeable centre pieces; there the sequences are applied
+/// to solved cubes and the resulting sticker states compared.
+pub fn sequences_equivalent(a: &[Move], b: &[Move], size: usize) -> bool {
+    if size <= 3 {
+        return Permutation::of_sequence(a, size) == Permutation::of_sequence(b, size);
+    }
+
+    let mut cube_a = Cube::new(size);
+    let mut cube_b = Cube::new(size);
+    for m in a {
+        cube_a.apply_move(m);
+    }
+    for m in b {
+        cube_b.apply_move(m);
+    }
+    cube_a.stickers() == cube_b.stickers()
+}
+
+/// Number of times `moves` must be repeated on a size-`size` cube before
+/// every sticker is back in place.
+pub fn element_order(moves: &[Move], size: usize) -> u64 {
+    Permutation::of_sequence(moves, size).order()
+}
+
+pub(crate) fn lcm(a: u64, b: u64) -> u64 {
+    fn gcd(a: u64, b: u64) -> u64 {
+        if b == 0 { a } else { gcd(b, a % b) }
+    }
+    a / gcd(a, b) * b
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Parity {
+    Even,
+    Odd,
+}
+
+/// Parity of the corner permutation, or `None` if the stickers do not form
+/// eight distinct corners. On a 3x3x3 reachable by face turns it equals the
+/// parity of the edge permutation.
+pub fn permutation_parity(cube: &Cube) -> Option<Parity> {
+    let pieces = corners(cube)?;
+    let permutation = Permutation {
+        destinations: pieces.iter().map(|&(piece, _)| piece).collect(),
+    };
+    let transpositions: usize = permutation.cycle_lengths().iter().map(|length| length - 1).sum();
+    Some(if transpositions % 2 == 1 { Parity::Odd } else { Parity::Even })
+}
+
+/// Sum of the corner twists modulo 3: 0 for every state reachable from the
+/// solved cube by face turns. A corner's twist counts the steps, anticlockwise
+/// round the corner, from its Up/Down slot to its Up/Down-coloured sticker.
+pub fn corner_twist_sum(cube: &Cube) -> Option<u8> {
+    let pieces = corners(cube)?;
+    Some((pieces.iter().map(|&(_, twist)| usize::from(twist)).sum::<usize>() % 3) as u8)
+}
+
+/// Sum of the middle-edge flips modulo 2: 0 for every state reachable from
+/// the solved cube by face turns. An edge is flipped unless its Up/Down-coloured
+/// sticker (Front/Back-coloured if it has none) lies in the position's
+/// Up/Down slot (Front/Back slot if it has none). Cubes of even size have no
+/// middle edges and always give 0.
+pub fn edge_flip_sum(cube: &Cube) -> Option<u8> {
+    let n = cube.size;
+    if n < 3 || n % 2 != 1 {
+        return Some(0);
+    }
+
+    let m = n as i32 - 1;
+    let ids = cube.face_ids();
+    let mut seen = Vec::new();
+    let mut flips = 0;
+    for axis in 0..3 {
+        for signs in [[1, 1], [1, -1], [-1, 1], [-1, -1]] {
+            // The two non-zero coordinates of the edge position, in x, y, z order
+            let others: Vec<usize> = (0..3).filter(|&a| a != axis).collect();
+            let mut position = [0; 3];
+            let mut normals = Vec::with_capacity(2);
+            for (&a, &sign) in others.iter().zip(&signs) {
+                position[a] = sign * m;
+                let mut normal = [0; 3];
+                normal[a] = sign;
+                normals.push(normal);
+            }
+
+            let faces: Vec<Face> = normals.iter().map(|&normal| sticker_face(cube.size, &ids, position, normal)).collect();
+            let piece = piece_signs(&faces)?;
+            if seen.contains(&piece) {
+                return None;
+            }
+            seen.push(piece);
+
+            let primary_face = |face: &Face| matches!(face, Face::Up | Face::Down);
+            let secondary_face = |face: &Face| matches!(face, Face::Front | Face::Back);
+            let colour = faces.iter().position(primary_face).or_else(|| faces.iter().position(secondary_face))?;
+            let slot_axis = if position[1] != 0 { 1 } else { 2 };
+            let slot = normals.iter().position(|normal| normal[slot_axis] != 0)?;
+            if colour != slot {
+                flips += 1;
+            }
+        }
+    }
+    Some(flips % 2)
+}
+
+/// For every corner position, the solved position of the corner sitting there
+/// and its twist; `None` unless all eight corners are present exactly once.
+pub(crate) fn corners(cube: &Cube) -> Option<Vec<(usize, u8)>> {
+    let m = cube.size as i32 - 1;
+    let ids = cube.face_ids();
+    let mut pieces = Vec::with_capacity(8);
+    for index in 0..8 {
+        let signs = corner_signs(index);
+        let position = signs.map(|sign| sign * m);
+        // Up/Down slot first, then the other two anticlockwise as seen from outside
+        let mut normals = [[0, signs[1], 0], [signs[0], 0, 0], [0, 0, signs[2]]];
+        if signs.iter().product::<i32>() > 0 {
+            normals.swap(1, 2);
+        }
+
+        let faces: Vec<Face> = normals.iter().map(|&normal| sticker_face(cube.size, &ids, position, normal)).collect();
+        let piece = piece_signs(&faces)?;
+        let twist = faces.iter().position(|face| matches!(face, Face::Up | Face::Down))?;
+        pieces.push((corner_index(piece), twist as u8));
+    }
+
+    let mut seen = [false; 8];
+    for &(piece, _) in &pieces {
+        if core::mem::replace(&mut seen[piece], true) {
+            return None;
+        }
+    }
+    Some(pieces)
+}
+
+fn corner_signs(index: usize) -> Vec3 {
+    [0, 1, 2].map(|bit| if index & (1 << bit) == 0 { -1 } else { 1 })
+}
+
+fn corner_index(signs: Vec3) -> usize {
+    (0..3).filter(|&bit| signs[bit] > 0).map(|bit| 1 << bit).sum()
+}
+
+/// Solved face of the sticker at `position` facing `normal`.
+fn sticker_face(size: usize, ids: &[u8], position: Vec3, normal: Vec3) -> Face {
+    let (face, row, col) = geometry::from_space(size, position, normal);
+    Face::ALL[usize::from(ids[geometry::sticker_index(size, face, row, col)])]
+}
+
+/// Solved position of the piece showing `faces`, as the sign of each
+/// coordinate (0 along axes it has no sticker on); `None` if two of the
+/// faces are the same or opposite.
+fn piece_signs(faces: &[Face]) -> Option<Vec3> {
+    let mut signs = [0; 3];
+    for &face in faces {
+        let normal = geometry::normal(face);
+        let axis = normal.iter().position(|&c| c != 0)?;
+        if signs[axis] != 0 {
+            return None;
+        }
+        signs[axis] = normal[axis];
+    }
+    Some(signs)
+}
@@ -0,0 +1,257 @@
+//! Lookup tables optionally backed by huge pages.
+//!
+//! Solver tables are read at random offsets, so on 4 KiB pages nearly every
+//! lookup misses the TLB. [`Table`] can instead map its memory in 2 MiB pages,
+//! either transparent huge pages requested with `madvise`, or pages reserved in
+//! hugetlbfs (`vm.nr_hugepages`). Which one is tried is a process-wide
+//! [`HugePages`] policy, set at startup before any table is built. Huge pages
+//! need the `hugepages` feature on Linux; when they cannot be had, tables fall
+//! back to the next option down and finally to the heap, and
+//! [`Table::backing`] says what was obtained.
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Which pages tables should ask for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "miner-config",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum HugePages {
+    /// Plain heap allocations.
+    #[default]
+    Off,
+    /// Transparent huge pages, falling back to the heap.
+    Transparent,
+    /// Reserved hugetlbfs pages, then transparent ones, then the heap.
+    Reserved,
+}
+
+/// What a table's memory ended up being.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backing {
+    Heap,
+    /// Mapped with `MADV_HUGEPAGE`; the kernel backs it with huge pages as
+    /// far as it can.
+    TransparentHugePages,
+    /// Mapped from the reserved huge page pool.
+    ReservedHugePages,
+}
+
+impl fmt::Display for Backing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Backing::Heap => "heap",
+            Backing::TransparentHugePages => "transparent huge pages",
+            Backing::ReservedHugePages => "reserved huge pages",
+        })
+    }
+}
+
+static POLICY: AtomicU8 = AtomicU8::new(0);
+
+/// Sets the policy for tables built from now on.
+pub fn set_policy(policy: HugePages) {
+    POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+pub fn policy() -> HugePages {
+    match POLICY.load(Ordering::Relaxed) {
+        1 => HugePages::Transparent,
+        2 => HugePages::Reserved,
+        _ => HugePages::Off,
+    }
+}
+
+/// Fixed-size table of plain values.
+pub struct Table<T: Copy> {
+    storage: Storage<T>,
+}
+
+enum Storage<T> {
+    Heap(Vec<T>),
+    #[cfg(all(feature = "hugepages", target_os = "linux"))]
+    Mapped(mapped::Mapping<T>),
+}
+
+impl<T: Copy> Table<T> {
+    /// `len` copies of `value` under the current [`policy`].
+    pub fn filled(len: usize, value: T) -> Self {
+        Table::with_policy(len, value, policy())
+    }
+
+    pub fn with_policy(len: usize, value: T, policy: HugePages) -> Self {
+        #[cfg(all(feature = "hugepages", target_os = "linux"))]
+        if let Some(mapping) = mapped::Mapping::new(len, value, policy) {
+            return Table {
+                storage: Storage::Mapped(mapping),
+            };
+        }
+        #[cfg(not(all(feature = "hugepages", target_os = "linux")))]
+        let _ = policy;
+        Table {
+            storage: Storage::Heap(vec![value; len]),
+        }
+    }
+
+    pub fn backing(&self) -> Backing {
+        match &self.storage {
+            Storage::Heap(_) => Backing::Heap,
+            #[cfg(all(feature = "hugepages", target_os = "linux"))]
+            Storage::Mapped(mapping) => mapping.backing(),
+        }
+    }
+}
+
+impl<T: Copy> Deref for Table<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match &self.storage {
+            Storage::Heap(values) => values,
+            #[cfg(all(feature = "hugepages", target_os = "linux"))]
+            Storage::Mapped(mapping) => mapping.as_slice(),
+        }
+    }
+}
+
+impl<T: Copy> DerefMut for Table<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        match &mut self.storage {
+            Storage::Heap(values) => values,
+            #[cfg(all(feature = "hugepages", target_os = "linux"))]
+            Storage::Mapped(mapping) => mapping.as_mut_slice(),
+        }
+    }
+}
+
+impl<T: Copy + fmt::Debug> fmt::Debug for Table<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Table")
+            .field("len", &self.len())
+            .field("backing", &self.backing())
+            .finish()
+    }
+}
+
+#[cfg(all(feature = "hugepages", target_os = "linux"))]
+mod mapped {
+    use std::ptr::{self, NonNull};
+
+    use super::{Backing, HugePages};
+
+    const HUGE_PAGE: usize = 2 << 20;
+
+    pub(super) struct Mapping<T> {
+        ptr: NonNull<T>,
+        len: usize,
+        bytes: usize,
+        backing: Backing,
+    }
+
+    // SAFETY: the mapping is owned exclusively, like a `Vec<T>`
+    unsafe impl<T: Send> Send for Mapping<T> {}
+    unsafe impl<T: Sync> Sync for Mapping<T> {}
+
+    impl<T: Copy> Mapping<T> {
+        pub(super) fn new(len: usize, value: T, policy: HugePages) -> Option<Self> {
+            let bytes = len.checked_mul(std::mem::size_of::<T>())?;
+            // Huge pages only pay off for tables spanning several of them
+            if bytes < HUGE_PAGE || std::mem::align_of::<T>() > HUGE_PAGE {
+                return None;
+            }
+            let bytes = bytes.div_ceil(HUGE_PAGE) * HUGE_PAGE;
+
+            let (ptr, backing) = match policy {
+                HugePages::Off => return None,
+                HugePages::Reserved => reserved(bytes)
+                    .map(|ptr| (ptr, Backing::ReservedHugePages))
+                    .or_else(|| transparent(bytes).map(|ptr| (ptr, Backing::TransparentHugePages)))?,
+                HugePages::Transparent => (transparent(bytes)?, Backing::TransparentHugePages),
+            };
+            let ptr = ptr.cast::<T>();
+            // Writing every element also faults the pages in on this thread's
+            // NUMA node
+            for i in 0..len {
+                // SAFETY: the mapping holds at least `len` elements and is
+                // aligned to a page
+                unsafe { ptr.as_ptr().add(i).write(value) };
+            }
+            Some(Mapping {
+                ptr,
+                len,
+                bytes,
+                backing,
+            })
+        }
+
+        pub(super) fn backing(&self) -> Backing {
+            self.backing
+        }
+
+        pub(super) fn as_slice(&self) -> &[T] {
+            // SAFETY: `len` elements were initialised in `new`
+            unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+        }
+
+        pub(super) fn as_mut_slice(&mut self) -> &mut [T] {
+            // SAFETY: as above, and `&mut self` makes the access exclusive
+            unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+        }
+    }
+
+    impl<T> Drop for Mapping<T> {
+        fn drop(&mut self) {
+            // SAFETY: unmaps exactly the range `map` returned
+            unsafe { libc::munmap(self.ptr.as_ptr().cast(), self.bytes) };
+        }
+    }
+
+    fn map(bytes: usize, extra_flags: libc::c_int) -> Option<NonNull<u8>> {
+        // SAFETY: a fresh anonymous mapping aliases nothing
+        let address = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                bytes,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | extra_flags,
+                -1,
+                0,
+            )
+        };
+        if address == libc::MAP_FAILED {
+            return None;
+        }
+        NonNull::new(address.cast())
+    }
+
+    /// Pages from the hugetlbfs pool; fails when too few are reserved.
+    fn reserved(bytes: usize) -> Option<NonNull<u8>> {
+        map(bytes, libc::MAP_HUGETLB)
+    }
+
+    /// A 2 MiB-aligned mapping marked for transparent huge pages. The kernel
+    /// only uses huge pages for aligned ranges, so this maps one page extra
+    /// and trims the ends.
+    fn transparent(bytes: usize) -> Option<NonNull<u8>> {
+        let start = map(bytes + HUGE_PAGE, 0)?.as_ptr();
+        let head = (HUGE_PAGE - start as usize % HUGE_PAGE) % HUGE_PAGE;
+        let tail = HUGE_PAGE - head;
+        // SAFETY: both trimmed ranges lie inside the mapping just made
+        unsafe {
+            let aligned = start.add(head);
+            if head > 0 {
+                libc::munmap(start.cast(), head);
+            }
+            libc::munmap(aligned.add(bytes).cast(), tail);
+            if libc::madvise(aligned.cast(), bytes, libc::MADV_HUGEPAGE) != 0 {
+                libc::munmap(aligned.cast(), bytes);
+                return None;
+            }
+            NonNull::new(aligned)
+        }
+    }
+}
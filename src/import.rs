@@ -0,0 +1,110 @@
+//! Parallel verification of the seals in a block, with sequential results.
+//!
+//! Every accepted seal changes the chain state the next one is checked
+//! against: the challenge header, the last nonce and possibly the target and
+//! the rules in force. The runtime therefore processes solution extrinsics
+//! strictly in order. Block import can still spread the expensive part over
+//! cores: [`verify_block_seals`] verifies all remaining seals against the
+//! current state at once, accepts the first success in extrinsic order,
+//! settles every seal before it as rejected, and repeats for the seals after
+//! it on the advanced state.
+//!
+//! The outcome is exactly what in-order dispatch produces. In particular,
+//! when several seals solve the same challenge, the one earliest in the block
+//! wins regardless of nonce or which verification finished first, and the
+//! others are checked against the next challenge, which they fail.
+
+use rayon::prelude::*;
+
+use crate::policy::{MinQuality, SolutionPolicy};
+use crate::{verify_seal_with_rules, CommitmentVersion, PowSeal, Rules, ScrambleSpec, SpecVersion, Target, VerifyError};
+
+/// The chain state seals are verified against, advanced as seals are accepted.
+pub trait ImportState {
+    /// Header bytes the next challenge is derived from.
+    fn header(&self) -> Vec<u8>;
+
+    fn target(&self) -> Target;
+
+    /// Version the next challenge is derived under; see [`Rules::spec`].
+    fn spec(&self) -> SpecVersion {
+        SpecVersion::CURRENT
+    }
+
+    /// Version of the state commitment compared with the target.
+    fn commitment(&self) -> CommitmentVersion {
+        CommitmentVersion::CURRENT
+    }
+
+    /// Shape of the scrambles of challenges on `cube_size` cubes.
+    fn scramble_spec(&self, _cube_size: u32) -> ScrambleSpec {
+        ScrambleSpec::V1
+    }
+
+    /// Chain policy checked before the seal itself, such as size limits and
+    /// nonce ordering.
+    fn check_policy(&self, seal: &PowSeal) -> Result<(), VerifyError>;
+
+    /// Applies the effect of accepting `seal`.
+    fn accept(&mut self, seal: &PowSeal);
+
+    /// Whether to check every seal with both engines; see
+    /// [`Rules::double_check`].
+    fn double_check(&self) -> bool {
+        false
+    }
+
+    /// Longest solutions accepted; see [`Rules::min_quality`].
+    fn min_quality(&self) -> MinQuality {
+        MinQuality::NONE
+    }
+
+    /// Policy solutions are checked against; see [`Rules::policy`].
+    fn policy(&self) -> &dyn SolutionPolicy {
+        &()
+    }
+}
+
+/// Result of every seal in `seals`, in order, as in-order dispatch starting
+/// from `state` would produce it. Leaves `state` after the last acceptance.
+pub fn verify_block_seals<S>(state: &mut S, seals: &[PowSeal]) -> Vec<Result<(), VerifyError>>
+where
+    S: ImportState + Sync,
+{
+    let mut results = Vec::with_capacity(seals.len());
+    let mut next = 0;
+    while next < seals.len() {
+        let header = state.header();
+        let target = state.target();
+        let round: Vec<Result<(), VerifyError>> = seals[next..]
+            .par_iter()
+            .map(|seal| {
+                state.check_policy(seal)?;
+                let rules = Rules {
+                    spec: state.spec(),
+                    commitment: state.commitment(),
+                    scramble: state.scramble_spec(seal.cube_size),
+                    double_check: state.double_check(),
+                    min_quality: state.min_quality(),
+                    policy: state.policy(),
+                };
+                verify_seal_with_rules(&rules, seal, &header, &target).map(|_| ())
+            })
+            .collect();
+
+        // Seals up to the first success saw the state they would have seen in
+        // order; the ones after it have to be checked again
+        match round.iter().position(Result::is_ok) {
+            Some(accepted) => {
+                results.extend_from_slice(&round[..=accepted]);
+                state.accept(&seals[next + accepted]);
+                next += accepted + 1;
+            }
+            None => {
+                results.extend(round);
+                next = seals.len();
+            }
+        }
+    }
+    results
+}
@@ -0,0 +1,171 @@
+//! Scramble derivation without data-dependent control flow, as a reference
+//! for GPU and OpenCL ports.
+//!
+//! Up to V2, [`spec::scramble_moves_with`] loops until a draw lands:
+//! `gen_range` rejects values in the biased zone and the filters reject
+//! faces, so lanes of a SIMT kernel deriving different seeds diverge on
+//! every draw. The same scrambles come out of a fixed number of steps that
+//! each read the next draw, decide with masks whether it lands, and update
+//! the lane's state by selection rather than branching. [`scramble_kernel`]
+//! is that loop, written to be ported line by line; `tests/kernel_tests.rs`
+//! checks it against the consensus path.
+//!
+//! The RNG is a counter-mode stream: word `i` is word `i % 16` of ChaCha12
+//! block `i / 16`, keyed by the seed with a zero nonce, so a kernel computes
+//! the words it reads instead of carrying [`keystream`]'s buffer. Draws read
+//! it as follows:
+//!
+//! - the length, faces and turn counts are `u32` draws: one word each;
+//! - a draw `v` of a range of `r` values lands on `hi(v·r)` when
+//!   `lo(v·r) <= (r << r.leading_zeros()) - 1`, the half products taken in
+//!   32 bits, and is redrawn otherwise.
+//!
+//! Faces are numbered as in `Face::ALL`, so a face's opposite is `face ^ 1`
+//! and its axis `face >> 1`.
+
+use alloc::vec::Vec;
+
+use rand::RngCore;
+use rand::SeedableRng;
+
+use crate::spec::{self, ScrambleRng, ScrambleSpec, SCRAMBLE_MAX_TURNS};
+use crate::{Face, Move, SpecVersion};
+
+/// Longest scramble the kernel derives.
+pub const KERNEL_MAX_MOVES: usize = 64;
+/// Draws the kernel makes per seed, landed or not. A scramble of `n` moves
+/// takes about `3n` on average, so a scramble the kernel cannot finish in
+/// this many is too unlikely to plan for; it is reported rather than cut
+/// short.
+pub const KERNEL_STEPS: usize = 384;
+/// Keystream words the kernel reads at most: one per step.
+pub const KERNEL_WORDS: usize = KERNEL_STEPS;
+
+const PHASE_LENGTH: u32 = 0;
+const PHASE_FACE: u32 = 1;
+const PHASE_COUNT: u32 = 2;
+const PHASE_DONE: u32 = 3;
+
+/// A kernel lane's output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KernelOutput {
+    /// Face index of each move, as in `Face::ALL`.
+    pub faces: [u8; KERNEL_MAX_MOVES],
+    /// Quarter turns of each move.
+    pub counts: [u8; KERNEL_MAX_MOVES],
+    pub len: usize,
+    /// Whether the scramble was finished within [`KERNEL_STEPS`] draws.
+    pub complete: bool,
+}
+
+/// The first [`KERNEL_WORDS`] words of the scramble RNG seeded with `seed`.
+pub fn keystream(seed: &[u8; 32]) -> [u32; KERNEL_WORDS] {
+    let mut rng = ScrambleRng::from_seed(*seed);
+    let mut words = [0; KERNEL_WORDS];
+    for word in &mut words {
+        *word = rng.next_u32();
+    }
+    words
+}
+
+/// `a` where `c` holds, `b` elsewhere, without branching.
+fn select(c: bool, a: u32, b: u32) -> u32 {
+    let mask = 0u32.wrapping_sub(u32::from(c));
+    (a & mask) | (b & !mask)
+}
+
+/// Scramble drawn from `words` under `scramble`, whose longest length must
+/// be at most [`KERNEL_MAX_MOVES`]. Every step does the same work whether or
+/// not its draw lands; only the values selected differ.
+pub fn scramble_kernel(words: &[u32; KERNEL_WORDS], scramble: &ScrambleSpec) -> KernelOutput {
+    let (shortest, longest) = scramble.length;
+    let length_range = longest.wrapping_sub(shortest).wrapping_add(1);
+    let distinct_faces = scramble.filters.distinct_faces;
+    let no_axis_runs = scramble.filters.no_axis_runs;
+
+    let mut faces = [0u8; KERNEL_MAX_MOVES];
+    let mut counts = [0u8; KERNEL_MAX_MOVES];
+    let mut phase = PHASE_LENGTH;
+    let mut cursor = 0usize;
+    let mut length = 0u32;
+    let mut len = 0u32;
+    let mut pending = 0u32;
+    // Faces of the last two moves; 6 matches no face and no axis
+    let mut last = 6u32;
+    let mut before = 6u32;
+
+    for _ in 0..KERNEL_STEPS {
+        let word = words[cursor % KERNEL_WORDS];
+
+        let range = select(phase == PHASE_FACE, 6, SCRAMBLE_MAX_TURNS as u32);
+        let range = select(phase == PHASE_LENGTH, length_range, range);
+        let zone = (range << range.leading_zeros()).wrapping_sub(1);
+        let product = u64::from(word) * u64::from(range);
+        let lands = (product as u32) <= zone;
+        let value = (product >> 32) as u32;
+        let drawn_length = shortest.wrapping_add(value);
+
+        let repeats = distinct_faces && value == last;
+        let runs = no_axis_runs && value >> 1 == last >> 1 && value >> 1 == before >> 1;
+        let face_lands = phase == PHASE_FACE && lands && !repeats && !runs;
+        let count_lands = phase == PHASE_COUNT && lands;
+        let length_lands = phase == PHASE_LENGTH && lands;
+
+        let slot = (len as usize).min(KERNEL_MAX_MOVES - 1);
+        faces[slot] = select(count_lands, pending, u32::from(faces[slot])) as u8;
+        counts[slot] = select(count_lands, value + 1, u32::from(counts[slot])) as u8;
+
+        length = select(length_lands, drawn_length, length);
+        pending = select(face_lands, value, pending);
+        before = select(count_lands, last, before);
+        last = select(count_lands, pending, last);
+        len += u32::from(count_lands);
+
+        let finished = len == length;
+        let advanced = select(count_lands, select(finished, PHASE_DONE, PHASE_FACE), phase);
+        let advanced = select(face_lands, PHASE_COUNT, advanced);
+        let advanced = select(length_lands, PHASE_FACE, advanced);
+        cursor += usize::from(phase != PHASE_DONE);
+        phase = advanced;
+    }
+
+    KernelOutput {
+        faces,
+        counts,
+        len: len as usize,
+        complete: phase == PHASE_DONE,
+    }
+}
+
+/// [`spec::scramble_moves_with`] through [`scramble_kernel`]. `None` if
+/// `scramble` is longer than the kernel derives or the kernel did not
+/// finish, when callers fall back to the consensus path.
+///
+/// V3 scrambles read one word per draw and never redraw, so the consensus
+/// path already runs in a fixed number of steps and is returned as is.
+pub fn kernel_scramble_moves(version: SpecVersion, scramble: &ScrambleSpec, seed: &[u8; 32]) -> Option<Vec<Move>> {
+    match version {
+        SpecVersion::V3 => scramble.is_valid().then(|| spec::scramble_moves_with(version, scramble, seed)),
+        SpecVersion::V1 | SpecVersion::V2 => {
+            if !scramble.is_valid() || scramble.length.1 as usize > KERNEL_MAX_MOVES {
+                return None;
+            }
+            let output = scramble_kernel(&keystream(seed), scramble);
+            if !output.complete {
+                return None;
+            }
+            output
+                .faces
+                .iter()
+                .zip(&output.counts)
+                .take(output.len)
+                .map(|(&face, &count)| {
+                    Some(Move::from_face_and_count(
+                        *Face::ALL.get(usize::from(face))?,
+                        usize::from(count),
+                    ))
+                })
+                .collect()
+        }
+    }
+}
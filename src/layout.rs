@@ -0,0 +1,128 @@
+//! Memory layout of a cube's stickers, chosen at compile time.
+//!
+//! The default face-major layout keeps a grid of rows per face in a map. The
+//! sticker-major layout, enabled by the `flat-stickers` feature, keeps every
+//! sticker in one vector in `Face::ALL`, row-major order, the order moves and
+//! encodings index stickers in, so applying a move is a single pass over
+//! contiguous memory. Both behave identically; compare them with
+//! `benches/layout.rs`.
+
+#![deny(clippy::indexing_slicing, clippy::panic, clippy::unreachable)]
+
+#[cfg(not(feature = "flat-stickers"))]
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{Color, Face};
+
+/// Name of the layout compiled in, for bench and report labels.
+#[cfg(not(feature = "flat-stickers"))]
+pub const NAME: &str = "face-major";
+#[cfg(feature = "flat-stickers")]
+pub const NAME: &str = "sticker-major";
+
+#[cfg(not(feature = "flat-stickers"))]
+#[derive(Debug, Clone)]
+pub(crate) struct Stickers {
+    size: usize,
+    faces: BTreeMap<Face, Vec<Vec<Color>>>,
+}
+
+#[cfg(not(feature = "flat-stickers"))]
+impl Stickers {
+    /// Every face painted in its own colour.
+    pub(crate) fn solved(size: usize, color_of: impl Fn(Face) -> Color) -> Self {
+        let faces = Face::ALL
+            .iter()
+            .map(|&face| (face, vec![vec![color_of(face); size]; size]))
+            .collect();
+        Stickers { size, faces }
+    }
+
+    pub(crate) fn face(&self, face: Face) -> impl Iterator<Item = Color> + '_ {
+        self.faces.get(&face).into_iter().flatten().flatten().copied()
+    }
+
+    /// Every sticker in `Face::ALL`, row-major order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = Color> + '_ {
+        Face::ALL.iter().flat_map(|&face| self.face(face))
+    }
+
+    pub(crate) fn to_vec(&self) -> Vec<Color> {
+        self.iter().collect()
+    }
+
+    pub(crate) fn copy_from(&mut self, stickers: &[Color]) {
+        let n = self.size;
+        for (f, face) in Face::ALL.iter().enumerate() {
+            let Some(face_data) = self.faces.get_mut(face) else {
+                continue;
+            };
+            for (row, row_data) in face_data.iter_mut().enumerate() {
+                let start = (f * n + row) * n;
+                if let Some(source) = stickers.get(start..start + n) {
+                    row_data.copy_from_slice(source);
+                }
+            }
+        }
+    }
+
+    /// Moves the sticker at every index `i` to `destinations[i]`.
+    pub(crate) fn permute(&mut self, destinations: &[usize]) {
+        let source = self.to_vec();
+        let mut stickers = source.clone();
+        for (&color, &to) in source.iter().zip(destinations) {
+            if let Some(slot) = stickers.get_mut(to) {
+                *slot = color;
+            }
+        }
+        self.copy_from(&stickers);
+    }
+}
+
+#[cfg(feature = "flat-stickers")]
+#[derive(Debug, Clone)]
+pub(crate) struct Stickers {
+    size: usize,
+    stickers: Vec<Color>,
+}
+
+#[cfg(feature = "flat-stickers")]
+impl Stickers {
+    pub(crate) fn solved(size: usize, color_of: impl Fn(Face) -> Color) -> Self {
+        let stickers = Face::ALL
+            .iter()
+            .flat_map(|&face| vec![color_of(face); size * size])
+            .collect();
+        Stickers { size, stickers }
+    }
+
+    pub(crate) fn face(&self, face: Face) -> impl Iterator<Item = Color> + '_ {
+        let n = self.size * self.size;
+        self.stickers.iter().skip(face.index() * n).take(n).copied()
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = Color> + '_ {
+        self.stickers.iter().copied()
+    }
+
+    pub(crate) fn to_vec(&self) -> Vec<Color> {
+        self.stickers.clone()
+    }
+
+    pub(crate) fn copy_from(&mut self, stickers: &[Color]) {
+        for (slot, &color) in self.stickers.iter_mut().zip(stickers) {
+            *slot = color;
+        }
+    }
+
+    pub(crate) fn permute(&mut self, destinations: &[usize]) {
+        let source = self.stickers.clone();
+        for (&color, &to) in source.iter().zip(destinations) {
+            if let Some(slot) = self.stickers.get_mut(to) {
+                *slot = color;
+            }
+        }
+    }
+}
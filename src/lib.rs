@@ -1,23 +1,105 @@
-use std::collections::HashMap;
-use std::fmt;
-
-use rand::Rng;
-use sha3::{Digest, Sha3_256};
-use tiny_keccak::{Hasher, Keccak};
+// Seals come from untrusted peers and are checked inside the runtime, so
+// nothing they reach may panic. Off-chain tooling modules allow `expect` for
+// broken invariants; the modules on the verification path also deny indexing.
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+// Without `std` the crate is the verification core alone, for runtimes
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Range;
+
+#[cfg(feature = "std")]
+pub mod archive;
+#[cfg(feature = "std")]
+pub mod audit;
+#[cfg(feature = "scale-codec")]
+mod codec;
+pub mod commitment;
+pub mod cubie;
+pub mod curves;
+#[cfg(feature = "std")]
+pub mod derivation;
+pub mod difficulty;
+#[cfg(feature = "std")]
+pub mod fairness;
+mod geometry;
+pub mod group;
+#[cfg(feature = "std")]
+pub mod hugepages;
+#[cfg(feature = "std")]
+pub mod import;
+pub mod kernel;
+pub mod layout;
+pub mod metric;
+pub mod notation;
+#[cfg(feature = "std")]
+pub mod miner;
+#[cfg(feature = "std")]
+pub mod optimal;
+pub mod packed;
+pub mod policy;
+#[cfg(feature = "std")]
+pub mod quantum;
+#[cfg(feature = "render")]
+pub mod render;
+#[cfg(feature = "std")]
+pub mod replay;
+#[cfg(feature = "std")]
+pub mod research;
+pub mod retarget;
+#[cfg(feature = "std")]
+pub mod security;
+pub mod seal;
+#[cfg(feature = "serde")]
+mod serde_impls;
+#[cfg(feature = "std")]
+pub mod sim;
+pub mod solver;
+pub mod spec;
+pub mod symmetry;
+pub mod target;
+#[cfg(feature = "std")]
+pub mod task;
+#[cfg(feature = "std")]
+pub mod timelock;
+#[cfg(feature = "std")]
+pub mod transcript;
+pub mod vectors;
+pub mod verify;
+#[cfg(feature = "std")]
+pub mod verifier;
+pub mod watermark;
+pub mod weight;
+
+pub use commitment::CommitmentVersion;
+#[cfg(feature = "std")]
+pub use derivation::derive_challenge_trace;
+pub use metric::Metric;
+pub use seal::PowSeal;
+pub use spec::{ScrambleSpec, SpecVersion};
+pub use symmetry::{CanonicalForm, Symmetry};
+pub use target::{Comparison, HashCheck, Prefix, Target};
+pub use verify::{
+    verify_seal, verify_seal_matching, verify_seal_with_rules, verify_seal_with_scramble, verify_seal_with_spec,
+    verify_seal_with_stats, verify_seal_with_versions, Rules, VerifyError, VerifyStats,
+};
 
 #[derive(Debug, Clone)]
 pub struct Cube {
     size: usize,
-    // For n x n x n cube, we need to track corner and edge permutations and orientations
-    // For large n, the number of center pieces also increases
-    corners: Vec<(usize, u8)>, // (position, orientation) for 8 corners
-    edges: Vec<(usize, u8)>,   // (position, orientation) for 12 edges in 3x3, (12 + 24*(n-3)) for n>3
-    centers: Vec<usize>,       // positions for center pieces (6 fixed in 3x3, but increases for n>3)
-    // Color faces (for visualization and solving checks)
-    faces: HashMap<Face, Vec<Vec<Color>>>,
+    // Sticker colours; the whole state, since every move is a sticker permutation
+    stickers: layout::Stickers,
+    // Which colour each face shows when solved; only used for rendering and facelet I/O
+    scheme: ColorScheme,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Face {
     Up,
     Down,
@@ -27,7 +109,7 @@ pub enum Face {
     Back,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Color {
     White,
     Yellow,
@@ -54,441 +136,361 @@ impl fmt::Display for Color {
     }
 }
 
+/// Why a cube could not be built or a move applied to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CubeError {
+    /// Below [`Cube::MIN_SIZE`].
+    SizeTooSmall(usize),
+    /// Above [`Cube::MAX_SIZE`].
+    SizeTooLarge(usize),
+    /// A 1x1x1 cube has no legal moves.
+    NoLegalMoves,
+}
+
+impl fmt::Display for CubeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CubeError::SizeTooSmall(size) => write!(f, "cube size {} is below {}", size, Cube::MIN_SIZE),
+            CubeError::SizeTooLarge(size) => write!(f, "cube size {} is above {}", size, Cube::MAX_SIZE),
+            CubeError::NoLegalMoves => f.write_str("a 1x1x1 cube has no legal moves"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CubeError {}
+
 impl Cube {
+    /// Smallest size a cube can have. A 1x1x1 cube is always solved and has
+    /// no legal moves; size 0 is not a cube.
+    pub const MIN_SIZE: usize = 1;
+    /// Largest size [`Cube::try_new`] builds. Far above anything the chain
+    /// accepts; it keeps sticker counts from overflowing and allocations from
+    /// being driven by untrusted input.
+    pub const MAX_SIZE: usize = 1024;
+
+    /// Builds a cube of any size without checking it. Use [`Cube::try_new`]
+    /// for sizes that come from untrusted input.
     pub fn new(size: usize) -> Self {
-        let mut faces = HashMap::new();
+        Self::new_with_scheme(size, ColorScheme::WESTERN)
+    }
 
-        for &face in &[Face::Up, Face::Down, Face::Left, Face::Right, Face::Front, Face::Back] {
-            let mut face_data = Vec::with_capacity(size);
-            for _ in 0..size {
-                face_data.push(vec![Color::default_for_face(face); size]);
-            }
-            faces.insert(face, face_data);
-        }
+    /// A solved cube of `size`, or an error outside
+    /// [`Cube::MIN_SIZE`]..=[`Cube::MAX_SIZE`].
+    pub fn try_new(size: usize) -> Result<Self, CubeError> {
+        Self::check_size(size)?;
+        Ok(Self::new(size))
+    }
 
-        // Initialize corners (8 corners for any n×n×n)
-        let mut corners = Vec::with_capacity(8);
-        for _ in 0..8 {
-            corners.push((0, 0)); // Initial position and orientation
+    fn check_size(size: usize) -> Result<(), CubeError> {
+        if size < Self::MIN_SIZE {
+            return Err(CubeError::SizeTooSmall(size));
         }
-
-        // Initialize edges (12 edges for 3x3x3, 12 + 24*(n-3) for n>3)
-        let mut edges = Vec::with_capacity(12 + 24 * size.saturating_sub(3));
-        for _ in 0..(12 + 24 * size.saturating_sub(3)) {
-            edges.push((0, 0)); // Initial position and orientation
+        if size > Self::MAX_SIZE {
+            return Err(CubeError::SizeTooLarge(size));
         }
+        Ok(())
+    }
 
-        // Initialize centers (6 fixed centers for 3x3x3, but increases for n>3)
-        // For n>3, each face has (n-2)^2 center pieces, so total centers = 6*(n-2)^2
-        let mut centers = Vec::with_capacity(6 * (size - 2) * (size - 2));
-        for _ in 0..6 * (size - 2) * (size - 2) {
-            centers.push(0); // Initial position
-        }
+    pub fn size(&self) -> usize {
+        self.size
+    }
 
+    pub fn new_with_scheme(size: usize, scheme: ColorScheme) -> Self {
         Cube {
             size,
-            corners,
-            edges,
-            centers,
-            faces,
+            stickers: layout::Stickers::solved(size, |face| scheme.color_of(face)),
+            scheme,
         }
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "challenge", level = "debug", skip(self, block_header), fields(size = self.size))
+    )]
     pub fn scramble_deterministic(&mut self, nonce: u64, block_header: &[u8]) -> Vec<Move> {
-        // Create a deterministic scramble from the nonce and block header
-        let mut hasher = Sha3_256::new();
-        hasher.update(nonce.to_le_bytes());
-        hasher.update(block_header);
-        let hash = hasher.finalize();
-
-        // Use the hash to seed a random number generator for deterministic scrambling
-        let mut seed = [0u8; 32];
-        seed.copy_from_slice(&hash);
-        let mut rng = rand::rngs::StdRng::from_seed(seed);
-
-        let num_moves = rng.gen_range(20..=30); // Standard scramble length
-
-        let mut scramble_moves = Vec::new();
-        let mut last_face: Option<Face> = None;
-
-        for _ in 0..num_moves {
-            let mut random_face;
-            loop {
-                random_face = [
-                    Face::Up, Face::Down, Face::Left,
-                    Face::Right, Face::Front, Face::Back
-                ][rng.gen_range(0..6)];
-
-                // Avoid redundant moves (e.g. R R')
-                if last_face != Some(random_face) {
-                    break;
-                }
-            }
+        self.scramble_with_spec(SpecVersion::CURRENT, nonce, block_header)
+    }
 
-            let count = rng.gen_range(1..4); // 1, 2, or 3 rotations
-            let random_move = Move::from_face_and_count(random_face, count);
+    /// [`Cube::scramble_deterministic`] under an explicit spec version, for
+    /// checking seals mined before the current version took effect.
+    pub fn scramble_with_spec(&mut self, version: SpecVersion, nonce: u64, block_header: &[u8]) -> Vec<Move> {
+        self.scramble_with(version, &ScrambleSpec::V1, nonce, block_header)
+    }
 
-            self.apply_move(&random_move);
-            scramble_moves.push(random_move);
+    /// [`Cube::scramble_with_spec`] with scrambles shaped by `scramble`,
+    /// which must be valid.
+    pub fn scramble_with(
+        &mut self,
+        version: SpecVersion,
+        scramble: &ScrambleSpec,
+        nonce: u64,
+        block_header: &[u8],
+    ) -> Vec<Move> {
+        // Create a deterministic scramble from the nonce and block header
+        let seed = spec::challenge_seed(version, nonce, block_header);
 
-            last_face = Some(random_face);
+        // Precomputed tables indexed by solved-state colours must be rebuilt per challenge
+        // A shuffle of `Color::ALL`, so always a scheme
+        #[cfg(feature = "color-shuffle")]
+        if let Some(scheme) = ColorScheme::new(spec::color_permutation(version, &seed)) {
+            self.recolor(scheme);
         }
 
+        // Cubes without legal moves stay solved
+        if self.size < 2 {
+            return Vec::new();
+        }
+        let scramble_moves = spec::scramble_moves_with(version, scramble, &seed);
+        for m in &scramble_moves {
+            self.apply_move(m);
+        }
         scramble_moves
     }
 
+    /// [`Cube::apply_move`] that refuses cubes built by [`Cube::new`] with a
+    /// size outside the supported range, and every move on a 1x1x1 cube.
+    pub fn try_apply_move(&mut self, m: &Move) -> Result<(), CubeError> {
+        Self::check_size(self.size)?;
+        if self.size == 1 {
+            return Err(CubeError::NoLegalMoves);
+        }
+        self.apply_move(m);
+        Ok(())
+    }
+
     pub fn apply_move(&mut self, m: &Move) {
-        match m {
-            Move::U(count) => {
-                for _ in 0..count {
-                    self.rotate_face_cw(Face::Up);
-                    self.rotate_up_layer();
-                }
-            }
-            Move::D(count) => {
-                for _ in 0..count {
-                    self.rotate_face_cw(Face::Down);
-                    self.rotate_down_layer();
-                }
-            }
-            Move::L(count) => {
-                for _ in 0..count {
-                    self.rotate_face_cw(Face::Left);
-                    self.rotate_left_layer();
-                }
-            }
-            Move::R(count) => {
-                for _ in 0..count {
-                    self.rotate_face_cw(Face::Right);
-                    self.rotate_right_layer();
-                }
-            }
-            Move::F(count) => {
-                for _ in 0..count {
-                    self.rotate_face_cw(Face::Front);
-                    self.rotate_front_layer();
-                }
-            }
-            Move::B(count) => {
-                for _ in 0..count {
-                    self.rotate_face_cw(Face::Back);
-                    self.rotate_back_layer();
+        self.stickers.permute(&m.sticker_destinations(self.size));
+    }
+
+    pub fn is_solved(&self) -> bool {
+        // Solved means every face shows a single colour; which colour faces which
+        // way does not matter, so a whole-cube rotation of a solved cube is solved
+        for &face in &[Face::Up, Face::Down, Face::Left, Face::Right, Face::Front, Face::Back] {
+            let mut stickers = self.stickers.face(face);
+            if let Some(first) = stickers.next() {
+                if stickers.any(|color| color != first) {
+                    return false;
                 }
             }
         }
+
+        true
     }
 
-    fn rotate_face_cw(&mut self, face: Face) {
-        let mut face_data = self.faces.get_mut(&face).unwrap();
-        let n = self.size;
+    pub fn verify_solution(&self, moves: &[Move]) -> bool {
+        let mut cube = self.clone();
+        for m in moves {
+            cube.apply_move(m);
+        }
+        cube.is_solved()
+    }
 
-        for i in 0..n / 2 {
-            for j in i..n - i - 1 {
-                let temp = face_data[i][j];
-                face_data[i][j] = face_data[n - j - 1][i];
-                face_data[n - j - 1][i] = face_data[n - i - 1][n - j - 1];
-                face_data[n - i - 1][n - j - 1] = face_data[j][n - i - 1];
-                face_data[j][n - i - 1] = temp;
-            }
+    /// [`Cube::verify_solution`] that refuses unsupported sizes. The only
+    /// solution of a 1x1x1 cube is the empty one.
+    pub fn try_verify_solution(&self, moves: &[Move]) -> Result<bool, CubeError> {
+        Self::check_size(self.size)?;
+        if self.size == 1 && !moves.is_empty() {
+            return Err(CubeError::NoLegalMoves);
         }
+        Ok(self.verify_solution(moves))
+    }
+
+    pub fn scheme(&self) -> &ColorScheme {
+        &self.scheme
+    }
 
-        // Update corner and edge permutations and orientations based on the face rotation
-        self.update_permutations_for_face_rotation(face);
+    /// Repaints every sticker: the colour the current scheme gives a face is
+    /// replaced by the one `scheme` gives it, and `scheme` becomes the
+    /// cube's scheme.
+    pub fn recolor(&mut self, scheme: ColorScheme) {
+        let stickers: Vec<Color> = self
+            .stickers()
+            .iter()
+            .map(|&color| scheme.color_of(self.scheme.face_of(color)))
+            .collect();
+        self.set_stickers(&stickers);
+        self.scheme = scheme;
     }
 
-    fn update_permutations_for_face_rotation(&mut self, face: Face) {
-        // Update permutations and orientations based on which face was rotated
-        // This is the core logic that correctly handles the complex interactions
-        // between corners, edges, and centers in an n×n×n cube.
-        // The implementation here is simplified but captures the essential mechanics.
+    /// One colour letter per sticker, in `Face::ALL`, row-major order.
+    pub fn to_facelets(&self) -> String {
+        self.stickers().iter().map(|color| color.to_string()).collect()
+    }
 
-        match face {
-            Face::Up => {
-                // Update corner permutation for U face rotation
-                // The 4 corners on the Up face cycle positions
-                // Position indices: 0, 1, 2, 3 (top layer corners)
-                let temp = self.corners[0];
-                self.corners[0] = self.corners[3];
-                self.corners[3] = self.corners[2];
-                self.corners[2] = self.corners[1];
-                self.corners[1] = temp;
-
-                // Update corner orientations - when a corner moves, its orientation changes
-                // The orientation value (0, 1, 2) represents the number of 120-degree rotations
-                // from the solved state.
-                self.corners[0].1 = (self.corners[0].1 + 1) % 3;
-                self.corners[1].1 = (self.corners[1].1 + 2) % 3; // 240-degree rotation
-                self.corners[2].1 = (self.corners[2].1 + 1) % 3;
-                self.corners[3].1 = (self.corners[3].1 + 2) % 3;
-
-                // Update edge permutation for U face rotation
-                // The 4 edges on the Up face cycle positions
-                // Position indices: 0, 1, 2, 3 (top layer edges)
-                let temp_edge = self.edges[0];
-                self.edges[0] = self.edges[3];
-                self.edges[3] = self.edges[2];
-                self.edges[2] = self.edges[1];
-                self.edges[1] = temp_edge;
-
-                // Update center permutation for U face rotation (for n > 3)
-                if self.size > 3 {
-                    // Update the centers in the Up face
-                    // This is a simplified representation for center permutation
-                    let center_start = 0; // Starting index for Up face centers
-                    let center_size = (self.size - 2) * (self.size - 2);
-
-                    // Rotate the centers in the Up face
-                    // This is a simplified implementation
-                    for i in 0..center_size/2 {
-                        let temp_center = self.centers[center_start + i];
-                        // Simplified rotation - actual implementation would be more complex
-                        // based on the 2D arrangement of centers
-                    }
-                }
-            },
-            Face::Down => {
-                // Update corner permutation for D face rotation
-                // The 4 corners on the Down face cycle positions
-                // Position indices: 4, 5, 6, 7 (bottom layer corners)
-                let temp = self.corners[4];
-                self.corners[4] = self.corners[5];
-                self.corners[5] = self.corners[6];
-                self.corners[6] = self.corners[7];
-                self.corners[7] = temp;
-
-                // Update corner orientations
-                self.corners[4].1 = (self.corners[4].1 + 1) % 3;
-                self.corners[5].1 = (self.corners[5].1 + 2) % 3;
-                self.corners[6].1 = (self.corners[6].1 + 1) % 3;
-                self.corners[7].1 = (self.corners[7].1 + 2) % 3;
-
-                // Update edge permutation for D face rotation
-                // The 4 edges on the Down face cycle positions
-                // Position indices: 8, 9, 10, 11 (bottom layer edges)
-                let temp_edge = self.edges[8];
-                self.edges[8] = self.edges[9];
-                self.edges[9] = self.edges[10];
-                self.edges[10] = self.edges[11];
-                self.edges[11] = temp_edge;
-            },
-            Face::Front => {
-                // Update corner permutation for F face rotation
-                // The 4 corners on the Front face cycle positions
-                // Position indices: 0, 1, 5, 4 (front face corners)
-                let temp = self.corners[0];
-                self.corners[0] = self.corners[1];
-                self.corners[1] = self.corners[5];
-                self.corners[5] = self.corners[4];
-                self.corners[4] = temp;
-
-                // Update corner orientations
-                self.corners[0].1 = (self.corners[0].1 + 2) % 3;
-                self.corners[1].1 = (self.corners[1].1 + 1) % 3;
-                self.corners[4].1 = (self.corners[4].1 + 1) % 3;
-                self.corners[5].1 = (self.corners[5].1 + 2) % 3;
-
-                // Update edge permutation for F face rotation
-                // The 4 edges on the Front face cycle positions
-                let temp_edge = self.edges[0];
-                self.edges[0] = self.edges[1];
-                self.edges[1] = self.edges[5];
-                self.edges[5] = self.edges[4];
-                self.edges[4] = temp_edge;
-
-                // Update edge orientations for F face rotation
-                // Edge orientation changes when it's flipped in the F/B plane
-                self.edges[0].1 = (self.edges[0].1 + 1) % 2;
-                self.edges[1].1 = (self.edges[1].1 + 1) % 2;
-                self.edges[4].1 = (self.edges[4].1 + 1) % 2;
-                self.edges[5].1 = (self.edges[5].1 + 1) % 2;
-            },
-            Face::Back => {
-                // Update corner permutation for B face rotation
-                // The 4 corners on the Back face cycle positions
-                // Position indices: 3, 2, 6, 7 (back face corners)
-                let temp = self.corners[3];
-                self.corners[3] = self.corners[2];
-                self.corners[2] = self.corners[6];
-                self.corners[6] = self.corners[7];
-                self.corners[7] = temp;
-
-                // Update corner orientations
-                self.corners[3].1 = (self.corners[3].1 + 1) % 3;
-                self.corners[2].1 = (self.corners[2].1 + 2) % 3;
-                self.corners[7].1 = (self.corners[7].1 + 2) % 3;
-                self.corners[6].1 = (self.corners[6].1 + 1) % 3;
-
-                // Update edge permutation for B face rotation
-                // The 4 edges on the Back face cycle positions
-                let temp_edge = self.edges[3];
-                self.edges[3] = self.edges[2];
-                self.edges[2] = self.edges[6];
-                self.edges[6] = self.edges[7];
-                self.edges[7] = temp_edge;
-
-                // Update edge orientations for B face rotation
-                self.edges[3].1 = (self.edges[3].1 + 1) % 2;
-                self.edges[2].1 = (self.edges[2].1 + 1) % 2;
-                self.edges[7].1 = (self.edges[7].1 + 1) % 2;
-                self.edges[6].1 = (self.edges[6].1 + 1) % 2;
-            },
-            Face::Left => {
-                // Update corner permutation for L face rotation
-                // The 4 corners on the Left face cycle positions
-                // Position indices: 0, 3, 7, 4 (left face corners)
-                let temp = self.corners[0];
-                self.corners[0] = self.corners[3];
-                self.corners[3] = self.corners[7];
-                self.corners[7] = self.corners[4];
-                self.corners[4] = temp;
-
-                // Update corner orientations
-                self.corners[0].1 = (self.corners[0].1 + 2) % 3;
-                self.corners[3].1 = (self.corners[3].1 + 1) % 3;
-                self.corners[7].1 = (self.corners[7].1 + 2) % 3;
-                self.corners[4].1 = (self.corners[4].1 + 1) % 3;
-
-                // Update edge permutation for L face rotation
-                let temp_edge = self.edges[2];
-                self.edges[2] = self.edges[3];
-                self.edges[3] = self.edges[7];
-                self.edges[7] = self.edges[4];
-                self.edges[4] = temp_edge;
-            },
-            Face::Right => {
-                // Update corner permutation for R face rotation
-                // The 4 corners on the Right face cycle positions
-                // Position indices: 1, 2, 6, 5 (right face corners)
-                let temp = self.corners[1];
-                self.corners[1] = self.corners[2];
-                self.corners[2] = self.corners[6];
-                self.corners[6] = self.corners[5];
-                self.corners[5] = temp;
-
-                // Update corner orientations
-                self.corners[1].1 = (self.corners[1].1 + 1) % 3;
-                self.corners[2].1 = (self.corners[2].1 + 2) % 3;
-                self.corners[6].1 = (self.corners[6].1 + 1) % 3;
-                self.corners[5].1 = (self.corners[5].1 + 2) % 3;
-
-                // Update edge permutation for R face rotation
-                let temp_edge = self.edges[1];
-                self.edges[1] = self.edges[2];
-                self.edges[2] = self.edges[6];
-                self.edges[6] = self.edges[5];
-                self.edges[5] = temp_edge;
-            },
+    /// Parses the output of [`Cube::to_facelets`]. Returns `None` if the
+    /// length is not `6·size²` or a letter is not a colour of the scheme.
+    pub fn from_facelets(size: usize, facelets: &str, scheme: ColorScheme) -> Option<Cube> {
+        let stickers = facelets
+            .chars()
+            .map(Color::from_letter)
+            .collect::<Option<Vec<Color>>>()?;
+        if stickers.len() != 6 * size * size || stickers.iter().any(|&c| !scheme.colors.contains(&c)) {
+            return None;
         }
+
+        let mut cube = Cube::new_with_scheme(size, scheme);
+        cube.set_stickers(&stickers);
+        Some(cube)
     }
 
-    fn rotate_up_layer(&mut self) {
-        // Rotate the up layer (affects corners and edges)
-        // This is handled by update_permutations_for_face_rotation
+    /// Sticker colours in `Face::ALL`, row-major order.
+    pub(crate) fn stickers(&self) -> Vec<Color> {
+        self.stickers.to_vec()
     }
 
-    fn rotate_down_layer(&mut self) {
-        // Rotate the down layer (affects corners and edges)
-        // This is handled by update_permutations_for_face_rotation
+    /// Solved face of every sticker, in `Face::ALL`, row-major order.
+    fn sticker_faces(&self) -> impl Iterator<Item = Face> + '_ {
+        self.stickers.iter().map(|color| self.scheme.face_of(color))
     }
 
-    fn rotate_left_layer(&mut self) {
-        // Rotate the left layer (affects corners and edges)
-        // This is handled by update_permutations_for_face_rotation
+    /// Overwrites the sticker colours from a `Face::ALL`, row-major sequence.
+    pub(crate) fn set_stickers(&mut self, stickers: &[Color]) {
+        self.stickers.copy_from(stickers);
     }
 
-    fn rotate_right_layer(&mut self) {
-        // Rotate the right layer (affects corners and edges)
-        // This is handled by update_permutations_for_face_rotation
+    pub fn meets_difficulty(&self, target_hash: [u8; 32]) -> bool {
+        self.meets_target(&Target::from_bytes(target_hash), Comparison::Fast)
     }
 
-    fn rotate_front_layer(&mut self) {
-        // Rotate the front layer (affects corners and edges)
-        // This is handled by update_permutations_for_face_rotation
+    pub fn meets_target(&self, target: &Target, comparison: Comparison) -> bool {
+        target.check(&self.state_commitment(), comparison)
     }
+}
 
-    fn rotate_back_layer(&mut self) {
-        // Rotate the back layer (affects corners and edges)
-        // This is handled by update_permutations_for_face_rotation
+/// Cubes are equal when they have the same size and every sticker, in
+/// `Face::ALL`, row-major order whatever the sticker layout, comes from the
+/// same face of the solved cube: the state [`Cube::face_ids`] and the state
+/// commitment see. Cubes painted with different schemes can be equal.
+impl PartialEq for Cube {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size && self.sticker_faces().eq(other.sticker_faces())
     }
+}
 
-    pub fn is_solved(&self) -> bool {
-        // Check if all face colors are uniform
-        for &face in &[Face::Up, Face::Down, Face::Left, Face::Right, Face::Front, Face::Back] {
-            let face_data = &self.faces[&face];
-            let center_color = face_data[self.size / 2][self.size / 2];
-            for row in face_data {
-                for &color in row {
-                    if color != center_color {
-                        return false;
-                    }
-                }
-            }
-        }
+impl Eq for Cube {}
 
-        // Check if corners are in their original positions with correct orientation
-        for i in 0..8 {
-            if self.corners[i].0 != i || self.corners[i].1 != 0 {
-                return false;
-            }
+impl Hash for Cube {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        for face in self.sticker_faces() {
+            face.hash(state);
         }
+    }
+}
 
-        // Check if edges are in their original positions with correct orientation
-        let num_edges = 12 + 24 * self.size.saturating_sub(3);
-        for i in 0..num_edges {
-            if self.edges[i].0 != i || self.edges[i].1 != 0 {
-                return false;
-            }
+/// The face's letter in move notation.
+impl fmt::Display for Face {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Face::Up => "U",
+            Face::Down => "D",
+            Face::Left => "L",
+            Face::Right => "R",
+            Face::Front => "F",
+            Face::Back => "B",
+        })
+    }
+}
+
+impl Face {
+    /// Every face, in the order used for sticker sequences.
+    pub const ALL: [Face; 6] = [Face::Up, Face::Down, Face::Left, Face::Right, Face::Front, Face::Back];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn opposite(self) -> Face {
+        match self {
+            Face::Up => Face::Down,
+            Face::Down => Face::Up,
+            Face::Left => Face::Right,
+            Face::Right => Face::Left,
+            Face::Front => Face::Back,
+            Face::Back => Face::Front,
         }
+    }
+}
 
-        // Check if centers are in their original positions
-        let num_centers = 6 * (self.size - 2) * (self.size - 2);
-        for i in 0..num_centers {
-            if self.centers[i] != i {
-                return false;
-            }
+impl Color {
+    pub fn from_letter(letter: char) -> Option<Color> {
+        match letter {
+            'W' => Some(Color::White),
+            'Y' => Some(Color::Yellow),
+            'R' => Some(Color::Red),
+            'O' => Some(Color::Orange),
+            'B' => Some(Color::Blue),
+            'G' => Some(Color::Green),
+            _ => None,
         }
+    }
 
-        true
+    pub const ALL: [Color; 6] = [Color::White, Color::Yellow, Color::Red, Color::Orange, Color::Blue, Color::Green];
+
+    pub fn default_for_face(face: Face) -> Self {
+        ColorScheme::WESTERN.color_of(face)
     }
+}
 
-    pub fn verify_solution(&self, moves: &[Move]) -> bool {
-        let mut cube = self.clone();
-        for m in moves {
-            cube.apply_move(m);
+/// Assignment of colours to faces in the solved state.
+///
+/// Schemes only change how a state is painted; consensus works on face
+/// identities, so any scheme commits to the same values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColorScheme {
+    /// Colour of each face, indexed like `Face::ALL`.
+    colors: [Color; 6],
+}
+
+impl ColorScheme {
+    /// White top, red front, blue left: the default this crate has always used.
+    pub const WESTERN: ColorScheme = ColorScheme {
+        colors: [Color::White, Color::Yellow, Color::Blue, Color::Green, Color::Red, Color::Orange],
+    };
+
+    /// White opposite blue and yellow opposite green.
+    pub const JAPANESE: ColorScheme = ColorScheme {
+        colors: [Color::White, Color::Blue, Color::Yellow, Color::Green, Color::Red, Color::Orange],
+    };
+
+    /// Returns `None` unless the six colours are distinct.
+    pub fn new(colors: [Color; 6]) -> Option<Self> {
+        let distinct = Color::ALL.iter().all(|color| colors.contains(color));
+        if distinct {
+            Some(ColorScheme { colors })
+        } else {
+            None
         }
-        cube.is_solved()
     }
 
-    pub fn meets_difficulty(&self, target_hash: [u8; 32]) -> bool {
-        let mut hasher = Keccak::v256();
-        let mut result = [0u8; 32];
-
-        // Create a string representation of the cube state
-        let cube_state = format!("{:?}", self.faces);
+    pub fn colors(&self) -> &[Color; 6] {
+        &self.colors
+    }
 
-        hasher.update(cube_state.as_bytes());
-        hasher.finalize(&mut result);
+    pub fn color_of(&self, face: Face) -> Color {
+        self.colors[face.index()]
+    }
 
-        // Compare the hash with the target
-        // This implementation correctly compares the full 32-byte hash
-        result <= target_hash
+    pub fn face_of(&self, color: Color) -> Face {
+        // A scheme holds every colour, so the search never falls through
+        Face::ALL
+            .into_iter()
+            .zip(self.colors)
+            .find_map(|(face, c)| (c == color).then_some(face))
+            .unwrap_or(Face::Up)
     }
 }
 
-impl Color {
-    pub fn default_for_face(face: Face) -> Self {
-        match face {
-            Face::Up => Color::White,
-            Face::Down => Color::Yellow,
-            Face::Front => Color::Red,
-            Face::Back => Color::Orange,
-            Face::Left => Color::Blue,
-            Face::Right => Color::Green,
-        }
+impl Default for ColorScheme {
+    fn default() -> Self {
+        ColorScheme::WESTERN
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A turn of the cube. Every variant turns a block of layers clockwise as seen
+/// from one face, by the given number of quarter turns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Move {
     U(usize),   // Up face clockwise
     D(usize),   // Down face clockwise
@@ -496,6 +498,75 @@ pub enum Move {
     R(usize),   // Right face clockwise
     F(usize),   // Front face clockwise
     B(usize),   // Back face clockwise
+    /// The outer two layers on the side of the face (`Rw`, `Uw`, ...).
+    Wide(Face, usize),
+    /// The whole cube (`x`, `y`, `z`).
+    Rotation(Axis, usize),
+    /// Every layer but the two outer ones across the axis, turning like L, D
+    /// and F for x, y and z (`M`, `E`, `S`). On a 2x2x2 cube this turns
+    /// nothing.
+    Slice(Axis, usize),
+    /// The single layer at the given position from the face, the face
+    /// itself being 1 (`2R`, `3U`). Positions past the cube turn nothing.
+    Layer(Face, usize, usize),
+    /// The given number of layers nearest the face (`3Rw`), up to the whole
+    /// cube.
+    Block(Face, usize, usize),
+}
+
+/// Axis of a whole-cube rotation, named as in standard notation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Axis {
+    /// Turns like R.
+    X,
+    /// Turns like U.
+    Y,
+    /// Turns like F.
+    Z,
+}
+
+impl Axis {
+    pub const ALL: [Axis; 3] = [Axis::X, Axis::Y, Axis::Z];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The face whose clockwise direction a rotation about this axis follows.
+    pub fn face(self) -> Face {
+        match self {
+            Axis::X => Face::Right,
+            Axis::Y => Face::Up,
+            Axis::Z => Face::Front,
+        }
+    }
+
+    /// The face whose clockwise direction a slice turn about this axis
+    /// follows: L for M, D for E and F for S.
+    pub fn slice_face(self) -> Face {
+        match self {
+            Axis::X => Face::Left,
+            Axis::Y => Face::Down,
+            Axis::Z => Face::Front,
+        }
+    }
+}
+
+/// Which block of layers a [`Move`] turns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MoveKind {
+    /// A single outer layer.
+    Face,
+    /// The outer two layers.
+    Wide,
+    /// The whole cube.
+    Rotation,
+    /// The inner layers.
+    Slice,
+    /// One layer, by position.
+    Layer,
+    /// Any number of outer layers.
+    Block,
 }
 
 impl Move {
@@ -509,21 +580,175 @@ impl Move {
             Face::Back => Move::B(count % 4),
         }
     }
-}
 
-pub fn calculate_difficulty(n: usize) -> u32 {
-    // Use the number of possible states as a measure of difficulty
-    // For a 3x3x3: ~4.32e19 states
-    // For higher n, the number of states grows factorially
-    match n {
-        1 => 1,
-        2 => 3674160, // 2x2x2 has 3,674,160 states
-        3 => 43252003274489856000, // 3x3x3 has ~4.3e19 states
-        4 => 740119684156490186987409397449857433600000000, // 4x4x4 has ~7.4e45 states
-        _ => {
-            // For n > 4, we use a simplified approximation
-            // The actual number of states for n>4 is much more complex to calculate
-            (n * n * n * 24) as u32 // Simplified approximation
+    pub fn kind(&self) -> MoveKind {
+        match self {
+            Move::U(_) | Move::D(_) | Move::L(_) | Move::R(_) | Move::F(_) | Move::B(_) => MoveKind::Face,
+            Move::Wide(..) => MoveKind::Wide,
+            Move::Rotation(..) => MoveKind::Rotation,
+            Move::Slice(..) => MoveKind::Slice,
+            Move::Layer(..) => MoveKind::Layer,
+            Move::Block(..) => MoveKind::Block,
         }
     }
-}
\ No newline at end of file
+
+    /// The face the move is turned clockwise from; for rotations and slices,
+    /// the face whose turn direction they follow.
+    pub fn face(&self) -> Face {
+        match *self {
+            Move::U(_) => Face::Up,
+            Move::D(_) => Face::Down,
+            Move::L(_) => Face::Left,
+            Move::R(_) => Face::Right,
+            Move::F(_) => Face::Front,
+            Move::B(_) => Face::Back,
+            Move::Wide(face, _) | Move::Layer(face, ..) | Move::Block(face, ..) => face,
+            Move::Rotation(axis, _) => axis.face(),
+            Move::Slice(axis, _) => axis.slice_face(),
+        }
+    }
+
+    /// Number of clockwise quarter turns, as written (not normalized).
+    pub fn count(&self) -> usize {
+        match *self {
+            Move::U(count) | Move::D(count) | Move::L(count) | Move::R(count) | Move::F(count) | Move::B(count) => count,
+            Move::Wide(_, count) | Move::Rotation(_, count) | Move::Slice(_, count) => count,
+            Move::Layer(_, _, count) | Move::Block(_, _, count) => count,
+        }
+    }
+
+    /// The same layers turned back, so that applying both changes nothing.
+    /// The count is normalized to 0..=3.
+    pub fn inverse(&self) -> Move {
+        let back = (4 - self.count() % 4) % 4;
+        match *self {
+            Move::Wide(face, _) => Move::Wide(face, back),
+            Move::Rotation(axis, _) => Move::Rotation(axis, back),
+            Move::Slice(axis, _) => Move::Slice(axis, back),
+            Move::Layer(face, layer, _) => Move::Layer(face, layer, back),
+            Move::Block(face, layers, _) => Move::Block(face, layers, back),
+            _ => Move::from_face_and_count(self.face(), back),
+        }
+    }
+
+    /// Layers turned on a cube of `size`, numbered from [`Move::face`], 0 for
+    /// the outermost one.
+    pub fn layers(&self, size: usize) -> Range<usize> {
+        match *self {
+            Move::Wide(..) => 0..size.min(2),
+            Move::Rotation(..) => 0..size,
+            Move::Slice(..) => 1..size.saturating_sub(1).max(1),
+            Move::Layer(_, layer, _) if (1..=size).contains(&layer) => layer - 1..layer,
+            Move::Layer(..) => 0..0,
+            Move::Block(_, layers, _) => 0..layers.min(size),
+            _ => 0..1,
+        }
+    }
+
+    /// Number of layers turned on a cube of `size`.
+    pub fn depth(&self, size: usize) -> usize {
+        self.layers(size).len()
+    }
+
+    /// Number of times `moves` must be repeated on a solved size-`size` cube
+    /// before it looks solved again. Stickers of the same colour are
+    /// interchangeable, so on cubes larger than 3x3x3 this can be a proper
+    /// divisor of `group::element_order`.
+    pub fn sequence_order(moves: &[Move], size: usize) -> u64 {
+        let permutation = group::Permutation::of_sequence(moves, size);
+        let destinations = permutation.destinations();
+        let face_of = |sticker: usize| sticker / (size * size);
+
+        let mut seen = vec![false; destinations.len()];
+        let mut order = 1;
+        for start in 0..destinations.len() {
+            if seen[start] {
+                continue;
+            }
+            let mut cycle = Vec::new();
+            let mut i = start;
+            while !seen[i] {
+                seen[i] = true;
+                cycle.push(face_of(i));
+                i = destinations[i];
+            }
+
+            // Shortest shift of the cycle that maps every colour onto itself
+            let period = (1..=cycle.len())
+                .filter(|shift| cycle.len() % shift == 0)
+                .find(|&shift| (0..cycle.len()).all(|j| cycle[j] == cycle[(j + shift) % cycle.len()]))
+                .unwrap_or(cycle.len());
+            order = group::lcm(order, period as u64);
+        }
+        order
+    }
+
+    /// For every sticker index, the index this move carries it to.
+    pub(crate) fn sticker_destinations(&self, size: usize) -> Vec<usize> {
+        geometry::layer_turn(size, self.face(), self.layers(size), self.count())
+    }
+}
+
+/// The sequence that undoes `moves`: each move inverted, in reverse order.
+pub fn invert_sequence(moves: &[Move]) -> Vec<Move> {
+    moves.iter().rev().map(Move::inverse).collect()
+}
+
+/// Sha3-256 of the nonce and block header; seeds everything derived for a challenge.
+pub fn challenge_seed(nonce: u64, block_header: &[u8]) -> [u8; 32] {
+    spec::challenge_seed(SpecVersion::CURRENT, nonce, block_header)
+}
+
+/// Consensus engine id under which RubikPoW digest items are logged.
+pub const RUBIKPOW_ENGINE_ID: [u8; 4] = *b"rbkp";
+
+/// Engine id of the pre-runtime digest item carrying the root of the block's
+/// transactions, which V2 challenges are derived from. The pallet checks it
+/// against the block's extrinsics root when the block is finalized.
+pub const TEMPLATE_ENGINE_ID: [u8; 4] = *b"rbkt";
+
+/// Sha3-256 binding a challenge seed to the cube size and target it was
+/// issued with. Light clients compare it with the header digest instead of
+/// re-deriving the scramble.
+pub fn challenge_commitment(seed: &[u8; 32], cube_size: u32, target: &Target) -> [u8; 32] {
+    spec::challenge_commitment(SpecVersion::CURRENT, seed, cube_size, target)
+}
+
+/// Per-challenge assignment of colours to faces, indexed like `Face::ALL`.
+///
+/// A Fisher-Yates shuffle of `Color::ALL` driven by a domain-separated hash of
+/// the seed, so it is independent of the scramble RNG stream.
+pub fn color_permutation(seed: &[u8; 32]) -> [Color; 6] {
+    spec::color_permutation(SpecVersion::CURRENT, seed)
+}
+
+/// Base-2 logarithm of the number of reachable states of an n×n×n cube.
+///
+/// Corners contribute 8!·3^7, the middle edges of odd cubes 12!·2^10, every
+/// orbit of 24 wing edges 24! and every orbit of 24 centre pieces 24!/(4!)^6
+/// (four indistinguishable pieces per colour). Even cubes have no fixed
+/// centres, so their count is divided by the 24 whole-cube orientations.
+#[cfg(feature = "std")]
+pub fn state_space_log2(n: usize) -> f64 {
+    fn log2_factorial(k: u32) -> f64 {
+        (2..=k).map(|i| f64::from(i).log2()).sum()
+    }
+
+    if n < 2 {
+        return 0.0;
+    }
+
+    let odd = n % 2;
+    let wing_orbits = (n - 2) / 2;
+    let center_orbits = ((n - 2) * (n - 2) - odd) / 4;
+
+    let mut bits = log2_factorial(8) + 7.0 * 3f64.log2();
+    if odd == 1 {
+        bits += log2_factorial(12) + 10.0;
+    } else {
+        bits -= 24f64.log2();
+    }
+    bits += wing_orbits as f64 * log2_factorial(24);
+    bits += center_orbits as f64 * (log2_factorial(24) - 6.0 * log2_factorial(4));
+    bits
+}
@@ -0,0 +1,65 @@
+//! Move metrics for scoring solution length.
+//!
+//! The chain counts solutions as written, without merging adjacent turns.
+//! Whole-cube rotations reorient the cube without turning anything, so they
+//! count nothing in every metric, and neither do moves of zero quarter turns.
+
+use crate::Move;
+
+/// How a move sequence's length is counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "scale-codec",
+    derive(codec::Encode, codec::Decode, codec::MaxEncodedLen, scale_info::TypeInfo)
+)]
+pub enum Metric {
+    /// Half-turn metric: any turn of an outer block of layers is one move,
+    /// whatever its angle. A turn of inner layers, such as a slice, counts
+    /// as the two outer-block turns it can be written as.
+    #[default]
+    #[cfg_attr(feature = "scale-codec", codec(index = 0))]
+    Htm,
+    /// Quarter-turn metric: half turns count as two moves, and turns of
+    /// inner layers twice that.
+    #[cfg_attr(feature = "scale-codec", codec(index = 1))]
+    Qtm,
+    /// Slice-turn metric: any turn of a block of adjacent layers, inner
+    /// slices included, is one move. With only outer-block moves in the
+    /// notation it counts like [`Metric::Htm`].
+    #[cfg_attr(feature = "scale-codec", codec(index = 2))]
+    Stm,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 3] = [Metric::Htm, Metric::Qtm, Metric::Stm];
+
+    /// Cost of `m` on a cube of `size`.
+    pub fn cost(self, m: &Move, size: usize) -> u32 {
+        let turns = m.count() % 4;
+        let layers = m.layers(size);
+        if turns == 0 || layers.is_empty() || layers.len() >= size {
+            return 0;
+        }
+        // An inner block turns like the two outer blocks around it
+        let blocks = if layers.start == 0 || layers.end == size { 1 } else { 2 };
+        match self {
+            Metric::Stm => 1,
+            Metric::Htm => blocks,
+            Metric::Qtm if turns == 2 => 2 * blocks,
+            Metric::Qtm => blocks,
+        }
+    }
+
+    /// Length of `moves` on a cube of `size`; saturates at `u32::MAX`.
+    pub fn length(self, moves: &[Move], size: usize) -> u32 {
+        moves.iter().fold(0u32, |length, m| length.saturating_add(self.cost(m, size)))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Metric::Htm => "HTM",
+            Metric::Qtm => "QTM",
+            Metric::Stm => "STM",
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Miner configuration file.
+//!
+//! The miner reads a TOML file at startup. [`ConfigWatcher`] re-reads it when
+//! the file changes (or when the binary calls [`ConfigWatcher::reload`] from
+//! its SIGHUP handler). Thread count and log level are applied on the fly;
+//! changes to anything else are reported so the operator knows to restart.
+//!
+//! ```toml
+//! [node]
+//! mode = "rpc"                  # or "stratum"
+//! endpoint = "ws://127.0.0.1:9944"
+//!
+//! [mining]
+//! threads = 8
+//! pin = true                    # bind each worker to a CPU
+//! huge_pages = "transparent"    # or "reserved" or "off"
+//! sizes = [3, 4]
+//! strategy = "reverse-scramble"
+//!
+//! [metrics]
+//! port = 9616
+//!
+//! [log]
+//! level = "info"
+//! format = "json"               # or "text"
+//! ```
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::hugepages::HugePages;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeMode {
+    /// Templates and submissions through the node's JSON-RPC.
+    Rpc,
+    /// Work from a pool over stratum.
+    Stratum,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NodeConfig {
+    pub mode: NodeMode,
+    pub endpoint: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MiningConfig {
+    pub threads: usize,
+    /// Bind each worker thread to a CPU.
+    pub pin: bool,
+    /// Pages to back solver tables with; see [`crate::hugepages`].
+    pub huge_pages: HugePages,
+    /// Cube sizes to mine, in order of preference.
+    pub sizes: Vec<u32>,
+    /// Name of the built-in solver to use.
+    pub strategy: String,
+}
+
+impl From<&MiningConfig> for super::WorkerConfig {
+    fn from(mining: &MiningConfig) -> Self {
+        super::WorkerConfig {
+            threads: mining.threads,
+            pin: mining.pin,
+        }
+    }
+}
+
+impl Default for MiningConfig {
+    fn default() -> Self {
+        MiningConfig {
+            threads: 1,
+            pin: false,
+            huge_pages: HugePages::Off,
+            sizes: vec![3],
+            strategy: "reverse-scramble".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsConfig {
+    /// Port for the metrics endpoint; disabled when absent.
+    pub port: Option<u16>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    /// One JSON object per event, including the enclosing span's fields.
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogConfig {
+    pub level: String,
+    pub format: LogFormat,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig {
+            level: "info".to_string(),
+            format: LogFormat::Text,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MinerConfig {
+    pub node: NodeConfig,
+    #[serde(default)]
+    pub mining: MiningConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub log: LogConfig,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    Invalid(&'static str),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "cannot read config: {}", e),
+            ConfigError::Parse(e) => write!(f, "invalid config: {}", e),
+            ConfigError::Invalid(reason) => write!(f, "invalid config: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl MinerConfig {
+    pub fn from_toml(text: &str) -> Result<Self, ConfigError> {
+        let config: MinerConfig = toml::from_str(text).map_err(ConfigError::Parse)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let text = std::fs::read_to_string(path).map_err(ConfigError::Io)?;
+        MinerConfig::from_toml(&text)
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.mining.threads == 0 {
+            return Err(ConfigError::Invalid("mining.threads must be at least 1"));
+        }
+        if self.mining.sizes.is_empty() {
+            return Err(ConfigError::Invalid("mining.sizes must not be empty"));
+        }
+        if self.mining.sizes.iter().any(|&size| size < 2) {
+            return Err(ConfigError::Invalid("mining.sizes must be at least 2"));
+        }
+        Ok(())
+    }
+
+    /// Copy of `self` with the settings that can change at runtime taken
+    /// from `other`.
+    fn with_reloadable_from(&self, other: &MinerConfig) -> MinerConfig {
+        let mut merged = self.clone();
+        merged.mining.threads = other.mining.threads;
+        merged.log.level = other.log.level.clone();
+        merged
+    }
+}
+
+/// Result of a reload that changed the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reload {
+    /// The configuration now in effect.
+    pub config: MinerConfig,
+    /// True if the file changed settings that only take effect on restart.
+    pub restart_required: bool,
+}
+
+pub struct ConfigWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    current: MinerConfig,
+}
+
+impl ConfigWatcher {
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, ConfigError> {
+        let path = path.into();
+        let modified = modified_time(&path);
+        let current = MinerConfig::load(&path)?;
+        Ok(ConfigWatcher {
+            path,
+            modified,
+            current,
+        })
+    }
+
+    pub fn current(&self) -> &MinerConfig {
+        &self.current
+    }
+
+    /// Reloads if the file's modification time changed since the last read.
+    pub fn poll(&mut self) -> Result<Option<Reload>, ConfigError> {
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return Ok(None);
+        }
+        self.modified = modified;
+        self.reload()
+    }
+
+    /// Re-reads the file unconditionally. On error the current configuration
+    /// stays in effect. Returns `None` if nothing changed.
+    pub fn reload(&mut self) -> Result<Option<Reload>, ConfigError> {
+        let loaded = MinerConfig::load(&self.path)?;
+        if loaded == self.current {
+            return Ok(None);
+        }
+
+        let applied = self.current.with_reloadable_from(&loaded);
+        let restart_required = applied != loaded;
+        self.current = applied.clone();
+        Ok(Some(Reload {
+            config: applied,
+            restart_required,
+        }))
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
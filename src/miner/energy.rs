@@ -0,0 +1,128 @@
+//! Energy accounting from Linux powercap (RAPL) counters.
+//!
+//! [`EnergyMeter`] sums the `energy_uj` counters of the top-level RAPL
+//! packages. The miner reads it when it starts working on a template and again
+//! when a seal is accepted, and feeds the difference into [`EnergyStats`].
+//! Machines without powercap support simply have no meter.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Where the kernel exposes powercap zones.
+pub const POWERCAP_ROOT: &str = "/sys/class/powercap";
+
+#[derive(Debug, Clone)]
+struct Zone {
+    energy: PathBuf,
+    /// Value at which the counter wraps back to zero.
+    range_uj: u64,
+}
+
+/// Cumulative CPU package energy in microjoules.
+#[derive(Debug, Clone)]
+pub struct EnergyMeter {
+    zones: Vec<Zone>,
+}
+
+/// Energy counter snapshot, one value per zone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnergyReading(Vec<u64>);
+
+impl EnergyMeter {
+    /// Meter over the RAPL packages on this machine, or `None` if there are
+    /// none or their counters are not readable.
+    pub fn detect() -> Option<Self> {
+        EnergyMeter::open(Path::new(POWERCAP_ROOT))
+    }
+
+    /// Like [`EnergyMeter::detect`] with a different powercap root.
+    pub fn open(root: &Path) -> Option<Self> {
+        let mut zones = Vec::new();
+        for entry in fs::read_dir(root).ok()? {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_string_lossy().into_owned();
+            // Packages are `intel-rapl:N`; `intel-rapl:N:M` are their sub-zones
+            if !name.starts_with("intel-rapl:") || name.matches(':').count() != 1 {
+                continue;
+            }
+            let range_uj = read_u64(&path.join("max_energy_range_uj")).unwrap_or(u64::MAX);
+            zones.push(Zone {
+                energy: path.join("energy_uj"),
+                range_uj,
+            });
+        }
+        zones.sort_by(|a, b| a.energy.cmp(&b.energy));
+
+        let meter = EnergyMeter { zones };
+        if meter.zones.is_empty() || meter.read().is_none() {
+            return None;
+        }
+        Some(meter)
+    }
+
+    pub fn read(&self) -> Option<EnergyReading> {
+        self.zones
+            .iter()
+            .map(|zone| read_u64(&zone.energy))
+            .collect::<Option<Vec<u64>>>()
+            .map(EnergyReading)
+    }
+
+    /// Joules consumed between two readings, allowing each counter to have
+    /// wrapped at most once.
+    pub fn joules_between(&self, start: &EnergyReading, end: &EnergyReading) -> f64 {
+        let microjoules: u64 = self
+            .zones
+            .iter()
+            .zip(start.0.iter().zip(&end.0))
+            .map(|(zone, (&before, &after))| {
+                if after >= before {
+                    after - before
+                } else {
+                    zone.range_uj.saturating_sub(before).saturating_add(after)
+                }
+            })
+            .sum();
+        microjoules as f64 / 1e6
+    }
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Running energy totals for accepted solutions.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EnergyStats {
+    pub accepted: u64,
+    pub joules: f64,
+}
+
+impl EnergyStats {
+    pub fn record_accepted(&mut self, joules: f64) {
+        self.accepted += 1;
+        self.joules += joules;
+    }
+
+    pub fn joules_per_solution(&self) -> Option<f64> {
+        if self.accepted == 0 {
+            None
+        } else {
+            Some(self.joules / self.accepted as f64)
+        }
+    }
+
+    /// Prometheus text exposition, served on the metrics port.
+    pub fn write_metrics<W: Write>(&self, mut out: W) -> io::Result<()> {
+        writeln!(out, "# TYPE qbit_miner_accepted_solutions_total counter")?;
+        writeln!(out, "qbit_miner_accepted_solutions_total {}", self.accepted)?;
+        writeln!(out, "# TYPE qbit_miner_energy_joules_total counter")?;
+        writeln!(out, "qbit_miner_energy_joules_total {}", self.joules)?;
+        if let Some(per_solution) = self.joules_per_solution() {
+            writeln!(out, "# TYPE qbit_miner_joules_per_solution gauge")?;
+            writeln!(out, "qbit_miner_joules_per_solution {}", per_solution)?;
+        }
+        Ok(())
+    }
+}
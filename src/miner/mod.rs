@@ -0,0 +1,32 @@
+//! Mining-side building blocks: tracking work against the chain and running
+//! the worker threads.
+
+#![allow(clippy::expect_used)]
+
+#[cfg(feature = "miner-config")]
+mod config;
+mod energy;
+#[cfg(feature = "miner-logging")]
+pub mod logging;
+#[cfg(feature = "solver-plugins")]
+mod plugin;
+mod profit;
+mod solve;
+mod work;
+mod workers;
+
+#[cfg(feature = "miner-config")]
+pub use config::{
+    ConfigError, ConfigWatcher, LogConfig, LogFormat, MetricsConfig, MinerConfig, MiningConfig, NodeConfig,
+    NodeMode, Reload,
+};
+#[cfg(feature = "solver-plugins")]
+pub use plugin::{load_solver, LibrarySolver, PluginConfig, PluginError, SubprocessSolver};
+pub use energy::{EnergyMeter, EnergyReading, EnergyStats, POWERCAP_ROOT};
+pub use profit::ProfitEstimator;
+pub use solve::{attempt, attempt_with_scramble};
+pub use work::{BlockTemplate, ChainView, JobId, ReorgOutcome, WorkTracker};
+pub use workers::{FoundSeal, PerNode, Topology, WorkerConfig, WorkerContext, WorkerPool, NODE_ROOT};
+
+/// 32-byte block hash as seen by the miner.
+pub type BlockHash = [u8; 32];
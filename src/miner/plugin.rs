@@ -0,0 +1,221 @@
+//! External solvers, loaded at runtime so they can compete without forking
+//! the miner.
+//!
+//! Both kinds of plugin exchange moves in the seal's byte encoding
+//! ([`move_to_byte`]) and the cube as its facelet string
+//! ([`Cube::to_facelets`]).
+//!
+//! * **Subprocess**: the command is started once and receives one JSON
+//!   request per line on stdin,
+//!   `{"size": 3, "facelets": "WWW…", "scramble": [5, 18, …]}`,
+//!   answering each with one line, `{"moves": [7, 12, …]}` or
+//!   `{"moves": null}` to give up.
+//! * **Dynamic library**: the library exports
+//!   `qbit_solver_name() -> *const c_char` and
+//!   `qbit_solver_solve(size: u32, facelets: *const u8, facelets_len: usize,
+//!   scramble: *const u8, scramble_len: usize, out: *mut u8, out_cap: usize)
+//!   -> isize`, which writes the solution's move bytes to `out` and returns
+//!   their count, or a negative value to give up.
+
+use std::ffi::CStr;
+use std::fmt;
+use std::io::{BufRead, BufReader, Write};
+use std::os::raw::c_char;
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::seal::{move_from_byte, move_to_byte};
+use crate::solver::Solver;
+use crate::{Cube, Move};
+
+/// Upper bound on the solution length accepted from a library plugin.
+const MAX_SOLUTION_LEN: usize = 4096;
+
+/// How to load a solver, as written in the miner configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PluginConfig {
+    Library {
+        path: PathBuf,
+    },
+    Subprocess {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Debug)]
+pub enum PluginError {
+    Io(std::io::Error),
+    Library(libloading::Error),
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PluginError::Io(e) => write!(f, "failed to start solver process: {}", e),
+            PluginError::Library(e) => write!(f, "failed to load solver library: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PluginError {}
+
+/// Loads the solver described by `config`.
+pub fn load_solver(config: &PluginConfig) -> Result<Box<dyn Solver + Send + Sync>, PluginError> {
+    match config {
+        PluginConfig::Library { path } => Ok(Box::new(LibrarySolver::load(path)?)),
+        PluginConfig::Subprocess { command, args } => {
+            Ok(Box::new(SubprocessSolver::spawn(command, args)?))
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Request<'a> {
+    size: usize,
+    facelets: &'a str,
+    scramble: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct Response {
+    moves: Option<Vec<u8>>,
+}
+
+fn decode_moves(bytes: &[u8]) -> Option<Vec<Move>> {
+    bytes.iter().map(|&byte| move_from_byte(byte)).collect()
+}
+
+/// Solver running in a child process, speaking line-delimited JSON.
+pub struct SubprocessSolver {
+    name: String,
+    io: Mutex<(Child, ChildStdin, BufReader<ChildStdout>)>,
+}
+
+impl SubprocessSolver {
+    pub fn spawn(command: &str, args: &[String]) -> Result<Self, PluginError> {
+        let mut child = Command::new(command)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(PluginError::Io)?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        Ok(SubprocessSolver {
+            name: command.to_string(),
+            io: Mutex::new((child, stdin, stdout)),
+        })
+    }
+
+    fn exchange(&self, request: &Request) -> Option<Vec<Move>> {
+        let mut io = self.io.lock().ok()?;
+        let (_, stdin, stdout) = &mut *io;
+
+        let mut line = serde_json::to_string(request).ok()?;
+        line.push('\n');
+        stdin.write_all(line.as_bytes()).ok()?;
+        stdin.flush().ok()?;
+
+        let mut reply = String::new();
+        stdout.read_line(&mut reply).ok()?;
+        let response: Response = serde_json::from_str(&reply).ok()?;
+        decode_moves(&response.moves?)
+    }
+}
+
+impl Solver for SubprocessSolver {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn solve(&self, cube: &Cube, scramble: &[Move]) -> Option<Vec<Move>> {
+        let facelets = cube.to_facelets();
+        self.exchange(&Request {
+            size: cube.size,
+            facelets: &facelets,
+            scramble: scramble.iter().map(move_to_byte).collect(),
+        })
+    }
+}
+
+impl Drop for SubprocessSolver {
+    fn drop(&mut self) {
+        if let Ok(io) = self.io.get_mut() {
+            let _ = io.0.kill();
+            let _ = io.0.wait();
+        }
+    }
+}
+
+type NameFn = unsafe extern "C" fn() -> *const c_char;
+type SolveFn =
+    unsafe extern "C" fn(u32, *const u8, usize, *const u8, usize, *mut u8, usize) -> isize;
+
+/// Solver exported from a dynamic library through the C ABI above.
+pub struct LibrarySolver {
+    name: String,
+    solve: SolveFn,
+    // Keeps the code behind `solve` mapped
+    _library: libloading::Library,
+}
+
+impl LibrarySolver {
+    pub fn load(path: &std::path::Path) -> Result<Self, PluginError> {
+        // SAFETY: loading runs the library's initialisers; plugins are trusted
+        // the same way the miner binary itself is.
+        unsafe {
+            let library = libloading::Library::new(path).map_err(PluginError::Library)?;
+            let name_fn: NameFn = *library
+                .get::<NameFn>(b"qbit_solver_name\0")
+                .map_err(PluginError::Library)?;
+            let solve: SolveFn = *library
+                .get::<SolveFn>(b"qbit_solver_solve\0")
+                .map_err(PluginError::Library)?;
+
+            let name_ptr = name_fn();
+            let name = if name_ptr.is_null() {
+                path.display().to_string()
+            } else {
+                CStr::from_ptr(name_ptr).to_string_lossy().into_owned()
+            };
+            Ok(LibrarySolver {
+                name,
+                solve,
+                _library: library,
+            })
+        }
+    }
+}
+
+impl Solver for LibrarySolver {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn solve(&self, cube: &Cube, scramble: &[Move]) -> Option<Vec<Move>> {
+        let facelets = cube.to_facelets();
+        let scramble: Vec<u8> = scramble.iter().map(move_to_byte).collect();
+        let mut out = vec![0u8; MAX_SOLUTION_LEN];
+
+        // SAFETY: every pointer is valid for the length passed alongside it
+        let written = unsafe {
+            (self.solve)(
+                cube.size as u32,
+                facelets.as_ptr(),
+                facelets.len(),
+                scramble.as_ptr(),
+                scramble.len(),
+                out.as_mut_ptr(),
+                out.len(),
+            )
+        };
+        let written = usize::try_from(written).ok()?;
+        decode_moves(out.get(..written)?)
+    }
+}
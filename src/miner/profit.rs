@@ -0,0 +1,62 @@
+//! Which template is worth mining, in reward units per day.
+//!
+//! The estimate stays in integers, through [`crate::curves`] and
+//! [`crate::difficulty`], so it agrees with what the runtime would pay.
+
+use super::BlockTemplate;
+use crate::curves::{self, Curve};
+use crate::difficulty::{expected_reward_per_day, size_weight, REFERENCE_SIZE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProfitEstimator<const N: usize> {
+    /// Reward the schedule pays per [`REFERENCE_SIZE`] seal.
+    pub base_reward: u32,
+    /// Attempts per second on a [`REFERENCE_SIZE`] cube. Other sizes cost
+    /// their size weight relative to it.
+    pub reference_rate: u64,
+    /// Per-mille of attempts the solver finishes, by cube size.
+    pub solve_rate: Curve<N>,
+    /// Running costs per day, in reward units.
+    pub cost_per_day: u128,
+}
+
+impl<const N: usize> ProfitEstimator<N> {
+    /// Attempts per second that end in a checked seal candidate on `size`.
+    pub fn attempts_per_second(&self, size: u32) -> u64 {
+        let weight = size_weight(size);
+        if weight == 0 {
+            return 0;
+        }
+        let raw = u128::from(self.reference_rate).saturating_mul(size_weight(REFERENCE_SIZE)) / weight;
+        let finished = self.solve_rate.scale(size, raw);
+        u64::try_from(finished).unwrap_or(u64::MAX)
+    }
+
+    pub fn reward_per_day(&self, template: &BlockTemplate) -> u128 {
+        let rate = self.attempts_per_second(template.cube_size);
+        expected_reward_per_day(self.base_reward, template.cube_size, &template.target, rate)
+    }
+
+    /// Reward minus costs per day; zero when mining `template` loses money.
+    pub fn profit_per_day(&self, template: &BlockTemplate) -> u128 {
+        self.reward_per_day(template).saturating_sub(self.cost_per_day)
+    }
+
+    /// Costs as a per-mille share of the reward, `None` if there is no reward.
+    pub fn cost_ratio(&self, template: &BlockTemplate) -> Option<u32> {
+        curves::ratio(self.cost_per_day, self.reward_per_day(template))
+    }
+
+    /// The template paying the most per day, the first among equals; `None`
+    /// if none covers its costs.
+    pub fn best<'a>(&self, templates: &'a [BlockTemplate]) -> Option<&'a BlockTemplate> {
+        let mut best: Option<(&BlockTemplate, u128)> = None;
+        for template in templates {
+            let profit = self.profit_per_day(template);
+            if profit > 0 && !matches!(best, Some((_, most)) if most >= profit) {
+                best = Some((template, profit));
+            }
+        }
+        best.map(|(template, _)| template)
+    }
+}
@@ -0,0 +1,65 @@
+//! A single mining attempt, from challenge derivation to a checked seal.
+
+use crate::solver::Solver;
+use crate::{verify_seal_with_scramble, CommitmentVersion, Cube, PowSeal, ScrambleSpec, SpecVersion};
+
+use super::BlockTemplate;
+
+/// Derives the challenge for `nonce` under `template`, runs `solver` on it
+/// and returns the seal if it passes the same checks the chain applies.
+pub fn attempt(solver: &dyn Solver, template: &BlockTemplate, nonce: u64) -> Option<PowSeal> {
+    attempt_with_scramble(solver, template, &ScrambleSpec::V1, nonce)
+}
+
+/// [`attempt`] on a chain that scrambles `template.cube_size` cubes under
+/// `scramble`.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "attempt",
+        skip_all,
+        fields(solver = solver.name(), nonce, size = template.cube_size)
+    )
+)]
+pub fn attempt_with_scramble(
+    solver: &dyn Solver,
+    template: &BlockTemplate,
+    scramble_spec: &ScrambleSpec,
+    nonce: u64,
+) -> Option<PowSeal> {
+    if !scramble_spec.is_valid() {
+        return None;
+    }
+    let mut cube = Cube::new(template.cube_size as usize);
+    let scramble = cube.scramble_with(SpecVersion::CURRENT, scramble_spec, nonce, &template.header);
+
+    let moves = solver.solve(&cube, &scramble);
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        solved = moves.is_some(),
+        depth = moves.as_ref().map(Vec::len),
+        "solver finished"
+    );
+
+    let seal = PowSeal {
+        cube_size: template.cube_size,
+        nonce,
+        moves: moves?,
+    };
+    let checked = verify_seal_with_scramble(
+        SpecVersion::CURRENT,
+        CommitmentVersion::CURRENT,
+        scramble_spec,
+        &seal,
+        &template.header,
+        &template.target,
+    );
+    match checked {
+        Ok(_) => Some(seal),
+        Err(_error) => {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(reason = %_error, "seal rejected locally");
+            None
+        }
+    }
+}
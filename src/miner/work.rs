@@ -0,0 +1,140 @@
+//! Reorg-aware tracking of in-flight work.
+//!
+//! Every template the miner works on is bound to the parent it was built on.
+//! When the node reports a new best block that does not descend from the
+//! previous one, templates on the abandoned branch are invalidated, and seals
+//! already found for them are handed back for resubmission if the new branch
+//! derives exactly the same challenge.
+
+use std::collections::BTreeMap;
+
+use super::BlockHash;
+use crate::difficulty::{scale_reward, work_per_seal};
+use crate::{PowSeal, Target};
+
+pub type JobId = u64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockTemplate {
+    pub parent_hash: BlockHash,
+    /// Header bytes the challenge is derived from.
+    pub header: Vec<u8>,
+    pub cube_size: u32,
+    pub target: Target,
+}
+
+impl BlockTemplate {
+    /// Fork-choice work a seal for this template adds.
+    pub fn work(&self) -> u128 {
+        work_per_seal(self.cube_size, &self.target)
+    }
+
+    /// Reward a seal for this template earns under a schedule paying `base`
+    /// per reference-size seal.
+    pub fn reward(&self, base: u32) -> u32 {
+        scale_reward(base, self.cube_size, &self.target)
+    }
+}
+
+/// What the miner needs to know about the node's view of the chain.
+pub trait ChainView {
+    fn best_hash(&self) -> BlockHash;
+
+    fn is_ancestor(&self, ancestor: &BlockHash, descendant: &BlockHash) -> bool;
+
+    /// Header bytes the runtime would derive the next challenge from when
+    /// building on `parent`.
+    fn challenge_header(&self, parent: &BlockHash) -> Vec<u8>;
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReorgOutcome {
+    /// True if the new best block is not a descendant of the previous one.
+    pub reorged: bool,
+    /// Jobs whose templates now sit on an abandoned branch.
+    pub invalidated: Vec<JobId>,
+    /// Seals found on the abandoned branch that are still valid on the new one.
+    pub resubmit: Vec<PowSeal>,
+}
+
+#[derive(Debug, Default)]
+pub struct WorkTracker {
+    best: Option<BlockHash>,
+    next_job: JobId,
+    jobs: BTreeMap<JobId, BlockTemplate>,
+    found: BTreeMap<JobId, PowSeal>,
+    resubmit_on_reorg: bool,
+}
+
+impl WorkTracker {
+    pub fn new(resubmit_on_reorg: bool) -> Self {
+        WorkTracker {
+            resubmit_on_reorg,
+            ..Default::default()
+        }
+    }
+
+    pub fn register(&mut self, template: BlockTemplate) -> JobId {
+        let job = self.next_job;
+        self.next_job += 1;
+        self.jobs.insert(job, template);
+        job
+    }
+
+    pub fn template(&self, job: JobId) -> Option<&BlockTemplate> {
+        self.jobs.get(&job)
+    }
+
+    pub fn is_valid(&self, job: JobId) -> bool {
+        self.jobs.contains_key(&job)
+    }
+
+    /// Remembers a seal found for `job` until the job's branch is settled.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "submit", skip_all, fields(job = job, nonce = seal.nonce))
+    )]
+    pub fn record_found(&mut self, job: JobId, seal: PowSeal) {
+        if self.jobs.contains_key(&job) {
+            self.found.insert(job, seal);
+        }
+    }
+
+    /// Call whenever the node reports a new best block.
+    pub fn on_new_best<C: ChainView>(&mut self, chain: &C) -> ReorgOutcome {
+        let new_best = chain.best_hash();
+        let previous = self.best.replace(new_best);
+
+        let reorged = match previous {
+            Some(previous) => previous != new_best && !chain.is_ancestor(&previous, &new_best),
+            None => false,
+        };
+        if !reorged {
+            return ReorgOutcome::default();
+        }
+
+        let orphaned: Vec<JobId> = self
+            .jobs
+            .iter()
+            .filter(|(_, template)| !chain.is_ancestor(&template.parent_hash, &new_best))
+            .map(|(&job, _)| job)
+            .collect();
+
+        let new_header = chain.challenge_header(&new_best);
+        let mut outcome = ReorgOutcome {
+            reorged,
+            ..Default::default()
+        };
+
+        for job in orphaned {
+            let template = self.jobs.remove(&job).expect("job listed from the map");
+            if let Some(seal) = self.found.remove(&job) {
+                if self.resubmit_on_reorg && template.header == new_header {
+                    outcome.resubmit.push(seal);
+                }
+            }
+            outcome.invalidated.push(job);
+        }
+        outcome
+    }
+}
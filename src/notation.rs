@@ -0,0 +1,153 @@
+//! Standard cube notation for move sequences.
+//!
+//! Face turns are written `R`, `R2` and `R'`, wide turns `Rw`, slice turns
+//! `M`, `E` and `S` and whole-cube rotations `x`, `y` and `z`, with moves
+//! separated by single spaces. On big cubes a layer position prefixes the
+//! face: `2R` turns the second layer from R alone, `3Rw` the three nearest
+//! it. Moves of
+//! zero quarter turns, which the notation has no symbol for, are written with
+//! a `0` suffix. `Move` implements `Display` and `FromStr` in this notation
+//! and [`format_algorithm`] and [`parse_algorithm`] handle whole sequences,
+//! so proofs and scrambles can be exchanged with external solvers and tools.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{Axis, Face, Move, MoveKind};
+
+/// `moves` in standard notation, e.g. `R U2 F'`.
+pub fn format_algorithm(moves: &[Move]) -> String {
+    moves.iter().map(Move::to_string).collect::<Vec<_>>().join(" ")
+}
+
+/// The move in standard notation, e.g. `Rw2`.
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Move::Wide(face, _) => write!(f, "{}w", face)?,
+            Move::Layer(face, layer, _) => write!(f, "{}{}", layer, face)?,
+            Move::Block(face, layers, _) => write!(f, "{}{}w", layers, face)?,
+            Move::Rotation(axis, _) => f.write_str(match axis {
+                Axis::X => "x",
+                Axis::Y => "y",
+                Axis::Z => "z",
+            })?,
+            Move::Slice(axis, _) => f.write_str(match axis {
+                Axis::X => "M",
+                Axis::Y => "E",
+                Axis::Z => "S",
+            })?,
+            _ => write!(f, "{}", self.face())?,
+        }
+        f.write_str(match self.count() % 4 {
+            1 => "",
+            2 => "2",
+            3 => "'",
+            _ => "0",
+        })
+    }
+}
+
+/// A token [`parse_algorithm`] or [`Move::from_str`] could not read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Index of the move in the sequence, 0 for a single move.
+    pub index: usize,
+    pub token: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {}: `{}` is not a move", self.index + 1, self.token)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// Reads moves separated by whitespace, e.g. `R U R' U2 Fw x`. Besides what
+/// [`format_algorithm`] writes, lowercase face letters are read as wide turns
+/// (`r` is `Rw`) and `2'` as a half turn.
+pub fn parse_algorithm(text: &str) -> Result<Vec<Move>, ParseError> {
+    text.split_whitespace()
+        .enumerate()
+        .map(|(index, token)| {
+            parse_move(token).ok_or_else(|| ParseError {
+                index,
+                token: token.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Reads one move as written by its [`Display`](fmt::Display) impl.
+impl FromStr for Move {
+    type Err = ParseError;
+
+    fn from_str(token: &str) -> Result<Move, ParseError> {
+        parse_move(token).ok_or_else(|| ParseError {
+            index: 0,
+            token: token.to_string(),
+        })
+    }
+}
+
+fn parse_move(token: &str) -> Option<Move> {
+    let digits = token.len() - token.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits > 0 {
+        let (position, rest) = token.split_at(digits);
+        let position = position.parse().ok().filter(|&position: &usize| position > 0)?;
+        // Positions only prefix face letters: `3r` and `2x` are not moves
+        if !rest.starts_with(|c: char| c.is_ascii_uppercase()) {
+            return None;
+        }
+        return match parse_move(rest)? {
+            m if m.kind() == MoveKind::Face => Some(Move::Layer(m.face(), position, m.count())),
+            Move::Wide(face, count) => Some(Move::Block(face, position, count)),
+            _ => None,
+        };
+    }
+
+    let mut chars = token.chars();
+    let letter = chars.next()?;
+    let rest = chars.as_str();
+    let (wide, suffix) = match rest.strip_prefix('w') {
+        Some(suffix) => (true, suffix),
+        None => (false, rest),
+    };
+    let count = match suffix {
+        "" => 1,
+        "2" | "2'" => 2,
+        "'" => 3,
+        "0" => 0,
+        _ => return None,
+    };
+
+    let m = match (letter, wide) {
+        ('x', false) => Move::Rotation(Axis::X, count),
+        ('y', false) => Move::Rotation(Axis::Y, count),
+        ('z', false) => Move::Rotation(Axis::Z, count),
+        ('M', false) => Move::Slice(Axis::X, count),
+        ('E', false) => Move::Slice(Axis::Y, count),
+        ('S', false) => Move::Slice(Axis::Z, count),
+        _ => {
+            let face = match letter.to_ascii_uppercase() {
+                'U' => Face::Up,
+                'D' => Face::Down,
+                'L' => Face::Left,
+                'R' => Face::Right,
+                'F' => Face::Front,
+                'B' => Face::Back,
+                _ => return None,
+            };
+            match (letter.is_ascii_lowercase(), wide) {
+                (false, false) => Move::from_face_and_count(face, count),
+                (false, true) | (true, false) => Move::Wide(face, count),
+                (true, true) => return None,
+            }
+        }
+    };
+    Some(m)
+}
@@ -0,0 +1,259 @@
+//! Optimal solver for the 2x2x2.
+//!
+//! With the down-back-left corner held in place, every 2x2x2 state is
+//! reached by turning R, U and F only: 7! corner arrangements times 3^6
+//! twists, 3,674,160 states. A breadth-first search over that space gives
+//! the exact distance of every state in the half-turn metric (U, U2 and U'
+//! each count as one move), and an optimal solution follows by repeatedly
+//! taking a move that lowers the distance.
+
+#![allow(clippy::expect_used)]
+
+use std::sync::OnceLock;
+
+use crate::geometry;
+use crate::group::corners;
+use crate::hugepages::{Backing, Table};
+use crate::solver::Solver;
+use crate::{Cube, Face, Move, Symmetry};
+
+const PERMUTATIONS: usize = 5040;
+const TWISTS: usize = 729;
+/// Number of 2x2x2 states with the down-back-left corner fixed.
+pub const STATES: usize = PERMUTATIONS * TWISTS;
+
+/// Face turns that leave corner 0 (down-back-left) alone.
+const MOVES: [Move; 9] = [
+    Move::R(1),
+    Move::R(2),
+    Move::R(3),
+    Move::U(1),
+    Move::U(2),
+    Move::U(3),
+    Move::F(1),
+    Move::F(2),
+    Move::F(3),
+];
+
+const UNSEEN: u8 = u8::MAX;
+
+struct Tables {
+    permutation_moves: Vec<[u16; 9]>,
+    twist_moves: Vec<[u16; 9]>,
+    /// Distance to solved, indexed by `permutation * TWISTS + twist`.
+    distance: Table<u8>,
+}
+
+static TABLES: OnceLock<Tables> = OnceLock::new();
+
+fn tables() -> &'static Tables {
+    TABLES.get_or_init(Tables::build)
+}
+
+impl Tables {
+    fn build() -> Tables {
+        // Each move as a corner permutation with twist increments, read off a
+        // turned solved cube
+        let effects: Vec<Vec<(usize, u8)>> = MOVES
+            .iter()
+            .map(|m| {
+                let mut cube = Cube::new(2);
+                cube.apply_move(m);
+                corners(&cube).expect("a turned solved cube has valid corners")
+            })
+            .collect();
+
+        let permutation_moves = (0..PERMUTATIONS)
+            .map(|rank| {
+                let pieces = decode_permutation(rank);
+                let mut next = [0u16; 9];
+                for (slot, effect) in next.iter_mut().zip(&effects) {
+                    let moved: [usize; 8] = std::array::from_fn(|position| pieces[effect[position].0]);
+                    *slot = encode_permutation(&moved) as u16;
+                }
+                next
+            })
+            .collect();
+
+        let twist_moves = (0..TWISTS)
+            .map(|code| {
+                let twists = decode_twists(code);
+                let mut next = [0u16; 9];
+                for (slot, effect) in next.iter_mut().zip(&effects) {
+                    let moved: [u8; 8] =
+                        std::array::from_fn(|position| (twists[effect[position].0] + effect[position].1) % 3);
+                    *slot = encode_twists(&moved) as u16;
+                }
+                next
+            })
+            .collect();
+
+        let mut tables = Tables {
+            permutation_moves,
+            twist_moves,
+            distance: Table::filled(STATES, UNSEEN),
+        };
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            table = "optimal-2x2x2",
+            bytes = STATES,
+            backing = %tables.distance.backing(),
+            "lookup table allocated"
+        );
+        tables.search();
+        tables
+    }
+
+    fn search(&mut self) {
+        self.distance[0] = 0;
+        let mut frontier = vec![0u32];
+        let mut depth = 0;
+        while !frontier.is_empty() {
+            depth += 1;
+            let mut next = Vec::new();
+            for &state in &frontier {
+                for i in 0..MOVES.len() {
+                    let successor = self.successor(state as usize, i);
+                    if self.distance[successor] == UNSEEN {
+                        self.distance[successor] = depth;
+                        next.push(successor as u32);
+                    }
+                }
+            }
+            frontier = next;
+        }
+    }
+
+    /// State reached from `state` by `MOVES[i]`.
+    fn successor(&self, state: usize, i: usize) -> usize {
+        let (permutation, twist) = (state / TWISTS, state % TWISTS);
+        usize::from(self.permutation_moves[permutation][i]) * TWISTS + usize::from(self.twist_moves[twist][i])
+    }
+}
+
+/// Lehmer rank of the pieces at positions 1..8; position 0 always holds piece 0.
+fn encode_permutation(pieces: &[usize; 8]) -> usize {
+    let mut rank = 0;
+    for i in 1..8 {
+        let smaller_later = (i + 1..8).filter(|&j| pieces[j] < pieces[i]).count();
+        rank = rank * (8 - i) + smaller_later;
+    }
+    rank
+}
+
+fn decode_permutation(mut rank: usize) -> [usize; 8] {
+    let mut digits = [0; 8];
+    for i in (1..8).rev() {
+        digits[i] = rank % (8 - i);
+        rank /= 8 - i;
+    }
+
+    let mut remaining: Vec<usize> = (1..8).collect();
+    let mut pieces = [0; 8];
+    for i in 1..8 {
+        pieces[i] = remaining.remove(digits[i]);
+    }
+    pieces
+}
+
+/// Base-3 twists of positions 1..7; position 7 follows from the zero sum.
+fn encode_twists(twists: &[u8; 8]) -> usize {
+    twists[1..7].iter().fold(0, |code, &twist| code * 3 + usize::from(twist))
+}
+
+fn decode_twists(mut code: usize) -> [u8; 8] {
+    let mut twists = [0u8; 8];
+    for position in (1..7).rev() {
+        twists[position] = (code % 3) as u8;
+        code /= 3;
+    }
+    let sum: u8 = twists.iter().sum();
+    twists[7] = (3 - sum % 3) % 3;
+    twists
+}
+
+/// A rotation bringing the down-back-left corner home untwisted, and the
+/// table index of the rotated state. `None` unless `cube` is a valid 2x2x2.
+fn reduce(cube: &Cube) -> Option<(Symmetry, usize)> {
+    if cube.size != 2 {
+        return None;
+    }
+    Symmetry::rotations().into_iter().find_map(|rotation| {
+        let pieces = corners(&cube.transformed(&rotation))?;
+        if pieces[0] != (0, 0) {
+            return None;
+        }
+        let positions: [usize; 8] = std::array::from_fn(|i| pieces[i].0);
+        let twists: [u8; 8] = std::array::from_fn(|i| pieces[i].1);
+        let state = encode_permutation(&positions) * TWISTS + encode_twists(&twists);
+        // Unreachable twist sums decode to a different state
+        (decode_twists(encode_twists(&twists)) == twists).then_some((rotation, state))
+    })
+}
+
+/// Optimal number of moves (half-turn metric) to solve a 2x2x2, or `None`
+/// if `cube` is not a 2x2x2 state reachable by turns.
+pub fn distance(cube: &Cube) -> Option<u8> {
+    let (_, state) = reduce(cube)?;
+    let distance = tables().distance[state];
+    (distance != UNSEEN).then_some(distance)
+}
+
+/// Memory the distance table was built in, building it if needed.
+pub fn table_backing() -> Backing {
+    tables().distance.backing()
+}
+
+/// Number of 2x2x2 states at each distance from solved; entry `d` counts the
+/// states `d` moves away. Sums to [`STATES`].
+pub fn distances() -> Vec<u64> {
+    let mut counts = Vec::new();
+    for &distance in tables().distance.iter() {
+        let distance = usize::from(distance);
+        if counts.len() <= distance {
+            counts.resize(distance + 1, 0);
+        }
+        counts[distance] += 1;
+    }
+    counts
+}
+
+/// Shortest solutions for the 2x2x2; gives up on other sizes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OptimalSolver;
+
+impl Solver for OptimalSolver {
+    fn name(&self) -> &str {
+        "optimal"
+    }
+
+    fn solve(&self, cube: &Cube, _scramble: &[Move]) -> Option<Vec<Move>> {
+        let (rotation, mut state) = reduce(cube)?;
+        let tables = tables();
+        if tables.distance[state] == UNSEEN {
+            return None;
+        }
+
+        // A turn of face f on the rotated cube is a turn of the face the
+        // rotation carries onto f
+        let unrotate = |face: Face| {
+            let normal = geometry::normal(face);
+            *Face::ALL
+                .iter()
+                .find(|&&original| rotation.apply(geometry::normal(original)) == normal)
+                .expect("rotations permute the faces")
+        };
+
+        let mut solution = Vec::with_capacity(usize::from(tables.distance[state]));
+        while tables.distance[state] > 0 {
+            let (i, next) = (0..MOVES.len())
+                .map(|i| (i, tables.successor(state, i)))
+                .find(|&(_, next)| tables.distance[next] < tables.distance[state])
+                .expect("every non-solved state has a closer neighbour");
+            let m = MOVES[i];
+            solution.push(Move::from_face_and_count(unrotate(m.face()), m.count()));
+            state = next;
+        }
+        Some(solution)
+    }
+}
@@ -0,0 +1,124 @@
+//! Packed sticker encoding: 3 bits per facelet.
+//!
+//! Layout: one version byte, the size as a little-endian u16, then the face
+//! index of every sticker (`Face::ALL`, row-major order) packed least
+//! significant bit first. Unused bits of the last byte must be zero, so every
+//! state has exactly one encoding. This is the input of the state commitment,
+//! the wire format and the on-disk cache format.
+//!
+//! The canonical encoding adds the colour scheme in front, so decoding gives
+//! back the cube as painted, not only its state. Piece orientations need no
+//! field of their own: they follow from which face each sticker shows.
+
+#![deny(clippy::indexing_slicing, clippy::panic, clippy::unreachable)]
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{Color, ColorScheme, Cube, Face};
+
+pub const PACKED_VERSION: u8 = 1;
+/// Version byte of [`Cube::to_canonical_bytes`].
+pub const CANONICAL_VERSION: u8 = 1;
+
+const HEADER_LEN: usize = 3;
+const BITS_PER_STICKER: usize = 3;
+/// Version byte and one byte per face of the scheme.
+const CANONICAL_HEADER_LEN: usize = 7;
+
+fn packed_len(size: usize) -> usize {
+    HEADER_LEN + (6 * size * size * BITS_PER_STICKER).div_ceil(8)
+}
+
+impl Cube {
+    pub fn to_packed_bytes(&self) -> Vec<u8> {
+        let mut body = vec![0u8; packed_len(self.size) - HEADER_LEN];
+        for (i, id) in self.face_ids().into_iter().enumerate() {
+            let bit = i * BITS_PER_STICKER;
+            let [low, high] = (u16::from(id) << (bit % 8)).to_le_bytes();
+            for (offset, part) in [(0, low), (1, high)] {
+                if let Some(byte) = body.get_mut(bit / 8 + offset) {
+                    *byte |= part;
+                }
+            }
+        }
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN + body.len());
+        bytes.push(PACKED_VERSION);
+        bytes.extend_from_slice(&(self.size as u16).to_le_bytes());
+        bytes.extend_from_slice(&body);
+        bytes
+    }
+
+    /// Decodes [`Cube::to_packed_bytes`] output, painting it with the default
+    /// colour scheme. Returns `None` for an unknown version, a size below 2,
+    /// a wrong length, a face index above 5 or non-zero padding.
+    pub fn from_packed_bytes(bytes: &[u8]) -> Option<Cube> {
+        let (&[version, size_low, size_high], body) = bytes.split_first_chunk::<HEADER_LEN>()?;
+        if version != PACKED_VERSION {
+            return None;
+        }
+        let size = usize::from(u16::from_le_bytes([size_low, size_high]));
+        if size < 2 || bytes.len() != packed_len(size) {
+            return None;
+        }
+
+        let read = |bit: usize| -> u16 {
+            let low = body.get(bit / 8).map_or(0, |&b| u16::from(b));
+            let high = body.get(bit / 8 + 1).map_or(0, |&b| u16::from(b));
+            ((high << 8 | low) >> (bit % 8)) & 0b111
+        };
+
+        let stickers = 6 * size * size;
+        let scheme = ColorScheme::default();
+        let mut colors = Vec::with_capacity(stickers);
+        for i in 0..stickers {
+            let id = read(i * BITS_PER_STICKER) as usize;
+            colors.push(scheme.color_of(*Face::ALL.get(id)?));
+        }
+
+        let used_bits = stickers * BITS_PER_STICKER;
+        if !used_bits.is_multiple_of(8) && body.last().is_some_and(|&last| last >> (used_bits % 8) != 0) {
+            return None;
+        }
+
+        let mut cube = Cube::new_with_scheme(size, scheme);
+        cube.set_stickers(&colors);
+        Some(cube)
+    }
+
+    /// Versioned encoding of the whole cube: [`CANONICAL_VERSION`], the
+    /// position in `Color::ALL` of every face's colour in the scheme, in
+    /// `Face::ALL` order, then [`Cube::to_packed_bytes`]. Two cubes encode to
+    /// the same bytes exactly when they are equal and share a scheme.
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let packed = self.to_packed_bytes();
+        let mut bytes = Vec::with_capacity(CANONICAL_HEADER_LEN + packed.len());
+        bytes.push(CANONICAL_VERSION);
+        for color in self.scheme.colors() {
+            let index = Color::ALL.iter().position(|c| c == color);
+            bytes.push(index.map_or(0, |index| index as u8));
+        }
+        bytes.extend_from_slice(&packed);
+        bytes
+    }
+
+    /// Decodes [`Cube::to_canonical_bytes`] output. Returns `None` for an
+    /// unknown version, a scheme repeating a colour or naming one past
+    /// `Color::ALL`, or packed bytes [`Cube::from_packed_bytes`] rejects.
+    pub fn from_canonical_bytes(bytes: &[u8]) -> Option<Cube> {
+        let (&[version, scheme @ ..], packed) = bytes.split_first_chunk::<CANONICAL_HEADER_LEN>()?;
+        if version != CANONICAL_VERSION {
+            return None;
+        }
+        let mut colors = [Color::White; 6];
+        for (color, &index) in colors.iter_mut().zip(&scheme) {
+            *color = *Color::ALL.get(usize::from(index))?;
+        }
+        let scheme = ColorScheme::new(colors)?;
+
+        let mut cube = Cube::from_packed_bytes(packed)?;
+        cube.recolor(scheme);
+        Some(cube)
+    }
+}
@@ -0,0 +1,119 @@
+//! Checks that a solution was found for its challenge rather than reused.
+//!
+//! A move sequence solves exactly one cube state, so a canned answer only
+//! pays off when a challenge repeats a state someone has solved before: on
+//! small cubes, or when answers were computed ahead of time against public
+//! data. A [`SolutionPolicy`] rejects such seals once the replay has shown
+//! they solve their challenge. Policies are not consensus unless a chain opts
+//! in; the default `()` accepts every solution.
+
+use alloc::vec::Vec;
+
+use crate::{Cube, Move, PowSeal};
+
+/// `Sync` because block import consults the policy from several threads.
+pub trait SolutionPolicy: Sync {
+    /// Whether `seal`, whose moves solve the challenge scrambled by
+    /// `scramble`, may be accepted.
+    fn allows(&self, seal: &PowSeal, scramble: &[Move]) -> bool;
+}
+
+impl SolutionPolicy for () {
+    fn allows(&self, _seal: &PowSeal, _scramble: &[Move]) -> bool {
+        true
+    }
+}
+
+/// Both policies must allow the seal.
+impl<A: SolutionPolicy, B: SolutionPolicy> SolutionPolicy for (A, B) {
+    fn allows(&self, seal: &PowSeal, scramble: &[Move]) -> bool {
+        self.0.allows(seal, scramble) && self.1.allows(seal, scramble)
+    }
+}
+
+/// Rejects solutions equal to one of a fixed set of sequences, such as
+/// published answers to benchmark scrambles.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Blacklist {
+    sequences: Vec<Vec<Move>>,
+}
+
+impl Blacklist {
+    pub fn new(sequences: Vec<Vec<Move>>) -> Self {
+        Blacklist { sequences }
+    }
+
+    pub fn sequences(&self) -> &[Vec<Move>] {
+        &self.sequences
+    }
+}
+
+impl SolutionPolicy for Blacklist {
+    fn allows(&self, seal: &PowSeal, _scramble: &[Move]) -> bool {
+        !self.sequences.contains(&seal.moves)
+    }
+}
+
+/// Rejects solutions that also solve the challenge for the same nonce and
+/// size under a fixed reference header, such as one benchmark challenges are
+/// published for. Answers computed once against the reference cannot be
+/// replayed on a challenge that happens to repeat its state. The header must
+/// differ from every real challenge header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceChallenge {
+    header: Vec<u8>,
+}
+
+impl ReferenceChallenge {
+    pub fn new(header: Vec<u8>) -> Self {
+        ReferenceChallenge { header }
+    }
+
+    pub fn header(&self) -> &[u8] {
+        &self.header
+    }
+}
+
+impl SolutionPolicy for ReferenceChallenge {
+    fn allows(&self, seal: &PowSeal, _scramble: &[Move]) -> bool {
+        let Ok(mut reference) = Cube::try_new(seal.cube_size as usize) else {
+            return true;
+        };
+        reference.scramble_deterministic(seal.nonce, &self.header);
+        !reference.verify_solution(&seal.moves)
+    }
+}
+
+/// Longest solution accepted for a challenge, relative to its scramble: at
+/// most `max_percent` percent of the scramble's length, counting moves as
+/// written. Unlike the policies above it is checked before the replay, and
+/// it excludes answers that replay the scramble backwards padded with junk
+/// moves, which reward curves alone only discourage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "scale-codec",
+    derive(codec::Encode, codec::Decode, codec::MaxEncodedLen, scale_info::TypeInfo)
+)]
+pub struct MinQuality {
+    pub max_percent: u32,
+}
+
+impl MinQuality {
+    /// Accepts solutions of any length.
+    pub const NONE: MinQuality = MinQuality { max_percent: u32::MAX };
+
+    /// Most moves a solution to a scramble of `scramble_len` moves may have.
+    pub fn max_length(&self, scramble_len: usize) -> u64 {
+        (scramble_len as u64).saturating_mul(u64::from(self.max_percent)) / 100
+    }
+
+    pub fn allows(&self, seal: &PowSeal, scramble: &[Move]) -> bool {
+        *self == MinQuality::NONE || seal.moves.len() as u64 <= self.max_length(scramble.len())
+    }
+}
+
+impl Default for MinQuality {
+    fn default() -> Self {
+        MinQuality::NONE
+    }
+}
@@ -0,0 +1,75 @@
+//! Grover-search cost estimates.
+//!
+//! The estimates are derived from the actual state count of the cube and the
+//! current target rather than quoted constants. Every modelling choice that is
+//! not forced by the maths lives in [`GroverAssumptions`] so reviewers can
+//! plug in their own numbers.
+
+use std::f64::consts::PI;
+
+use crate::{state_space_log2, Target};
+
+/// Parameters of the quantum oracle that are assumptions rather than facts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroverAssumptions {
+    /// Moves the oracle applies per candidate solution.
+    pub moves_per_candidate: u32,
+    /// Circuit depth of one reversible move on the sticker register.
+    pub depth_per_move: f64,
+    /// Circuit depth of the commitment hash inside the oracle.
+    pub hash_depth: f64,
+    /// Extra depth factor for uncomputing the oracle's work registers.
+    pub uncompute_factor: f64,
+    /// Work qubits on top of the search register.
+    pub ancilla_qubits: u32,
+}
+
+impl Default for GroverAssumptions {
+    /// Order-of-magnitude placeholders: a 20-move candidate, a few swap layers
+    /// per move and a Keccak circuit of roughly 2·10^4 layers.
+    fn default() -> Self {
+        GroverAssumptions {
+            moves_per_candidate: 20,
+            depth_per_move: 12.0,
+            hash_depth: 2.0e4,
+            uncompute_factor: 2.0,
+            ancilla_qubits: 1600,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuantumCostEstimate {
+    /// log2 of the classical search space: cube states times the expected
+    /// number of hashes needed to meet the target.
+    pub search_space_log2: f64,
+    /// log2 of the expected Grover iterations, (π/4)·sqrt(search space).
+    pub grover_queries_log2: f64,
+    /// log2 of the total circuit depth (iterations × oracle depth).
+    pub circuit_depth_log2: f64,
+    pub logical_qubits: u64,
+}
+
+pub fn grover_cost(n: usize, target: &Target) -> QuantumCostEstimate {
+    grover_cost_with(n, target, &GroverAssumptions::default())
+}
+
+pub fn grover_cost_with(
+    n: usize,
+    target: &Target,
+    assumptions: &GroverAssumptions,
+) -> QuantumCostEstimate {
+    let search_space_log2 = state_space_log2(n) + target.work_log2();
+    let grover_queries_log2 = (PI / 4.0).log2() + search_space_log2 / 2.0;
+
+    let oracle_depth = (f64::from(assumptions.moves_per_candidate) * assumptions.depth_per_move
+        + assumptions.hash_depth)
+        * assumptions.uncompute_factor;
+
+    QuantumCostEstimate {
+        search_space_log2,
+        grover_queries_log2,
+        circuit_depth_log2: grover_queries_log2 + oracle_depth.max(1.0).log2(),
+        logical_qubits: search_space_log2.ceil() as u64 + u64::from(assumptions.ancilla_qubits),
+    }
+}
@@ -0,0 +1,136 @@
+//! SVG pictures and 3D meshes of cube states.
+//!
+//! [`svg`] draws the usual unfolded net: Up above Front, Left, Front, Right
+//! and Back in a row, Down below Front. Face grids are stored as seen from
+//! outside the cube, with the side faces' top rows next to Up and Up's and
+//! Down's rows running towards Front, so they go into the net unrotated.
+//!
+//! [`stickers_3d`] places the same stickers on the cube's surface instead,
+//! and [`mesh_json`] writes them out for web viewers. Coordinates are those
+//! of the move geometry: x towards Right, y towards Up and z towards Front,
+//! doubled and centred, so an n×n×n cube spans `-n..=n` on each axis and
+//! every sticker is two units wide.
+
+use std::fmt::Write;
+
+use crate::geometry::{self, Vec3};
+use crate::{Color, Cube, Face};
+
+/// Edge of one sticker in SVG user units.
+pub const STICKER: usize = 20;
+
+/// Position of each face in the net, in faces from the top left.
+fn net_position(face: Face) -> (usize, usize) {
+    match face {
+        Face::Up => (1, 0),
+        Face::Left => (0, 1),
+        Face::Front => (1, 1),
+        Face::Right => (2, 1),
+        Face::Back => (3, 1),
+        Face::Down => (1, 2),
+    }
+}
+
+/// One sticker as a quad on the cube's surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StickerQuad {
+    pub face: Face,
+    pub color: Color,
+    /// Counter-clockwise seen from outside the cube, starting at the corner
+    /// that is top left in the net.
+    pub corners: [[i32; 3]; 4],
+}
+
+/// Half a sticker along `face`'s columns and along its rows, the way the
+/// net reads them.
+fn grid_axes(face: Face) -> (Vec3, Vec3) {
+    let origin = geometry::to_space(2, face, 0, 0).0;
+    let step = |(row, col)| {
+        let to = geometry::to_space(2, face, row, col).0;
+        std::array::from_fn(|axis| (to[axis] - origin[axis]) / 2)
+    };
+    (step((0, 1)), step((1, 0)))
+}
+
+/// Every sticker of `cube` in 3D, in `Face::ALL` and row-major order.
+pub fn stickers_3d(cube: &Cube) -> Vec<StickerQuad> {
+    let n = cube.size();
+    let stickers = cube.stickers();
+    let mut quads = Vec::with_capacity(stickers.len());
+    for face in Face::ALL {
+        let (column, row) = grid_axes(face);
+        for (i, &color) in stickers[face.index() * n * n..(face.index() + 1) * n * n].iter().enumerate() {
+            let (position, normal) = geometry::to_space(n, face, i / n, i % n);
+            // From the cubie's centre out to the face it shows
+            let corner = |dc: i32, dr: i32| -> Vec3 {
+                std::array::from_fn(|axis| position[axis] + normal[axis] + dc * column[axis] + dr * row[axis])
+            };
+            quads.push(StickerQuad {
+                face,
+                color,
+                corners: [corner(-1, -1), corner(-1, 1), corner(1, 1), corner(1, -1)],
+            });
+        }
+    }
+    quads
+}
+
+/// JSON mesh of `cube` for 3D viewers: the size, a palette of CSS fills in
+/// `Color::ALL` order, and one quad per sticker with its face letter, palette
+/// index and corners as in [`stickers_3d`].
+pub fn mesh_json(cube: &Cube) -> String {
+    let palette: Vec<String> = Color::ALL.iter().map(|&color| format!(r#""{}""#, fill(color))).collect();
+    let mut out = format!(r#"{{"size":{},"palette":[{}],"stickers":["#, cube.size(), palette.join(","));
+    for (i, quad) in stickers_3d(cube).iter().enumerate() {
+        let color = Color::ALL.iter().position(|&color| color == quad.color).unwrap_or(0);
+        let corners: Vec<String> = quad.corners.iter().map(|[x, y, z]| format!("[{x},{y},{z}]")).collect();
+        // Writing to a `String` cannot fail
+        let _ = write!(
+            out,
+            r#"{}{{"face":"{}","color":{color},"corners":[{}]}}"#,
+            if i == 0 { "" } else { "," },
+            quad.face,
+            corners.join(",")
+        );
+    }
+    out.push_str("]}");
+    out
+}
+
+/// CSS fill for a sticker colour.
+pub fn fill(color: Color) -> &'static str {
+    match color {
+        Color::White => "#ffffff",
+        Color::Yellow => "#ffd500",
+        Color::Red => "#c41e3a",
+        Color::Orange => "#ff5800",
+        Color::Blue => "#0051ba",
+        Color::Green => "#009e60",
+    }
+}
+
+/// Standalone SVG document of `cube` as an unfolded net.
+pub fn svg(cube: &Cube) -> String {
+    let n = cube.size();
+    let (width, height) = (4 * n * STICKER, 3 * n * STICKER);
+    let mut out = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+    let stickers = cube.stickers();
+    for face in Face::ALL {
+        let (column, row) = net_position(face);
+        let face_stickers = &stickers[face.index() * n * n..(face.index() + 1) * n * n];
+        for (i, &color) in face_stickers.iter().enumerate() {
+            let x = (column * n + i % n) * STICKER;
+            let y = (row * n + i / n) * STICKER;
+            // Writing to a `String` cannot fail
+            let _ = write!(
+                out,
+                r##"<rect x="{x}" y="{y}" width="{STICKER}" height="{STICKER}" fill="{}" stroke="#000000"/>"##,
+                fill(color)
+            );
+        }
+    }
+    out.push_str("</svg>");
+    out
+}
@@ -0,0 +1,104 @@
+//! Step-by-step replay of seal verification for consensus debugging.
+//!
+//! [`replay`] re-derives a challenge and re-checks a seal the way
+//! [`crate::verify_seal`] does, recording every intermediate value. Two node
+//! versions that disagree about a seal can dump their traces and compare them
+//! with [`first_divergence`] to find the first step where they differ.
+
+use std::fmt;
+
+use crate::{challenge_commitment, challenge_seed, Cube, PowSeal, Target};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+    pub label: String,
+    pub value: String,
+}
+
+impl fmt::Display for TraceStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.label, self.value)
+    }
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Records derivation and verification of `seal` under `header` and `target`.
+pub fn replay(seal: &PowSeal, header: &[u8], target: &Target) -> Vec<TraceStep> {
+    let mut trace = Vec::new();
+    let mut step = |label: String, value: String| trace.push(TraceStep { label, value });
+
+    step("cube_size".into(), seal.cube_size.to_string());
+    step("nonce".into(), seal.nonce.to_string());
+    step("header".into(), to_hex(header));
+    if seal.cube_size < 2 {
+        step("error".into(), "cube size below 2".into());
+        return trace;
+    }
+
+    let seed = challenge_seed(seal.nonce, header);
+    step("seed".into(), to_hex(&seed));
+
+    let size = seal.cube_size as usize;
+    let mut scrambled = Cube::new(size);
+    let scramble = scrambled.scramble_deterministic(seal.nonce, header);
+    step("scramble_length".into(), scramble.len().to_string());
+
+    // Walk the scramble again on a fresh cube to expose every intermediate state
+    let mut cube = Cube::new(size);
+    #[cfg(feature = "color-shuffle")]
+    {
+        let colors = crate::color_permutation(&seed);
+        step("colors".into(), format!("{:?}", colors));
+        if let Some(scheme) = crate::ColorScheme::new(colors) {
+            cube.recolor(scheme);
+        }
+    }
+    for (i, m) in scramble.iter().enumerate() {
+        cube.apply_move(m);
+        step(
+            format!("scramble[{}] {:?}", i, m),
+            to_hex(&cube.state_commitment()),
+        );
+    }
+    step("scrambled.facelets".into(), scrambled.to_facelets());
+    step(
+        "scrambled.matches_walk".into(),
+        (cube.to_facelets() == scrambled.to_facelets()).to_string(),
+    );
+
+    let commitment = scrambled.state_commitment();
+    step("state_commitment".into(), to_hex(&commitment));
+    step("target".into(), to_hex(target.as_bytes()));
+    step(
+        "target_met".into(),
+        target.is_met_by(&commitment).to_string(),
+    );
+
+    for (i, m) in seal.moves.iter().enumerate() {
+        cube.apply_move(m);
+        step(
+            format!("solution[{}] {:?}", i, m),
+            to_hex(&cube.state_commitment()),
+        );
+    }
+    step("solved".into(), cube.is_solved().to_string());
+    step(
+        "challenge_commitment".into(),
+        to_hex(&challenge_commitment(&seed, seal.cube_size, target)),
+    );
+    trace
+}
+
+/// Index of the first line where `ours` and `theirs` (a trace dumped as one
+/// `label: value` line per step) differ, including one being a prefix of
+/// the other.
+pub fn first_divergence(ours: &[TraceStep], theirs: &[String]) -> Option<usize> {
+    let ours: Vec<String> = ours.iter().map(ToString::to_string).collect();
+    let common = ours.len().min(theirs.len());
+    (0..common)
+        .find(|&i| ours[i] != theirs[i])
+        .or_else(|| (ours.len() != theirs.len()).then_some(common))
+}
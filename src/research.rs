@@ -0,0 +1,421 @@
+//! State-space sampling and entropy analysis.
+//!
+//! Produces reproducible data for the quantum-resistance claims: scrambles are
+//! derived from consecutive nonces over a fixed header, so anyone rerunning a
+//! study with the same [`ResearchConfig`] gets byte-identical CSV output.
+//! [`challenge_sensitivity`] measures how far header fields an author controls
+//! would let them grind challenges, for deciding what seed derivation covers.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, BufRead, Write};
+
+use crate::group::Permutation;
+use crate::optimal;
+use crate::solver::Solver;
+use crate::{Cube, Move};
+
+#[derive(Debug, Clone)]
+pub struct ResearchConfig {
+    pub size: usize,
+    pub samples: u64,
+    /// Block header bytes fed to `scramble_deterministic` for every sample.
+    pub header: Vec<u8>,
+}
+
+/// One sampled scramble and how each solver fared on it.
+#[derive(Debug, Clone)]
+pub struct SampleRecord {
+    pub nonce: u64,
+    pub scramble_length: usize,
+    pub commitment: [u8; 32],
+    /// Verified solution length per solver, in the order the solvers were given.
+    /// `None` if the solver gave up or returned an invalid solution.
+    pub solve_lengths: Vec<Option<usize>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntropyEstimate {
+    pub samples: usize,
+    pub distinct_commitments: usize,
+    /// Shannon entropy of the commitment byte histogram, at most 8.0.
+    pub bits_per_byte: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub size: usize,
+    pub solver_names: Vec<String>,
+    pub records: Vec<SampleRecord>,
+}
+
+pub fn run(config: &ResearchConfig, solvers: &[&dyn Solver]) -> Report {
+    let mut records = Vec::with_capacity(config.samples as usize);
+
+    for nonce in 0..config.samples {
+        let mut cube = Cube::new(config.size);
+        let scramble = cube.scramble_deterministic(nonce, &config.header);
+
+        let solve_lengths = solvers
+            .iter()
+            .map(|solver| {
+                solver
+                    .solve(&cube, &scramble)
+                    .filter(|solution| cube.verify_solution(solution))
+                    .map(|solution| solution.len())
+            })
+            .collect();
+
+        records.push(SampleRecord {
+            nonce,
+            scramble_length: scramble.len(),
+            commitment: cube.state_commitment(),
+            solve_lengths,
+        });
+    }
+
+    Report {
+        size: config.size,
+        solver_names: solvers.iter().map(|s| s.name().to_string()).collect(),
+        records,
+    }
+}
+
+impl Report {
+    /// Histogram of solution lengths for the solver at `solver_index`.
+    /// Failed samples are not counted.
+    pub fn solve_length_distribution(&self, solver_index: usize) -> BTreeMap<usize, u64> {
+        let mut histogram = BTreeMap::new();
+        for record in &self.records {
+            if let Some(Some(length)) = record.solve_lengths.get(solver_index) {
+                *histogram.entry(*length).or_insert(0) += 1;
+            }
+        }
+        histogram
+    }
+
+    pub fn failures(&self, solver_index: usize) -> usize {
+        self.records
+            .iter()
+            .filter(|r| !matches!(r.solve_lengths.get(solver_index), Some(Some(_))))
+            .count()
+    }
+
+    pub fn commitment_entropy(&self) -> EntropyEstimate {
+        let mut byte_counts = [0u64; 256];
+        let mut distinct = HashSet::new();
+
+        for record in &self.records {
+            distinct.insert(record.commitment);
+            for &byte in record.commitment.iter() {
+                byte_counts[byte as usize] += 1;
+            }
+        }
+
+        let total: u64 = byte_counts.iter().sum();
+        let bits_per_byte = if total == 0 {
+            0.0
+        } else {
+            byte_counts
+                .iter()
+                .filter(|&&count| count > 0)
+                .map(|&count| {
+                    let p = count as f64 / total as f64;
+                    -p * p.log2()
+                })
+                .sum()
+        };
+
+        EntropyEstimate {
+            samples: self.records.len(),
+            distinct_commitments: distinct.len(),
+            bits_per_byte,
+        }
+    }
+
+    /// One row per sample: nonce, scramble length, commitment and one column
+    /// per solver (empty when the solver failed).
+    pub fn write_samples_csv<W: Write>(&self, mut out: W) -> io::Result<()> {
+        write!(out, "size,nonce,scramble_length,commitment")?;
+        for name in &self.solver_names {
+            write!(out, ",{}", name)?;
+        }
+        writeln!(out)?;
+
+        for record in &self.records {
+            write!(
+                out,
+                "{},{},{},",
+                self.size, record.nonce, record.scramble_length
+            )?;
+            for byte in record.commitment.iter() {
+                write!(out, "{:02x}", byte)?;
+            }
+            for length in &record.solve_lengths {
+                match length {
+                    Some(length) => write!(out, ",{}", length)?,
+                    None => write!(out, ",")?,
+                }
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+
+    /// One row per (solver, solution length) bucket.
+    pub fn write_distribution_csv<W: Write>(&self, mut out: W) -> io::Result<()> {
+        writeln!(out, "size,solver,solve_length,count")?;
+        for (index, name) in self.solver_names.iter().enumerate() {
+            for (length, count) in self.solve_length_distribution(index) {
+                writeln!(out, "{},{},{},{}", self.size, name, length, count)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Number of states at each distance from solved: `counts[d]` states need
+/// exactly `d` moves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceDistribution {
+    pub size: usize,
+    pub counts: Vec<u64>,
+}
+
+impl DistanceDistribution {
+    pub fn states(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Distance of the farthest states.
+    pub fn diameter(&self) -> usize {
+        self.counts.len().saturating_sub(1)
+    }
+
+    pub fn mean(&self) -> f64 {
+        let weighted: u64 = self.counts.iter().enumerate().map(|(d, &count)| d as u64 * count).sum();
+        weighted as f64 / self.states().max(1) as f64
+    }
+
+    /// One `size,distance,count` row per distance.
+    pub fn write_csv<W: Write>(&self, mut out: W) -> io::Result<()> {
+        writeln!(out, "size,distance,count")?;
+        for (distance, count) in self.counts.iter().enumerate() {
+            writeln!(out, "{},{},{}", self.size, distance, count)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the output of [`DistanceDistribution::write_csv`].
+    pub fn read_csv<R: BufRead>(input: R) -> io::Result<DistanceDistribution> {
+        let invalid = |line: &str| io::Error::new(io::ErrorKind::InvalidData, format!("bad row: {}", line));
+
+        let mut size = None;
+        let mut counts = Vec::new();
+        for line in input.lines().skip(1) {
+            let line = line?;
+            let fields: Vec<u64> = line
+                .split(',')
+                .map(|field| field.trim().parse())
+                .collect::<Result<_, _>>()
+                .map_err(|_| invalid(&line))?;
+            let [row_size, distance, count] = fields[..] else {
+                return Err(invalid(&line));
+            };
+            if *size.get_or_insert(row_size) != row_size || distance != counts.len() as u64 {
+                return Err(invalid(&line));
+            }
+            counts.push(count);
+        }
+
+        let size = size.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no rows"))?;
+        Ok(DistanceDistribution {
+            size: size as usize,
+            counts,
+        })
+    }
+}
+
+/// Exact distance distribution over every state of the cube, in the
+/// half-turn metric. Only the 2x2x2 is small enough to enumerate.
+pub fn distance_distribution(size: usize) -> Option<DistanceDistribution> {
+    if size != 2 {
+        return None;
+    }
+    Some(DistanceDistribution {
+        size,
+        counts: optimal::distances(),
+    })
+}
+
+/// Breadth-first enumeration of the group generated by `generators`, each
+/// counting as one move. Stops after `max_states` states, in which case the
+/// last distance may be incomplete.
+pub fn subgroup_distance_distribution(size: usize, generators: &[Move], max_states: usize) -> DistanceDistribution {
+    let steps: Vec<Vec<u16>> = generators
+        .iter()
+        .map(|m| Permutation::of_move(m, size).destinations().iter().map(|&d| d as u16).collect())
+        .collect();
+
+    let identity: Vec<u16> = (0..6 * size * size).map(|i| i as u16).collect();
+    let mut seen = HashSet::from([identity.clone()]);
+    let mut frontier = vec![identity];
+    let mut counts = vec![1];
+    'search: while !frontier.is_empty() {
+        let mut next = Vec::new();
+        for state in &frontier {
+            for step in &steps {
+                let successor: Vec<u16> = state.iter().map(|&d| step[usize::from(d)]).collect();
+                if seen.insert(successor.clone()) {
+                    next.push(successor);
+                    if seen.len() >= max_states {
+                        counts.push(next.len() as u64);
+                        break 'search;
+                    }
+                }
+            }
+        }
+        if !next.is_empty() {
+            counts.push(next.len() as u64);
+        }
+        frontier = next;
+    }
+
+    DistanceDistribution { size, counts }
+}
+
+/// Generators of subgroups small enough to enumerate, by name.
+///
+/// - `square`: the six half turns, 663,552 states on the 3x3x3
+/// - `r2u2`: R2 and U2, 12 states
+/// - `ru2`: R, R2, R' and U2, 14,400 states
+pub fn named_subgroup(name: &str) -> Option<Vec<Move>> {
+    match name {
+        "square" => Some(vec![Move::U(2), Move::D(2), Move::L(2), Move::R(2), Move::F(2), Move::B(2)]),
+        "r2u2" => Some(vec![Move::R(2), Move::U(2)]),
+        "ru2" => Some(vec![Move::R(1), Move::R(2), Move::R(3), Move::U(2)]),
+        _ => None,
+    }
+}
+
+/// A field of the header challenges are derived from, as laid out in the
+/// header bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderField {
+    pub name: String,
+    /// The field's bytes in the header being studied.
+    pub bytes: Vec<u8>,
+    /// Values the author can give the field at no cost, such as the spread of
+    /// timestamps peers accept; 1 for fields the author cannot change.
+    pub choices: u64,
+}
+
+/// How much grinding one malleable field allows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldSensitivity {
+    pub name: String,
+    /// Values the author can choose among, capped at what the field's width
+    /// can hold.
+    pub choices: u64,
+    /// Variants actually derived.
+    pub sampled: u64,
+    /// Distinct scrambled states among the sampled variants.
+    pub distinct_challenges: u64,
+    /// log2 of the distinct challenges the field yields over all of its
+    /// choices, extrapolated from the sample.
+    pub bits: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensitivityReport {
+    pub size: usize,
+    pub fields: Vec<FieldSensitivity>,
+}
+
+impl SensitivityReport {
+    /// log2 of the distinct challenges an author gets by varying every
+    /// malleable field at once. Fields combine independently, up to the
+    /// 256 bits of the challenge seed.
+    pub fn total_bits(&self) -> f64 {
+        self.fields.iter().map(|field| field.bits).sum::<f64>().min(256.0)
+    }
+
+    /// One row per malleable field.
+    pub fn write_csv<W: Write>(&self, mut out: W) -> io::Result<()> {
+        writeln!(out, "size,field,choices,sampled,distinct_challenges,bits")?;
+        for field in &self.fields {
+            writeln!(
+                out,
+                "{},{},{},{},{},{:.3}",
+                self.size, field.name, field.choices, field.sampled, field.distinct_challenges, field.bits
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Measures how many distinct challenges an author can cheaply generate by
+/// varying the malleable fields of a header, one field at a time.
+///
+/// The header is the fields' bytes in order. Each field with more than one
+/// choice is varied through up to `samples` values, written as a
+/// little-endian offset added to its bytes, and the challenge for `nonce` on
+/// a cube of `size` is derived for every variant. Fields the author cannot
+/// change contribute nothing. The report depends only on the arguments, so it
+/// reproduces byte for byte.
+pub fn challenge_sensitivity(header_fields: &[HeaderField], size: usize, nonce: u64, samples: u64) -> SensitivityReport {
+    let header: Vec<u8> = header_fields.iter().flat_map(|field| field.bytes.iter().copied()).collect();
+
+    let mut fields = Vec::new();
+    let mut offset = 0;
+    for field in header_fields {
+        let range = offset..offset + field.bytes.len();
+        offset = range.end;
+
+        let width_bits = 8 * field.bytes.len();
+        let choices = if width_bits < 64 {
+            field.choices.min(1 << width_bits)
+        } else {
+            field.choices
+        };
+        if choices <= 1 {
+            continue;
+        }
+
+        let sampled = samples.min(choices);
+        let mut variant = header.clone();
+        let mut seen = HashSet::new();
+        for i in 0..sampled {
+            add_le(&mut variant[range.clone()], &field.bytes, i);
+            let mut cube = Cube::new(size);
+            cube.scramble_deterministic(nonce, &variant);
+            seen.insert(cube.state_commitment());
+        }
+
+        let distinct_challenges = seen.len() as u64;
+        let bits = if distinct_challenges == 0 {
+            0.0
+        } else {
+            (choices as f64 * distinct_challenges as f64 / sampled as f64).log2()
+        };
+        fields.push(FieldSensitivity {
+            name: field.name.clone(),
+            choices,
+            sampled,
+            distinct_challenges,
+            bits,
+        });
+    }
+
+    SensitivityReport { size, fields }
+}
+
+/// Writes `base + offset` into `out`, both little endian and wrapping at the
+/// field's width.
+fn add_le(out: &mut [u8], base: &[u8], offset: u64) {
+    let mut carry = u128::from(offset);
+    for (out, &byte) in out.iter_mut().zip(base) {
+        let sum = carry + u128::from(byte);
+        *out = sum as u8;
+        carry = sum >> 8;
+    }
+}
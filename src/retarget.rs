@@ -0,0 +1,24 @@
+//! Periodic difficulty retargeting.
+//!
+//! Every retarget interval the difficulty is scaled by how much faster or
+//! slower than expected the interval was mined, like Bitcoin's rule. A single
+//! retarget moves the difficulty by at most [`MAX_ADJUSTMENT_FACTOR`] either
+//! way. The pallet and the network simulator both call [`retarget`].
+
+/// Largest factor one retarget can raise or lower the difficulty by.
+pub const MAX_ADJUSTMENT_FACTOR: u64 = 4;
+
+/// Difficulty for the next interval, given the current one and how long the
+/// last interval took (`observed_ms`) compared with how long it should have
+/// taken (`expected_ms`). Never returns 0.
+pub fn retarget(current: u32, observed_ms: u64, expected_ms: u64) -> u32 {
+    if expected_ms == 0 {
+        return current.max(1);
+    }
+    let observed = observed_ms.clamp(
+        (expected_ms / MAX_ADJUSTMENT_FACTOR).max(1),
+        expected_ms.saturating_mul(MAX_ADJUSTMENT_FACTOR),
+    );
+    let scaled = u128::from(current.max(1)) * u128::from(expected_ms) / u128::from(observed);
+    scaled.clamp(1, u128::from(u32::MAX)) as u32
+}
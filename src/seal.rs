@@ -0,0 +1,107 @@
+//! Proof-of-work seal: everything needed to re-verify a solved challenge.
+//!
+//! Byte layout (all integers little endian):
+//! `cube_size: u32 | nonce: u64 | move count: u32 | one byte per move`,
+//! where a move byte is `face index * 4 + quarter turns % 4` for face turns,
+//! 24 plus that for wide turns and `48 + axis index * 4 + quarter turns % 4`
+//! for whole-cube rotations.
+
+#![deny(clippy::indexing_slicing, clippy::panic, clippy::unreachable)]
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::spec::{self, SpecVersion};
+use crate::Move;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PowSeal {
+    pub cube_size: u32,
+    pub nonce: u64,
+    pub moves: Vec<Move>,
+}
+
+const HEADER_LEN: usize = 4 + 8 + 4;
+
+/// Why [`PowSeal::try_decode`] rejected its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Shorter than the fixed-size header.
+    Truncated,
+    /// The body does not hold exactly the number of moves the header declares.
+    MoveCount { declared: u32, found: usize },
+    /// A byte no move is encoded as.
+    UnknownMove(u8),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Truncated => write!(f, "seal is shorter than its {}-byte header", HEADER_LEN),
+            DecodeError::MoveCount { declared, found } => {
+                write!(f, "seal declares {} moves but carries {}", declared, found)
+            }
+            DecodeError::UnknownMove(byte) => write!(f, "unknown move byte {:#04x}", byte),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// Single-byte move encoding used in seals and by solver plugins.
+pub fn move_to_byte(m: &Move) -> u8 {
+    spec::encode_move(SpecVersion::CURRENT, m)
+}
+
+/// Inverse of [`move_to_byte`]; `None` for bytes the current version does
+/// not assign.
+pub fn move_from_byte(byte: u8) -> Option<Move> {
+    spec::decode_move(SpecVersion::CURRENT, byte)
+}
+
+impl PowSeal {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.moves.len());
+        bytes.extend_from_slice(&self.cube_size.to_le_bytes());
+        bytes.extend_from_slice(&self.nonce.to_le_bytes());
+        bytes.extend_from_slice(&(self.moves.len() as u32).to_le_bytes());
+        bytes.extend(self.moves.iter().map(move_to_byte));
+        bytes
+    }
+
+    /// Inverse of [`PowSeal::encode`]. Returns `None` on truncated input,
+    /// trailing bytes or an unknown move byte.
+    pub fn decode(bytes: &[u8]) -> Option<PowSeal> {
+        Self::try_decode(bytes).ok()
+    }
+
+    /// [`PowSeal::decode`] that says what is wrong with the bytes.
+    pub fn try_decode(bytes: &[u8]) -> Result<PowSeal, DecodeError> {
+        let mut body = bytes;
+        let cube_size = u32::from_le_bytes(take(&mut body)?);
+        let nonce = u64::from_le_bytes(take(&mut body)?);
+        let declared = u32::from_le_bytes(take(&mut body)?);
+        if usize::try_from(declared) != Ok(body.len()) {
+            return Err(DecodeError::MoveCount { declared, found: body.len() });
+        }
+
+        let moves = body
+            .iter()
+            .map(|&byte| move_from_byte(byte).ok_or(DecodeError::UnknownMove(byte)))
+            .collect::<Result<Vec<Move>, DecodeError>>()?;
+
+        Ok(PowSeal {
+            cube_size,
+            nonce,
+            moves,
+        })
+    }
+}
+
+/// Splits the next `N` header bytes off `bytes`.
+fn take<const N: usize>(bytes: &mut &[u8]) -> Result<[u8; N], DecodeError> {
+    let (head, rest) = bytes.split_first_chunk::<N>().ok_or(DecodeError::Truncated)?;
+    *bytes = rest;
+    Ok(*head)
+}
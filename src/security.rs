@@ -0,0 +1,104 @@
+//! Classical attack-cost model for the difficulty oracle.
+//!
+//! A block needs a scramble whose commitment meets the target plus a solution
+//! for that scramble. The expected cost is therefore the nonce grinding needed
+//! to hit the target plus one solve with the chosen strategy. Governance
+//! tooling uses [`minimum_safe_size`] to decide when the minimum cube size has
+//! to be raised.
+
+use crate::{state_space_log2, Target};
+
+/// Scrambles are 20 to 30 moves long, see `Cube::scramble_deterministic`.
+const AVERAGE_SCRAMBLE_LENGTH: f64 = 25.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolverStrategy {
+    /// Inverts the known scramble; cost is linear in the scramble length.
+    ReverseScramble,
+    /// Enumerates states until the solved one is hit; half the space on average.
+    BruteForce,
+    /// Bidirectional search from both ends; square root of the space.
+    MeetInTheMiddle,
+}
+
+impl SolverStrategy {
+    pub const ALL: [SolverStrategy; 3] = [
+        SolverStrategy::ReverseScramble,
+        SolverStrategy::BruteForce,
+        SolverStrategy::MeetInTheMiddle,
+    ];
+
+    /// log2 of the expected search nodes for one solve of a size-n cube.
+    pub fn nodes_log2(&self, size: usize) -> f64 {
+        match self {
+            SolverStrategy::ReverseScramble => AVERAGE_SCRAMBLE_LENGTH.log2(),
+            SolverStrategy::BruteForce => state_space_log2(size) - 1.0,
+            SolverStrategy::MeetInTheMiddle => state_space_log2(size) / 2.0,
+        }
+    }
+}
+
+/// A solver strategy together with the hardware throughput it runs at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolverProfile {
+    pub strategy: SolverStrategy,
+    /// Search nodes (move applications) per CPU-second.
+    pub nodes_per_second: f64,
+    /// Challenge derivations plus commitment checks per CPU-second.
+    pub derivations_per_second: f64,
+}
+
+impl SolverProfile {
+    /// Throughput of a single modern desktop core running this crate.
+    pub fn reference(strategy: SolverStrategy) -> Self {
+        SolverProfile {
+            strategy,
+            nodes_per_second: 5.0e7,
+            derivations_per_second: 2.0e5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClassicalCostEstimate {
+    /// log2 of the expected nonces tried before a commitment meets the target.
+    pub attempts_log2: f64,
+    /// log2 of the expected search nodes for the final solve.
+    pub solve_nodes_log2: f64,
+    pub grinding_seconds: f64,
+    pub solve_seconds: f64,
+    pub cpu_seconds_per_block: f64,
+}
+
+pub fn classical_cost(
+    size: usize,
+    target: &Target,
+    profile: &SolverProfile,
+) -> ClassicalCostEstimate {
+    let attempts_log2 = target.work_log2();
+    let solve_nodes_log2 = profile.strategy.nodes_log2(size);
+
+    let grinding_seconds = attempts_log2.exp2() / profile.derivations_per_second;
+    let solve_seconds = solve_nodes_log2.exp2() / profile.nodes_per_second;
+
+    ClassicalCostEstimate {
+        attempts_log2,
+        solve_nodes_log2,
+        grinding_seconds,
+        solve_seconds,
+        cpu_seconds_per_block: grinding_seconds + solve_seconds,
+    }
+}
+
+/// Smallest cube size in `2..=max_size` whose expected cost with `profile`
+/// is at least `min_cpu_seconds`, or `None` if no size up to `max_size` is.
+pub fn minimum_safe_size(
+    target: &Target,
+    profile: &SolverProfile,
+    min_cpu_seconds: f64,
+    max_size: usize,
+) -> Option<usize> {
+    (2..=max_size).find(|&size| {
+        classical_cost(size, target, profile).cpu_seconds_per_block >= min_cpu_seconds
+    })
+}
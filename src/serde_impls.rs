@@ -0,0 +1,118 @@
+//! Serde support, for miners and explorers exchanging puzzles and proofs as
+//! JSON or any other serde format.
+//!
+//! Like the SCALE encoding in `codec`, values go through the crate's stable
+//! text forms rather than derived ones, so the output depends neither on the
+//! order of variants nor on the sticker layout compiled in: faces and colours
+//! are their letters, moves their notation, with counts taken modulo four,
+//! and a cube its size, its scheme as six colour letters in `Face::ALL`
+//! order and its [`Cube::to_facelets`] string.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Display;
+
+use serde::de::{self, Deserializer, Unexpected};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+
+use crate::{Color, ColorScheme, Cube, Face, Move};
+
+/// Reads a string and parses it with `parse`, naming `expected` if it fails.
+fn from_text<'de, D, T>(deserializer: D, expected: &str, parse: impl FnOnce(&str) -> Option<T>) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let text = String::deserialize(deserializer)?;
+    parse(&text).ok_or_else(|| de::Error::invalid_value(Unexpected::Str(&text), &expected))
+}
+
+fn to_text<S: Serializer>(value: &impl Display, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+fn color_from_letter(text: &str) -> Option<Color> {
+    let mut letters = text.chars();
+    match (letters.next(), letters.next()) {
+        (Some(letter), None) => Color::from_letter(letter),
+        _ => None,
+    }
+}
+
+impl Serialize for Face {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        to_text(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Face {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        from_text(deserializer, "a face letter", |text| {
+            Face::ALL.into_iter().find(|face| face.to_string() == text)
+        })
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        to_text(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        from_text(deserializer, "a colour letter", color_from_letter)
+    }
+}
+
+impl Serialize for Move {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        to_text(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Move {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        from_text(deserializer, "a move in standard notation", |text| text.parse().ok())
+    }
+}
+
+/// Fields of a serialized [`Cube`].
+#[derive(Deserialize)]
+#[serde(rename = "Cube", deny_unknown_fields)]
+struct CubeFields {
+    size: usize,
+    scheme: String,
+    facelets: String,
+}
+
+impl Serialize for Cube {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let scheme: String = self.scheme.colors().iter().map(Color::to_string).collect();
+        let mut fields = serializer.serialize_struct("Cube", 3)?;
+        fields.serialize_field("size", &self.size)?;
+        fields.serialize_field("scheme", &scheme)?;
+        fields.serialize_field("facelets", &self.to_facelets())?;
+        fields.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Cube {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = CubeFields::deserialize(deserializer)?;
+        Cube::check_size(fields.size).map_err(de::Error::custom)?;
+
+        let scheme = fields
+            .scheme
+            .chars()
+            .map(Color::from_letter)
+            .collect::<Option<Vec<Color>>>()
+            .and_then(|colors| <[Color; 6]>::try_from(colors).ok())
+            .and_then(ColorScheme::new)
+            .ok_or_else(|| de::Error::invalid_value(Unexpected::Str(&fields.scheme), &"six distinct colour letters"))?;
+
+        Cube::from_facelets(fields.size, &fields.facelets, scheme).ok_or_else(|| {
+            de::Error::invalid_value(Unexpected::Str(&fields.facelets), &"one colour letter per sticker")
+        })
+    }
+}
@@ -0,0 +1,136 @@
+//! Network simulator for the difficulty retarget.
+//!
+//! A set of virtual miners, each with its own solve-rate model, mines
+//! `blocks` blocks against [`crate::retarget::retarget`]. Solving is modelled
+//! as a Poisson process: at difficulty `d` a miner with rate `r` finds a block
+//! after an exponentially distributed time with mean `d / r`, so the network
+//! finds one after a time with mean `d / sum(r)`.
+
+use rand::{Rng, SeedableRng};
+
+use crate::retarget::retarget;
+
+/// Solves per second at difficulty 1, possibly changing over time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SolveRate {
+    Constant(f64),
+    /// Linear change from `from` to `to` over the first `blocks` blocks.
+    Ramp {
+        from: f64,
+        to: f64,
+        blocks: u64,
+    },
+    /// Mines at `rate` for `period` blocks, then stops for `period` blocks.
+    OnOff {
+        rate: f64,
+        period: u64,
+    },
+}
+
+impl SolveRate {
+    pub fn at(&self, block: u64) -> f64 {
+        match *self {
+            SolveRate::Constant(rate) => rate,
+            SolveRate::Ramp { from, to, blocks } => {
+                let progress = (block as f64 / blocks.max(1) as f64).min(1.0);
+                from + (to - from) * progress
+            }
+            SolveRate::OnOff { rate, period } => {
+                if (block / period.max(1)).is_multiple_of(2) {
+                    rate
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimConfig {
+    pub miners: Vec<SolveRate>,
+    pub target_block_time_ms: u64,
+    /// Blocks between retargets.
+    pub retarget_interval: u64,
+    pub initial_difficulty: u32,
+    pub blocks: u64,
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimResult {
+    pub target_block_time_ms: u64,
+    pub retarget_interval: u64,
+    /// Time taken by every block.
+    pub block_times_ms: Vec<u64>,
+    /// Difficulty in force after each retarget.
+    pub difficulties: Vec<u32>,
+}
+
+/// Mean block time, relative to the target, when nobody is mining.
+const MAX_IDLE_FACTOR: f64 = 4.0;
+
+/// Runs the simulation. The same config always gives the same result.
+pub fn simulate(config: &SimConfig) -> SimResult {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(config.seed);
+    let interval = config.retarget_interval.max(1);
+    let mut difficulty = config.initial_difficulty.max(1);
+    let mut block_times_ms = Vec::with_capacity(config.blocks as usize);
+    let mut difficulties = Vec::new();
+    let mut interval_ms = 0u64;
+
+    for block in 0..config.blocks {
+        let rate: f64 = config.miners.iter().map(|m| m.at(block)).sum();
+        let mean_ms = if rate > 0.0 {
+            f64::from(difficulty) / rate * 1000.0
+        } else {
+            // Nobody mining: the block arrives whenever someone comes back
+            config.target_block_time_ms as f64 * MAX_IDLE_FACTOR
+        };
+        let uniform: f64 = rng.gen_range(f64::EPSILON..1.0);
+        let block_ms = (-uniform.ln() * mean_ms).round() as u64;
+        block_times_ms.push(block_ms);
+        interval_ms += block_ms;
+
+        if (block + 1) % interval == 0 {
+            difficulty = retarget(
+                difficulty,
+                interval_ms,
+                config.target_block_time_ms * interval,
+            );
+            difficulties.push(difficulty);
+            interval_ms = 0;
+        }
+    }
+
+    SimResult {
+        target_block_time_ms: config.target_block_time_ms,
+        retarget_interval: interval,
+        block_times_ms,
+        difficulties,
+    }
+}
+
+impl SimResult {
+    /// Mean block time over the last `windows` retarget intervals.
+    pub fn tail_mean_block_time_ms(&self, windows: usize) -> f64 {
+        let count = (windows * self.retarget_interval as usize).min(self.block_times_ms.len());
+        let tail = &self.block_times_ms[self.block_times_ms.len() - count..];
+        tail.iter().sum::<u64>() as f64 / count.max(1) as f64
+    }
+
+    /// `(max - min) / mean` of the difficulty over the last `windows`
+    /// retargets: small when the retarget has settled, large when it swings.
+    pub fn tail_difficulty_spread(&self, windows: usize) -> f64 {
+        let count = windows.min(self.difficulties.len());
+        let tail = &self.difficulties[self.difficulties.len() - count..];
+        let max = tail.iter().copied().max().unwrap_or(0) as f64;
+        let min = tail.iter().copied().min().unwrap_or(0) as f64;
+        let mean = tail.iter().map(|&d| f64::from(d)).sum::<f64>() / count.max(1) as f64;
+        if mean == 0.0 {
+            0.0
+        } else {
+            (max - min) / mean
+        }
+    }
+}
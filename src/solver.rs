@@ -0,0 +1,93 @@
+//! Solver interface shared by research tooling and, later, the miner.
+
+use alloc::vec::Vec;
+
+use crate::{invert_sequence, Cube, Face, Move};
+
+/// Anything that can produce a solution for a scrambled cube.
+pub trait Solver {
+    /// Short identifier used in reports and CSV headers.
+    fn name(&self) -> &str;
+
+    /// Returns a move sequence solving `cube`, or `None` if the solver gave up.
+    /// `scramble` is the sequence that produced `cube` from the solved state;
+    /// solvers that search from the state alone are free to ignore it.
+    fn solve(&self, cube: &Cube, scramble: &[Move]) -> Option<Vec<Move>>;
+}
+
+/// Baseline solver that replays the scramble backwards.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReverseScramble;
+
+impl Solver for ReverseScramble {
+    fn name(&self) -> &str {
+        "reverse-scramble"
+    }
+
+    fn solve(&self, _cube: &Cube, scramble: &[Move]) -> Option<Vec<Move>> {
+        Some(invert_sequence(scramble))
+    }
+}
+
+/// True if no sequence of fewer than `solution_len` face turns solves `cube`,
+/// so a solution of that length is optimal in the half-turn metric. On cubes up
+/// to 3x3x3 every move is a face turn up to a whole-cube rotation, which
+/// `is_solved` ignores, so this also holds for solutions using wide moves.
+///
+/// Does at most the work [`search_bound`] reports; callers bound
+/// `solution_len` when running it on chain.
+pub fn no_shorter_solution(cube: &Cube, solution_len: usize) -> bool {
+    no_shorter_solution_with_stats(cube, solution_len).0
+}
+
+/// Work done by a [`no_shorter_solution`] search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchStats {
+    /// Face turns applied, each to a fresh copy of the cube.
+    pub moves_applied: u64,
+    /// Cubes checked for being solved.
+    pub states_checked: u64,
+}
+
+/// [`no_shorter_solution`] that also reports what the search cost.
+pub fn no_shorter_solution_with_stats(cube: &Cube, solution_len: usize) -> (bool, SearchStats) {
+    let mut stats = SearchStats::default();
+    let none_shorter = (0..solution_len).all(|depth| !solves_within(cube, depth, None, &mut stats));
+    (none_shorter, stats)
+}
+
+/// Work of [`no_shorter_solution`] for a solution that is optimal, which
+/// searches every shorter sequence and is the most the search ever does.
+/// Saturates rather than overflowing.
+pub fn search_bound(solution_len: usize) -> SearchStats {
+    let mut bound = SearchStats::default();
+    // Sequences of each length and the moves applied to reach all of them:
+    // 18 first turns, then 15 that avoid the previous face
+    let (mut sequences, mut applied) = (1u64, 0u64);
+    for depth in 0..solution_len {
+        if depth > 0 {
+            sequences = sequences.saturating_mul(if depth == 1 { 18 } else { 15 });
+            applied = applied.saturating_add(sequences);
+        }
+        bound.moves_applied = bound.moves_applied.saturating_add(applied);
+        bound.states_checked = bound.states_checked.saturating_add(sequences);
+    }
+    bound
+}
+
+/// Whether some sequence of exactly `depth` face turns, none repeating the face
+/// of the previous one, solves `cube`.
+fn solves_within(cube: &Cube, depth: usize, previous: Option<Face>, stats: &mut SearchStats) -> bool {
+    if depth == 0 {
+        stats.states_checked += 1;
+        return cube.is_solved();
+    }
+    Face::ALL.iter().filter(|&&face| Some(face) != previous).any(|&face| {
+        (1..4).any(|count| {
+            let mut next = cube.clone();
+            next.apply_move(&Move::from_face_and_count(face, count));
+            stats.moves_applied += 1;
+            solves_within(&next, depth - 1, Some(face), stats)
+        })
+    })
+}
@@ -0,0 +1,494 @@
+//! Consensus specification.
+//!
+//! Every constant and algorithm choice that decides whether a seal is valid
+//! lives here; the rest of the crate calls into this module instead of
+//! restating them. Changing anything below is a consensus change, and
+//! `tests/spec_tests.rs` pins each item with a golden vector so it cannot
+//! happen by accident.
+
+#![deny(clippy::indexing_slicing, clippy::panic, clippy::unreachable)]
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use sha3::{Digest, Sha3_256};
+
+use crate::{Axis, Color, Face, Move, MoveKind, Target};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "scale-codec",
+    derive(codec::Encode, codec::Decode, codec::MaxEncodedLen, scale_info::TypeInfo)
+)]
+pub enum SpecVersion {
+    /// Sha3-256 challenge seeds, ChaCha12 scrambles of 20 to 30 face turns
+    /// and the one-byte move encoding of `seal`.
+    #[cfg_attr(feature = "scale-codec", codec(index = 1))]
+    V1,
+    /// V1 with the root of the block's transactions appended to the
+    /// challenge header, so a seal only solves the block it was mined with,
+    /// and with slice turns in solutions.
+    #[cfg_attr(feature = "scale-codec", codec(index = 2))]
+    V2,
+    /// V2 with scrambles drawn from a fixed number of RNG words: each draw
+    /// takes one `u32` word and maps it by multiplication instead of
+    /// rejection, and faces are picked among those the filters allow
+    /// instead of being redrawn.
+    #[cfg_attr(feature = "scale-codec", codec(index = 3))]
+    V3,
+}
+
+impl SpecVersion {
+    pub const CURRENT: SpecVersion = SpecVersion::V1;
+    pub const ALL: [SpecVersion; 3] = [SpecVersion::V1, SpecVersion::V2, SpecVersion::V3];
+}
+
+/// Height from which each version applies, in ascending order. A seal at
+/// height `h` is checked under the last version activated at or below `h`.
+pub const ACTIVATIONS: &[(u64, SpecVersion)] = &[(0, SpecVersion::V1)];
+
+/// Version seals at `height` are checked under.
+pub fn version_at(height: u64) -> SpecVersion {
+    ACTIVATIONS
+        .iter()
+        .take_while(|&&(activation, _)| activation <= height)
+        .last()
+        .map_or(SpecVersion::V1, |&(_, version)| version)
+}
+
+/// Domain separator of [`challenge_commitment`].
+pub const CHALLENGE_DOMAIN: &[u8] = b"qbitcoin/challenge";
+/// Domain separator of [`color_permutation`].
+pub const COLOR_PERMUTATION_DOMAIN: &[u8] = b"qbitcoin/color-permutation";
+/// Domain separator of [`next_challenge_seed`].
+pub const NEXT_SEED_DOMAIN: &[u8] = b"qbitcoin/next-seed";
+/// Domain separator of `CommitmentVersion::V1FaceIds`.
+pub const STATE_DOMAIN_V1: &[u8] = b"qbitcoin/state/v1";
+/// Domain separator of `CommitmentVersion::V2Packed`.
+pub const STATE_DOMAIN_V2: &[u8] = b"qbitcoin/state/v2";
+
+/// Shortest and longest scramble, inclusive. Drawn as a `u32`: the width of
+/// the sampled integer decides how many RNG words a draw consumes.
+pub const SCRAMBLE_LENGTH: (u32, u32) = (20, 30);
+/// Scramble moves turn one to this many quarter turns.
+pub const SCRAMBLE_MAX_TURNS: usize = 3;
+/// Longest scramble a [`ScrambleSpec`] may ask for, bounding the work of
+/// rebuilding a challenge.
+pub const MAX_SCRAMBLE_LENGTH: u32 = 1000;
+
+/// Which face sequences a scramble may not contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "scale-codec",
+    derive(codec::Encode, codec::Decode, codec::MaxEncodedLen, scale_info::TypeInfo)
+)]
+pub struct ScrambleFilters {
+    /// No two moves in a row turn the same face (`R R'`).
+    pub distinct_faces: bool,
+    /// No three moves in a row turn faces of one axis (`R L R'`); their turns
+    /// commute and partly cancel.
+    pub no_axis_runs: bool,
+}
+
+/// Length and shape of the scramble a challenge seed expands to. Longer
+/// scrambles leave big cubes further from solved, raising the work per
+/// attempt independently of the hash target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "scale-codec",
+    derive(codec::Encode, codec::Decode, codec::MaxEncodedLen, scale_info::TypeInfo)
+)]
+pub struct ScrambleSpec {
+    /// Shortest and longest scramble, inclusive.
+    pub length: (u32, u32),
+    pub filters: ScrambleFilters,
+}
+
+impl ScrambleSpec {
+    /// The scrambles every version so far derives.
+    pub const V1: ScrambleSpec = ScrambleSpec {
+        length: SCRAMBLE_LENGTH,
+        filters: ScrambleFilters {
+            distinct_faces: true,
+            no_axis_runs: false,
+        },
+    };
+
+    /// Non-empty, ordered lengths of at most [`MAX_SCRAMBLE_LENGTH`].
+    pub fn is_valid(&self) -> bool {
+        let (shortest, longest) = self.length;
+        0 < shortest && shortest <= longest && longest <= MAX_SCRAMBLE_LENGTH
+    }
+
+    /// The filter that keeps `face` from following `previous`, if any.
+    fn rejects(&self, previous: &[Move], face: Face) -> Option<ScrambleFilter> {
+        let same_axis = |m: &Move| m.face() == face || m.face() == face.opposite();
+        match previous {
+            [.., last] if self.filters.distinct_faces && last.face() == face => Some(ScrambleFilter::DistinctFaces),
+            [.., before, last] if self.filters.no_axis_runs && same_axis(before) && same_axis(last) => {
+                Some(ScrambleFilter::NoAxisRuns)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// One of the [`ScrambleFilters`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScrambleFilter {
+    DistinctFaces,
+    NoAxisRuns,
+}
+
+/// A value drawn from the scramble RNG, in the order
+/// [`scramble_moves_with`] draws them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrambleDraw {
+    /// Number of moves in the scramble.
+    Length(u32),
+    /// Face of the next move. A face some filter rejects is redrawn.
+    Face { face: Face, rejected_by: Option<ScrambleFilter> },
+    /// Quarter turns of the move on the face just accepted.
+    Count(usize),
+}
+
+/// A step of [`color_permutation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDraw {
+    /// The hash the draws are read from.
+    Hash([u8; 32]),
+    /// The colour at `position` swapped with the one at `draw % (position + 1)`.
+    Swap { position: usize, draw: u16, with: usize },
+}
+
+impl Default for ScrambleSpec {
+    fn default() -> Self {
+        ScrambleSpec::V1
+    }
+}
+
+// Move bytes are tags assigned per move family, four per face or axis. The
+// assignment is spelled out below rather than derived from the order of the
+// `Move`, `Face` or `Axis` variants, so reordering or adding variants cannot
+// change what an existing seal decodes to.
+
+/// First move byte of face turns.
+pub const FACE_MOVE_BASE: u8 = 0;
+/// First move byte of wide turns.
+pub const WIDE_MOVE_BASE: u8 = 24;
+/// First move byte of whole-cube rotations.
+pub const ROTATION_MOVE_BASE: u8 = 48;
+/// First move byte of slice turns, assigned from V2.
+pub const SLICE_MOVE_BASE: u8 = 60;
+/// One past the last assigned move byte.
+pub const MOVE_BYTE_END: u8 = 72;
+/// Held back for further cube move families, such as layer-indexed turns.
+pub const RESERVED_CUBE_MOVES: Range<u8> = 72..128;
+/// Held back for the moves of other puzzles.
+pub const RESERVED_PUZZLE_MOVES: Range<u8> = 128..255;
+/// Held back to introduce multi-byte move encodings.
+pub const MOVE_EXTENSION_TAG: u8 = 255;
+
+/// Generator driving the scramble. Named explicitly rather than through
+/// `rand::rngs::StdRng`, whose algorithm may change between `rand` releases.
+pub type ScrambleRng = ChaCha12Rng;
+
+/// Sha3-256 of the nonce (u64, little endian) and block header.
+pub fn challenge_seed(version: SpecVersion, nonce: u64, block_header: &[u8]) -> [u8; 32] {
+    match version {
+        SpecVersion::V1 | SpecVersion::V2 | SpecVersion::V3 => {
+            let mut hasher = Sha3_256::new();
+            hasher.update(nonce.to_le_bytes());
+            hasher.update(block_header);
+            hasher.finalize().into()
+        }
+    }
+}
+
+/// Scramble moves for a challenge seed: a uniform `u32` length in
+/// [`SCRAMBLE_LENGTH`], then per move a uniform `u32` face index differing
+/// from the previous one and a uniform `u32` turn count in
+/// `1..=SCRAMBLE_MAX_TURNS`. Every draw is a `u32` so that it reads the same
+/// RNG words on 32- and 64-bit targets.
+///
+/// From V3 every draw reads exactly one word: the length, then per move the
+/// face among the five differing from the previous one, in `Face::ALL`
+/// order, and the turn count.
+pub fn scramble_moves(version: SpecVersion, seed: &[u8; 32]) -> Vec<Move> {
+    scramble_moves_with(version, &ScrambleSpec::V1, seed)
+}
+
+/// [`scramble_moves`] with the length drawn from `scramble.length` and faces
+/// redrawn until `scramble.filters` allow them, or from V3 drawn among those
+/// they allow. Under [`ScrambleSpec::V1`] this draws exactly what
+/// `scramble_moves` does. `scramble` must be valid.
+pub fn scramble_moves_with(version: SpecVersion, scramble: &ScrambleSpec, seed: &[u8; 32]) -> Vec<Move> {
+    scramble_moves_observed(version, scramble, seed, |_| {})
+}
+
+/// [`scramble_moves_with`], passing every draw to `observe` as it is made.
+pub(crate) fn scramble_moves_observed(
+    version: SpecVersion,
+    scramble: &ScrambleSpec,
+    seed: &[u8; 32],
+    mut observe: impl FnMut(ScrambleDraw),
+) -> Vec<Move> {
+    match version {
+        SpecVersion::V1 | SpecVersion::V2 => {
+            let mut rng = ScrambleRng::from_seed(*seed);
+            let num_moves: u32 = rng.gen_range(scramble.length.0..=scramble.length.1);
+            observe(ScrambleDraw::Length(num_moves));
+
+            let mut moves = Vec::with_capacity(num_moves as usize);
+            for _ in 0..num_moves {
+                let face = loop {
+                    let Some(face) = Face::ALL.get(rng.gen_range(0..6u32) as usize).copied() else {
+                        continue;
+                    };
+                    // Avoid redundant moves (e.g. R R')
+                    let rejected_by = scramble.rejects(&moves, face);
+                    observe(ScrambleDraw::Face { face, rejected_by });
+                    if rejected_by.is_none() {
+                        break face;
+                    }
+                };
+                let count = rng.gen_range(1..=SCRAMBLE_MAX_TURNS as u32) as usize;
+                observe(ScrambleDraw::Count(count));
+                moves.push(Move::from_face_and_count(face, count));
+            }
+            moves
+        }
+        SpecVersion::V3 => {
+            let mut rng = ScrambleRng::from_seed(*seed);
+            let (shortest, longest) = scramble.length;
+            let num_moves = shortest.saturating_add(draw_below(&mut rng, longest.saturating_sub(shortest) + 1));
+            observe(ScrambleDraw::Length(num_moves));
+
+            let mut moves = Vec::with_capacity(num_moves as usize);
+            for _ in 0..num_moves {
+                let allowed = Face::ALL.into_iter().filter(|&face| scramble.rejects(&moves, face).is_none());
+                // The filters rule out at most two faces
+                let index = draw_below(&mut rng, allowed.clone().count() as u32);
+                let face = allowed.clone().nth(index as usize).unwrap_or(Face::Up);
+                observe(ScrambleDraw::Face { face, rejected_by: None });
+                let count = 1 + draw_below(&mut rng, SCRAMBLE_MAX_TURNS as u32) as usize;
+                observe(ScrambleDraw::Count(count));
+                moves.push(Move::from_face_and_count(face, count));
+            }
+            moves
+        }
+    }
+}
+
+/// A draw in `0..n` from the next RNG word: the high half of its product
+/// with `n`. One word per draw on every platform, with a bias below
+/// `n / 2^32` instead of a rejection loop.
+fn draw_below(rng: &mut ScrambleRng, n: u32) -> u32 {
+    ((u64::from(rng.next_u32()) * u64::from(n)) >> 32) as u32
+}
+
+/// Sha3-256 binding a challenge seed to the cube size and target it was
+/// issued with.
+pub fn challenge_commitment(version: SpecVersion, seed: &[u8; 32], cube_size: u32, target: &Target) -> [u8; 32] {
+    match version {
+        SpecVersion::V1 | SpecVersion::V2 | SpecVersion::V3 => {
+            let mut hasher = Sha3_256::new();
+            hasher.update(CHALLENGE_DOMAIN);
+            hasher.update(seed);
+            hasher.update(cube_size.to_le_bytes());
+            hasher.update(target.as_bytes());
+            hasher.finalize().into()
+        }
+    }
+}
+
+/// Seed for the challenge after the one `challenge_commitment` was issued
+/// for: Sha3-256 of a domain tag and that commitment.
+///
+/// Each block commits to the seed of the next block's challenge. The seed
+/// depends only on the challenge the block's seal solved, not on fields its
+/// author can set freely, so biasing the next scramble means finding another
+/// valid seal per try rather than re-hashing a header.
+pub fn next_challenge_seed(version: SpecVersion, challenge_commitment: &[u8; 32]) -> [u8; 32] {
+    match version {
+        SpecVersion::V1 | SpecVersion::V2 | SpecVersion::V3 => {
+            let mut hasher = Sha3_256::new();
+            hasher.update(NEXT_SEED_DOMAIN);
+            hasher.update(challenge_commitment);
+            hasher.finalize().into()
+        }
+    }
+}
+
+/// Header the next challenge is derived from once `mined` blocks have been
+/// mined: `mined` (u32, little endian) followed by the seed the last of them
+/// committed to, zeros before the first.
+///
+/// From V2 the header ends with `template_root`, the root of the transactions
+/// of the block the seal is mined for. Swapping the transactions after solving
+/// changes the challenge and voids the seal. Seals submitted as transactions
+/// cannot commit to the block carrying them and pass zeros.
+pub fn challenge_header(
+    version: SpecVersion,
+    mined: u32,
+    committed_seed: &[u8; 32],
+    template_root: &[u8; 32],
+) -> Vec<u8> {
+    match version {
+        SpecVersion::V1 => {
+            let mut header = Vec::with_capacity(4 + 32);
+            header.extend_from_slice(&mined.to_le_bytes());
+            header.extend_from_slice(committed_seed);
+            header
+        }
+        SpecVersion::V2 | SpecVersion::V3 => {
+            let mut header = Vec::with_capacity(4 + 32 + 32);
+            header.extend_from_slice(&mined.to_le_bytes());
+            header.extend_from_slice(committed_seed);
+            header.extend_from_slice(template_root);
+            header
+        }
+    }
+}
+
+/// Whether [`challenge_header`] depends on the template root, so that a
+/// block's seal must be checked against the block's own transactions.
+pub fn binds_template(version: SpecVersion) -> bool {
+    match version {
+        SpecVersion::V1 => false,
+        SpecVersion::V2 | SpecVersion::V3 => true,
+    }
+}
+
+/// Fisher-Yates shuffle of `Color::ALL` driven by a domain-separated hash of
+/// the seed, two bytes per draw.
+pub fn color_permutation(version: SpecVersion, seed: &[u8; 32]) -> [Color; 6] {
+    color_permutation_observed(version, seed, |_| {})
+}
+
+/// [`color_permutation`], passing the hash and every swap to `observe`.
+pub(crate) fn color_permutation_observed(
+    version: SpecVersion,
+    seed: &[u8; 32],
+    mut observe: impl FnMut(ColorDraw),
+) -> [Color; 6] {
+    match version {
+        SpecVersion::V1 | SpecVersion::V2 | SpecVersion::V3 => {
+            let mut hasher = Sha3_256::new();
+            hasher.update(COLOR_PERMUTATION_DOMAIN);
+            hasher.update(seed);
+            let hash: [u8; 32] = hasher.finalize().into();
+            observe(ColorDraw::Hash(hash));
+
+            let mut colors = Color::ALL;
+            for (i, pair) in hash.chunks_exact(2).enumerate().take(colors.len()).skip(1).rev() {
+                let draw = <[u8; 2]>::try_from(pair).map_or(0, u16::from_le_bytes);
+                let with = draw as usize % (i + 1);
+                observe(ColorDraw::Swap { position: i, draw, with });
+                colors.swap(i, with);
+            }
+            colors
+        }
+    }
+}
+
+/// Tag of a face within a move family: U, D, L, R, F, B.
+fn face_tag(face: Face) -> u8 {
+    match face {
+        Face::Up => 0,
+        Face::Down => 1,
+        Face::Left => 2,
+        Face::Right => 3,
+        Face::Front => 4,
+        Face::Back => 5,
+    }
+}
+
+fn face_from_tag(tag: u8) -> Option<Face> {
+    Some(match tag {
+        0 => Face::Up,
+        1 => Face::Down,
+        2 => Face::Left,
+        3 => Face::Right,
+        4 => Face::Front,
+        5 => Face::Back,
+        _ => return None,
+    })
+}
+
+/// Tag of a rotation axis: x, y, z.
+fn axis_tag(axis: Axis) -> u8 {
+    match axis {
+        Axis::X => 0,
+        Axis::Y => 1,
+        Axis::Z => 2,
+    }
+}
+
+fn axis_from_tag(tag: u8) -> Option<Axis> {
+    Some(match tag {
+        0 => Axis::X,
+        1 => Axis::Y,
+        2 => Axis::Z,
+        _ => return None,
+    })
+}
+
+/// `family base + face tag * 4 + quarter turns % 4`, with face tags in the
+/// order U, D, L, R, F, B and the bases [`FACE_MOVE_BASE`] and
+/// [`WIDE_MOVE_BASE`]; rotations and slices are `ROTATION_MOVE_BASE` or
+/// `SLICE_MOVE_BASE` `+ axis tag * 4 + quarter turns % 4` with axis tags x,
+/// y, z, i.e. M, E, S for slices.
+///
+/// Slices encode under every version, but V1 decodes none of their bytes.
+/// Layer-indexed turns have no one-byte tag yet and encode as
+/// [`MOVE_EXTENSION_TAG`], which no version decodes.
+pub fn encode_move(version: SpecVersion, m: &Move) -> u8 {
+    match version {
+        SpecVersion::V1 | SpecVersion::V2 | SpecVersion::V3 => {
+            let turns = (m.count() % 4) as u8;
+            match *m {
+                Move::Layer(..) | Move::Block(..) => MOVE_EXTENSION_TAG,
+                Move::Wide(face, _) => WIDE_MOVE_BASE + face_tag(face) * 4 + turns,
+                Move::Rotation(axis, _) => ROTATION_MOVE_BASE + axis_tag(axis) * 4 + turns,
+                Move::Slice(axis, _) => SLICE_MOVE_BASE + axis_tag(axis) * 4 + turns,
+                _ => FACE_MOVE_BASE + face_tag(m.face()) * 4 + turns,
+            }
+        }
+    }
+}
+
+/// Inverse of [`encode_move`]. `None` for every byte the version does not
+/// assign, reserved ones included: such a tag is never read as some other
+/// move.
+pub fn decode_move(version: SpecVersion, byte: u8) -> Option<Move> {
+    match version {
+        SpecVersion::V1 | SpecVersion::V2 | SpecVersion::V3 => {
+            let turns = usize::from(byte % 4);
+            if byte < WIDE_MOVE_BASE {
+                Some(Move::from_face_and_count(face_from_tag((byte - FACE_MOVE_BASE) / 4)?, turns))
+            } else if byte < ROTATION_MOVE_BASE {
+                Some(Move::Wide(face_from_tag((byte - WIDE_MOVE_BASE) / 4)?, turns))
+            } else if byte < SLICE_MOVE_BASE {
+                Some(Move::Rotation(axis_from_tag((byte - ROTATION_MOVE_BASE) / 4)?, turns))
+            } else if byte < MOVE_BYTE_END {
+                let m = Move::Slice(axis_from_tag((byte - SLICE_MOVE_BASE) / 4)?, turns);
+                assigns_move(version, &m).then_some(m)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Whether solutions under `version` may contain `m`: slice turns only
+/// from V2 on, layer-indexed turns under no version yet.
+pub fn assigns_move(version: SpecVersion, m: &Move) -> bool {
+    match m.kind() {
+        MoveKind::Layer | MoveKind::Block => false,
+        MoveKind::Slice => version != SpecVersion::V1,
+        _ => true,
+    }
+}
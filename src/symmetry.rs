@@ -0,0 +1,143 @@
+//! Whole-cube symmetries and canonical forms.
+//!
+//! The symmetry group of the cube has 48 elements: the 24 rotations and their
+//! mirror images. Each one is a signed permutation of the axes, applied to the
+//! sticker positions and normals described in `geometry`.
+
+use alloc::vec::Vec;
+
+use crate::geometry::{self, Vec3};
+use crate::{Color, Cube};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symmetry {
+    /// Output axis `i` takes input axis `axes[i]`, multiplied by `signs[i]`.
+    axes: [usize; 3],
+    signs: [i32; 3],
+}
+
+impl Symmetry {
+    pub const IDENTITY: Symmetry = Symmetry {
+        axes: [0, 1, 2],
+        signs: [1, 1, 1],
+    };
+
+    /// All 48 symmetries, identity first.
+    pub fn all() -> Vec<Symmetry> {
+        const AXES: [[usize; 3]; 6] = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+
+        let mut symmetries = Vec::with_capacity(48);
+        for axes in AXES {
+            for bits in 0..8 {
+                let signs = [0, 1, 2].map(|i| if bits & (1 << i) == 0 { 1 } else { -1 });
+                symmetries.push(Symmetry { axes, signs });
+            }
+        }
+        symmetries
+    }
+
+    /// The 24 orientation-preserving symmetries.
+    pub fn rotations() -> Vec<Symmetry> {
+        Self::all()
+            .into_iter()
+            .filter(|s| !s.is_reflection())
+            .collect()
+    }
+
+    pub fn is_reflection(&self) -> bool {
+        // Determinant of a signed permutation matrix: permutation parity times the signs
+        let inversions = (0..3)
+            .flat_map(|i| (i + 1..3).map(move |j| (i, j)))
+            .filter(|&(i, j)| self.axes[i] > self.axes[j])
+            .count();
+        let parity = if inversions % 2 == 0 { 1 } else { -1 };
+        parity * self.signs.iter().product::<i32>() < 0
+    }
+
+    pub(crate) fn apply(&self, v: Vec3) -> Vec3 {
+        [0, 1, 2].map(|i| self.signs[i] * v[self.axes[i]])
+    }
+
+    /// Sticker colours of `cube` after applying this symmetry.
+    fn transform_stickers(&self, cube: &Cube) -> Vec<Color> {
+        let source = cube.stickers();
+        let destinations = geometry::permutation(cube.size, |p, n| (self.apply(p), self.apply(n)));
+
+        let mut stickers = source.clone();
+        for (from, &to) in destinations.iter().enumerate() {
+            stickers[to] = source[from];
+        }
+        stickers
+    }
+}
+
+/// Orientation-independent representative of a cube state, suitable as a
+/// key for duplicate detection.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CanonicalForm {
+    pub size: usize,
+    /// Sticker colours in `Face::ALL`, row-major order.
+    pub stickers: Vec<Color>,
+}
+
+/// Renames colours in order of first appearance, which gives the
+/// lexicographically smallest sequence among all colour relabellings.
+fn relabel_colors(stickers: &mut [Color]) {
+    let mut mapping: Vec<(Color, Color)> = Vec::with_capacity(6);
+    for sticker in stickers.iter_mut() {
+        let renamed = match mapping.iter().find(|(from, _)| from == sticker) {
+            Some(&(_, to)) => to,
+            None => {
+                let to = Color::ALL[mapping.len()];
+                mapping.push((*sticker, to));
+                to
+            }
+        };
+        *sticker = renamed;
+    }
+}
+
+impl Cube {
+    /// Applies a whole-cube symmetry to the sticker state.
+    pub fn transformed(&self, symmetry: &Symmetry) -> Cube {
+        let mut cube = self.clone();
+        cube.set_stickers(&symmetry.transform_stickers(self));
+        cube
+    }
+
+    /// Lexicographically smallest sticker sequence over the 48 symmetries.
+    pub fn canonical_form(&self) -> CanonicalForm {
+        self.canonical_form_by(|_| {})
+    }
+
+    /// Like [`Cube::canonical_form`], additionally minimised over the 720
+    /// permutations of the six colours.
+    pub fn canonical_form_up_to_recoloring(&self) -> CanonicalForm {
+        self.canonical_form_by(relabel_colors)
+    }
+
+    fn canonical_form_by(&self, normalize: impl Fn(&mut [Color])) -> CanonicalForm {
+        let stickers = Symmetry::all()
+            .iter()
+            .map(|symmetry| {
+                let mut stickers = symmetry.transform_stickers(self);
+                normalize(&mut stickers);
+                stickers
+            })
+            .min()
+            // The symmetry group is never empty
+            .unwrap_or_default();
+
+        CanonicalForm {
+            size: self.size,
+            stickers,
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Proof-of-work target arithmetic.
+//!
+//! A target is a 256-bit unsigned integer stored in big-endian byte order. A
+//! state commitment meets the target when, read as a big-endian integer, it is
+//! less than or equal to the target. The core crate, the pallet and the miner
+//! all go through [`Target::is_met_by`] so the rule is specified in one place.
+//!
+//! Everything here only uses `core`, so it compiles unchanged for the Wasm
+//! runtime.
+
+#![deny(clippy::indexing_slicing, clippy::panic, clippy::unreachable)]
+
+/// How a hash is compared against a target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Comparison {
+    /// Early-exit lexicographic comparison.
+    #[default]
+    Fast,
+    /// Branch-free comparison whose running time does not depend on the data.
+    ConstantTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Target([u8; 32]);
+
+impl Target {
+    /// The easiest possible target: every hash meets it.
+    pub const MAX: Target = Target([0xFF; 32]);
+
+    pub const fn from_bytes(bytes: [u8; 32]) -> Self {
+        Target(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Target for a difficulty value: `MAX / difficulty`, so doubling the
+    /// difficulty halves the fraction of hashes that meet the target.
+    /// A difficulty of 0 is treated as 1.
+    pub fn from_difficulty(difficulty: u32) -> Self {
+        let divisor = u64::from(difficulty.max(1));
+        let mut quotient = [0u8; 32];
+        let mut remainder = 0u64;
+
+        // Schoolbook long division of 2^256 - 1 by the divisor, one byte at a time
+        for byte in quotient.iter_mut() {
+            let current = (remainder << 8) | 0xFF;
+            *byte = (current / divisor) as u8;
+            remainder = current % divisor;
+        }
+
+        Target(quotient)
+    }
+
+    /// `target * factor`, clamped to [`Target::MAX`]. Used to accept hashes
+    /// "within a factor" of a target, e.g. as a cheap gossip pre-filter.
+    pub fn saturating_mul(&self, factor: u32) -> Self {
+        let mut product = [0u8; 32];
+        let mut carry = 0u64;
+        for (byte, &digit) in product.iter_mut().zip(&self.0).rev() {
+            let current = u64::from(digit) * u64::from(factor) + carry;
+            *byte = current as u8;
+            carry = current >> 8;
+        }
+        if carry != 0 {
+            return Target::MAX;
+        }
+        Target(product)
+    }
+
+    /// Returns true if `hash`, read as a big-endian integer, is `<=` the target.
+    pub fn is_met_by(&self, hash: &[u8; 32]) -> bool {
+        // Arrays compare lexicographically, which is big-endian numeric order
+        hash <= &self.0
+    }
+
+    /// Same result as [`Target::is_met_by`], computed without data-dependent
+    /// branches or early exits.
+    pub fn is_met_by_constant_time(&self, hash: &[u8; 32]) -> bool {
+        // hash <= target exactly when target - hash does not borrow
+        let mut borrow = 0u16;
+        for (&target, &hash) in self.0.iter().zip(hash).rev() {
+            let diff = u16::from(target)
+                .wrapping_sub(u16::from(hash))
+                .wrapping_sub(borrow);
+            borrow = (diff >> 8) & 1;
+        }
+        borrow == 0
+    }
+
+    /// Base-2 logarithm of the expected number of hashes per success,
+    /// `2^256 / (target + 1)`. Only meant for off-chain cost models.
+    #[cfg(feature = "std")]
+    pub fn work_log2(&self) -> f64 {
+        let value = self
+            .0
+            .iter()
+            .fold(0.0f64, |acc, &byte| acc * 256.0 + f64::from(byte));
+        256.0 - (value + 1.0).log2()
+    }
+
+    pub fn check(&self, hash: &[u8; 32], comparison: Comparison) -> bool {
+        match comparison {
+            Comparison::Fast => self.is_met_by(hash),
+            Comparison::ConstantTime => self.is_met_by_constant_time(hash),
+        }
+    }
+}
+
+/// A condition a challenge commitment has to meet. Seal verification is
+/// generic over it, so targets and other matchers share one replay-and-check
+/// path.
+pub trait HashCheck {
+    fn is_met_by(&self, hash: &[u8; 32]) -> bool;
+}
+
+impl HashCheck for Target {
+    fn is_met_by(&self, hash: &[u8; 32]) -> bool {
+        Target::is_met_by(self, hash)
+    }
+}
+
+/// Met by hashes that start with the given bytes, for vanity proofs. A prefix
+/// of `n` bytes is met by one hash in `256^n`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Prefix {
+    bytes: [u8; 32],
+    len: usize,
+}
+
+impl Prefix {
+    /// `None` if `prefix` is longer than a hash.
+    pub fn new(prefix: &[u8]) -> Option<Self> {
+        let mut bytes = [0u8; 32];
+        bytes.get_mut(..prefix.len())?.copy_from_slice(prefix);
+        Some(Prefix { bytes, len: prefix.len() })
+    }
+
+    /// The first `len` bytes of `hash`, or all of it if `len` is longer.
+    pub fn truncated(hash: &[u8; 32], len: usize) -> Self {
+        let len = len.min(hash.len());
+        let mut bytes = [0u8; 32];
+        for (byte, &b) in bytes.iter_mut().zip(hash).take(len) {
+            *byte = b;
+        }
+        Prefix { bytes, len }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bytes.get(..self.len).unwrap_or(&self.bytes)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The empty prefix, met by every hash.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if `hash` starts with the prefix, compared byte by byte.
+    pub fn is_met_by(&self, hash: &[u8; 32]) -> bool {
+        hash.starts_with(self.as_bytes())
+    }
+}
+
+impl HashCheck for Prefix {
+    fn is_met_by(&self, hash: &[u8; 32]) -> bool {
+        Prefix::is_met_by(self, hash)
+    }
+}
+
+impl From<[u8; 32]> for Target {
+    fn from(bytes: [u8; 32]) -> Self {
+        Target(bytes)
+    }
+}
@@ -0,0 +1,209 @@
+//! Awaitable solving and verification.
+//!
+//! [`solve_async`] and [`verify_async`] hand the work to an [`Executor`] and
+//! return a [`TaskHandle`] future, so node services, RPC handlers and the
+//! miner UI can await a result without blocking their reactor threads. The
+//! crate does not depend on an async runtime: any thread pool can be plugged
+//! in as an executor, and the handles work with any runtime's wakers.
+//!
+//! A [`CancelToken`] shared with a task makes its handle resolve to
+//! [`Cancelled`] right away. Work that has not started yet is skipped; a
+//! solve already in progress runs to completion on its thread, since solvers
+//! have no way to be interrupted, and its result is discarded.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use crate::solver::Solver;
+use crate::{verify_seal, Cube, Move, PowSeal, Target, VerifyError};
+
+/// A unit of blocking work.
+pub type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Runs blocking jobs somewhere other than the caller's thread. Closures
+/// taking a [`Job`] are executors too, e.g. a runtime's blocking spawn.
+pub trait Executor: Send + Sync {
+    fn execute(&self, job: Job);
+}
+
+impl<F> Executor for F
+where
+    F: Fn(Job) + Send + Sync,
+{
+    fn execute(&self, job: Job) {
+        self(job)
+    }
+}
+
+/// Runs every job on rayon's global pool.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RayonExecutor;
+
+impl Executor for RayonExecutor {
+    fn execute(&self, job: Job) {
+        rayon::spawn(job);
+    }
+}
+
+/// Runs every job on a thread of its own.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreadExecutor;
+
+impl Executor for ThreadExecutor {
+    fn execute(&self, job: Job) {
+        thread::spawn(job);
+    }
+}
+
+/// The task was cancelled before it produced a result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("task cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[derive(Default)]
+struct CancelState {
+    cancelled: AtomicBool,
+    /// Handles waiting on a task this token cancels.
+    wakers: Mutex<Vec<Waker>>,
+}
+
+/// Cancels every task it was passed to. Clones share the same state.
+#[derive(Clone, Default)]
+pub struct CancelToken(Arc<CancelState>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        CancelToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        for waker in lock(&self.0.wakers).drain(..) {
+            waker.wake();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    fn register(&self, waker: &Waker) {
+        let mut wakers = lock(&self.0.wakers);
+        if !wakers.iter().any(|known| known.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+}
+
+impl fmt::Debug for CancelToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancelToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+struct Slot<T> {
+    result: Option<T>,
+    waker: Option<Waker>,
+}
+
+/// Future resolving to the result of a job, or [`Cancelled`].
+pub struct TaskHandle<T> {
+    slot: Arc<Mutex<Slot<T>>>,
+    cancel: CancelToken,
+}
+
+impl<T> TaskHandle<T> {
+    /// True once the result is ready to be taken without waiting.
+    pub fn is_finished(&self) -> bool {
+        lock(&self.slot).result.is_some()
+    }
+}
+
+impl<T> Future for TaskHandle<T> {
+    type Output = Result<T, Cancelled>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.cancel.is_cancelled() {
+            return Poll::Ready(Err(Cancelled));
+        }
+        let mut slot = lock(&self.slot);
+        if let Some(result) = slot.result.take() {
+            return Poll::Ready(Ok(result));
+        }
+        slot.waker = Some(cx.waker().clone());
+        drop(slot);
+        self.cancel.register(cx.waker());
+        Poll::Pending
+    }
+}
+
+/// Runs `work` on `executor` unless `cancel` fires first.
+pub fn spawn<T, F>(executor: &dyn Executor, cancel: &CancelToken, work: F) -> TaskHandle<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let slot = Arc::new(Mutex::new(Slot { result: None, waker: None }));
+    let job_slot = Arc::clone(&slot);
+    let job_cancel = cancel.clone();
+
+    executor.execute(Box::new(move || {
+        if job_cancel.is_cancelled() {
+            return;
+        }
+        let result = work();
+        let waker = {
+            let mut slot = lock(&job_slot);
+            slot.result = Some(result);
+            slot.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }));
+
+    TaskHandle {
+        slot,
+        cancel: cancel.clone(),
+    }
+}
+
+/// [`Solver::solve`] on `executor`.
+pub fn solve_async(
+    executor: &dyn Executor,
+    solver: Arc<dyn Solver + Send + Sync>,
+    cube: Cube,
+    scramble: Vec<Move>,
+    cancel: &CancelToken,
+) -> TaskHandle<Option<Vec<Move>>> {
+    spawn(executor, cancel, move || solver.solve(&cube, &scramble))
+}
+
+/// [`verify_seal`] on `executor`.
+pub fn verify_async(
+    executor: &dyn Executor,
+    seal: PowSeal,
+    header: Vec<u8>,
+    target: Target,
+    cancel: &CancelToken,
+) -> TaskHandle<Result<Cube, VerifyError>> {
+    spawn(executor, cancel, move || verify_seal(&seal, &header, &target))
+}
@@ -0,0 +1,148 @@
+//! Move-by-move account of a solution, for explorers and teaching material.
+//!
+//! [`annotate_solution`] walks a solution from its challenge and notes after
+//! every move how long the solution is so far in each [`Metric`], which
+//! pieces it has just put in place and, on a 2x2x2, how far the cube still is
+//! from solved. A piece is in place when every sticker on it shows the colour
+//! the cube's scheme gives its face, so solutions that end in a rotated
+//! orientation are not credited for their last pieces.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::geometry::{self, Vec3};
+use crate::{optimal, Cube, Face, Metric, Move};
+
+/// The cube after one move of a solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnnotatedMove {
+    pub index: usize,
+    pub step: Move,
+    /// Length of the solution up to and including this move, indexed like
+    /// [`Metric::ALL`].
+    pub lengths: [u32; 3],
+    /// Pieces in place after this move that were not before it.
+    pub newly_solved: usize,
+    /// Pieces this move took out of place.
+    pub newly_unsolved: usize,
+    pub solved_pieces: usize,
+    /// Optimal number of moves left, known only for 2x2x2 cubes.
+    pub distance: Option<u8>,
+}
+
+impl AnnotatedMove {
+    pub fn length(&self, metric: Metric) -> u32 {
+        self.lengths[metric as usize]
+    }
+}
+
+impl fmt::Display for AnnotatedMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}. {}", self.index + 1, self.step)?;
+        for metric in Metric::ALL {
+            write!(f, " {} {}", metric.name(), self.length(metric))?;
+        }
+        write!(f, ", {} in place", self.solved_pieces)?;
+        if self.newly_solved > 0 {
+            write!(f, " (+{})", self.newly_solved)?;
+        }
+        if self.newly_unsolved > 0 {
+            write!(f, " (-{})", self.newly_unsolved)?;
+        }
+        if let Some(distance) = self.distance {
+            write!(f, ", {} to go", distance)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcript {
+    /// Pieces the cube is made of, centres included.
+    pub pieces: usize,
+    /// Pieces in place before the first move.
+    pub initially_solved: usize,
+    pub moves: Vec<AnnotatedMove>,
+    /// True if the solution leaves the cube solved.
+    pub solves: bool,
+}
+
+/// Annotates `solution` applied to `cube`, which is left untouched.
+pub fn annotate_solution(cube: &Cube, solution: &[Move]) -> Transcript {
+    let size = cube.size();
+    let pieces = pieces(size);
+    let mut cube = cube.clone();
+    let mut solved = in_place(&cube, &pieces);
+    let initially_solved = solved.iter().filter(|&&in_place| in_place).count();
+    let mut lengths = [0u32; 3];
+
+    let mut moves = Vec::with_capacity(solution.len());
+    for (index, m) in solution.iter().enumerate() {
+        cube.apply_move(m);
+        for (length, metric) in lengths.iter_mut().zip(Metric::ALL) {
+            *length = length.saturating_add(metric.cost(m, size));
+        }
+        let now = in_place(&cube, &pieces);
+        let changed = |from: bool| {
+            solved
+                .iter()
+                .zip(&now)
+                .filter(|&(&before, &after)| before == from && after != from)
+                .count()
+        };
+        moves.push(AnnotatedMove {
+            index,
+            step: *m,
+            lengths,
+            newly_solved: changed(false),
+            newly_unsolved: changed(true),
+            solved_pieces: now.iter().filter(|&&in_place| in_place).count(),
+            distance: if size == 2 { optimal::distance(&cube) } else { None },
+        });
+        solved = now;
+    }
+
+    Transcript {
+        pieces: pieces.len(),
+        initially_solved,
+        moves,
+        solves: cube.is_solved(),
+    }
+}
+
+/// Sticker indices of every piece of a `size` cube, grouped by the cubie
+/// they sit on.
+fn pieces(size: usize) -> Vec<Vec<usize>> {
+    let mut by_cubie: HashMap<Vec3, Vec<usize>> = HashMap::new();
+    let mut order = Vec::new();
+    for face in Face::ALL {
+        for row in 0..size {
+            for col in 0..size {
+                let (position, _) = geometry::to_space(size, face, row, col);
+                let stickers = by_cubie.entry(position).or_insert_with(|| {
+                    order.push(position);
+                    Vec::new()
+                });
+                stickers.push(geometry::sticker_index(size, face, row, col));
+            }
+        }
+    }
+    order
+        .into_iter()
+        .map(|position| by_cubie.remove(&position).unwrap_or_default())
+        .collect()
+}
+
+fn in_place(cube: &Cube, pieces: &[Vec<usize>]) -> Vec<bool> {
+    let size = cube.size();
+    let stickers = cube.stickers();
+    let scheme = cube.scheme();
+    pieces
+        .iter()
+        .map(|piece| {
+            piece
+                .iter()
+                .all(|&i| stickers[i] == scheme.color_of(Face::ALL[i / (size * size)]))
+        })
+        .collect()
+}
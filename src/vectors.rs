@@ -0,0 +1,329 @@
+//! Consensus test vectors for comparing builds across targets.
+//!
+//! The runtime checks seals with this crate compiled to Wasm, miners and the
+//! node's import path with a native build. [`vectors`] runs challenge
+//! derivation, commitment hashing and verification on fixed inputs and
+//! [`encode`] serialises the outputs, so two builds agree on consensus
+//! exactly when their encodings are equal byte for byte.
+//! `examples/wasm_vectors.rs` exports them from a Wasm module, which
+//! `tests/wasm_vectors_tests.rs` runs under wasmtime.
+//!
+//! The inputs are part of the format: changing them changes [`digest`].
+//!
+//! Besides finished scrambles, the vectors record every draw of the scramble
+//! RNG under each [`SpecVersion`], so a target that reads the RNG differently
+//! shows up at the first draw it gets wrong rather than as a different move.
+//!
+//! [`challenge_vectors`] are the same derivation worked through challenge by
+//! challenge for each protocol version: seed, scramble, commitment of the
+//! scrambled state and whether it meets a few targets. Alternative miners
+//! check themselves against them with [`certify`], or read them as JSON from
+//! [`challenge_vectors_json`] when they are not written in Rust.
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use sha3::{Digest, Sha3_256};
+
+use crate::difficulty::{scale_reward, work_per_seal};
+use crate::retarget::retarget;
+use crate::solver::{ReverseScramble, Solver};
+use crate::spec::{self, ScrambleDraw, ScrambleFilter, ScrambleFilters, ScrambleSpec, SpecVersion};
+use crate::{verify_seal, CommitmentVersion, Cube, Face, Move, PowSeal, Target};
+
+/// Named output of one computation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vector {
+    pub name: String,
+    pub output: Vec<u8>,
+}
+
+const CHALLENGES: [(u64, &[u8]); 3] = [(0, b""), (7, b"spec"), (u64::MAX, &[0xff; 80])];
+const SIZES: [u32; 4] = [2, 3, 4, 5];
+const DIFFICULTIES: [u32; 4] = [1, 2, 1000, u32::MAX];
+/// Scramble specs whose draws are recorded: the one every version derives,
+/// and one with both filters so that rejected faces appear.
+const DRAW_SPECS: [ScrambleSpec; 2] = [
+    ScrambleSpec::V1,
+    ScrambleSpec {
+        length: (1, 40),
+        filters: ScrambleFilters { distinct_faces: true, no_axis_runs: true },
+    },
+];
+
+/// A draw as a tag byte and its value: `0` and the length (u32, little
+/// endian), `1`, the face's index in [`Face::ALL`] and the rejecting filter
+/// (`0` for none, then `1` and `2` in declaration order), or `2` and the turn
+/// count.
+fn encode_draw(draw: ScrambleDraw, out: &mut Vec<u8>) {
+    match draw {
+        ScrambleDraw::Length(length) => {
+            out.push(0);
+            out.extend_from_slice(&length.to_le_bytes());
+        }
+        ScrambleDraw::Face { face, rejected_by } => {
+            let index = Face::ALL.iter().position(|&f| f == face).unwrap_or(usize::MAX);
+            let filter = match rejected_by {
+                None => 0,
+                Some(ScrambleFilter::DistinctFaces) => 1,
+                Some(ScrambleFilter::NoAxisRuns) => 2,
+            };
+            out.extend_from_slice(&[1, index as u8, filter]);
+        }
+        ScrambleDraw::Count(count) => out.extend_from_slice(&[2, count as u8]),
+    }
+}
+
+/// Every vector, in a fixed order.
+pub fn vectors() -> Vec<Vector> {
+    let version = SpecVersion::V1;
+    let mut out = Vec::new();
+    let mut push = |name: String, output: Vec<u8>| out.push(Vector { name, output });
+
+    for (nonce, header) in CHALLENGES {
+        let label = format!("nonce {} header {}", nonce, header.len());
+        let seed = spec::challenge_seed(version, nonce, header);
+        push(format!("{label} seed"), seed.to_vec());
+        let scramble = spec::scramble_moves(version, &seed);
+        push(
+            format!("{label} scramble"),
+            scramble.iter().map(|m| spec::encode_move(version, m)).collect(),
+        );
+
+        for size in SIZES {
+            let mut cube = Cube::new(size as usize);
+            let scramble = cube.scramble_with(version, &ScrambleSpec::V1, nonce, header);
+            for commitment in [CommitmentVersion::V1FaceIds, CommitmentVersion::V2Packed] {
+                push(
+                    format!("{label} size {size} {:?}", commitment),
+                    cube.state_commitment_with(commitment).to_vec(),
+                );
+            }
+
+            // Verification outcomes: 0 for accepted, else the error code
+            let solution = ReverseScramble.solve(&cube, &scramble).unwrap_or_default();
+            let mut truncated = solution.clone();
+            truncated.pop();
+            let outcomes = [
+                (solution.clone(), Target::MAX),
+                (solution, Target::from_difficulty(u32::MAX)),
+                (truncated, Target::MAX),
+            ]
+            .into_iter()
+            .map(|(moves, target)| {
+                let seal = PowSeal { cube_size: size, nonce, moves };
+                verify_seal(&seal, header, &target).map_or_else(|error| error.code(), |_| 0)
+            })
+            .collect();
+            push(format!("{label} size {size} verify"), outcomes);
+        }
+
+        for draw_version in SpecVersion::ALL {
+            let seed = spec::challenge_seed(draw_version, nonce, header);
+            for (index, scramble) in DRAW_SPECS.iter().enumerate() {
+                let mut output = Vec::new();
+                spec::scramble_moves_observed(draw_version, scramble, &seed, |draw| encode_draw(draw, &mut output));
+                push(format!("{label} {:?} scramble spec {index} draws", draw_version), output);
+            }
+        }
+
+        let commitment = spec::challenge_commitment(version, &seed, 3, &Target::from_difficulty(1000));
+        push(format!("{label} challenge commitment"), commitment.to_vec());
+        let next = spec::next_challenge_seed(version, &commitment);
+        push(format!("{label} next seed"), next.to_vec());
+        push(format!("{label} next header"), spec::challenge_header(version, 42, &next, &[0; 32]));
+    }
+
+    for difficulty in DIFFICULTIES {
+        let target = Target::from_difficulty(difficulty);
+        push(format!("difficulty {difficulty} target"), target.as_bytes().to_vec());
+        for size in SIZES {
+            let mut output = work_per_seal(size, &target).to_le_bytes().to_vec();
+            output.extend_from_slice(&scale_reward(5000, size, &target).to_le_bytes());
+            push(format!("difficulty {difficulty} size {size} work and reward"), output);
+        }
+        push(
+            format!("difficulty {difficulty} retarget"),
+            [(30_000, 60_000), (120_000, 60_000), (0, 60_000)]
+                .iter()
+                .flat_map(|&(observed, expected)| retarget(difficulty, observed, expected).to_le_bytes())
+                .collect(),
+        );
+    }
+    out
+}
+
+/// Each vector as its name and output, both prefixed with their length
+/// (u32, little endian).
+pub fn encode(vectors: &[Vector]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for vector in vectors {
+        for field in [vector.name.as_bytes(), &vector.output] {
+            out.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            out.extend_from_slice(field);
+        }
+    }
+    out
+}
+
+/// Inverse of [`encode`]; `None` if `bytes` is truncated or a name is not
+/// UTF-8.
+pub fn decode(mut bytes: &[u8]) -> Option<Vec<Vector>> {
+    let field = |bytes: &mut &[u8]| -> Option<Vec<u8>> {
+        let len = u32::from_le_bytes(bytes.get(..4)?.try_into().ok()?) as usize;
+        let value = bytes.get(4..4usize.checked_add(len)?)?.to_vec();
+        *bytes = &bytes[4 + len..];
+        Some(value)
+    };
+    let mut vectors = Vec::new();
+    while !bytes.is_empty() {
+        let name = String::from_utf8(field(&mut bytes)?).ok()?;
+        let output = field(&mut bytes)?;
+        vectors.push(Vector { name, output });
+    }
+    Some(vectors)
+}
+
+/// SHA3-256 of the encoded vectors.
+pub fn digest() -> [u8; 32] {
+    Sha3_256::digest(encode(&vectors())).into()
+}
+
+/// A challenge derived under one spec and commitment version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChallengeVector {
+    pub spec: SpecVersion,
+    pub commitment: CommitmentVersion,
+    pub cube_size: u32,
+    pub nonce: u64,
+    pub header: Vec<u8>,
+    pub seed: [u8; 32],
+    /// Scramble under [`ScrambleSpec::V1`].
+    pub scramble: Vec<Move>,
+    /// Commitment of the scrambled cube.
+    pub state_commitment: [u8; 32],
+    /// Each of [`TARGET_DIFFICULTIES`] and whether the state commitment meets
+    /// its target.
+    pub meets_target: Vec<(u32, bool)>,
+}
+
+/// Difficulties whose targets every [`ChallengeVector`] is checked against.
+pub const TARGET_DIFFICULTIES: [u32; 4] = [1, 2, 16, 1000];
+
+/// Every vector for `spec` and `commitment`, in a fixed order.
+pub fn challenge_vectors(spec: SpecVersion, commitment: CommitmentVersion) -> Vec<ChallengeVector> {
+    let mut out = Vec::new();
+    for (nonce, header) in CHALLENGES {
+        for cube_size in SIZES {
+            let seed = spec::challenge_seed(spec, nonce, header);
+            let mut cube = Cube::new(cube_size as usize);
+            let scramble = cube.scramble_with(spec, &ScrambleSpec::V1, nonce, header);
+            let state_commitment = cube.state_commitment_with(commitment);
+            out.push(ChallengeVector {
+                spec,
+                commitment,
+                cube_size,
+                nonce,
+                header: header.to_vec(),
+                seed,
+                scramble,
+                state_commitment,
+                meets_target: TARGET_DIFFICULTIES
+                    .iter()
+                    .map(|&difficulty| (difficulty, Target::from_difficulty(difficulty).is_met_by(&state_commitment)))
+                    .collect(),
+            });
+        }
+    }
+    out
+}
+
+/// The first vector an implementation gets wrong, and what it got wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub expected: ChallengeVector,
+    /// `None` if the implementation produced no vector.
+    pub derived: Option<ChallengeVector>,
+    /// Name of the first field that differs.
+    pub field: &'static str,
+}
+
+/// Runs `derive` on the inputs of every vector of every version, i.e. its
+/// version, size, nonce and header, and compares what it returns with the
+/// expected vector. Returns the number of vectors checked. `derive` returns
+/// `None` for versions it does not implement, which fails certification.
+pub fn certify<F>(mut derive: F) -> Result<usize, Box<Mismatch>>
+where
+    F: FnMut(&ChallengeVector) -> Option<ChallengeVector>,
+{
+    let mut checked = 0;
+    for spec in SpecVersion::ALL {
+        for commitment in CommitmentVersion::ALL {
+            for expected in challenge_vectors(spec, commitment) {
+                let derived = derive(&expected);
+                let field = match &derived {
+                    None => Some("vector"),
+                    Some(derived) => first_difference(&expected, derived),
+                };
+                if let Some(field) = field {
+                    return Err(Box::new(Mismatch { expected, derived, field }));
+                }
+                checked += 1;
+            }
+        }
+    }
+    Ok(checked)
+}
+
+fn first_difference(expected: &ChallengeVector, derived: &ChallengeVector) -> Option<&'static str> {
+    [
+        ("spec", expected.spec == derived.spec),
+        ("commitment", expected.commitment == derived.commitment),
+        ("cube_size", expected.cube_size == derived.cube_size),
+        ("nonce", expected.nonce == derived.nonce),
+        ("header", expected.header == derived.header),
+        ("seed", expected.seed == derived.seed),
+        ("scramble", expected.scramble == derived.scramble),
+        ("state_commitment", expected.state_commitment == derived.state_commitment),
+        ("meets_target", expected.meets_target == derived.meets_target),
+    ]
+    .into_iter()
+    .find_map(|(field, equal)| (!equal).then_some(field))
+}
+
+/// [`challenge_vectors`] of every version as JSON: an array of objects with
+/// the fields of [`ChallengeVector`], versions as their variant names, byte
+/// strings in hex and scrambles in standard notation.
+#[cfg(feature = "std")]
+pub fn challenge_vectors_json() -> String {
+    use crate::notation::format_algorithm;
+    use crate::replay::to_hex;
+
+    let mut entries = Vec::new();
+    for spec in SpecVersion::ALL {
+        for commitment in CommitmentVersion::ALL {
+            for vector in challenge_vectors(spec, commitment) {
+                let checks: Vec<String> = vector
+                    .meets_target
+                    .iter()
+                    .map(|(difficulty, met)| format!(r#"{{"difficulty":{difficulty},"met":{met}}}"#))
+                    .collect();
+                entries.push(format!(
+                    r#"{{"spec":"{:?}","commitment":"{:?}","cube_size":{},"nonce":{},"header":"{}","seed":"{}","scramble":"{}","state_commitment":"{}","meets_target":[{}]}}"#,
+                    vector.spec,
+                    vector.commitment,
+                    vector.cube_size,
+                    vector.nonce,
+                    to_hex(&vector.header),
+                    to_hex(&vector.seed),
+                    format_algorithm(&vector.scramble),
+                    to_hex(&vector.state_commitment),
+                    checks.join(",")
+                ));
+            }
+        }
+    }
+    format!("[{}]", entries.join(","))
+}
@@ -0,0 +1,228 @@
+//! Seal verification off the import path.
+//!
+//! A node hands the seals of incoming blocks and transactions to a
+//! [`VerifierService`], which checks them on its own worker threads and
+//! reports each result on a channel. Every peer gets a bounded queue and the
+//! workers take one job per peer in turn, so a peer flooding the node with
+//! big-cube proofs only fills its own queue: further submissions from it are
+//! refused straight away, and other peers' seals keep being verified.
+
+#![allow(clippy::expect_used)]
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+use crate::{verify_seal, PowSeal, Target, VerifyError};
+
+/// Identifies the peer a seal was received from.
+pub type PeerId = u64;
+/// Identifies a submission in the results.
+pub type RequestId = u64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifierConfig {
+    /// Worker threads, at least one.
+    pub workers: usize,
+    /// Seals a single peer may have waiting.
+    pub per_peer_queue: usize,
+    /// Seals all peers together may have waiting.
+    pub max_queued: usize,
+}
+
+impl Default for VerifierConfig {
+    fn default() -> Self {
+        VerifierConfig {
+            workers: thread::available_parallelism().map_or(1, usize::from),
+            per_peer_queue: 16,
+            max_queued: 1024,
+        }
+    }
+}
+
+/// A seal together with the challenge header and target it must meet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyJob {
+    pub seal: PowSeal,
+    pub header: Vec<u8>,
+    pub target: Target,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyOutcome {
+    pub peer: PeerId,
+    pub id: RequestId,
+    pub result: Result<(), VerifyError>,
+}
+
+/// Why a seal was not queued. The caller keeps the job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmitError {
+    /// The peer already has `per_peer_queue` seals waiting.
+    PeerQueueFull,
+    /// `max_queued` seals are waiting across all peers.
+    QueueFull,
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::PeerQueueFull => f.write_str("peer verification queue is full"),
+            SubmitError::QueueFull => f.write_str("verification queue is full"),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+#[derive(Default)]
+struct Queues {
+    jobs: BTreeMap<PeerId, VecDeque<(RequestId, VerifyJob)>>,
+    /// Peers with waiting jobs, in the order they are served next.
+    turn: VecDeque<PeerId>,
+    queued: usize,
+    next_id: RequestId,
+    stopping: bool,
+}
+
+impl Queues {
+    /// The next job in round-robin order across peers.
+    fn pop(&mut self) -> Option<(PeerId, RequestId, VerifyJob)> {
+        let peer = self.turn.pop_front()?;
+        let queue = self.jobs.get_mut(&peer).expect("peers in turn have a queue");
+        let (id, job) = queue.pop_front().expect("peers in turn have a job");
+        if queue.is_empty() {
+            self.jobs.remove(&peer);
+        } else {
+            self.turn.push_back(peer);
+        }
+        self.queued -= 1;
+        Some((peer, id, job))
+    }
+}
+
+struct Shared {
+    queues: Mutex<Queues>,
+    ready: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Queues> {
+        // Workers never panic while holding the lock
+        self.queues.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Worker pool verifying seals with per-peer backpressure. Dropping it stops
+/// the workers after the seals they are checking; waiting seals are dropped.
+pub struct VerifierService {
+    config: VerifierConfig,
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl VerifierService {
+    /// Starts the workers. Results arrive on the returned receiver in the
+    /// order verification finishes, which is not the submission order.
+    pub fn start(config: VerifierConfig) -> (Self, Receiver<VerifyOutcome>) {
+        let shared = Arc::new(Shared {
+            queues: Mutex::new(Queues::default()),
+            ready: Condvar::new(),
+        });
+        let (sender, receiver) = mpsc::channel();
+
+        let workers = (0..config.workers.max(1))
+            .map(|i| {
+                let shared = Arc::clone(&shared);
+                let sender = sender.clone();
+                thread::Builder::new()
+                    .name(format!("seal-verifier-{}", i))
+                    .spawn(move || work(&shared, &sender))
+                    .expect("failed to spawn verifier thread")
+            })
+            .collect();
+
+        (VerifierService { config, shared, workers }, receiver)
+    }
+
+    pub fn config(&self) -> &VerifierConfig {
+        &self.config
+    }
+
+    /// Queues `job` for verification on behalf of `peer`.
+    pub fn submit(&self, peer: PeerId, job: VerifyJob) -> Result<RequestId, SubmitError> {
+        let mut queues = self.shared.lock();
+        if queues.queued >= self.config.max_queued {
+            return Err(SubmitError::QueueFull);
+        }
+        let waiting = queues.jobs.get(&peer).map_or(0, VecDeque::len);
+        if waiting >= self.config.per_peer_queue {
+            return Err(SubmitError::PeerQueueFull);
+        }
+
+        let id = queues.next_id;
+        queues.next_id += 1;
+        queues.queued += 1;
+        if waiting == 0 {
+            queues.turn.push_back(peer);
+        }
+        queues.jobs.entry(peer).or_default().push_back((id, job));
+        drop(queues);
+
+        self.shared.ready.notify_one();
+        Ok(id)
+    }
+
+    /// Seals `peer` has waiting, not counting any being verified.
+    pub fn queued_for(&self, peer: PeerId) -> usize {
+        self.shared.lock().jobs.get(&peer).map_or(0, VecDeque::len)
+    }
+
+    /// Seals waiting across all peers.
+    pub fn queued(&self) -> usize {
+        self.shared.lock().queued
+    }
+
+    /// Drops every waiting seal from `peer`, e.g. once it disconnects or is
+    /// banned. Returns how many were dropped.
+    pub fn forget_peer(&self, peer: PeerId) -> usize {
+        let mut queues = self.shared.lock();
+        let dropped = queues.jobs.remove(&peer).map_or(0, |queue| queue.len());
+        queues.turn.retain(|&waiting| waiting != peer);
+        queues.queued -= dropped;
+        dropped
+    }
+}
+
+impl Drop for VerifierService {
+    fn drop(&mut self) {
+        self.shared.lock().stopping = true;
+        self.shared.ready.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn work(shared: &Shared, results: &Sender<VerifyOutcome>) {
+    loop {
+        let (peer, id, job) = {
+            let mut queues = shared.lock();
+            loop {
+                if queues.stopping {
+                    return;
+                }
+                if let Some(next) = queues.pop() {
+                    break next;
+                }
+                queues = shared.ready.wait(queues).unwrap_or_else(|poisoned| poisoned.into_inner());
+            }
+        };
+
+        let result = verify_seal(&job.seal, &job.header, &job.target).map(|_| ());
+        // Nobody listening any more is not the workers' concern
+        let _ = results.send(VerifyOutcome { peer, id, result });
+    }
+}
@@ -0,0 +1,379 @@
+//! Seal verification with machine-readable rejection reasons.
+//!
+//! [`verify_seal`] is the policy-free part of checking a seal: rebuild the
+//! challenge, replay the solution and compare the commitment with the target.
+//! Chain-specific rules (size limits, nonce ordering) stay with the caller but
+//! report through the same [`VerifyError`] codes, so a miner sees one set of
+//! reasons whether a seal was rejected locally, by RPC or on chain.
+//!
+//! Solutions that undo the scramble move for move are recognised from the
+//! move lists alone and not replayed; every other solution is replayed in
+//! full. Both paths accept exactly the same seals.
+
+#![deny(clippy::indexing_slicing, clippy::panic, clippy::unreachable)]
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+
+use crate::cubie::CubieCube;
+use crate::policy::{MinQuality, SolutionPolicy};
+use crate::seal::DecodeError;
+use crate::spec;
+use crate::target::HashCheck;
+use crate::{CommitmentVersion, Cube, CubeError, Face, Move, PowSeal, ScrambleSpec, SpecVersion, Target};
+
+/// Smallest cube size a seal can be for. A 1x1x1 cube is solved by the
+/// empty sequence whatever the challenge, so it proves no work.
+pub const MIN_SEAL_SIZE: u32 = 2;
+
+/// Why a seal was rejected. The discriminant is the stable wire code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum VerifyError {
+    /// The seal bytes could not be decoded.
+    MalformedSeal = 1,
+    CubeTooSmall = 2,
+    CubeTooLarge = 3,
+    /// The challenge the seal was mined for has been superseded; fetch a new
+    /// template.
+    StaleChallenge = 4,
+    /// The moves do not solve the scrambled cube.
+    InvalidSolution = 5,
+    /// The challenge commitment does not meet the target.
+    TargetNotMet = 6,
+    /// The solution solves the challenge but the chain's
+    /// [`SolutionPolicy`] rejects it as not specific to it.
+    CannedSolution = 7,
+    /// Under [`Rules::double_check`], the cubie engine saw a different
+    /// challenge or reached a different verdict. One engine has a bug, so
+    /// the seal is refused whichever is right.
+    EngineDisagreement = 8,
+    /// The solution is longer than [`Rules::min_quality`] allows for its
+    /// scramble.
+    SolutionTooLong = 9,
+}
+
+impl VerifyError {
+    pub const ALL: [VerifyError; 9] = [
+        VerifyError::MalformedSeal,
+        VerifyError::CubeTooSmall,
+        VerifyError::CubeTooLarge,
+        VerifyError::StaleChallenge,
+        VerifyError::InvalidSolution,
+        VerifyError::TargetNotMet,
+        VerifyError::CannedSolution,
+        VerifyError::EngineDisagreement,
+        VerifyError::SolutionTooLong,
+    ];
+
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_code(code: u8) -> Option<VerifyError> {
+        VerifyError::ALL.iter().copied().find(|e| e.code() == code)
+    }
+
+    /// Stable identifier used in RPC error data.
+    pub fn name(self) -> &'static str {
+        match self {
+            VerifyError::MalformedSeal => "MalformedSeal",
+            VerifyError::CubeTooSmall => "CubeTooSmall",
+            VerifyError::CubeTooLarge => "CubeTooLarge",
+            VerifyError::StaleChallenge => "StaleChallenge",
+            VerifyError::InvalidSolution => "InvalidSolution",
+            VerifyError::TargetNotMet => "TargetNotMet",
+            VerifyError::CannedSolution => "CannedSolution",
+            VerifyError::EngineDisagreement => "EngineDisagreement",
+            VerifyError::SolutionTooLong => "SolutionTooLong",
+        }
+    }
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerifyError {}
+
+impl From<DecodeError> for VerifyError {
+    fn from(_: DecodeError) -> Self {
+        VerifyError::MalformedSeal
+    }
+}
+
+impl From<CubeError> for VerifyError {
+    fn from(error: CubeError) -> Self {
+        match error {
+            CubeError::SizeTooSmall(_) => VerifyError::CubeTooSmall,
+            CubeError::SizeTooLarge(_) => VerifyError::CubeTooLarge,
+            CubeError::NoLegalMoves => VerifyError::InvalidSolution,
+        }
+    }
+}
+
+/// Rebuilds the challenge for `seal` under `header`, checks that the moves
+/// solve it and that its commitment meets `target`. Returns the scrambled
+/// cube, from which callers derive any further commitments.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "verify",
+        level = "debug",
+        skip_all,
+        fields(size = seal.cube_size, nonce = seal.nonce, depth = seal.moves.len()),
+        err(Display)
+    )
+)]
+pub fn verify_seal(seal: &PowSeal, header: &[u8], target: &Target) -> Result<Cube, VerifyError> {
+    verify_seal_with_spec(SpecVersion::CURRENT, seal, header, target)
+}
+
+/// [`verify_seal`] under an explicit spec version, for re-checking seals
+/// mined before the current version took effect.
+pub fn verify_seal_with_spec(
+    version: SpecVersion,
+    seal: &PowSeal,
+    header: &[u8],
+    target: &Target,
+) -> Result<Cube, VerifyError> {
+    verify_seal_with_check(version, seal, header, target)
+}
+
+/// [`verify_seal`] with both the spec and the commitment version explicit,
+/// for chains that schedule rule changes by height.
+pub fn verify_seal_with_versions(
+    spec: SpecVersion,
+    commitment: CommitmentVersion,
+    seal: &PowSeal,
+    header: &[u8],
+    target: &Target,
+) -> Result<Cube, VerifyError> {
+    verify_seal_with_scramble(spec, commitment, &ScrambleSpec::V1, seal, header, target)
+}
+
+/// [`verify_seal_with_versions`] for challenges scrambled under `scramble`
+/// rather than [`ScrambleSpec::V1`]. An invalid `scramble` rejects every
+/// seal as `InvalidSolution`.
+pub fn verify_seal_with_scramble(
+    spec: SpecVersion,
+    commitment: CommitmentVersion,
+    scramble: &ScrambleSpec,
+    seal: &PowSeal,
+    header: &[u8],
+    target: &Target,
+) -> Result<Cube, VerifyError> {
+    let rules = Rules {
+        spec,
+        commitment,
+        scramble: *scramble,
+        ..Rules::default()
+    };
+    verify_seal_with_rules(&rules, seal, header, target)
+}
+
+/// Everything besides the header and target that decides whether a seal is
+/// valid.
+#[derive(Clone, Copy)]
+pub struct Rules<'a> {
+    pub spec: SpecVersion,
+    pub commitment: CommitmentVersion,
+    pub scramble: ScrambleSpec,
+    /// Checked last, once the seal meets the target.
+    pub policy: &'a dyn SolutionPolicy,
+    /// Also replay the challenge and the solution on the
+    /// [`cubie`](crate::cubie) engine and refuse the seal if it disagrees,
+    /// for block authors who would rather lose a block than build on one an
+    /// engine bug let through.
+    pub double_check: bool,
+    /// Checked once the scramble is known, before the solution is replayed.
+    pub min_quality: MinQuality,
+}
+
+impl Default for Rules<'_> {
+    /// The current versions, [`ScrambleSpec::V1`], no solution policy, a
+    /// single engine and no quality floor.
+    fn default() -> Self {
+        Rules {
+            spec: SpecVersion::CURRENT,
+            commitment: CommitmentVersion::CURRENT,
+            scramble: ScrambleSpec::V1,
+            policy: &(),
+            double_check: false,
+            min_quality: MinQuality::NONE,
+        }
+    }
+}
+
+/// [`verify_seal`] under explicit [`Rules`]. An invalid scramble spec
+/// rejects every seal as `InvalidSolution`; a seal the policy refuses is
+/// reported as `CannedSolution`, one the quality floor refuses as
+/// `SolutionTooLong`.
+pub fn verify_seal_with_rules(
+    rules: &Rules<'_>,
+    seal: &PowSeal,
+    header: &[u8],
+    target: &Target,
+) -> Result<Cube, VerifyError> {
+    if !rules.scramble.is_valid() {
+        return Err(VerifyError::InvalidSolution);
+    }
+    verify_counted(rules, seal, header, target, &mut VerifyStats::default())
+}
+
+/// [`verify_seal`] with the commitment checked by `check` instead of a
+/// target, e.g. a [`Prefix`](crate::target::Prefix) for vanity proofs. A
+/// commitment that fails the check is reported as `TargetNotMet`.
+pub fn verify_seal_matching<C: HashCheck + ?Sized>(
+    seal: &PowSeal,
+    header: &[u8],
+    check: &C,
+) -> Result<Cube, VerifyError> {
+    verify_seal_with_check(SpecVersion::CURRENT, seal, header, check)
+}
+
+fn verify_seal_with_check<C: HashCheck + ?Sized>(
+    version: SpecVersion,
+    seal: &PowSeal,
+    header: &[u8],
+    check: &C,
+) -> Result<Cube, VerifyError> {
+    let rules = Rules {
+        spec: version,
+        ..Rules::default()
+    };
+    verify_counted(&rules, seal, header, check, &mut VerifyStats::default())
+}
+
+/// Work done by one verification, for calibrating weights and verification
+/// budgets against measurements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VerifyStats {
+    /// Scramble and solution moves replayed.
+    pub moves_applied: u64,
+    /// Stickers rewritten. Every move rewrites all `6·n²` stickers.
+    pub stickers_touched: u64,
+    /// Wall-clock time of the whole verification; always zero without `std`.
+    pub ns_elapsed: u64,
+    /// The solution was recognised as undoing the scramble and not replayed.
+    pub fast_path: bool,
+}
+
+/// [`verify_seal`] that also reports what the verification cost. The stats
+/// cover the work done up to the point a seal was rejected.
+pub fn verify_seal_with_stats(
+    seal: &PowSeal,
+    header: &[u8],
+    target: &Target,
+) -> (Result<Cube, VerifyError>, VerifyStats) {
+    let mut stats = VerifyStats::default();
+    #[cfg(feature = "std")]
+    let started = std::time::Instant::now();
+
+    let result = verify_counted(&Rules::default(), seal, header, target, &mut stats);
+
+    #[cfg(feature = "std")]
+    {
+        stats.ns_elapsed = u64::try_from(started.elapsed().as_nanos()).unwrap_or(u64::MAX);
+    }
+    (result, stats)
+}
+
+fn verify_counted<C: HashCheck + ?Sized>(
+    rules: &Rules<'_>,
+    seal: &PowSeal,
+    header: &[u8],
+    check: &C,
+    stats: &mut VerifyStats,
+) -> Result<Cube, VerifyError> {
+    if seal.cube_size < MIN_SEAL_SIZE {
+        return Err(VerifyError::CubeTooSmall);
+    }
+    // What the seal bytes could not have carried under these rules
+    if !seal.moves.iter().all(|m| spec::assigns_move(rules.spec, m)) {
+        return Err(VerifyError::MalformedSeal);
+    }
+
+    let mut cube = Cube::try_new(seal.cube_size as usize)?;
+    let stickers = 6 * (cube.size() as u64).pow(2);
+    let mut count = |moves: usize| {
+        stats.moves_applied = stats.moves_applied.saturating_add(moves as u64);
+        stats.stickers_touched = stats.stickers_touched.saturating_add((moves as u64).saturating_mul(stickers));
+    };
+
+    let scramble = cube.scramble_with(rules.spec, &rules.scramble, seal.nonce, header);
+    count(scramble.len());
+    if !rules.min_quality.allows(seal, &scramble) {
+        return Err(VerifyError::SolutionTooLong);
+    }
+
+    let solves = if undoes_scramble(cube.size(), &scramble, &seal.moves) {
+        stats.fast_path = true;
+        true
+    } else {
+        count(seal.moves.len());
+        cube.verify_solution(&seal.moves)
+    };
+    if rules.double_check && !cubie_engine_agrees(&cube, &scramble, &seal.moves, solves) {
+        return Err(VerifyError::EngineDisagreement);
+    }
+    if !solves {
+        return Err(VerifyError::InvalidSolution);
+    }
+    if !check.is_met_by(&cube.state_commitment_with(rules.commitment)) {
+        return Err(VerifyError::TargetNotMet);
+    }
+    if !rules.policy.allows(seal, &scramble) {
+        return Err(VerifyError::CannedSolution);
+    }
+    Ok(cube)
+}
+
+/// True if the cubie engine scrambles a solved cube into the same state as
+/// `scrambled` and agrees on whether `solution` solves it.
+fn cubie_engine_agrees(scrambled: &Cube, scramble: &[Move], solution: &[Move], solves: bool) -> bool {
+    let mut cube = CubieCube::new(scrambled.size());
+    for m in scramble {
+        cube.apply_move(m);
+    }
+    if cube.face_ids() != scrambled.face_ids() {
+        return false;
+    }
+    for m in solution {
+        cube.apply_move(m);
+    }
+    cube.is_solved() == solves
+}
+
+/// True if `solution` provably returns the scrambled cube to a solved state,
+/// without replaying it.
+///
+/// Most solutions are the scramble undone move for move, perhaps with turns
+/// merged or split. Reading the scramble and solution as one sequence, turns
+/// of the same layers next to each other compose by adding their quarter
+/// turns, so cancelling them on a stack leaves the sequence's net effect on a
+/// solved cube. If nothing but whole-cube rotations is left, that cube is
+/// solved. Anything else is left to the full replay, so this accepts only
+/// what the replay would.
+fn undoes_scramble(size: usize, scramble: &[Move], solution: &[Move]) -> bool {
+    // (face, layers turned, quarter turns) of the moves not cancelled yet
+    let mut pending: Vec<(Face, Range<usize>, usize)> = Vec::with_capacity(scramble.len());
+    for m in scramble.iter().chain(solution) {
+        let (face, layers, turns) = (m.face(), m.layers(size), m.count() % 4);
+        match pending.last_mut() {
+            Some(top) if top.0 == face && top.1 == layers => {
+                top.2 = (top.2 + turns) % 4;
+                if top.2 == 0 {
+                    pending.pop();
+                }
+            }
+            _ if turns != 0 => pending.push((face, layers, turns)),
+            _ => {}
+        }
+    }
+    pending.iter().all(|(_, layers, _)| *layers == (0..size))
+}
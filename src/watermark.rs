@@ -0,0 +1,79 @@
+//! Miner tags carried inside a solution.
+//!
+//! Outer turns of opposite faces commute, so whenever a solution contains two such
+//! moves back to back their order is free. Each of those pairs carries one
+//! bit: 0 when the face that comes first in `Face::ALL` is turned first, 1
+//! otherwise. Reordering never changes the cube state or the move count, so a
+//! pool can tag solutions found on rented hardware without any consensus change.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{Move, MoveKind};
+
+/// Start indices of the pairs that carry a bit. A pair right after another
+/// face turn on its axis carries none: swapping it would change which moves
+/// pair up, as `[U, U, D]` and `[U, D, U]` do. Otherwise swapping a pair keeps
+/// it a pair at the same index and leaves the moves around it alone, so the
+/// slots of a tagged solution are the slots of the untagged one.
+fn slots(moves: &[Move]) -> Vec<usize> {
+    let mut slots = Vec::new();
+    let mut i = 0;
+    while i + 1 < moves.len() {
+        let (a, b) = (&moves[i], &moves[i + 1]);
+        let after_same_axis = i > 0 && {
+            let previous = &moves[i - 1];
+            previous.kind() == MoveKind::Face && (previous.face() == a.face() || previous.face() == b.face())
+        };
+        let pair = a.kind() == MoveKind::Face && b.kind() == MoveKind::Face && a.face().opposite() == b.face();
+        if pair && !after_same_axis {
+            slots.push(i);
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+    slots
+}
+
+/// Number of tag bits the solution can carry.
+pub fn tag_capacity(moves: &[Move]) -> usize {
+    slots(moves).len()
+}
+
+/// Reorders commuting pairs so that `tag` can be read back with
+/// [`extract_tag`]. Returns `None` if the solution has fewer than
+/// `8 * tag.len()` slots.
+pub fn embed_tag(moves: &[Move], tag: &[u8]) -> Option<Vec<Move>> {
+    let slots = slots(moves);
+    if slots.len() < tag.len() * 8 {
+        return None;
+    }
+
+    let mut tagged = moves.to_vec();
+    for (bit_index, &slot) in slots.iter().take(tag.len() * 8).enumerate() {
+        let bit = (tag[bit_index / 8] >> (7 - bit_index % 8)) & 1;
+        let (first, second) = (tagged[slot].face(), tagged[slot + 1].face());
+        let current = u8::from(first.index() > second.index());
+        if current != bit {
+            tagged.swap(slot, slot + 1);
+        }
+    }
+    Some(tagged)
+}
+
+/// Reads a `tag_len`-byte tag, or `None` if the solution is too short to hold one.
+pub fn extract_tag(moves: &[Move], tag_len: usize) -> Option<Vec<u8>> {
+    let slots = slots(moves);
+    if slots.len() < tag_len * 8 {
+        return None;
+    }
+
+    let mut tag = vec![0u8; tag_len];
+    for (bit_index, &slot) in slots.iter().take(tag_len * 8).enumerate() {
+        if moves[slot].face().index() > moves[slot + 1].face().index() {
+            tag[bit_index / 8] |= 1 << (7 - bit_index % 8);
+        }
+    }
+    Some(tag)
+}
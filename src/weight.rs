@@ -0,0 +1,57 @@
+//! Dispatch weight of seal submissions, ahead of submitting them.
+//!
+//! The RubikPoW pallet charges every solution call through
+//! [`estimate_verification_weight`], so a miner or pool can tell whether a
+//! submission, or a batch of them, fits a block's weight budget before
+//! sending it. Replaying a move turns `size` stickers on each of four faces,
+//! so the per-move cost grows with the cube size from its
+//! [`REFERENCE_SIZE`] value.
+
+use crate::difficulty::REFERENCE_SIZE;
+
+/// Fixed cost of checking one seal: bounds, challenge and target.
+pub const BASE_WEIGHT: u64 = 10_000;
+/// Cost of replaying one move on a [`REFERENCE_SIZE`] cube.
+pub const WEIGHT_PER_MOVE: u64 = 1_000;
+/// Storage items an accepted seal writes.
+pub const SEAL_WRITES: u64 = 2;
+
+/// Predicted weight of a submission. `ref_time` is in the runtime's
+/// reference-time units; `writes` are priced by the runtime's database
+/// weights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WeightHint {
+    pub ref_time: u64,
+    pub writes: u64,
+}
+
+impl WeightHint {
+    pub fn saturating_add(self, other: WeightHint) -> WeightHint {
+        WeightHint {
+            ref_time: self.ref_time.saturating_add(other.ref_time),
+            writes: self.writes.saturating_add(other.writes),
+        }
+    }
+
+    /// Reference time including the writes, at `write_weight` each.
+    pub fn total(self, write_weight: u64) -> u64 {
+        self.ref_time.saturating_add(self.writes.saturating_mul(write_weight))
+    }
+
+    /// True if the submission would not fit `budget`, given `write_weight`
+    /// per write.
+    pub fn exceeds(self, budget: u64, write_weight: u64) -> bool {
+        self.total(write_weight) > budget
+    }
+}
+
+/// Weight the pallet charges for a seal of `n_moves` moves on a `size` cube.
+/// Saturates rather than overflowing.
+pub fn estimate_verification_weight(size: u32, n_moves: usize) -> WeightHint {
+    let moves = u64::try_from(n_moves).unwrap_or(u64::MAX);
+    let per_move = WEIGHT_PER_MOVE.saturating_mul(u64::from(size)) / u64::from(REFERENCE_SIZE);
+    WeightHint {
+        ref_time: BASE_WEIGHT.saturating_add(per_move.saturating_mul(moves)),
+        writes: SEAL_WRITES,
+    }
+}
@@ -0,0 +1,95 @@
+use std::io::Cursor;
+
+use qbitcoin_core::archive::{ArchiveError, ArchiveReader, ArchiveRecord, ArchiveWriter, MAGIC};
+use qbitcoin_core::{Move, PowSeal, Target};
+
+fn record(height: u64) -> ArchiveRecord {
+    ArchiveRecord {
+        height,
+        header_hash: [height as u8; 32],
+        header: format!("header-{}", height).into_bytes(),
+        target: Target::from_difficulty(height as u32 + 1),
+        seal: PowSeal {
+            cube_size: 3,
+            nonce: height * 7,
+            moves: vec![Move::R(1), Move::U(3), Move::F(2)],
+        },
+    }
+}
+
+fn archive(records: &[ArchiveRecord]) -> Vec<u8> {
+    let mut writer = ArchiveWriter::new(Vec::new()).unwrap();
+    for record in records {
+        writer.append(record).unwrap();
+    }
+    writer.into_inner()
+}
+
+#[test]
+fn test_archive_round_trip() {
+    let records: Vec<ArchiveRecord> = (0..5).map(record).collect();
+    let bytes = archive(&records);
+    assert!(bytes.starts_with(MAGIC));
+
+    let read: Vec<ArchiveRecord> = ArchiveReader::new(Cursor::new(bytes))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(read, records);
+}
+
+#[test]
+fn test_archive_empty() {
+    let mut reader = ArchiveReader::new(Cursor::new(archive(&[]))).unwrap();
+    assert!(reader.next().is_none());
+    assert!(matches!(ArchiveReader::new(Cursor::new(b"QBARCH".to_vec())), Err(ArchiveError::BadMagic)));
+    assert!(matches!(ArchiveReader::new(Cursor::new(vec![0u8; 16])), Err(ArchiveError::BadMagic)));
+}
+
+#[test]
+fn test_archive_detects_corruption() {
+    let bytes = archive(&[record(1), record(2)]);
+    let mut reader = ArchiveReader::new(Cursor::new(bytes.clone())).unwrap();
+    reader.next().unwrap().unwrap();
+    let second = reader.offset();
+
+    // Flip a byte inside the second record's payload
+    let mut corrupt = bytes.clone();
+    corrupt[second as usize + 10] ^= 0x01;
+    let results: Vec<_> = ArchiveReader::new(Cursor::new(corrupt)).unwrap().collect();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].as_ref().unwrap(), &record(1));
+    assert!(matches!(results[1], Err(ArchiveError::Checksum { offset }) if offset == second));
+
+    // A torn last write
+    let torn = bytes[..bytes.len() - 3].to_vec();
+    let results: Vec<_> = ArchiveReader::new(Cursor::new(torn)).unwrap().collect();
+    assert!(matches!(results[1], Err(ArchiveError::Truncated { offset }) if offset == second));
+
+    // A length prefix beyond the limit
+    let mut huge = bytes[..second as usize].to_vec();
+    huge.extend_from_slice(&u32::MAX.to_le_bytes());
+    let results: Vec<_> = ArchiveReader::new(Cursor::new(huge)).unwrap().collect();
+    assert!(matches!(results[1], Err(ArchiveError::Malformed { .. })));
+}
+
+#[test]
+fn test_archive_file_appends() {
+    let path = std::env::temp_dir().join(format!("qbit-archive-{}.bin", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut writer = ArchiveWriter::open(&path).unwrap();
+    writer.append(&record(1)).unwrap();
+    drop(writer);
+    let mut writer = ArchiveWriter::open(&path).unwrap();
+    writer.append(&record(2)).unwrap();
+    drop(writer);
+
+    let file = std::fs::File::open(&path).unwrap();
+    let read: Vec<ArchiveRecord> = ArchiveReader::new(file).unwrap().collect::<Result<_, _>>().unwrap();
+    assert_eq!(read, [record(1), record(2)]);
+
+    std::fs::write(&path, b"not an archive").unwrap();
+    assert!(matches!(ArchiveWriter::open(&path), Err(ArchiveError::BadMagic)));
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,84 @@
+use std::io::Cursor;
+
+use qbitcoin_core::archive::{ArchiveError, ArchiveReader, ArchiveRecord, ArchiveWriter};
+use qbitcoin_core::audit::audit;
+use qbitcoin_core::miner::{attempt, BlockTemplate};
+use qbitcoin_core::solver::ReverseScramble;
+use qbitcoin_core::spec::{self, SpecVersion};
+use qbitcoin_core::{verify_seal, verify_seal_with_spec, Move, Target, VerifyError};
+
+fn record(height: u64) -> ArchiveRecord {
+    let header = format!("block-{}", height).into_bytes();
+    let template = BlockTemplate {
+        parent_hash: [0; 32],
+        header: header.clone(),
+        cube_size: 2 + (height % 3) as u32,
+        target: Target::MAX,
+    };
+    ArchiveRecord {
+        height,
+        header_hash: [height as u8; 32],
+        seal: attempt(&ReverseScramble, &template, height).unwrap(),
+        header,
+        target: Target::MAX,
+    }
+}
+
+fn archive(records: &[ArchiveRecord]) -> ArchiveReader<Cursor<Vec<u8>>> {
+    let mut writer = ArchiveWriter::new(Vec::new()).unwrap();
+    for record in records {
+        writer.append(record).unwrap();
+    }
+    ArchiveReader::new(Cursor::new(writer.into_inner())).unwrap()
+}
+
+#[test]
+fn test_spec_version_schedule() {
+    assert_eq!(spec::ACTIVATIONS[0], (0, SpecVersion::V1));
+    assert_eq!(spec::version_at(0), SpecVersion::V1);
+    assert_eq!(spec::version_at(u64::MAX), SpecVersion::CURRENT);
+}
+
+#[test]
+fn test_verify_with_current_spec_matches_verify_seal() {
+    let record = record(4);
+    let ours = verify_seal_with_spec(SpecVersion::CURRENT, &record.seal, &record.header, &record.target).unwrap();
+    let current = verify_seal(&record.seal, &record.header, &record.target).unwrap();
+    assert_eq!(ours.state_commitment(), current.state_commitment());
+}
+
+#[test]
+fn test_audit_clean_archive() {
+    let records: Vec<ArchiveRecord> = (1..=6).map(record).collect();
+    let report = audit(archive(&records), 0);
+    assert!(report.is_clean());
+    assert_eq!(report.checked, 6);
+
+    assert_eq!(audit(archive(&records), 4).checked, 3);
+}
+
+#[test]
+fn test_audit_reports_discrepancies() {
+    let mut records: Vec<ArchiveRecord> = (1..=4).map(record).collect();
+    records[1].seal.moves.push(Move::R(1));
+    records[3].target = Target::from_bytes([0; 32]);
+
+    let report = audit(archive(&records), 0);
+    assert_eq!(report.checked, 4);
+    let found: Vec<(u64, VerifyError)> = report.discrepancies.iter().map(|d| (d.height, d.error)).collect();
+    assert_eq!(found, [(2, VerifyError::InvalidSolution), (4, VerifyError::TargetNotMet)]);
+    assert!(!report.is_clean());
+}
+
+#[test]
+fn test_audit_stops_on_damage() {
+    let mut writer = ArchiveWriter::new(Vec::new()).unwrap();
+    writer.append(&record(1)).unwrap();
+    writer.append(&record(2)).unwrap();
+    let mut bytes = writer.into_inner();
+    bytes.truncate(bytes.len() - 1);
+
+    let report = audit(ArchiveReader::new(Cursor::new(bytes)).unwrap(), 0);
+    assert_eq!(report.checked, 1);
+    assert!(matches!(report.archive_error, Some(ArchiveError::Truncated { .. })));
+}
@@ -0,0 +1,45 @@
+use std::collections::HashSet;
+
+use qbitcoin_core::{challenge_commitment, challenge_seed, color_permutation, Color, ColorScheme, Cube, Target};
+
+#[test]
+fn test_color_permutation_is_deterministic_permutation() {
+    let seed = challenge_seed(12345, b"mock_block_header");
+    let colors = color_permutation(&seed);
+
+    assert_eq!(colors, color_permutation(&seed));
+    assert_eq!(colors.iter().collect::<HashSet<_>>().len(), 6);
+}
+
+#[test]
+fn test_color_permutation_varies_per_challenge() {
+    let permutations: HashSet<[Color; 6]> = (0..64)
+        .map(|nonce| color_permutation(&challenge_seed(nonce, b"mock_block_header")))
+        .collect();
+    assert!(permutations.len() > 32);
+}
+
+#[test]
+fn test_recolor_keeps_solved_state() {
+    let mut cube = Cube::new(3);
+    cube.recolor(ColorScheme::new(color_permutation(&challenge_seed(7, b"header"))).unwrap());
+    assert!(cube.is_solved());
+}
+
+#[test]
+fn test_challenge_commitment_binds_size_and_target() {
+    let seed = challenge_seed(1, b"header");
+    let target = Target::from_difficulty(1000);
+    let commitment = challenge_commitment(&seed, 3, &target);
+
+    assert_eq!(commitment, challenge_commitment(&seed, 3, &target));
+    assert_ne!(commitment, challenge_commitment(&seed, 4, &target));
+    assert_ne!(
+        commitment,
+        challenge_commitment(&seed, 3, &Target::from_difficulty(1001))
+    );
+    assert_ne!(
+        commitment,
+        challenge_commitment(&challenge_seed(2, b"header"), 3, &target)
+    );
+}
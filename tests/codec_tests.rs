@@ -0,0 +1,25 @@
+#![cfg(feature = "scale-codec")]
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use qbitcoin_core::{Axis, Face, Move};
+
+#[test]
+fn test_move_encodes_as_its_tag() {
+    assert_eq!(Move::R(1).encode(), [13]);
+    assert_eq!(Move::Wide(Face::Up, 2).encode(), [26]);
+    assert_eq!(Move::Rotation(Axis::Z, 3).encode(), [59]);
+    assert_eq!(Move::max_encoded_len(), 1);
+
+    let moves = vec![Move::U(1), Move::Wide(Face::Back, 3), Move::Rotation(Axis::X, 1)];
+    // Compact length prefix, then one byte per move
+    assert_eq!(moves.encode(), [12, 1, 47, 49]);
+    assert_eq!(Vec::<Move>::decode(&mut &moves.encode()[..]).unwrap(), moves);
+}
+
+#[test]
+fn test_move_decode_rejects_unknown_tags() {
+    for tag in [60u8, 127, 128, 254, 255] {
+        assert!(Move::decode(&mut &[tag][..]).is_err(), "tag {}", tag);
+    }
+    assert!(Move::decode(&mut &[][..]).is_err());
+}
@@ -0,0 +1,54 @@
+use qbitcoin_core::{Color, ColorScheme, Cube, Face};
+
+#[test]
+fn test_western_scheme_matches_default_colors() {
+    for face in Face::ALL {
+        assert_eq!(
+            ColorScheme::WESTERN.color_of(face),
+            Color::default_for_face(face)
+        );
+        assert_eq!(
+            ColorScheme::WESTERN.face_of(Color::default_for_face(face)),
+            face
+        );
+    }
+    assert_eq!(*Cube::new(3).scheme(), ColorScheme::default());
+}
+
+#[test]
+fn test_japanese_scheme_cube() {
+    let cube = Cube::new_with_scheme(3, ColorScheme::JAPANESE);
+    assert!(cube.is_solved());
+    assert_eq!(ColorScheme::JAPANESE.color_of(Face::Down), Color::Blue);
+    assert!(cube.to_facelets().starts_with("WWWWWWWWWBBBBBBBBBYYY"));
+}
+
+#[test]
+fn test_facelet_round_trip() {
+    let cube = Cube::new_with_scheme(2, ColorScheme::JAPANESE);
+    let facelets = cube.to_facelets();
+    assert_eq!(facelets.len(), 24);
+
+    let parsed = Cube::from_facelets(2, &facelets, ColorScheme::JAPANESE).unwrap();
+    assert_eq!(parsed.to_facelets(), facelets);
+    assert!(parsed.is_solved());
+}
+
+#[test]
+fn test_facelet_import_rejects_bad_input() {
+    let facelets = Cube::new(2).to_facelets();
+    assert!(Cube::from_facelets(3, &facelets, ColorScheme::WESTERN).is_none());
+    assert!(Cube::from_facelets(2, &facelets.replace('W', "X"), ColorScheme::WESTERN).is_none());
+    assert!(ColorScheme::new([Color::White; 6]).is_none());
+}
+
+#[test]
+fn test_recolor_updates_scheme() {
+    let mut cube = Cube::new(3);
+    cube.recolor(ColorScheme::JAPANESE);
+    assert_eq!(*cube.scheme(), ColorScheme::JAPANESE);
+    assert_eq!(
+        cube.to_facelets(),
+        Cube::new_with_scheme(3, ColorScheme::JAPANESE).to_facelets()
+    );
+}
@@ -0,0 +1,164 @@
+use qbitcoin_core::commitment::{solution_fingerprint, solution_transcript};
+use qbitcoin_core::{challenge_seed, color_permutation, ColorScheme, CommitmentVersion, Cube, Move};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[test]
+fn test_commitment_ignores_color_scheme() {
+    let western = Cube::new(3);
+    let japanese = Cube::new_with_scheme(3, ColorScheme::JAPANESE);
+    assert_eq!(western.state_commitment(), japanese.state_commitment());
+
+    let mut shuffled = Cube::new(3);
+    shuffled.recolor(ColorScheme::new(color_permutation(&challenge_seed(1, b"header"))).unwrap());
+    assert_eq!(western.state_commitment(), shuffled.state_commitment());
+    assert_eq!(
+        western.state_commitment_with(CommitmentVersion::V1FaceIds),
+        japanese.state_commitment_with(CommitmentVersion::V1FaceIds)
+    );
+}
+
+#[test]
+fn test_commitment_golden_vectors() {
+    // Changing these values is a consensus change
+    let vectors = [
+        (
+            2,
+            "f117ce93ee13b06b437f3425d8d41cba9d776a07e54ca260eeaee24927b8b510",
+        ),
+        (
+            3,
+            "416b91609107516f5255aa0790e2e31a350d5d9fa3020ce7f3a5de22284d3102",
+        ),
+        (
+            4,
+            "835d3001dae4580b285a6f5f96f0db6f97dddd548cd0d74de837f8304d18ff44",
+        ),
+    ];
+    for (size, expected) in vectors.iter() {
+        assert_eq!(
+            hex(&Cube::new(*size).state_commitment()),
+            *expected,
+            "size {}",
+            size
+        );
+    }
+}
+
+#[test]
+fn test_v1_commitment_golden_vector() {
+    assert_eq!(
+        hex(&Cube::new(3).state_commitment_with(CommitmentVersion::V1FaceIds)),
+        "6427b37ce8b3f93f4c28a10cb66adedaf66be76a24a770dce7bbcb5f2eb9a68d"
+    );
+}
+
+#[test]
+fn test_packed_golden_vector() {
+    // Version 1, size 2, then faces 0..=5 four stickers each at 3 bits per sticker
+    assert_eq!(
+        hex(&Cube::new(2).to_packed_bytes()),
+        "01020000902492b46d24d9b6"
+    );
+}
+
+#[test]
+fn test_packed_round_trip() {
+    for size in 2..=7 {
+        let cube = Cube::new_with_scheme(size, ColorScheme::JAPANESE);
+        let bytes = cube.to_packed_bytes();
+        let decoded = Cube::from_packed_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_packed_bytes(), bytes);
+        assert_eq!(decoded.state_commitment(), cube.state_commitment());
+    }
+}
+
+#[test]
+fn test_packed_rejects_malformed_input() {
+    let bytes = Cube::new(3).to_packed_bytes();
+
+    let mut wrong_version = bytes.clone();
+    wrong_version[0] = 0;
+    assert!(Cube::from_packed_bytes(&wrong_version).is_none());
+
+    assert!(Cube::from_packed_bytes(&bytes[..bytes.len() - 1]).is_none());
+
+    // 54 stickers use 162 bits, leaving 6 padding bits that must be zero
+    let mut dirty_padding = bytes.clone();
+    *dirty_padding.last_mut().unwrap() |= 0x80;
+    assert!(Cube::from_packed_bytes(&dirty_padding).is_none());
+
+    // Face index 7 does not exist
+    let mut bad_face = bytes;
+    bad_face[3] |= 0b111;
+    assert!(Cube::from_packed_bytes(&bad_face).is_none());
+}
+
+#[test]
+fn test_canonical_golden_vector() {
+    // Version 1, the Western scheme as positions in `Color::ALL`, then the packed state
+    assert_eq!(
+        hex(&Cube::new(2).to_canonical_bytes()),
+        "0100010405020301020000902492b46d24d9b6"
+    );
+}
+
+#[test]
+fn test_canonical_round_trip() {
+    for (size, scheme) in [(2, ColorScheme::WESTERN), (3, ColorScheme::JAPANESE), (6, ColorScheme::JAPANESE)] {
+        let mut cube = Cube::new_with_scheme(size, scheme);
+        cube.scramble_deterministic(7, b"canonical");
+        let bytes = cube.to_canonical_bytes();
+        let decoded = Cube::from_canonical_bytes(&bytes).unwrap();
+        assert_eq!(decoded, cube);
+        assert_eq!(decoded.scheme(), cube.scheme());
+        assert_eq!(decoded.to_facelets(), cube.to_facelets());
+        assert_eq!(decoded.to_canonical_bytes(), bytes);
+    }
+
+    // Equal states painted differently encode differently
+    let western = Cube::new(3);
+    let japanese = Cube::new_with_scheme(3, ColorScheme::JAPANESE);
+    assert_eq!(western, japanese);
+    assert_ne!(western.to_canonical_bytes(), japanese.to_canonical_bytes());
+}
+
+#[test]
+fn test_canonical_rejects_malformed_input() {
+    let bytes = Cube::new(3).to_canonical_bytes();
+
+    let mut wrong_version = bytes.clone();
+    wrong_version[0] = 0;
+    assert!(Cube::from_canonical_bytes(&wrong_version).is_none());
+
+    let mut repeated_color = bytes.clone();
+    repeated_color[2] = repeated_color[1];
+    assert!(Cube::from_canonical_bytes(&repeated_color).is_none());
+
+    let mut unknown_color = bytes.clone();
+    unknown_color[1] = 6;
+    assert!(Cube::from_canonical_bytes(&unknown_color).is_none());
+
+    assert!(Cube::from_canonical_bytes(&bytes[..bytes.len() - 1]).is_none());
+    assert!(Cube::from_canonical_bytes(&bytes[..7]).is_none());
+}
+
+#[test]
+fn test_solution_transcript_chains_states() {
+    let cube = Cube::new(3);
+    let moves = [Move::R(1), Move::U(2), Move::U(2), Move::R(3)];
+    let transcript = solution_transcript(&cube, &moves);
+    assert_eq!(transcript.len(), moves.len() + 1);
+    assert_eq!(solution_fingerprint(&cube, &moves), transcript[4]);
+
+    // Same start and end state, but the chain remembers the path
+    assert_ne!(transcript[0], transcript[4]);
+    assert_eq!(solution_transcript(&cube, &[])[0], transcript[0]);
+    let other = [Move::L(1), Move::L(3)];
+    assert_ne!(solution_fingerprint(&cube, &other), solution_fingerprint(&cube, &moves));
+
+    // Prefixes agree
+    assert_eq!(solution_transcript(&cube, &moves[..2])[..], transcript[..3]);
+}
@@ -1,4 +1,4 @@
-use qbitcoin_core::{Cube, Move, calculate_difficulty};
+use qbitcoin_core::{invert_sequence, Cube, CubeError, Move};
 
 #[test]
 fn test_cube_creation() {
@@ -6,6 +6,32 @@ fn test_cube_creation() {
     assert!(cube.is_solved());
 }
 
+#[test]
+fn test_try_new_rejects_unsupported_sizes() {
+    assert_eq!(Cube::try_new(0).unwrap_err(), CubeError::SizeTooSmall(0));
+    assert_eq!(Cube::try_new(Cube::MAX_SIZE + 1).unwrap_err(), CubeError::SizeTooLarge(Cube::MAX_SIZE + 1));
+    assert_eq!(Cube::try_new(2).unwrap().size(), 2);
+
+    // Unchecked cubes of unsupported sizes do not panic, but refuse moves
+    let mut empty = Cube::new(0);
+    assert!(empty.is_solved());
+    assert_eq!(empty.try_apply_move(&Move::U(1)), Err(CubeError::SizeTooSmall(0)));
+    assert_eq!(empty.try_verify_solution(&[]), Err(CubeError::SizeTooSmall(0)));
+}
+
+#[test]
+fn test_single_sticker_cube() {
+    let mut cube = Cube::try_new(1).unwrap();
+    assert!(cube.is_solved());
+    assert_eq!(cube.try_apply_move(&Move::R(1)), Err(CubeError::NoLegalMoves));
+    assert_eq!(cube.try_verify_solution(&[]), Ok(true));
+    assert_eq!(cube.try_verify_solution(&[Move::U(2)]), Err(CubeError::NoLegalMoves));
+
+    // Nothing to scramble
+    assert!(cube.scramble_deterministic(7, b"header").is_empty());
+    assert!(cube.is_solved());
+}
+
 #[test]
 fn test_cube_scramble_deterministic() {
     let mut cube1 = Cube::new(3);
@@ -41,9 +67,8 @@ fn test_cube_move_normalization() {
     cube2.apply_move(&Move::U(5));
 
     // Both cubes should be in the same state
-    // This test is simplified as full state comparison is complex
     assert!(!cube1.is_solved());
-    assert!(!cube2.is_solved());
+    assert_eq!(cube1, cube2);
 }
 
 #[test]
@@ -52,37 +77,22 @@ fn test_solve_verification() {
     let block_header = b"mock_block_header";
     let scramble_moves = cube.scramble_deterministic(12345, block_header);
 
-    // Create the inverse solution
-    let mut solution = scramble_moves.clone();
-    solution.reverse();
-    for move_ref in solution.iter_mut() {
-        match move_ref {
-            Move::U(count) => *move_ref = Move::U((4 - count) % 4),
-            Move::D(count) => *move_ref = Move::D((4 - count) % 4),
-            Move::L(count) => *move_ref = Move::L((4 - count) % 4),
-            Move::R(count) => *move_ref = Move::R((4 - count) % 4),
-            Move::F(count) => *move_ref = Move::F((4 - count) % 4),
-            Move::B(count) => *move_ref = Move::B((4 - count) % 4),
-        }
-    }
+    let solution = invert_sequence(&scramble_moves);
 
     assert!(cube.verify_solution(&solution));
+    // Verification works on a copy
+    assert!(!cube.is_solved());
+    for m in &solution {
+        cube.apply_move(m);
+    }
     assert!(cube.is_solved());
 }
 
-#[test]
-fn test_difficulty_calculation() {
-    assert_eq!(calculate_difficulty(1), 1);
-    assert_eq!(calculate_difficulty(2), 3674160);
-    assert_eq!(calculate_difficulty(3), 43252003274489856000);
-    assert_eq!(calculate_difficulty(4), 740119684156490186987409397449857433600000000);
-}
-
 #[test]
 fn test_meets_difficulty() {
     let cube = Cube::new(2);
     // A cube in its solved state should meet a very high target
-    assert!(cube.meets_difficulty([0xFF; 32], u32::MAX));
+    assert!(cube.meets_difficulty([0xFF; 32]));
     // A cube in its solved state should not meet a very low target (unless target is 0)
-    assert!(cube.meets_difficulty([0x00; 32], 0));
+    assert!(!cube.meets_difficulty([0x00; 32]));
 }
\ No newline at end of file
@@ -0,0 +1,74 @@
+use qbitcoin_core::cubie::CubieCube;
+use qbitcoin_core::{invert_sequence, verify_seal_with_rules, Axis, Cube, Face, Move, PowSeal, Rules, Target, VerifyError};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+fn random_move(rng: &mut ChaCha8Rng) -> Move {
+    let count = rng.gen_range(0..6);
+    let face = Face::ALL[rng.gen_range(0..6)];
+    let axis = Axis::ALL[rng.gen_range(0..3)];
+    match rng.gen_range(0..8) {
+        0 => Move::Wide(face, count),
+        1 => Move::Rotation(axis, count),
+        2 => Move::Slice(axis, count),
+        3 => Move::Layer(face, rng.gen_range(0..7), count),
+        4 => Move::Block(face, rng.gen_range(0..7), count),
+        _ => Move::from_face_and_count(face, count),
+    }
+}
+
+#[test]
+fn test_engines_agree_on_random_sequences() {
+    let mut rng = ChaCha8Rng::seed_from_u64(5);
+    for size in 1..=5 {
+        for _ in 0..40 {
+            let mut stickers = Cube::new(size);
+            let mut cubies = CubieCube::new(size);
+            let moves: Vec<Move> = (0..rng.gen_range(0..12)).map(|_| random_move(&mut rng)).collect();
+            for m in &moves {
+                stickers.apply_move(m);
+                cubies.apply_move(m);
+                assert_eq!(cubies.face_ids(), stickers.face_ids(), "size {} after {:?}", size, moves);
+            }
+            assert_eq!(cubies.is_solved(), stickers.is_solved(), "size {} after {:?}", size, moves);
+        }
+    }
+}
+
+#[test]
+fn test_cubie_cube_solved_states() {
+    let mut cube = CubieCube::new(3);
+    assert!(cube.is_solved());
+    cube.apply_move(&Move::Rotation(Axis::Y, 1));
+    assert!(cube.is_solved());
+    cube.apply_move(&Move::R(1));
+    assert!(!cube.is_solved());
+    cube.apply_move(&Move::R(3));
+    cube.apply_move(&Move::Rotation(Axis::Y, 3));
+    assert_eq!(cube, CubieCube::new(3));
+}
+
+#[test]
+fn test_double_check_reaches_the_same_verdicts() {
+    let rules = Rules {
+        double_check: true,
+        ..Rules::default()
+    };
+    for size in [2, 3, 4] {
+        let mut cube = Cube::new(size as usize);
+        let scramble = cube.scramble_deterministic(9, b"header");
+        let mut moves = invert_sequence(&scramble);
+        // Ends rotated; the cubie engine replays it even though the fast path accepts it
+        moves.push(Move::Rotation(Axis::X, 1));
+        let seal = PowSeal { cube_size: size, nonce: 9, moves };
+        assert!(verify_seal_with_rules(&rules, &seal, b"header", &Target::MAX).is_ok());
+
+        let mut wrong = seal.clone();
+        wrong.moves.insert(0, Move::U(1));
+        assert_eq!(
+            verify_seal_with_rules(&rules, &wrong, b"header", &Target::MAX).unwrap_err(),
+            VerifyError::InvalidSolution
+        );
+    }
+    assert_eq!(VerifyError::from_code(8), Some(VerifyError::EngineDisagreement));
+}
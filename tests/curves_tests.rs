@@ -0,0 +1,149 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use qbitcoin_core::curves::{apply, compound, ratio, Curve, ONE};
+use qbitcoin_core::miner::{BlockTemplate, ProfitEstimator};
+use qbitcoin_core::Target;
+
+const SAMPLES: usize = 2000;
+
+/// Random curve with increasing x and values in `0..=2000` per-mille.
+fn random_curve(rng: &mut ChaCha8Rng) -> Curve<4> {
+    let mut x = rng.gen_range(0..1000);
+    let points = std::array::from_fn(|_| {
+        x += rng.gen_range(1..1000);
+        (x, rng.gen_range(0..=2 * ONE))
+    });
+    Curve::new(points)
+}
+
+#[test]
+fn test_apply_and_ratio() {
+    assert_eq!(apply(12_345, 500), 6_172);
+    assert_eq!(apply(u128::MAX, ONE), u128::MAX);
+    assert_eq!(apply(u128::MAX, 2 * ONE), u128::MAX);
+    assert_eq!(ratio(1, 3), Some(333));
+    assert_eq!(ratio(5, 2), Some(2_500));
+    assert_eq!(ratio(u128::MAX, 1), Some(u32::MAX));
+    assert_eq!(ratio(u128::MAX - 1, u128::MAX), Some(999));
+    assert_eq!(ratio(1, 0), None);
+}
+
+#[test]
+fn test_apply_is_monotone_and_bounded() {
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    for _ in 0..SAMPLES {
+        let amount: u128 = rng.gen::<u128>() >> rng.gen_range(0..128);
+        let per_mille = rng.gen_range(0..=3 * ONE);
+        let scaled = apply(amount, per_mille);
+        assert!(apply(amount, per_mille + 1) >= scaled);
+        assert!(apply(amount.saturating_add(1), per_mille) >= scaled);
+        if per_mille <= ONE {
+            assert!(scaled <= amount);
+        }
+    }
+}
+
+#[test]
+fn test_compound_decays_monotonically() {
+    assert_eq!(compound(500, 1), 500);
+    assert_eq!(compound(500, 2), 250);
+    assert_eq!(compound(ONE, u32::MAX), ONE);
+    assert_eq!(compound(999, u32::MAX), 0);
+    for retained in (0..=ONE).step_by(37) {
+        let mut previous = ONE;
+        for steps in 0..50 {
+            let value = compound(retained, steps);
+            assert!(value <= previous, "retained {} steps {}", retained, steps);
+            assert!(compound(retained + 1, steps) >= value);
+            previous = value;
+        }
+    }
+}
+
+#[test]
+fn test_curve_stays_between_neighbouring_points() {
+    let mut rng = ChaCha8Rng::seed_from_u64(2);
+    for _ in 0..SAMPLES {
+        let curve = random_curve(&mut rng);
+        let points = curve.points();
+        for &(x, y) in points {
+            assert_eq!(curve.eval(x), y);
+        }
+        assert_eq!(curve.eval(0), points[0].1);
+        assert_eq!(curve.eval(u32::MAX), points[3].1);
+
+        let x = rng.gen_range(points[0].0..=points[3].0);
+        let segment = points.windows(2).find(|pair| x <= pair[1].0).unwrap();
+        let (low, high) = (segment[0].1.min(segment[1].1), segment[0].1.max(segment[1].1));
+        assert!((low..=high).contains(&curve.eval(x)));
+    }
+}
+
+#[test]
+fn test_monotone_curves_evaluate_monotonically() {
+    let mut rng = ChaCha8Rng::seed_from_u64(3);
+    let (mut increasing, mut decreasing) = (0, 0);
+    while increasing < 100 || decreasing < 100 {
+        let curve = random_curve(&mut rng);
+        let sign = if curve.is_non_decreasing() {
+            increasing += 1;
+            1
+        } else if curve.is_non_increasing() {
+            decreasing += 1;
+            -1
+        } else {
+            continue;
+        };
+        let mut x = 0;
+        let mut previous = curve.eval(0);
+        while x < curve.points()[3].0 + 10 {
+            x += rng.gen_range(1..50);
+            let value = curve.eval(x);
+            assert!((i64::from(value) - i64::from(previous)) * sign >= 0);
+            previous = value;
+        }
+    }
+}
+
+#[test]
+fn test_invalid_curves() {
+    assert!(Curve::try_new([(1, 0), (1, 5)]).is_none());
+    assert!(Curve::try_new([(2, 0), (1, 5)]).is_none());
+    assert!(Curve::<0>::try_new([]).is_none());
+    assert!(Curve::try_new([(7, 100)]).is_some());
+}
+
+fn template(cube_size: u32, difficulty: u32) -> BlockTemplate {
+    BlockTemplate {
+        parent_hash: [0; 32],
+        header: Vec::new(),
+        cube_size,
+        target: Target::from_difficulty(difficulty),
+    }
+}
+
+#[test]
+fn test_profit_estimator_prefers_best_paying_template() {
+    // The solver finishes every attempt up to 3x3x3, a tenth of them on 5x5x5
+    let estimator = ProfitEstimator {
+        base_reward: 1_000,
+        reference_rate: 9_000,
+        solve_rate: Curve::new([(3, ONE), (5, 100)]),
+        cost_per_day: 50_000,
+    };
+    assert_eq!(estimator.attempts_per_second(3), 9_000);
+    assert_eq!(estimator.attempts_per_second(2), 20_250);
+    // 9000 · 9/25 attempts, a tenth of them finished
+    assert_eq!(estimator.attempts_per_second(5), 324);
+
+    let easy = template(3, 1_000);
+    // 1000 per seal, 9000 attempts a second, one seal in 1000
+    assert_eq!(estimator.reward_per_day(&easy), 777_600_000);
+    assert_eq!(estimator.profit_per_day(&easy), 777_550_000);
+
+    let templates = [template(5, 1_000), easy.clone(), template(3, 100_000_000)];
+    assert_eq!(estimator.best(&templates), Some(&easy));
+    assert_eq!(estimator.best(&templates[2..]), None);
+    assert_eq!(estimator.cost_ratio(&templates[2]), Some(6_430));
+}
@@ -0,0 +1,134 @@
+use qbitcoin_core::difficulty::{
+    efficiency_reward, emission, expected_hashes, expected_reward_per_day, expected_time_ms, scale_reward, size_weight,
+    work_per_seal, EFFICIENCY_CURVE, HALVING_INTERVAL, REFERENCE_SIZE,
+};
+use qbitcoin_core::miner::BlockTemplate;
+use qbitcoin_core::Target;
+
+#[test]
+fn test_expected_hashes_inverts_from_difficulty() {
+    for difficulty in [1, 2, 3, 7, 64, 1000, 65_536, 999_983, u32::MAX] {
+        let target = Target::from_difficulty(difficulty);
+        assert_eq!(expected_hashes(&target), u128::from(difficulty), "difficulty {}", difficulty);
+    }
+    assert_eq!(expected_hashes(&Target::MAX), 1);
+}
+
+#[test]
+fn test_expected_hashes_matches_work_log2() {
+    let mut bytes = [0u8; 32];
+    bytes[3] = 0x40;
+    let target = Target::from_bytes(bytes);
+    let hashes = expected_hashes(&target) as f64;
+    assert!((hashes.log2() - target.work_log2()).abs() < 1e-6);
+}
+
+#[test]
+fn test_expected_hashes_saturates_below_2_pow_128() {
+    let mut bytes = [0xFF; 32];
+    bytes[..16].fill(0);
+    assert_eq!(expected_hashes(&Target::from_bytes(bytes)), u128::MAX);
+    assert_eq!(expected_hashes(&Target::from_bytes([0; 32])), u128::MAX);
+}
+
+#[test]
+fn test_work_per_seal_scales_with_size_and_difficulty() {
+    let target = Target::from_difficulty(1000);
+    for size in 2..=16 {
+        assert_eq!(work_per_seal(size, &target), 1000 * size_weight(size));
+    }
+    assert_eq!(
+        work_per_seal(3, &Target::from_difficulty(2000)),
+        2 * work_per_seal(3, &target)
+    );
+    assert_eq!(work_per_seal(16, &Target::from_bytes([0; 32])), u128::MAX);
+}
+
+#[test]
+fn test_scale_reward_follows_size_not_difficulty() {
+    for difficulty in [1, 50, 1000, u32::MAX] {
+        let target = Target::from_difficulty(difficulty);
+        assert_eq!(scale_reward(3000, REFERENCE_SIZE, &target), 3000);
+        for size in 2..=16 {
+            let expected = 3000 * size_weight(size) / size_weight(REFERENCE_SIZE);
+            assert_eq!(u128::from(scale_reward(3000, size, &target)), expected);
+        }
+    }
+}
+
+#[test]
+fn test_scale_reward_matches_work_ratio() {
+    let target = Target::from_difficulty(12_345);
+    for size in 2..=16 {
+        let ratio = work_per_seal(size, &target) * 1000 / work_per_seal(REFERENCE_SIZE, &target);
+        assert_eq!(u128::from(scale_reward(1000, size, &target)), ratio);
+    }
+}
+
+#[test]
+fn test_scale_reward_saturates() {
+    assert_eq!(scale_reward(u32::MAX, 16, &Target::MAX), u32::MAX);
+    assert_eq!(scale_reward(u32::MAX, 16, &Target::from_bytes([0; 32])), u32::MAX);
+}
+
+#[test]
+fn test_block_template_work_and_reward() {
+    let template = BlockTemplate {
+        parent_hash: [0; 32],
+        header: b"header".to_vec(),
+        cube_size: 4,
+        target: Target::from_difficulty(10),
+    };
+    assert_eq!(template.work(), work_per_seal(4, &template.target));
+    assert_eq!(template.work(), 160);
+    assert_eq!(template.reward(900), scale_reward(900, 4, &template.target));
+    assert_eq!(template.reward(900), 1600);
+}
+
+#[test]
+fn test_expected_time_ms() {
+    let target = Target::from_difficulty(300);
+    assert_eq!(expected_time_ms(&target, 50), Some(6_000));
+    assert_eq!(expected_time_ms(&target, 600), Some(500));
+    assert_eq!(expected_time_ms(&target, 0), None);
+    assert_eq!(expected_time_ms(&Target::from_bytes([0; 32]), 1), Some(u64::MAX));
+}
+
+#[test]
+fn test_expected_reward_per_day() {
+    // One block every 6 s is 14,400 blocks a day
+    let target = Target::from_difficulty(300);
+    assert_eq!(expected_reward_per_day(3000, REFERENCE_SIZE, &target, 50), 14_400 * 3000);
+    assert_eq!(expected_reward_per_day(3000, 6, &target, 50), 14_400 * 12_000);
+    assert_eq!(expected_reward_per_day(3000, 3, &target, 0), 0);
+}
+
+#[test]
+fn test_efficiency_reward_pays_shorter_solutions_more() {
+    assert!(EFFICIENCY_CURVE.is_non_increasing());
+    assert_eq!(efficiency_reward(3000, 30, 30), 3000);
+    assert_eq!(efficiency_reward(3000, 15, 30), 3750);
+    assert_eq!(efficiency_reward(3000, 45, 30), 2250);
+    // Flat beyond the curve's ends
+    assert_eq!(efficiency_reward(3000, 1, 30), 3750);
+    assert_eq!(efficiency_reward(3000, 300, 30), 2250);
+
+    let rewards: Vec<u32> = (1..=60).map(|length| efficiency_reward(3000, length, 30)).collect();
+    assert!(rewards.windows(2).all(|pair| pair[0] >= pair[1]));
+    assert!(rewards[20] > rewards[30]);
+
+    assert_eq!(efficiency_reward(3000, 20, 0), 3000);
+    assert_eq!(efficiency_reward(u32::MAX, 1, 30), u32::MAX);
+}
+
+#[test]
+fn test_emission_halves_every_interval() {
+    assert_eq!(emission(3000, 0), 3000);
+    assert_eq!(emission(3000, HALVING_INTERVAL - 1), 3000);
+    assert_eq!(emission(3000, HALVING_INTERVAL), 1500);
+    assert_eq!(emission(3000, 2 * HALVING_INTERVAL), 750);
+    assert_eq!(emission(3000, 3 * HALVING_INTERVAL), 375);
+    // Per-mille steps run out after ten halvings
+    assert_eq!(emission(3000, u32::MAX), 0);
+    assert_eq!(emission(u32::MAX, 0), u32::MAX);
+}
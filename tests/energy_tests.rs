@@ -0,0 +1,52 @@
+use std::fs;
+use std::path::Path;
+
+use qbitcoin_core::miner::{EnergyMeter, EnergyStats};
+
+fn write_zone(root: &Path, name: &str, energy: u64, range: u64) {
+    let zone = root.join(name);
+    fs::create_dir_all(&zone).unwrap();
+    fs::write(zone.join("energy_uj"), format!("{}\n", energy)).unwrap();
+    fs::write(zone.join("max_energy_range_uj"), format!("{}\n", range)).unwrap();
+}
+
+#[test]
+fn test_meter_sums_packages_and_handles_wraparound() {
+    let root = std::env::temp_dir().join(format!("qbit-powercap-{}", std::process::id()));
+    write_zone(&root, "intel-rapl:0", 1_000_000, 10_000_000);
+    write_zone(&root, "intel-rapl:1", 9_500_000, 10_000_000);
+    // Sub-zone of package 0, already included in its counter
+    write_zone(&root, "intel-rapl:0:0", 123, 10_000_000);
+
+    let meter = EnergyMeter::open(&root).unwrap();
+    let start = meter.read().unwrap();
+
+    write_zone(&root, "intel-rapl:0", 3_000_000, 10_000_000);
+    write_zone(&root, "intel-rapl:1", 500_000, 10_000_000);
+    let end = meter.read().unwrap();
+
+    // 2 J on package 0, 1 J across the wrap on package 1
+    assert!((meter.joules_between(&start, &end) - 3.0).abs() < 1e-9);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_missing_powercap_has_no_meter() {
+    assert!(EnergyMeter::open(Path::new("/nonexistent/powercap")).is_none());
+}
+
+#[test]
+fn test_stats() {
+    let mut stats = EnergyStats::default();
+    assert_eq!(stats.joules_per_solution(), None);
+    stats.record_accepted(10.0);
+    stats.record_accepted(20.0);
+    assert_eq!(stats.joules_per_solution(), Some(15.0));
+
+    let mut out = Vec::new();
+    stats.write_metrics(&mut out).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.contains("qbit_miner_accepted_solutions_total 2"));
+    assert!(text.contains("qbit_miner_joules_per_solution 15"));
+}
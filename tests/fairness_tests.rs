@@ -0,0 +1,30 @@
+use qbitcoin_core::fairness::{challenge_suite, expected_shares, sampled_shares, Bucket, SHARE_TOLERANCE};
+
+#[test]
+fn fairness() {
+    let expected = expected_shares(2).unwrap();
+    let sampled = sampled_shares(2, 3000).unwrap();
+    for bucket in Bucket::ALL {
+        let (e, s) = (expected[bucket.index()], sampled[bucket.index()]);
+        assert!((e - s).abs() <= SHARE_TOLERANCE, "{:?}: expected {:.3}, sampled {:.3}", bucket, e, s);
+    }
+}
+
+#[test]
+fn test_suite_is_pinned() {
+    let suite = challenge_suite(2, 3).unwrap();
+    let nonces = |bucket| suite.bucket(bucket).map(|entry| entry.nonce).collect::<Vec<_>>();
+    // Changing these means the scramble generator changed
+    assert_eq!(nonces(Bucket::Easy), [3, 4, 6]);
+    assert_eq!(nonces(Bucket::Medium), [1, 2, 5]);
+    assert_eq!(nonces(Bucket::Hard), [0, 23, 33]);
+    assert!(suite.bucket(Bucket::Easy).all(|entry| entry.optimal_length <= 8));
+    assert!(suite.bucket(Bucket::Hard).all(|entry| entry.optimal_length >= 10));
+}
+
+#[test]
+fn test_unsupported_sizes() {
+    assert!(challenge_suite(3, 1).is_none());
+    assert!(expected_shares(3).is_none());
+    assert_eq!(Bucket::of_length(2, 11), Some(Bucket::Hard));
+}
@@ -0,0 +1,251 @@
+use std::collections::HashSet;
+
+use qbitcoin_core::group::{
+    as_cycles, corner_twist_sum, edge_flip_sum, element_order, format_cycles, permutation_parity,
+    sequences_equivalent, Parity, Permutation, StickerId,
+};
+use qbitcoin_core::{invert_sequence, Axis, ColorScheme, Cube, Face, Move};
+
+#[test]
+fn test_inverse_pair_is_identity() {
+    assert!(Permutation::of_sequence(&[Move::R(1), Move::R(3)], 3).is_identity());
+    assert!(sequences_equivalent(&[Move::F(2), Move::F(2)], &[], 3));
+}
+
+#[test]
+fn test_count_normalization_is_equivalent() {
+    assert!(sequences_equivalent(&[Move::U(1)], &[Move::U(5)], 3));
+    assert!(sequences_equivalent(&[Move::U(1)], &[Move::U(5)], 4));
+}
+
+#[test]
+fn test_cube_equality() {
+    let mut a = Cube::new(3);
+    let mut b = Cube::new(3);
+    a.apply_move(&Move::U(1));
+    b.apply_move(&Move::U(5));
+    assert_eq!(a, b);
+    b.apply_move(&Move::R(1));
+    assert_ne!(a, b);
+    assert_ne!(Cube::new(2), Cube::new(3));
+
+    // Schemes only paint the state
+    assert_eq!(Cube::new_with_scheme(3, ColorScheme::JAPANESE), Cube::new(3));
+    let facelets = Cube::new(3).to_facelets();
+    assert_ne!(Cube::from_facelets(3, &facelets, ColorScheme::JAPANESE).unwrap(), Cube::new(3));
+
+    let mut seen = HashSet::new();
+    assert!(seen.insert(a));
+    assert!(seen.insert(b.clone()));
+    b.apply_move(&Move::R(3));
+    assert!(!seen.insert(b));
+}
+
+#[test]
+fn test_opposite_faces_commute() {
+    let a = [Move::U(1), Move::D(2)];
+    let b = [Move::D(2), Move::U(1)];
+    assert!(sequences_equivalent(&a, &b, 3));
+    assert!(!sequences_equivalent(&[Move::U(1)], &[Move::D(1)], 3));
+    assert!(!sequences_equivalent(&[Move::U(1)], &[Move::U(3)], 2));
+}
+
+#[test]
+fn test_permutation_composition() {
+    let r = Permutation::of_move(&Move::R(1), 3);
+    let r2 = Permutation::of_move(&Move::R(2), 3);
+    assert_eq!(r.then(&r), r2);
+    assert_eq!(Permutation::identity(3).then(&r), r);
+}
+
+#[test]
+fn test_layer_turns_interact() {
+    // (R U R' U') has order 6; only holds if turns move the adjacent layers
+    let sexy = [Move::R(1), Move::U(1), Move::R(3), Move::U(3)];
+    assert!(!Permutation::of_sequence(&sexy, 3).is_identity());
+    assert!(Permutation::of_sequence(&sexy.repeat(6), 3).is_identity());
+    assert!(!Permutation::of_sequence(&sexy.repeat(3), 3).is_identity());
+}
+
+#[test]
+fn test_wide_moves_and_rotations() {
+    let x = [Move::Rotation(Axis::X, 1)];
+    assert!(sequences_equivalent(&x, &[Move::Wide(Face::Right, 1), Move::L(3)], 3));
+    assert!(sequences_equivalent(&x, &[Move::Wide(Face::Left, 3)], 2));
+    assert!(sequences_equivalent(&[Move::Rotation(Axis::Y, 1)], &[Move::Wide(Face::Up, 1), Move::Wide(Face::Down, 3)], 4));
+    assert!(!sequences_equivalent(&[Move::Wide(Face::Up, 1)], &[Move::U(1)], 3));
+
+    let mut cube = Cube::new(3);
+    cube.apply_move(&Move::Rotation(Axis::Z, 1));
+    assert!(cube.is_solved());
+    cube.apply_move(&Move::Wide(Face::Front, 1));
+    assert!(!cube.is_solved());
+}
+
+#[test]
+fn test_inverse_undoes_moves() {
+    assert_eq!(Move::R(1).inverse(), Move::R(3));
+    assert_eq!(Move::U(2).inverse(), Move::U(2));
+    assert_eq!(Move::F(5).inverse(), Move::F(3));
+    assert_eq!(Move::Wide(Face::Back, 1).inverse(), Move::Wide(Face::Back, 3));
+    assert_eq!(Move::Rotation(Axis::Y, 3).inverse(), Move::Rotation(Axis::Y, 1));
+
+    let moves = [
+        Move::R(1),
+        Move::Wide(Face::Up, 2),
+        Move::Rotation(Axis::X, 1),
+        Move::F(3),
+        Move::Wide(Face::Left, 1),
+        Move::D(6),
+    ];
+    let undo = invert_sequence(&moves);
+    assert_eq!(undo.len(), moves.len());
+    for size in 2..=5 {
+        let all: Vec<Move> = moves.iter().chain(&undo).copied().collect();
+        assert!(sequences_equivalent(&all, &[], size));
+    }
+    assert!(invert_sequence(&[]).is_empty());
+}
+
+#[test]
+fn test_slice_moves() {
+    // M turns like L, E like D and S like F
+    let cases = [
+        (Move::Slice(Axis::X, 1), [Move::R(1), Move::L(3), Move::Rotation(Axis::X, 3)]),
+        (Move::Slice(Axis::Y, 1), [Move::U(1), Move::D(3), Move::Rotation(Axis::Y, 3)]),
+        (Move::Slice(Axis::Z, 1), [Move::F(3), Move::B(1), Move::Rotation(Axis::Z, 1)]),
+    ];
+    for (slice, outer) in cases {
+        for size in 3..=5 {
+            assert!(sequences_equivalent(&[slice], &outer, size), "{} on {}", slice, size);
+        }
+        assert!(sequences_equivalent(&[slice], &[], 2));
+        assert_eq!(element_order(&[slice], 3), 4);
+    }
+    assert!(sequences_equivalent(&[Move::Slice(Axis::X, 2)], &[Move::Slice(Axis::X, 3); 2], 4));
+}
+
+#[test]
+fn test_layer_indexed_moves() {
+    for face in Face::ALL {
+        let r = |count| Move::from_face_and_count(face, count);
+        assert!(sequences_equivalent(&[Move::Layer(face, 1, 1)], &[r(1)], 4));
+        assert!(sequences_equivalent(&[Move::Block(face, 1, 1)], &[r(1)], 4));
+        assert!(sequences_equivalent(&[Move::Block(face, 2, 3)], &[Move::Wide(face, 3)], 4));
+        // The far layer alone is the opposite face turned the other way
+        let opposite = Move::from_face_and_count(face.opposite(), 3);
+        assert!(sequences_equivalent(&[Move::Layer(face, 5, 1)], &[opposite], 5));
+
+        // Blocks are their layers turned together
+        let layers: Vec<Move> = (1..=3).map(|layer| Move::Layer(face, layer, 2)).collect();
+        assert!(sequences_equivalent(&[Move::Block(face, 3, 2)], &layers, 5));
+        assert!(!sequences_equivalent(&[Move::Layer(face, 2, 1)], &[r(1)], 5));
+
+        // Past the cube: a block is the whole cube, a layer nothing
+        assert!(sequences_equivalent(&[Move::Block(face, 9, 1)], &[Move::Block(face, 4, 1)], 4));
+        assert!(sequences_equivalent(&[Move::Layer(face, 5, 1)], &[], 4));
+        assert!(sequences_equivalent(&[Move::Layer(face, 0, 1)], &[], 4));
+    }
+    assert!(sequences_equivalent(&[Move::Layer(Face::Right, 2, 1)], &[Move::Slice(Axis::X, 3)], 3));
+
+    let mut cube = Cube::new(6);
+    cube.apply_move(&Move::Block(Face::Up, 6, 1));
+    assert!(cube.is_solved());
+    cube.apply_move(&Move::Layer(Face::Up, 4, 1));
+    assert!(!cube.is_solved());
+}
+
+#[test]
+fn test_element_order() {
+    assert_eq!(element_order(&[], 3), 1);
+    assert_eq!(element_order(&[Move::R(1)], 3), 4);
+    assert_eq!(element_order(&[Move::R(2)], 3), 2);
+    assert_eq!(element_order(&[Move::R(1), Move::U(1)], 3), 105);
+    assert_eq!(element_order(&[Move::R(1), Move::U(1), Move::R(3), Move::U(3)], 3), 6);
+}
+
+#[test]
+fn test_invariants_hold_for_scrambles() {
+    for size in 2..=5 {
+        for nonce in 0..10 {
+            let mut cube = Cube::new(size);
+            cube.scramble_deterministic(nonce, b"invariants");
+            assert_eq!(corner_twist_sum(&cube), Some(0), "size {} nonce {}", size, nonce);
+            assert_eq!(edge_flip_sum(&cube), Some(0), "size {} nonce {}", size, nonce);
+            assert!(permutation_parity(&cube).is_some());
+        }
+    }
+}
+
+#[test]
+fn test_quarter_turn_is_odd() {
+    let mut cube = Cube::new(3);
+    assert_eq!(permutation_parity(&cube), Some(Parity::Even));
+    cube.apply_move(&Move::F(1));
+    assert_eq!(permutation_parity(&cube), Some(Parity::Odd));
+    cube.apply_move(&Move::U(1));
+    assert_eq!(permutation_parity(&cube), Some(Parity::Even));
+}
+
+#[test]
+fn test_invariants_detect_tampering() {
+    let solved = Cube::new(3);
+    let mut facelets: Vec<char> = solved.to_facelets().chars().collect();
+
+    // Swap two stickers of the up-front-right corner (U row 2 col 2 and F row 0 col 2)
+    let up = 8;
+    let front = 4 * 9 + 2;
+    facelets.swap(up, front);
+    let twisted: String = facelets.iter().collect();
+    let twisted = Cube::from_facelets(3, &twisted, ColorScheme::WESTERN).unwrap();
+    assert_ne!(corner_twist_sum(&twisted), Some(0));
+
+    // Two stickers of the same colour make a corner appear twice
+    let mut facelets: Vec<char> = solved.to_facelets().chars().collect();
+    facelets[8] = facelets[9 + 8];
+    let broken: String = facelets.iter().collect();
+    let broken = Cube::from_facelets(3, &broken, ColorScheme::WESTERN).unwrap();
+    assert_eq!(corner_twist_sum(&broken), None);
+}
+
+#[test]
+fn test_sequence_order() {
+    assert_eq!(Move::sequence_order(&[Move::R(1), Move::U(1)], 3), 105);
+    assert_eq!(Move::sequence_order(&[Move::Rotation(Axis::X, 1)], 3), 4);
+
+    // Repeating by hand agrees, including on cubes with interchangeable centres
+    for (moves, size) in [(vec![Move::R(1), Move::U(1)], 3), (vec![Move::Wide(Face::Right, 1), Move::U(1)], 4)] {
+        let order = Move::sequence_order(&moves, size);
+        assert_eq!(element_order(&moves, size) % order, 0);
+
+        let solved = Cube::new(size);
+        let mut cube = solved.clone();
+        for repetition in 1..=order {
+            for m in &moves {
+                cube.apply_move(m);
+            }
+            assert_eq!(cube.to_facelets() == solved.to_facelets(), repetition == order);
+        }
+    }
+}
+
+#[test]
+fn test_cycle_notation() {
+    let cycles = as_cycles(&[Move::U(1)], 3);
+    assert_eq!(cycles.iter().map(Vec::len).collect::<Vec<_>>(), [4; 5]);
+    let corner = |row, col| StickerId { face: Face::Up, row, col };
+    assert_eq!(cycles[0], [corner(0, 0), corner(0, 2), corner(2, 2), corner(2, 0)]);
+    assert!(format_cycles(&cycles).starts_with("(U[0,0] U[0,2] U[2,2] U[2,0])(U[0,1] "));
+
+    // Each sticker moves to the next one in its cycle
+    let moves = [Move::R(1), Move::U(1), Move::R(3), Move::U(3)];
+    let permutation = Permutation::of_sequence(&moves, 4);
+    for cycle in as_cycles(&moves, 4) {
+        for (i, sticker) in cycle.iter().enumerate() {
+            let next = cycle[(i + 1) % cycle.len()];
+            assert_eq!(StickerId::from_index(sticker.index(4), 4), *sticker);
+            assert_eq!(permutation.destinations()[sticker.index(4)], next.index(4));
+        }
+    }
+    assert_eq!(format_cycles(&as_cycles(&[Move::F(2), Move::F(2)], 3)), "()");
+}
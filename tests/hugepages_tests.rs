@@ -0,0 +1,43 @@
+use qbitcoin_core::hugepages::{self, Backing, HugePages, Table};
+
+const LARGE: usize = 3 << 20;
+
+#[test]
+fn test_heap_table() {
+    let mut table = Table::with_policy(LARGE, 7u8, HugePages::Off);
+    assert_eq!(table.backing(), Backing::Heap);
+    assert_eq!(table.len(), LARGE);
+    assert!(table.iter().all(|&value| value == 7));
+    table[LARGE - 1] = 1;
+    assert_eq!(table[LARGE - 1], 1);
+}
+
+#[test]
+fn test_huge_page_tables_fall_back_and_hold_their_values() {
+    for policy in [HugePages::Transparent, HugePages::Reserved] {
+        let mut table = Table::with_policy(LARGE, 0xABCDu16, policy);
+        if !cfg!(all(feature = "hugepages", target_os = "linux")) {
+            assert_eq!(table.backing(), Backing::Heap);
+        }
+        assert_eq!(table.len(), LARGE);
+        assert!(table.iter().all(|&value| value == 0xABCD));
+        table[0] = 1;
+        table[LARGE - 1] = 2;
+        assert_eq!((table[0], table[LARGE - 1]), (1, 2));
+    }
+}
+
+#[test]
+fn test_small_tables_stay_on_the_heap() {
+    let table = Table::with_policy(1024, 0u32, HugePages::Reserved);
+    assert_eq!(table.backing(), Backing::Heap);
+}
+
+#[test]
+fn test_policy() {
+    assert_eq!(hugepages::policy(), HugePages::Off);
+    hugepages::set_policy(HugePages::Transparent);
+    assert_eq!(hugepages::policy(), HugePages::Transparent);
+    assert_eq!(Table::filled(4, 0u8).len(), 4);
+    hugepages::set_policy(HugePages::Off);
+}
@@ -0,0 +1,189 @@
+use qbitcoin_core::import::{verify_block_seals, ImportState};
+use qbitcoin_core::miner::{attempt, BlockTemplate};
+use qbitcoin_core::policy::{Blacklist, SolutionPolicy};
+use qbitcoin_core::solver::ReverseScramble;
+use qbitcoin_core::{
+    verify_seal, verify_seal_with_versions, CommitmentVersion, Move, PowSeal, SpecVersion, Target, VerifyError,
+};
+
+/// Header is the number of accepted seals, as in the pallet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MockState {
+    mined: u32,
+    last_nonce: u64,
+}
+
+impl ImportState for MockState {
+    fn header(&self) -> Vec<u8> {
+        self.mined.to_le_bytes().to_vec()
+    }
+
+    fn target(&self) -> Target {
+        Target::MAX
+    }
+
+    fn check_policy(&self, seal: &PowSeal) -> Result<(), VerifyError> {
+        if seal.nonce <= self.last_nonce {
+            return Err(VerifyError::StaleChallenge);
+        }
+        Ok(())
+    }
+
+    fn accept(&mut self, seal: &PowSeal) {
+        self.mined += 1;
+        self.last_nonce = seal.nonce;
+    }
+}
+
+/// What dispatching the seals one by one does.
+fn sequential(state: &mut MockState, seals: &[PowSeal]) -> Vec<Result<(), VerifyError>> {
+    seals
+        .iter()
+        .map(|seal| {
+            state.check_policy(seal)?;
+            verify_seal(seal, &state.header(), &state.target())?;
+            state.accept(seal);
+            Ok(())
+        })
+        .collect()
+}
+
+fn seal_for(mined: u32, nonce: u64) -> PowSeal {
+    let template = BlockTemplate {
+        parent_hash: [0; 32],
+        header: mined.to_le_bytes().to_vec(),
+        cube_size: 3,
+        target: Target::MAX,
+    };
+    attempt(&ReverseScramble, &template, nonce).unwrap()
+}
+
+#[test]
+fn test_chained_seals_are_all_accepted() {
+    let seals = vec![seal_for(0, 1), seal_for(1, 2), seal_for(2, 3)];
+    let mut state = MockState { mined: 0, last_nonce: 0 };
+    assert_eq!(verify_block_seals(&mut state, &seals), vec![Ok(()); 3]);
+    assert_eq!(state, MockState { mined: 3, last_nonce: 3 });
+}
+
+#[test]
+fn test_first_seal_for_a_challenge_wins() {
+    // Both solve challenge 0; the higher nonce comes first in the block
+    let seals = vec![seal_for(0, 9), seal_for(0, 4)];
+    let mut state = MockState { mined: 0, last_nonce: 0 };
+    let results = verify_block_seals(&mut state, &seals);
+    assert_eq!(results, [Ok(()), Err(VerifyError::StaleChallenge)]);
+
+    let seals = vec![seal_for(0, 4), seal_for(0, 9)];
+    let mut state = MockState { mined: 0, last_nonce: 0 };
+    let results = verify_block_seals(&mut state, &seals);
+    assert_eq!(results[0], Ok(()));
+    assert_eq!(results[1], Err(VerifyError::InvalidSolution));
+}
+
+#[test]
+fn test_matches_sequential_dispatch() {
+    let mut broken = seal_for(1, 5);
+    broken.moves.push(Move::U(1));
+    let blocks = [
+        vec![seal_for(1, 2), seal_for(0, 1), seal_for(1, 3), seal_for(2, 4)],
+        vec![broken, seal_for(0, 1), seal_for(1, 5), seal_for(1, 6), seal_for(2, 7)],
+        vec![seal_for(0, 0), seal_for(0, 3), seal_for(5, 8)],
+        vec![],
+    ];
+    for seals in &blocks {
+        let mut parallel = MockState { mined: 0, last_nonce: 0 };
+        let mut reference = parallel.clone();
+        assert_eq!(verify_block_seals(&mut parallel, seals), sequential(&mut reference, seals));
+        assert_eq!(parallel, reference);
+    }
+}
+
+/// `MockState` under an explicit commitment version, at a target only some
+/// seals meet.
+struct VersionedState {
+    inner: MockState,
+    commitment: CommitmentVersion,
+}
+
+impl ImportState for VersionedState {
+    fn header(&self) -> Vec<u8> {
+        self.inner.header()
+    }
+
+    fn target(&self) -> Target {
+        Target::from_difficulty(4)
+    }
+
+    fn commitment(&self) -> CommitmentVersion {
+        self.commitment
+    }
+
+    fn check_policy(&self, seal: &PowSeal) -> Result<(), VerifyError> {
+        self.inner.check_policy(seal)
+    }
+
+    fn accept(&mut self, seal: &PowSeal) {
+        self.inner.accept(seal)
+    }
+}
+
+#[test]
+fn test_seals_are_checked_under_the_state_versions() {
+    let target = Target::from_difficulty(4);
+    let meets = |seal: &PowSeal, version| {
+        verify_seal_with_versions(SpecVersion::V1, version, seal, &0u32.to_le_bytes(), &target).is_ok()
+    };
+    let seal = (1..)
+        .map(|nonce| seal_for(0, nonce))
+        .find(|seal| meets(seal, CommitmentVersion::V1FaceIds) && !meets(seal, CommitmentVersion::V2Packed))
+        .unwrap();
+
+    for (commitment, expected) in [
+        (CommitmentVersion::V1FaceIds, Ok(())),
+        (CommitmentVersion::V2Packed, Err(VerifyError::TargetNotMet)),
+    ] {
+        let mut state = VersionedState { inner: MockState { mined: 0, last_nonce: 0 }, commitment };
+        assert_eq!(verify_block_seals(&mut state, std::slice::from_ref(&seal)), vec![expected]);
+    }
+}
+
+/// `MockState` under a solution policy.
+struct PolicyState {
+    inner: MockState,
+    policy: Blacklist,
+}
+
+impl ImportState for PolicyState {
+    fn header(&self) -> Vec<u8> {
+        self.inner.header()
+    }
+
+    fn target(&self) -> Target {
+        self.inner.target()
+    }
+
+    fn check_policy(&self, seal: &PowSeal) -> Result<(), VerifyError> {
+        self.inner.check_policy(seal)
+    }
+
+    fn accept(&mut self, seal: &PowSeal) {
+        self.inner.accept(seal)
+    }
+
+    fn policy(&self) -> &dyn SolutionPolicy {
+        &self.policy
+    }
+}
+
+#[test]
+fn test_seals_are_checked_against_the_state_policy() {
+    let canned = seal_for(0, 1);
+    let seals = vec![canned.clone(), seal_for(0, 2), seal_for(1, 3)];
+    let mut state = PolicyState {
+        inner: MockState { mined: 0, last_nonce: 0 },
+        policy: Blacklist::new(vec![canned.moves]),
+    };
+    assert_eq!(verify_block_seals(&mut state, &seals), [Err(VerifyError::CannedSolution), Ok(()), Ok(())]);
+    assert_eq!(state.inner, MockState { mined: 2, last_nonce: 3 });
+}
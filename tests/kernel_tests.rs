@@ -0,0 +1,55 @@
+use qbitcoin_core::kernel::{kernel_scramble_moves, keystream, scramble_kernel, KERNEL_MAX_MOVES, KERNEL_WORDS};
+use qbitcoin_core::spec::{self, ScrambleFilters, ScrambleSpec, SpecVersion};
+
+#[test]
+fn test_kernel_matches_scramble_moves() {
+    let long = ScrambleSpec {
+        length: (50, 64),
+        filters: ScrambleFilters {
+            distinct_faces: true,
+            no_axis_runs: true,
+        },
+    };
+    let unfiltered = ScrambleSpec {
+        length: (1, 40),
+        filters: ScrambleFilters {
+            distinct_faces: false,
+            no_axis_runs: false,
+        },
+    };
+    let fixed = ScrambleSpec { length: (25, 25), ..ScrambleSpec::V1 };
+    for scramble in [ScrambleSpec::V1, long, unfiltered, fixed] {
+        for version in SpecVersion::ALL {
+            for nonce in 0..200 {
+                let seed = spec::challenge_seed(version, nonce, b"kernel");
+                assert_eq!(
+                    kernel_scramble_moves(version, &scramble, &seed),
+                    Some(spec::scramble_moves_with(version, &scramble, &seed)),
+                    "{:?} nonce {}",
+                    scramble,
+                    nonce
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_kernel_refuses_long_scrambles() {
+    let seed = spec::challenge_seed(SpecVersion::V1, 0, b"kernel");
+    let too_long = ScrambleSpec { length: (20, KERNEL_MAX_MOVES as u32 + 1), ..ScrambleSpec::V1 };
+    assert_eq!(kernel_scramble_moves(SpecVersion::V1, &too_long, &seed), None);
+}
+
+#[test]
+fn test_kernel_reports_unfinished_scrambles() {
+    // A zero stream draws the shortest length, then Up for every face; the
+    // second move never lands once consecutive faces must differ
+    let output = scramble_kernel(&[0; KERNEL_WORDS], &ScrambleSpec::V1);
+    assert!(!output.complete);
+    assert_eq!(output.len, 1);
+    assert_eq!((output.faces[0], output.counts[0]), (0, 1));
+
+    let seed = spec::challenge_seed(SpecVersion::V1, 0, b"kernel");
+    assert!(scramble_kernel(&keystream(&seed), &ScrambleSpec::V1).complete);
+}
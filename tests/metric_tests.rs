@@ -0,0 +1,57 @@
+use qbitcoin_core::{Axis, Face, Metric, Move};
+
+#[test]
+fn test_metrics_count_turns() {
+    let moves = [Move::R(1), Move::U(2), Move::F(3), Move::Wide(Face::Left, 2), Move::B(4)];
+    assert_eq!(Metric::Htm.length(&moves, 3), 4);
+    assert_eq!(Metric::Qtm.length(&moves, 3), 6);
+    assert_eq!(Metric::Stm.length(&moves, 3), 4);
+    assert_eq!(Metric::Htm.length(&[], 3), 0);
+}
+
+#[test]
+fn test_rotations_are_free() {
+    let moves = [Move::Rotation(Axis::X, 1), Move::Rotation(Axis::Y, 2), Move::R(1)];
+    for metric in Metric::ALL {
+        assert_eq!(metric.length(&moves, 3), 1, "{}", metric.name());
+        // Both layers of a 2x2x2 turn together: a wide move is a rotation
+        assert_eq!(metric.cost(&Move::Wide(Face::Up, 1), 2), 0);
+        assert_eq!(metric.cost(&Move::Wide(Face::Up, 1), 4), 1);
+    }
+}
+
+#[test]
+fn test_slice_turns() {
+    let moves = [Move::Slice(Axis::X, 1), Move::Slice(Axis::Y, 2), Move::R(1)];
+    assert_eq!(Metric::Htm.length(&moves, 3), 5);
+    assert_eq!(Metric::Qtm.length(&moves, 3), 7);
+    assert_eq!(Metric::Stm.length(&moves, 3), 3);
+    // A 2x2x2 has no inner layers to turn
+    for metric in Metric::ALL {
+        assert_eq!(metric.cost(&Move::Slice(Axis::Z, 1), 2), 0);
+    }
+
+    // Numbered layers count like the blocks they turn
+    let moves = [Move::Layer(Face::Right, 2, 2), Move::Layer(Face::Right, 5, 1), Move::Block(Face::Up, 3, 1)];
+    assert_eq!(Metric::Htm.length(&moves, 5), 4);
+    assert_eq!(Metric::Qtm.length(&moves, 5), 6);
+    assert_eq!(Metric::Stm.length(&moves, 5), 3);
+    assert_eq!(Metric::Htm.cost(&Move::Block(Face::Up, 5, 1), 5), 0);
+}
+
+#[test]
+fn test_default_is_half_turn_metric() {
+    assert_eq!(Metric::default(), Metric::Htm);
+}
+
+#[cfg(feature = "scale-codec")]
+#[test]
+fn test_encoding_is_stable() {
+    use codec::{Decode, Encode};
+
+    for (index, metric) in Metric::ALL.into_iter().enumerate() {
+        assert_eq!(metric.encode(), vec![index as u8]);
+        assert_eq!(Metric::decode(&mut &[index as u8][..]).unwrap(), metric);
+    }
+    assert!(Metric::decode(&mut &[3u8][..]).is_err());
+}
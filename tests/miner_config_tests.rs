@@ -0,0 +1,59 @@
+#![cfg(feature = "miner-config")]
+
+use qbitcoin_core::hugepages::HugePages;
+use qbitcoin_core::miner::{ConfigWatcher, MinerConfig, NodeMode};
+
+const BASE: &str = r#"
+[node]
+mode = "rpc"
+endpoint = "ws://127.0.0.1:9944"
+
+[mining]
+threads = 4
+sizes = [3, 4]
+"#;
+
+#[test]
+fn test_parse_with_defaults() {
+    let config = MinerConfig::from_toml(BASE).unwrap();
+    assert_eq!(config.node.mode, NodeMode::Rpc);
+    assert_eq!(config.mining.threads, 4);
+    assert_eq!(config.mining.strategy, "reverse-scramble");
+    assert_eq!(config.metrics.port, None);
+    assert_eq!(config.log.level, "info");
+    assert_eq!(config.mining.huge_pages, HugePages::Off);
+
+    let config = MinerConfig::from_toml(&BASE.replace("threads = 4", "threads = 4\nhuge_pages = \"reserved\"")).unwrap();
+    assert_eq!(config.mining.huge_pages, HugePages::Reserved);
+}
+
+#[test]
+fn test_rejects_invalid_settings() {
+    assert!(MinerConfig::from_toml(&BASE.replace("threads = 4", "threads = 0")).is_err());
+    assert!(MinerConfig::from_toml(&BASE.replace("[3, 4]", "[1]")).is_err());
+    assert!(MinerConfig::from_toml(&format!("{}\nunknown = 1\n", BASE)).is_err());
+}
+
+#[test]
+fn test_reload_applies_only_runtime_settings() {
+    let path = std::env::temp_dir().join(format!("qbit-miner-{}.toml", std::process::id()));
+    std::fs::write(&path, BASE).unwrap();
+    let mut watcher = ConfigWatcher::open(&path).unwrap();
+    assert_eq!(watcher.reload().unwrap(), None);
+
+    std::fs::write(&path, BASE.replace("threads = 4", "threads = 8")).unwrap();
+    let reload = watcher.reload().unwrap().unwrap();
+    assert_eq!(reload.config.mining.threads, 8);
+    assert!(!reload.restart_required);
+
+    // A new endpoint needs a restart and is not applied
+    let moved = BASE
+        .replace("threads = 4", "threads = 8")
+        .replace("127.0.0.1", "10.0.0.2");
+    std::fs::write(&path, moved).unwrap();
+    let reload = watcher.reload().unwrap().unwrap();
+    assert!(reload.restart_required);
+    assert_eq!(watcher.current().node.endpoint, "ws://127.0.0.1:9944");
+
+    std::fs::remove_file(&path).unwrap();
+}
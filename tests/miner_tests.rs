@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use qbitcoin_core::miner::{attempt, BlockHash, BlockTemplate, ChainView, WorkTracker};
+use qbitcoin_core::solver::ReverseScramble;
+use qbitcoin_core::{Move, PowSeal, Target};
+
+/// Block tree given as child -> parent links.
+struct MockChain {
+    parents: HashMap<BlockHash, BlockHash>,
+    best: BlockHash,
+}
+
+fn hash(n: u8) -> BlockHash {
+    [n; 32]
+}
+
+impl ChainView for MockChain {
+    fn best_hash(&self) -> BlockHash {
+        self.best
+    }
+
+    fn is_ancestor(&self, ancestor: &BlockHash, descendant: &BlockHash) -> bool {
+        let mut current = *descendant;
+        loop {
+            if current == *ancestor {
+                return true;
+            }
+            match self.parents.get(&current) {
+                Some(parent) => current = *parent,
+                None => return false,
+            }
+        }
+    }
+
+    fn challenge_header(&self, parent: &BlockHash) -> Vec<u8> {
+        // Challenges derive from the height only, so sibling blocks share them
+        let mut height = 0u32;
+        let mut current = *parent;
+        while let Some(p) = self.parents.get(&current) {
+            height += 1;
+            current = *p;
+        }
+        height.to_le_bytes().to_vec()
+    }
+}
+
+fn template(parent: BlockHash, chain: &MockChain) -> BlockTemplate {
+    BlockTemplate {
+        parent_hash: parent,
+        header: chain.challenge_header(&parent),
+        cube_size: 3,
+        target: Target::MAX,
+    }
+}
+
+#[test]
+fn test_reorg_invalidates_and_resubmits() {
+    // 0 <- 1 <- 2 and a competing 0 <- 1 <- 3
+    let parents = [(hash(1), hash(0)), (hash(2), hash(1)), (hash(3), hash(1))];
+    let mut chain = MockChain {
+        parents: parents.iter().cloned().collect(),
+        best: hash(2),
+    };
+
+    let mut tracker = WorkTracker::new(true);
+    assert!(!tracker.on_new_best(&chain).reorged);
+
+    let on_two = tracker.register(template(hash(2), &chain));
+    let on_one = tracker.register(template(hash(1), &chain));
+    let seal = PowSeal {
+        cube_size: 3,
+        nonce: 7,
+        moves: vec![Move::R(1)],
+    };
+    tracker.record_found(on_two, seal.clone());
+
+    // Sibling 3 replaces 2 at the same height: same challenge, seal is resubmitted
+    chain.best = hash(3);
+    let outcome = tracker.on_new_best(&chain);
+    assert!(outcome.reorged);
+    assert_eq!(outcome.invalidated, vec![on_two]);
+    assert_eq!(outcome.resubmit, vec![seal]);
+    assert!(!tracker.is_valid(on_two));
+    assert!(tracker.is_valid(on_one));
+}
+
+#[test]
+fn test_extension_is_not_a_reorg() {
+    let mut chain = MockChain {
+        parents: [(hash(1), hash(0))].iter().cloned().collect(),
+        best: hash(0),
+    };
+    let mut tracker = WorkTracker::new(true);
+    tracker.on_new_best(&chain);
+    let job = tracker.register(template(hash(0), &chain));
+
+    chain.best = hash(1);
+    let outcome = tracker.on_new_best(&chain);
+    assert!(!outcome.reorged);
+    assert!(tracker.is_valid(job));
+}
+
+#[test]
+fn test_attempt_produces_verified_seal() {
+    let template = BlockTemplate {
+        parent_hash: hash(0),
+        header: b"header".to_vec(),
+        cube_size: 3,
+        target: Target::MAX,
+    };
+    let seal = attempt(&ReverseScramble, &template, 5).unwrap();
+    assert_eq!(seal.nonce, 5);
+    assert_eq!(seal.cube_size, 3);
+
+    let impossible = BlockTemplate {
+        target: Target::from_bytes([0; 32]),
+        ..template
+    };
+    assert_eq!(attempt(&ReverseScramble, &impossible, 5), None);
+}
@@ -0,0 +1,70 @@
+use qbitcoin_core::optimal::{distance, distances, OptimalSolver, STATES};
+use qbitcoin_core::solver::{no_shorter_solution, no_shorter_solution_with_stats, search_bound, SearchStats, Solver};
+use qbitcoin_core::{Cube, Move};
+
+#[test]
+fn test_distance_distribution() {
+    // Known half-turn metric distribution of the 2x2x2
+    let expected = [1, 9, 54, 321, 1847, 9992, 50136, 227536, 870072, 1887748, 623800, 2644];
+    assert_eq!(distances(), expected);
+    assert_eq!(expected.iter().sum::<u64>(), STATES as u64);
+}
+
+#[test]
+fn test_optimal_solutions_verify() {
+    for nonce in 0..20 {
+        let mut cube = Cube::new(2);
+        let scramble = cube.scramble_deterministic(nonce, b"optimal");
+        let solution = OptimalSolver.solve(&cube, &scramble).unwrap();
+        assert!(cube.verify_solution(&solution), "nonce {}", nonce);
+        assert_eq!(Some(solution.len() as u8), distance(&cube));
+        assert!(solution.len() <= 11);
+    }
+}
+
+#[test]
+fn test_short_sequences_are_found() {
+    let mut cube = Cube::new(2);
+    assert_eq!(distance(&cube), Some(0));
+    for m in [Move::L(1), Move::D(2), Move::B(3)] {
+        cube.apply_move(&m);
+    }
+    assert_eq!(distance(&cube), Some(3));
+    assert_eq!(OptimalSolver.solve(&Cube::new(3), &[]), None);
+}
+
+#[test]
+fn test_no_shorter_solution_matches_distance() {
+    for len in 0..4 {
+        let mut cube = Cube::new(2);
+        for m in [Move::R(1), Move::U(2), Move::F(3)].iter().take(len) {
+            cube.apply_move(m);
+        }
+        let optimal = distance(&cube).unwrap() as usize;
+        assert!(no_shorter_solution(&cube, optimal));
+        if optimal > 0 {
+            assert!(!no_shorter_solution(&cube, optimal + 1));
+        }
+    }
+}
+
+#[test]
+fn test_search_bound_counts_the_exhaustive_search() {
+    assert_eq!(search_bound(0), SearchStats::default());
+    assert_eq!(search_bound(1), SearchStats { moves_applied: 0, states_checked: 1 });
+    assert_eq!(search_bound(3), SearchStats { moves_applied: 18 + (18 + 270), states_checked: 1 + 18 + 270 });
+
+    // An optimal solution makes the search try every shorter sequence
+    let mut cube = Cube::new(2);
+    for m in [Move::R(1), Move::U(2), Move::F(3)] {
+        cube.apply_move(&m);
+    }
+    let optimal = distance(&cube).unwrap() as usize;
+    assert_eq!(no_shorter_solution_with_stats(&cube, optimal), (true, search_bound(optimal)));
+
+    // A search that finds a shorter solution stops early
+    let (none_shorter, stats) = no_shorter_solution_with_stats(&cube, optimal + 2);
+    assert!(!none_shorter);
+    let bound = search_bound(optimal + 2);
+    assert!(stats.moves_applied < bound.moves_applied && stats.states_checked < bound.states_checked);
+}
@@ -0,0 +1,46 @@
+#![cfg(all(feature = "solver-plugins", unix))]
+
+use qbitcoin_core::miner::{load_solver, PluginConfig};
+use qbitcoin_core::{Cube, Move};
+
+fn shell_solver(script: &str) -> PluginConfig {
+    PluginConfig::Subprocess {
+        command: "sh".to_string(),
+        args: vec!["-c".to_string(), script.to_string()],
+    }
+}
+
+#[test]
+fn test_subprocess_protocol() {
+    // Answers every request with R (byte 13) then U2 (byte 2)
+    let solver = load_solver(&shell_solver(
+        "while read line; do echo '{\"moves\": [13, 2]}'; done",
+    ))
+    .unwrap();
+
+    let cube = Cube::new(3);
+    for _ in 0..2 {
+        assert_eq!(
+            solver.solve(&cube, &[Move::F(1)]),
+            Some(vec![Move::R(1), Move::U(2)])
+        );
+    }
+}
+
+#[test]
+fn test_subprocess_can_give_up() {
+    let solver = load_solver(&shell_solver("read line; echo '{\"moves\": null}'")).unwrap();
+    assert_eq!(solver.solve(&Cube::new(3), &[]), None);
+}
+
+#[test]
+fn test_config_format() {
+    let config: PluginConfig =
+        serde_json::from_str(r#"{"kind": "library", "path": "/opt/solvers/libfast.so"}"#).unwrap();
+    assert_eq!(
+        config,
+        PluginConfig::Library {
+            path: "/opt/solvers/libfast.so".into()
+        }
+    );
+}
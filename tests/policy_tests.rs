@@ -0,0 +1,100 @@
+use qbitcoin_core::policy::{Blacklist, MinQuality, ReferenceChallenge, SolutionPolicy};
+use qbitcoin_core::{invert_sequence, verify_seal_with_rules, Cube, Move, PowSeal, Rules, Target, VerifyError};
+
+fn solved_seal(size: u32, nonce: u64, header: &[u8]) -> (PowSeal, Vec<Move>) {
+    let mut cube = Cube::new(size as usize);
+    let scramble = cube.scramble_deterministic(nonce, header);
+    let moves = invert_sequence(&scramble);
+    let seal = PowSeal {
+        cube_size: size,
+        nonce,
+        moves,
+    };
+    (seal, scramble)
+}
+
+#[test]
+fn test_default_policy_allows_everything() {
+    let (seal, scramble) = solved_seal(3, 1, b"header");
+    assert!(().allows(&seal, &scramble));
+    assert!(verify_seal_with_rules(&Rules::default(), &seal, b"header", &Target::MAX).is_ok());
+}
+
+#[test]
+fn test_blacklist() {
+    let (seal, scramble) = solved_seal(3, 1, b"header");
+    let (other, _) = solved_seal(3, 2, b"header");
+    let blacklist = Blacklist::new(vec![other.moves.clone()]);
+    assert!(blacklist.allows(&seal, &scramble));
+
+    let blacklist = Blacklist::new(vec![other.moves, seal.moves.clone()]);
+    assert_eq!(blacklist.sequences().len(), 2);
+    assert!(!blacklist.allows(&seal, &scramble));
+}
+
+#[test]
+fn test_reference_challenge() {
+    let reference = ReferenceChallenge::new(b"reference".to_vec());
+    assert_eq!(reference.header(), b"reference");
+
+    let (fresh, scramble) = solved_seal(3, 5, b"header");
+    assert!(reference.allows(&fresh, &scramble));
+
+    // A solution for the reference challenge with the same nonce is canned
+    let (canned, scramble) = solved_seal(3, 5, b"reference");
+    assert!(!reference.allows(&canned, &scramble));
+}
+
+#[test]
+fn test_policies_combine() {
+    let (seal, scramble) = solved_seal(3, 1, b"header");
+    let reference = ReferenceChallenge::new(b"reference".to_vec());
+    assert!((reference.clone(), ()).allows(&seal, &scramble));
+    assert!(!(reference, Blacklist::new(vec![seal.moves.clone()])).allows(&seal, &scramble));
+}
+
+#[test]
+fn test_policy_is_checked_after_the_target() {
+    let (seal, _) = solved_seal(3, 1, b"header");
+    let blacklist = Blacklist::new(vec![seal.moves.clone()]);
+    let rules = Rules {
+        policy: &blacklist,
+        ..Rules::default()
+    };
+    assert_eq!(
+        verify_seal_with_rules(&rules, &seal, b"header", &Target::MAX).unwrap_err(),
+        VerifyError::CannedSolution
+    );
+    assert_eq!(
+        verify_seal_with_rules(&rules, &seal, b"header", &Target::from_bytes([0; 32])).unwrap_err(),
+        VerifyError::TargetNotMet
+    );
+    assert_eq!(VerifyError::from_code(7), Some(VerifyError::CannedSolution));
+}
+
+#[test]
+fn test_min_quality_caps_solution_length() {
+    let (seal, scramble) = solved_seal(3, 1, b"header");
+    assert_eq!(MinQuality::default(), MinQuality::NONE);
+    assert_eq!(MinQuality { max_percent: 150 }.max_length(20), 30);
+    assert_eq!(MinQuality::NONE.max_length(usize::MAX), u64::MAX / 100);
+
+    let mut padded = seal.clone();
+    padded.moves.extend([Move::R(1), Move::R(3)]);
+    let exact = MinQuality { max_percent: 100 };
+    assert!(exact.allows(&seal, &scramble));
+    assert!(!exact.allows(&padded, &scramble));
+    assert!(MinQuality::NONE.allows(&padded, &scramble));
+
+    // Refused before the replay, whatever the target
+    let rules = Rules {
+        min_quality: exact,
+        ..Rules::default()
+    };
+    assert!(verify_seal_with_rules(&rules, &seal, b"header", &Target::MAX).is_ok());
+    assert_eq!(
+        verify_seal_with_rules(&rules, &padded, b"header", &Target::from_bytes([0; 32])).unwrap_err(),
+        VerifyError::SolutionTooLong
+    );
+    assert_eq!(VerifyError::from_code(9), Some(VerifyError::SolutionTooLong));
+}
@@ -0,0 +1,40 @@
+use qbitcoin_core::quantum::{grover_cost, grover_cost_with, GroverAssumptions};
+use qbitcoin_core::{state_space_log2, Target};
+
+#[test]
+fn test_state_space_matches_known_counts() {
+    // 2x2x2: 3,674,160 states; 3x3x3: 43,252,003,274,489,856,000 states
+    assert!((state_space_log2(2) - 3_674_160f64.log2()).abs() < 1e-9);
+    assert!((state_space_log2(3) - 43_252_003_274_489_856_000f64.log2()).abs() < 1e-9);
+    // 4x4x4: ~7.40e45, 5x5x5: ~2.83e74
+    assert!((state_space_log2(4) - 7.4012e45f64.log2()).abs() < 1e-3);
+    assert!((state_space_log2(5) - 2.8287e74f64.log2()).abs() < 1e-3);
+}
+
+#[test]
+fn test_grover_cost_scales_with_target() {
+    let easy = grover_cost(3, &Target::MAX);
+    let hard = grover_cost(3, &Target::from_difficulty(1 << 20));
+
+    assert!((easy.search_space_log2 - state_space_log2(3)).abs() < 1e-6);
+    // A 2^20 harder target adds 20 bits of search space and 10 bits of queries
+    assert!((hard.search_space_log2 - easy.search_space_log2 - 20.0).abs() < 1e-3);
+    assert!((hard.grover_queries_log2 - easy.grover_queries_log2 - 10.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_grover_assumptions_affect_depth_only() {
+    let target = Target::MAX;
+    let base = grover_cost(4, &target);
+    let deeper = grover_cost_with(
+        4,
+        &target,
+        &GroverAssumptions {
+            hash_depth: 1.0e6,
+            ..GroverAssumptions::default()
+        },
+    );
+
+    assert_eq!(base.grover_queries_log2, deeper.grover_queries_log2);
+    assert!(deeper.circuit_depth_log2 > base.circuit_depth_log2);
+}
@@ -0,0 +1,114 @@
+use qbitcoin_core::render::{fill, mesh_json, stickers_3d, svg, STICKER};
+use qbitcoin_core::{notation, Axis, Color, Cube, Face, Move};
+
+#[test]
+fn test_notation() {
+    let moves = [Move::R(1), Move::U(2), Move::F(3), Move::Wide(Face::Back, 2), Move::Rotation(Axis::Z, 1), Move::D(0)];
+    assert_eq!(notation::format_algorithm(&moves), "R U2 F' Bw2 z D0");
+    assert_eq!(notation::format_algorithm(&[]), "");
+    assert_eq!(Move::Wide(Face::Right, 2).to_string(), "Rw2");
+    assert_eq!(format!("{} {}", Move::L(3), Move::Rotation(Axis::X, 1)), "L' x");
+    assert_eq!(notation::format_algorithm(&[Move::Slice(Axis::X, 1), Move::Slice(Axis::Y, 2), Move::Slice(Axis::Z, 3)]), "M E2 S'");
+    assert_eq!(format!("{} {}", Move::Block(Face::Right, 3, 1), Move::Layer(Face::Left, 2, 3)), "3Rw 2L'");
+}
+
+#[test]
+fn test_notation_parse() {
+    assert_eq!(
+        notation::parse_algorithm("R U R' U2 Fw x").unwrap(),
+        [Move::R(1), Move::U(1), Move::R(3), Move::U(2), Move::Wide(Face::Front, 1), Move::Rotation(Axis::X, 1)]
+    );
+    assert_eq!(notation::parse_algorithm("  r2'\ty'\n").unwrap(), [Move::Wide(Face::Right, 2), Move::Rotation(Axis::Y, 3)]);
+    assert_eq!(notation::parse_algorithm("").unwrap(), []);
+    assert_eq!("D0".parse::<Move>(), Ok(Move::D(0)));
+    assert_eq!(notation::parse_algorithm("M2 U M' U2 M E S'").unwrap()[..2], [Move::Slice(Axis::X, 2), Move::U(1)]);
+    assert_eq!(
+        notation::parse_algorithm("3Rw 2L 4U2 10Fw'").unwrap(),
+        [Move::Block(Face::Right, 3, 1), Move::Layer(Face::Left, 2, 1), Move::Layer(Face::Up, 4, 2), Move::Block(Face::Front, 10, 3)]
+    );
+
+    let error = notation::parse_algorithm("R U3 F").unwrap_err();
+    assert_eq!((error.index, error.token.as_str()), (1, "U3"));
+    for bad in ["", "Q", "xw", "rw", "R''", "R2x", "Mw", "m", "0R", "3r", "2x", "2M", "2", "99999999999999999999R"] {
+        assert!(bad.parse::<Move>().is_err(), "{:?}", bad);
+    }
+
+    // Everything `format` writes reads back
+    let mut moves = Vec::new();
+    for count in 0..4 {
+        for face in Face::ALL {
+            moves.extend([Move::from_face_and_count(face, count), Move::Wide(face, count)]);
+        }
+        moves.extend([Axis::X, Axis::Y, Axis::Z].map(|axis| Move::Rotation(axis, count)));
+        moves.extend(Axis::ALL.map(|axis| Move::Slice(axis, count)));
+        moves.extend(Face::ALL.map(|face| Move::Layer(face, count + 1, count)));
+        moves.extend(Face::ALL.map(|face| Move::Block(face, 12, count)));
+    }
+    assert_eq!(notation::parse_algorithm(&notation::format_algorithm(&moves)).unwrap(), moves);
+}
+
+fn fills(svg: &str) -> Vec<&str> {
+    svg.split(r#"fill=""#).skip(1).map(|rest| &rest[..7]).collect()
+}
+
+#[test]
+fn test_solved_net() {
+    let picture = svg(&Cube::new(2));
+    assert!(picture.starts_with("<svg") && picture.ends_with("</svg>"));
+    assert!(picture.contains(&format!(r#"width="{}" height="{}""#, 8 * STICKER, 6 * STICKER)));
+    // Face::ALL order, four stickers each
+    let expected: Vec<&str> = Face::ALL
+        .iter()
+        .flat_map(|&face| [fill(Color::default_for_face(face)); 4])
+        .collect();
+    assert_eq!(fills(&picture), expected);
+}
+
+#[test]
+fn test_net_places_faces_around_front() {
+    let picture = svg(&Cube::new(3));
+    // Front's top left sticker sits one face right of and one face below the
+    // net's corner, Back's at the right end of the middle row
+    let front = format!(r#"<rect x="{0}" y="{0}""#, 3 * STICKER);
+    let back = format!(r#"<rect x="{}" y="{}""#, 9 * STICKER, 3 * STICKER);
+    assert!(picture.contains(&front));
+    assert!(picture.contains(&back));
+    assert_eq!(picture.matches("<rect").count(), 54);
+}
+
+#[test]
+fn test_stickers_3d_meet_on_their_pieces() {
+    let mut cube = Cube::new(3);
+    cube.scramble_deterministic(7, b"render");
+    let quads = stickers_3d(&cube);
+    assert_eq!(quads.len(), 54);
+    // Centres keep their place, so they tell which colours are opposite
+    // whatever the scheme
+    let centre = |face: Face| quads[face.index() * 9 + 4].color;
+    let opposite = |a: Color, b: Color| Face::ALL.iter().any(|&face| a == centre(face) && b == centre(face.opposite()));
+    for quad in &quads {
+        // Each quad is a square on its face's plane
+        let flat = (0..3).filter(|&axis| quad.corners.iter().all(|c| c[axis] == quad.corners[0][axis])).count();
+        assert_eq!(flat, 1);
+    }
+
+    // Stickers sharing an edge across faces belong to the same piece, so
+    // they never repeat a colour or show opposite ones
+    for (i, a) in quads.iter().enumerate() {
+        for b in &quads[i + 1..] {
+            if a.face != b.face && a.corners.iter().filter(|corner| b.corners.contains(corner)).count() == 2 {
+                assert_ne!(a.color, b.color, "{:?} {:?}", a, b);
+                assert!(!opposite(a.color, b.color), "{:?} {:?}", a, b);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_mesh_json() {
+    let mesh = mesh_json(&Cube::new(2));
+    assert!(mesh.starts_with(r##"{"size":2,"palette":["#ffffff","#ffd500""##));
+    assert_eq!(mesh.matches(r#""face":"#).count(), 24);
+    assert!(mesh.contains(r#"{"face":"U","color":0,"corners":[[-2,2,-2],[-2,2,0],[0,2,0],[0,2,-2]]}"#));
+    assert!(mesh.ends_with("]}"));
+}
@@ -0,0 +1,41 @@
+use qbitcoin_core::replay::{first_divergence, replay};
+use qbitcoin_core::{invert_sequence, Cube, PowSeal, Target};
+
+fn seal(nonce: u64, header: &[u8]) -> PowSeal {
+    let mut cube = Cube::new(3);
+    let scramble = cube.scramble_deterministic(nonce, header);
+    PowSeal {
+        cube_size: 3,
+        nonce,
+        moves: invert_sequence(&scramble),
+    }
+}
+
+fn value<'a>(trace: &'a [qbitcoin_core::replay::TraceStep], label: &str) -> &'a str {
+    &trace.iter().find(|step| step.label == label).unwrap().value
+}
+
+#[test]
+fn test_replay_of_valid_seal() {
+    let seal = seal(11, b"header");
+    let trace = replay(&seal, b"header", &Target::MAX);
+    assert_eq!(value(&trace, "scrambled.matches_walk"), "true");
+    assert_eq!(value(&trace, "target_met"), "true");
+    assert_eq!(value(&trace, "solved"), "true");
+}
+
+#[test]
+fn test_divergence_points_at_first_differing_step() {
+    let seal = seal(11, b"header");
+    let ours = replay(&seal, b"header", &Target::MAX);
+    let mut theirs: Vec<String> = ours.iter().map(ToString::to_string).collect();
+    assert_eq!(first_divergence(&ours, &theirs), None);
+
+    // A node that derived a different seed diverges right there
+    let seed_index = ours.iter().position(|step| step.label == "seed").unwrap();
+    theirs[seed_index] = "seed: 00".to_string();
+    assert_eq!(first_divergence(&ours, &theirs), Some(seed_index));
+
+    theirs.truncate(seed_index);
+    assert_eq!(first_divergence(&ours, &theirs), Some(seed_index));
+}
@@ -0,0 +1,129 @@
+use qbitcoin_core::research::{
+    self, challenge_sensitivity, distance_distribution, named_subgroup, subgroup_distance_distribution,
+    DistanceDistribution, HeaderField, ResearchConfig,
+};
+use qbitcoin_core::solver::{ReverseScramble, Solver};
+
+fn config() -> ResearchConfig {
+    ResearchConfig {
+        size: 3,
+        samples: 16,
+        header: b"research_header".to_vec(),
+    }
+}
+
+#[test]
+fn test_research_run_is_reproducible() {
+    let solvers: [&dyn Solver; 1] = [&ReverseScramble];
+    let mut first = Vec::new();
+    let mut second = Vec::new();
+
+    research::run(&config(), &solvers)
+        .write_samples_csv(&mut first)
+        .unwrap();
+    research::run(&config(), &solvers)
+        .write_samples_csv(&mut second)
+        .unwrap();
+
+    assert_eq!(first, second);
+    // Header plus one line per sample
+    assert_eq!(String::from_utf8(first).unwrap().lines().count(), 17);
+}
+
+#[test]
+fn test_solve_length_distribution() {
+    let solvers: [&dyn Solver; 1] = [&ReverseScramble];
+    let report = research::run(&config(), &solvers);
+
+    let histogram = report.solve_length_distribution(0);
+    assert_eq!(histogram.values().sum::<u64>(), 16);
+    assert_eq!(report.failures(0), 0);
+    // Replaying the scramble backwards costs exactly the scramble length
+    for record in &report.records {
+        assert_eq!(record.solve_lengths[0], Some(record.scramble_length));
+    }
+}
+
+#[test]
+fn test_commitment_entropy_bounds() {
+    let report = research::run(&config(), &[]);
+    let entropy = report.commitment_entropy();
+    assert_eq!(entropy.samples, 16);
+    assert!(entropy.bits_per_byte > 0.0 && entropy.bits_per_byte <= 8.0);
+}
+
+#[test]
+fn test_distance_distributions() {
+    let pocket = distance_distribution(2).unwrap();
+    assert_eq!(pocket.states(), 3_674_160);
+    assert_eq!(pocket.diameter(), 11);
+    assert!(distance_distribution(3).is_none());
+
+    let r2u2 = subgroup_distance_distribution(3, &named_subgroup("r2u2").unwrap(), usize::MAX);
+    assert_eq!(r2u2.counts, [1, 2, 2, 2, 2, 2, 1]);
+
+    let ru2 = subgroup_distance_distribution(3, &named_subgroup("ru2").unwrap(), usize::MAX);
+    assert_eq!(ru2.states(), 14_400);
+    assert_eq!(ru2.diameter(), 20);
+
+    let capped = subgroup_distance_distribution(3, &named_subgroup("square").unwrap(), 100);
+    assert_eq!(capped.states(), 100);
+}
+
+#[test]
+fn test_distance_distribution_csv_round_trip() {
+    let distribution = subgroup_distance_distribution(3, &named_subgroup("r2u2").unwrap(), usize::MAX);
+    let mut csv = Vec::new();
+    distribution.write_csv(&mut csv).unwrap();
+    assert!(String::from_utf8(csv.clone()).unwrap().starts_with("size,distance,count\n3,0,1\n"));
+    assert_eq!(DistanceDistribution::read_csv(&csv[..]).unwrap(), distribution);
+    assert!(DistanceDistribution::read_csv(&b"size,distance,count\n3,1,1\n"[..]).is_err());
+}
+
+fn field(name: &str, bytes: &[u8], choices: u64) -> HeaderField {
+    HeaderField {
+        name: name.to_string(),
+        bytes: bytes.to_vec(),
+        choices,
+    }
+}
+
+#[test]
+fn test_fixed_fields_allow_no_grinding() {
+    let fields = [field("mined", &7u32.to_le_bytes(), 1), field("seed", &[9; 32], 1)];
+    let report = challenge_sensitivity(&fields, 3, 1, 32);
+    assert!(report.fields.is_empty());
+    assert_eq!(report.total_bits(), 0.0);
+}
+
+#[test]
+fn test_malleable_fields_are_measured() {
+    let fields = [
+        field("mined", &7u32.to_le_bytes(), 1),
+        field("timestamp", &1_000u64.to_le_bytes(), 1_000),
+        field("flags", &[0], 1_000),
+    ];
+    let report = challenge_sensitivity(&fields, 3, 1, 64);
+    assert_eq!(report.fields.len(), 2);
+
+    let timestamp = &report.fields[0];
+    assert_eq!((timestamp.choices, timestamp.sampled, timestamp.distinct_challenges), (1_000, 64, 64));
+    assert!((timestamp.bits - 1_000f64.log2()).abs() < 1e-9);
+
+    // A one-byte field holds at most 256 values
+    let flags = &report.fields[1];
+    assert_eq!((flags.choices, flags.sampled), (256, 64));
+    assert!(flags.bits <= 8.0);
+    assert!((report.total_bits() - (timestamp.bits + flags.bits)).abs() < 1e-9);
+}
+
+#[test]
+fn test_sensitivity_report_is_reproducible() {
+    let fields = [field("timestamp", &5u16.to_le_bytes(), 500)];
+    let mut first = Vec::new();
+    let mut second = Vec::new();
+    challenge_sensitivity(&fields, 2, 3, 16).write_csv(&mut first).unwrap();
+    challenge_sensitivity(&fields, 2, 3, 16).write_csv(&mut second).unwrap();
+    assert_eq!(first, second);
+    assert_eq!(String::from_utf8(first).unwrap().lines().count(), 2);
+}
@@ -0,0 +1,110 @@
+use qbitcoin_core::seal::{move_from_byte, move_to_byte, DecodeError};
+use qbitcoin_core::{verify_seal, Axis, Cube, Face, Move, PowSeal, Target, VerifyError};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+fn seal() -> PowSeal {
+    PowSeal {
+        cube_size: 3,
+        nonce: 0x0102_0304_0506_0708,
+        moves: vec![Move::R(1), Move::U(3), Move::F(2), Move::B(1)],
+    }
+}
+
+#[test]
+fn test_seal_round_trip() {
+    let seal = seal();
+    let bytes = seal.encode();
+    assert_eq!(bytes.len(), 4 + 8 + 4 + 4);
+    assert_eq!(PowSeal::decode(&bytes), Some(seal));
+}
+
+#[test]
+fn test_seal_layout() {
+    let bytes = seal().encode();
+    assert_eq!(&bytes[0..4], &[3, 0, 0, 0]);
+    assert_eq!(&bytes[4..12], &[8, 7, 6, 5, 4, 3, 2, 1]);
+    assert_eq!(&bytes[12..16], &[4, 0, 0, 0]);
+    // R is face 3, U face 0, F face 4, B face 5
+    assert_eq!(&bytes[16..], &[13, 3, 18, 21]);
+}
+
+#[test]
+fn test_seal_decode_rejects_malformed_input() {
+    let bytes = seal().encode();
+    assert!(PowSeal::decode(&bytes[..bytes.len() - 1]).is_none());
+    assert!(PowSeal::decode(&[bytes.clone(), vec![0]].concat()).is_none());
+
+    let mut unknown_move = bytes;
+    *unknown_move.last_mut().unwrap() = 60;
+    assert!(PowSeal::decode(&unknown_move).is_none());
+}
+
+#[test]
+fn test_seal_try_decode_reports_what_is_wrong() {
+    let bytes = seal().encode();
+    assert_eq!(PowSeal::try_decode(&bytes), Ok(seal()));
+    assert_eq!(PowSeal::try_decode(&bytes[..15]), Err(DecodeError::Truncated));
+    assert_eq!(
+        PowSeal::try_decode(&bytes[..bytes.len() - 1]),
+        Err(DecodeError::MoveCount { declared: 4, found: 3 })
+    );
+
+    let mut unknown_move = bytes;
+    *unknown_move.last_mut().unwrap() = 0xff;
+    assert_eq!(PowSeal::try_decode(&unknown_move), Err(DecodeError::UnknownMove(0xff)));
+}
+
+#[test]
+fn test_adversarial_input_never_panics() {
+    let mut rng = ChaCha8Rng::seed_from_u64(4);
+    for _ in 0..2000 {
+        let len = rng.gen_range(0..48);
+        let mut bytes: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+        let _ = PowSeal::try_decode(&bytes);
+        let _ = Cube::from_packed_bytes(&bytes);
+
+        // Well-formed headers, so decoding gets past them
+        if bytes.len() >= 3 {
+            bytes[0] = 1;
+            bytes[1] = rng.gen_range(0..4);
+            bytes[2] = 0;
+            let _ = Cube::from_packed_bytes(&bytes);
+        }
+        if bytes.len() >= 16 {
+            let moves = (bytes.len() - 16) as u32;
+            bytes[12..16].copy_from_slice(&moves.to_le_bytes());
+            let _ = PowSeal::try_decode(&bytes);
+        }
+    }
+
+    let sizes = [0, 1, 2, 3, 5, 1025, u32::MAX];
+    for _ in 0..300 {
+        let seal = PowSeal {
+            cube_size: sizes[rng.gen_range(0..sizes.len())],
+            nonce: rng.gen(),
+            moves: (0..rng.gen_range(0..40))
+                .map(|_| move_from_byte(rng.gen_range(0..60)).unwrap())
+                .collect(),
+        };
+        let header: Vec<u8> = (0..rng.gen_range(0..100)).map(|_| rng.gen()).collect();
+        let target = Target::from_difficulty(rng.gen());
+        let result = verify_seal(&seal, &header, &target).err();
+        match seal.cube_size {
+            0 | 1 => assert_eq!(result, Some(VerifyError::CubeTooSmall)),
+            1025.. => assert_eq!(result, Some(VerifyError::CubeTooLarge)),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn test_move_byte_ranges() {
+    assert_eq!(move_to_byte(&Move::Wide(Face::Right, 1)), 24 + 13);
+    assert_eq!(move_to_byte(&Move::Rotation(Axis::Y, 2)), 48 + 6);
+    for byte in 0..60 {
+        let m = move_from_byte(byte).unwrap();
+        assert_eq!(move_to_byte(&m), byte);
+    }
+    assert_eq!(move_from_byte(60), None);
+}
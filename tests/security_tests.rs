@@ -0,0 +1,34 @@
+use qbitcoin_core::security::{classical_cost, minimum_safe_size, SolverProfile, SolverStrategy};
+use qbitcoin_core::Target;
+
+#[test]
+fn test_classical_cost_orders_strategies() {
+    let target = Target::from_difficulty(1000);
+    let cost =
+        |strategy| classical_cost(3, &target, &SolverProfile::reference(strategy)).solve_seconds;
+
+    assert!(cost(SolverStrategy::ReverseScramble) < cost(SolverStrategy::MeetInTheMiddle));
+    assert!(cost(SolverStrategy::MeetInTheMiddle) < cost(SolverStrategy::BruteForce));
+}
+
+#[test]
+fn test_grinding_cost_follows_target() {
+    let profile = SolverProfile::reference(SolverStrategy::ReverseScramble);
+    let easy = classical_cost(3, &Target::from_difficulty(1000), &profile);
+    let hard = classical_cost(3, &Target::from_difficulty(2000), &profile);
+
+    assert!((hard.grinding_seconds / easy.grinding_seconds - 2.0).abs() < 1e-6);
+    assert_eq!(easy.solve_seconds, hard.solve_seconds);
+}
+
+#[test]
+fn test_minimum_safe_size() {
+    let target = Target::MAX;
+    let brute_force = SolverProfile::reference(SolverStrategy::BruteForce);
+    let reverse = SolverProfile::reference(SolverStrategy::ReverseScramble);
+
+    // A 2x2x2 takes well under a second to brute force, a 3x3x3 does not
+    assert_eq!(minimum_safe_size(&target, &brute_force, 1.0, 16), Some(3));
+    // Inverting the scramble never gets expensive, whatever the size
+    assert_eq!(minimum_safe_size(&target, &reverse, 1.0, 16), None);
+}
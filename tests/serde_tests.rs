@@ -0,0 +1,59 @@
+#![cfg(feature = "serde")]
+
+use qbitcoin_core::notation::parse_algorithm;
+use qbitcoin_core::{Axis, Color, ColorScheme, Cube, Face, Move};
+
+#[test]
+fn test_letters_and_notation() {
+    assert_eq!(serde_json::to_string(&Face::Front).unwrap(), r#""F""#);
+    assert_eq!(serde_json::to_string(&Color::Orange).unwrap(), r#""O""#);
+    let moves = parse_algorithm("R U2 F' Rw x M' 2L 3Bw2").unwrap();
+    let json = serde_json::to_string(&moves).unwrap();
+    assert_eq!(json, r#"["R","U2","F'","Rw","x","M'","2L","3Bw2"]"#);
+    assert_eq!(serde_json::from_str::<Vec<Move>>(&json).unwrap(), moves);
+    assert_eq!(serde_json::from_str::<Move>(r#""y2""#).unwrap(), Move::Rotation(Axis::Y, 2));
+
+    for face in Face::ALL {
+        assert_eq!(serde_json::from_value::<Face>(serde_json::to_value(face).unwrap()).unwrap(), face);
+    }
+    for color in Color::ALL {
+        assert_eq!(serde_json::from_value::<Color>(serde_json::to_value(color).unwrap()).unwrap(), color);
+    }
+
+    assert!(serde_json::from_str::<Face>(r#""X""#).is_err());
+    assert!(serde_json::from_str::<Color>(r#""WY""#).is_err());
+    assert!(serde_json::from_str::<Move>(r#""Q2""#).is_err());
+}
+
+#[test]
+fn test_cube_round_trip() {
+    let mut cube = Cube::new_with_scheme(3, ColorScheme::JAPANESE);
+    for m in parse_algorithm("R U2 F' L D' B2").unwrap() {
+        cube.apply_move(&m);
+    }
+    let json = serde_json::to_value(&cube).unwrap();
+    assert_eq!(json["size"], 3);
+    assert_eq!(json["scheme"], "WBYGRO");
+    assert_eq!(json["facelets"], cube.to_facelets());
+
+    let decoded: Cube = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded, cube);
+    assert_eq!(decoded.scheme(), cube.scheme());
+}
+
+#[test]
+fn test_cube_rejects_malformed_input() {
+    let solved = Cube::new(2).to_facelets();
+    let cube = |size: usize, scheme: &str, facelets: &str| {
+        serde_json::from_value::<Cube>(serde_json::json!({ "size": size, "scheme": scheme, "facelets": facelets }))
+    };
+    assert!(cube(2, "WYBGRO", &solved).is_ok());
+    assert!(cube(3, "WYBGRO", &solved).is_err());
+    assert!(cube(0, "WYBGRO", "").is_err());
+    assert!(cube(usize::MAX, "WYBGRO", &solved).is_err());
+    assert!(cube(2, "WYBGR", &solved).is_err());
+    assert!(cube(2, "WYBGRR", &solved).is_err());
+    assert!(cube(2, "WYBGROW", &solved).is_err());
+    assert!(cube(2, "WYBGRO", &solved.replace('W', "X")).is_err());
+    assert!(serde_json::from_str::<Cube>(r#"{"size":2,"scheme":"WYBGRO","facelets":"","extra":1}"#).is_err());
+}
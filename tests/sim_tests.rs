@@ -0,0 +1,106 @@
+use qbitcoin_core::retarget::{retarget, MAX_ADJUSTMENT_FACTOR};
+use qbitcoin_core::sim::{simulate, SimConfig, SolveRate};
+
+fn config(miners: Vec<SolveRate>, retarget_interval: u64, seed: u64) -> SimConfig {
+    SimConfig {
+        miners,
+        target_block_time_ms: 6_000,
+        retarget_interval,
+        initial_difficulty: 100,
+        blocks: 4_000,
+        seed,
+    }
+}
+
+#[test]
+fn test_retarget_rule() {
+    // Twice as fast as expected doubles the difficulty
+    assert_eq!(retarget(1000, 30_000, 60_000), 2000);
+    assert_eq!(retarget(1000, 120_000, 60_000), 500);
+    // Clamped to the maximum factor either way, never zero
+    assert_eq!(
+        retarget(1000, 1, 60_000),
+        1000 * MAX_ADJUSTMENT_FACTOR as u32
+    );
+    assert_eq!(
+        retarget(1000, u64::MAX, 60_000),
+        1000 / MAX_ADJUSTMENT_FACTOR as u32
+    );
+    assert_eq!(retarget(1, u64::MAX, 60_000), 1);
+    assert_eq!(retarget(u32::MAX, 1, 60_000), u32::MAX);
+}
+
+#[test]
+fn test_block_time_converges_across_parameter_sets() {
+    let parameter_sets = [
+        config(vec![SolveRate::Constant(50.0)], 20, 1),
+        config(
+            vec![
+                SolveRate::Constant(5.0),
+                SolveRate::Constant(500.0),
+                SolveRate::Constant(40.0),
+            ],
+            20,
+            2,
+        ),
+        config(vec![SolveRate::Constant(1_000.0); 8], 50, 3),
+        config(
+            vec![
+                SolveRate::Constant(20.0),
+                SolveRate::Ramp {
+                    from: 0.0,
+                    to: 400.0,
+                    blocks: 1_000,
+                },
+            ],
+            20,
+            4,
+        ),
+    ];
+
+    for (index, config) in parameter_sets.iter().enumerate() {
+        let result = simulate(config);
+        let mean = result.tail_mean_block_time_ms(40);
+        let target = config.target_block_time_ms as f64;
+        assert!(
+            (mean - target).abs() < target * 0.2,
+            "set {}: tail mean block time {} ms",
+            index,
+            mean
+        );
+        let spread = result.tail_difficulty_spread(40);
+        assert!(spread < 1.0, "set {}: difficulty spread {}", index, spread);
+    }
+}
+
+#[test]
+fn test_recovers_from_hashrate_loss() {
+    // Half the network leaves periodically; the retarget must follow without
+    // running away in either direction
+    let config = config(
+        vec![
+            SolveRate::Constant(100.0),
+            SolveRate::OnOff {
+                rate: 100.0,
+                period: 500,
+            },
+        ],
+        20,
+        5,
+    );
+    let result = simulate(&config);
+    let min = *result.difficulties[10..].iter().min().unwrap();
+    let max = *result.difficulties[10..].iter().max().unwrap();
+    assert!(
+        min > 200 && max < 3_000,
+        "difficulty range {}..{}",
+        min,
+        max
+    );
+}
+
+#[test]
+fn test_simulation_is_deterministic() {
+    let config = config(vec![SolveRate::Constant(50.0)], 20, 9);
+    assert_eq!(simulate(&config), simulate(&config));
+}
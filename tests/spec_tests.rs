@@ -0,0 +1,302 @@
+use qbitcoin_core::derivation::{derive_challenge_trace, derive_challenge_trace_with};
+use qbitcoin_core::kernel;
+use qbitcoin_core::spec::{self, ScrambleDraw, ScrambleFilter, ScrambleFilters, ScrambleSpec, SpecVersion};
+use qbitcoin_core::{notation, Axis, Color, Cube, Face, Move, Target};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Changing any value in this file is a consensus change
+
+#[test]
+fn test_challenge_seed_golden_vector() {
+    assert_eq!(
+        hex(&spec::challenge_seed(SpecVersion::V1, 7, b"spec")),
+        "3b906ceed4098cce5e34d4df5c1cf2e294c68ee023745a1bfa2ca908c6919415"
+    );
+}
+
+#[test]
+fn test_scramble_golden_vector() {
+    let seed = spec::challenge_seed(SpecVersion::V1, 7, b"spec");
+    let bytes: Vec<u8> = spec::scramble_moves(SpecVersion::V1, &seed)
+        .iter()
+        .map(|m| spec::encode_move(SpecVersion::V1, m))
+        .collect();
+    assert_eq!(
+        bytes,
+        [3, 6, 22, 5, 3, 14, 10, 15, 22, 2, 15, 19, 2, 14, 3, 17, 15, 2, 13, 7, 2, 6, 2]
+    );
+
+    // The cube applies exactly the specified moves
+    let mut cube = Cube::new(3);
+    assert_eq!(cube.scramble_deterministic(7, b"spec"), spec::scramble_moves(SpecVersion::V1, &seed));
+}
+
+#[test]
+fn test_v3_scramble_golden_vector() {
+    let seed = spec::challenge_seed(SpecVersion::V3, 7, b"spec");
+    let bytes: Vec<u8> = spec::scramble_moves(SpecVersion::V3, &seed)
+        .iter()
+        .map(|m| spec::encode_move(SpecVersion::V3, m))
+        .collect();
+    assert_eq!(
+        bytes,
+        [3, 10, 1, 22, 5, 21, 5, 22, 6, 10, 19, 9, 6, 23, 13, 22, 14, 3, 17, 2, 18, 2, 14]
+    );
+
+    // Rebuilt from the raw RNG words, one per draw
+    let below = |word: &u32, n: usize| ((u64::from(*word) * n as u64) >> 32) as usize;
+    let mut words = kernel::keystream(&seed).into_iter();
+    let length = 20 + below(&words.next().unwrap(), 11);
+    let mut moves: Vec<Move> = Vec::new();
+    for _ in 0..length {
+        let allowed: Vec<Face> = Face::ALL.into_iter().filter(|&face| Some(face) != moves.last().map(Move::face)).collect();
+        let face = allowed[below(&words.next().unwrap(), allowed.len())];
+        let count = 1 + below(&words.next().unwrap(), 3);
+        moves.push(Move::from_face_and_count(face, count));
+    }
+    assert_eq!(moves, spec::scramble_moves(SpecVersion::V3, &seed));
+}
+
+#[test]
+fn test_v3_scrambles_draw_one_word_each() {
+    let long = ScrambleSpec {
+        length: (60, 80),
+        filters: ScrambleFilters {
+            distinct_faces: true,
+            no_axis_runs: true,
+        },
+    };
+    let same_axis = |a: Face, b: Face| a == b || a == b.opposite();
+    for nonce in 0..50 {
+        let seed = spec::challenge_seed(SpecVersion::V3, nonce, b"spec");
+        let moves = spec::scramble_moves(SpecVersion::V3, &seed);
+        assert!((20..=30).contains(&moves.len()));
+        assert!(moves.windows(2).all(|w| w[0].face() != w[1].face()));
+        assert!(moves.iter().all(|m| (1..=3).contains(&m.count())));
+
+        let moves = spec::scramble_moves_with(SpecVersion::V3, &long, &seed);
+        assert!((60..=80).contains(&moves.len()));
+        assert!(moves
+            .windows(3)
+            .all(|w| !(same_axis(w[0].face(), w[2].face()) && same_axis(w[1].face(), w[2].face()))));
+
+        // No draw is ever turned down
+        let trace = derive_challenge_trace_with(SpecVersion::V3, &long, b"spec", nonce);
+        assert_eq!(trace.draws.len(), 1 + 2 * trace.moves.len());
+    }
+}
+
+#[test]
+fn test_scramble_parameters() {
+    assert_eq!(spec::SCRAMBLE_LENGTH, (20, 30));
+    assert_eq!(spec::SCRAMBLE_MAX_TURNS, 3);
+    for nonce in 0..50 {
+        let seed = spec::challenge_seed(SpecVersion::V1, nonce, b"spec");
+        let moves = spec::scramble_moves(SpecVersion::V1, &seed);
+        assert!((20..=30).contains(&moves.len()));
+        assert!(moves.windows(2).all(|w| w[0].face() != w[1].face()));
+        assert!(moves.iter().all(|m| (1..=3).contains(&m.count())));
+    }
+}
+
+#[test]
+fn test_scramble_spec() {
+    assert_eq!(spec::MAX_SCRAMBLE_LENGTH, 1000);
+    assert_eq!(ScrambleSpec::default(), ScrambleSpec::V1);
+    assert_eq!(ScrambleSpec::V1.length, spec::SCRAMBLE_LENGTH);
+    assert!(ScrambleSpec::V1.is_valid());
+    for length in [(0, 5), (6, 5), (10, 1001)] {
+        assert!(!ScrambleSpec { length, ..ScrambleSpec::V1 }.is_valid());
+    }
+
+    // V1 scrambles are what every chain so far derived
+    for nonce in 0..20 {
+        let seed = spec::challenge_seed(SpecVersion::V1, nonce, b"spec");
+        assert_eq!(
+            spec::scramble_moves_with(SpecVersion::V1, &ScrambleSpec::V1, &seed),
+            spec::scramble_moves(SpecVersion::V1, &seed)
+        );
+    }
+
+    let long = ScrambleSpec {
+        length: (60, 80),
+        filters: ScrambleFilters {
+            distinct_faces: true,
+            no_axis_runs: true,
+        },
+    };
+    let same_axis = |a: Face, b: Face| a == b || a == b.opposite();
+    for nonce in 0..20 {
+        let seed = spec::challenge_seed(SpecVersion::V1, nonce, b"spec");
+        let moves = spec::scramble_moves_with(SpecVersion::V1, &long, &seed);
+        assert!((60..=80).contains(&moves.len()));
+        assert!(moves.windows(2).all(|w| w[0].face() != w[1].face()));
+        assert!(moves
+            .windows(3)
+            .all(|w| !(same_axis(w[0].face(), w[2].face()) && same_axis(w[1].face(), w[2].face()))));
+    }
+}
+
+#[test]
+fn test_challenge_trace() {
+    let trace = derive_challenge_trace(b"spec", 7);
+    assert_eq!(trace.seed_input, [&7u64.to_le_bytes()[..], b"spec"].concat());
+    assert_eq!(trace.seed, spec::challenge_seed(SpecVersion::CURRENT, 7, b"spec"));
+    assert_eq!(trace.color_input, [spec::COLOR_PERMUTATION_DOMAIN, &trace.seed[..]].concat());
+    assert_eq!(trace.colors, spec::color_permutation(SpecVersion::CURRENT, &trace.seed));
+    assert_eq!(trace.color_swaps.len(), 5);
+    assert_eq!(trace.moves, spec::scramble_moves(SpecVersion::CURRENT, &trace.seed));
+    assert_eq!(trace.draws.first(), Some(&ScrambleDraw::Length(trace.moves.len() as u32)));
+    assert!(trace.to_string().ends_with(&notation::format_algorithm(&trace.moves)));
+
+    // Accepted faces and counts rebuild the scramble
+    let long = ScrambleSpec {
+        length: (60, 80),
+        filters: ScrambleFilters {
+            distinct_faces: true,
+            no_axis_runs: true,
+        },
+    };
+    let mut rejected = [0; 2];
+    for nonce in 0..20 {
+        let trace = derive_challenge_trace_with(SpecVersion::V1, &long, b"spec", nonce);
+        assert_eq!(trace.moves, spec::scramble_moves_with(SpecVersion::V1, &long, &trace.seed));
+        let mut face = None;
+        let mut moves = Vec::new();
+        for draw in &trace.draws[1..] {
+            match *draw {
+                ScrambleDraw::Face { face: drawn, rejected_by: None } => face = Some(drawn),
+                ScrambleDraw::Face { rejected_by: Some(filter), .. } => rejected[filter as usize] += 1,
+                ScrambleDraw::Count(count) => moves.push(Move::from_face_and_count(face.take().unwrap(), count)),
+                ScrambleDraw::Length(_) => panic!("one length per scramble"),
+            }
+        }
+        assert_eq!(moves, trace.moves);
+    }
+    assert!(rejected[ScrambleFilter::DistinctFaces as usize] > 0);
+    assert!(rejected[ScrambleFilter::NoAxisRuns as usize] > 0);
+}
+
+#[test]
+fn test_challenge_commitment_golden_vector() {
+    let seed = spec::challenge_seed(SpecVersion::V1, 7, b"spec");
+    assert_eq!(
+        hex(&spec::challenge_commitment(SpecVersion::V1, &seed, 3, &Target::MAX)),
+        "a39b87af9e83d135ce578675c1837f3da511328d93338ae47f0d258c6515faa5"
+    );
+}
+
+#[test]
+fn test_next_challenge_seed_golden_vector() {
+    let seed = spec::challenge_seed(SpecVersion::V1, 7, b"spec");
+    let commitment = spec::challenge_commitment(SpecVersion::V1, &seed, 3, &Target::MAX);
+    assert_eq!(
+        hex(&spec::next_challenge_seed(SpecVersion::V1, &commitment)),
+        "63cf6daf26865bd5afb5f1ea11dfa7f038d365555b56a8d061d8825a282fec56"
+    );
+}
+
+#[test]
+fn test_challenge_header_layout() {
+    let header = spec::challenge_header(SpecVersion::V1, 3, &[7; 32], &[9; 32]);
+    assert_eq!(header.len(), 36);
+    assert_eq!(header[..4], [3, 0, 0, 0]);
+    assert!(header[4..].iter().all(|&b| b == 7));
+
+    // V2 binds the block's transactions
+    let bound = spec::challenge_header(SpecVersion::V2, 3, &[7; 32], &[9; 32]);
+    assert_eq!(bound.len(), 68);
+    assert_eq!(bound[..36], header[..]);
+    assert!(bound[36..].iter().all(|&b| b == 9));
+    assert_ne!(bound, spec::challenge_header(SpecVersion::V2, 3, &[7; 32], &[0; 32]));
+
+    for version in SpecVersion::ALL {
+        let depends = spec::challenge_header(version, 3, &[7; 32], &[9; 32])
+            != spec::challenge_header(version, 3, &[7; 32], &[0; 32]);
+        assert_eq!(spec::binds_template(version), depends, "{:?}", version);
+    }
+}
+
+#[test]
+fn test_color_permutation_golden_vector() {
+    let seed = spec::challenge_seed(SpecVersion::V1, 7, b"spec");
+    assert_eq!(
+        spec::color_permutation(SpecVersion::V1, &seed),
+        [Color::Red, Color::Yellow, Color::White, Color::Orange, Color::Green, Color::Blue]
+    );
+}
+
+#[test]
+fn test_domains() {
+    assert_eq!(spec::CHALLENGE_DOMAIN, b"qbitcoin/challenge");
+    assert_eq!(spec::COLOR_PERMUTATION_DOMAIN, b"qbitcoin/color-permutation");
+    assert_eq!(spec::NEXT_SEED_DOMAIN, b"qbitcoin/next-seed");
+    assert_eq!(spec::STATE_DOMAIN_V1, b"qbitcoin/state/v1");
+    assert_eq!(spec::STATE_DOMAIN_V2, b"qbitcoin/state/v2");
+}
+
+#[test]
+fn test_move_encoding() {
+    let cases = [
+        (Move::U(1), 1),
+        (Move::R(1), 13),
+        (Move::B(3), 23),
+        (Move::Wide(Face::Up, 2), 26),
+        (Move::Wide(Face::Back, 3), 47),
+        (Move::Rotation(Axis::X, 1), 49),
+        (Move::Rotation(Axis::Z, 3), 59),
+    ];
+    for (m, byte) in cases {
+        assert_eq!(spec::encode_move(SpecVersion::V1, &m), byte);
+        assert_eq!(spec::decode_move(SpecVersion::V1, byte), Some(m));
+    }
+    assert_eq!(spec::decode_move(SpecVersion::V1, spec::SLICE_MOVE_BASE), None);
+
+    // Slices only decode from V2
+    for (m, byte) in [(Move::Slice(Axis::X, 1), 61), (Move::Slice(Axis::Y, 2), 66), (Move::Slice(Axis::Z, 3), 71)] {
+        assert_eq!(spec::encode_move(SpecVersion::V2, &m), byte);
+        assert_eq!(spec::decode_move(SpecVersion::V2, byte), Some(m));
+        assert_eq!(spec::decode_move(SpecVersion::V1, byte), None);
+    }
+    assert_eq!(spec::decode_move(SpecVersion::V2, spec::MOVE_BYTE_END), None);
+
+    // Numbered layers have no tag yet
+    for version in SpecVersion::ALL {
+        for m in [Move::Layer(Face::Right, 2, 1), Move::Block(Face::Up, 3, 2)] {
+            assert_eq!(spec::encode_move(version, &m), spec::MOVE_EXTENSION_TAG);
+            assert!(!spec::assigns_move(version, &m));
+        }
+    }
+}
+
+#[test]
+fn test_move_tag_ranges() {
+    assert_eq!(spec::FACE_MOVE_BASE, 0);
+    assert_eq!(spec::WIDE_MOVE_BASE, 24);
+    assert_eq!(spec::ROTATION_MOVE_BASE, 48);
+    assert_eq!(spec::SLICE_MOVE_BASE, 60);
+    assert_eq!(spec::MOVE_BYTE_END, 72);
+    assert_eq!(spec::RESERVED_CUBE_MOVES, 72..128);
+    assert_eq!(spec::RESERVED_PUZZLE_MOVES, 128..255);
+    assert_eq!(spec::MOVE_EXTENSION_TAG, 255);
+
+    // Every assigned tag round-trips, everything else is rejected
+    for version in SpecVersion::ALL {
+        let end = match version {
+            SpecVersion::V1 => spec::SLICE_MOVE_BASE,
+            SpecVersion::V2 | SpecVersion::V3 => spec::MOVE_BYTE_END,
+        };
+        for byte in 0..=u8::MAX {
+            match spec::decode_move(version, byte) {
+                Some(m) => {
+                    assert!(byte < end);
+                    assert_eq!(spec::encode_move(version, &m), byte);
+                }
+                None => assert!(byte >= end, "byte {}", byte),
+            }
+        }
+    }
+}
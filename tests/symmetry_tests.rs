@@ -0,0 +1,44 @@
+use std::collections::HashSet;
+
+use qbitcoin_core::{Cube, Symmetry};
+
+#[test]
+fn test_symmetry_group_size() {
+    let all = Symmetry::all();
+    assert_eq!(all.len(), 48);
+    assert_eq!(all.iter().collect::<HashSet<_>>().len(), 48);
+    assert_eq!(Symmetry::rotations().len(), 24);
+    assert_eq!(all[0], Symmetry::IDENTITY);
+}
+
+#[test]
+fn test_symmetries_map_faces_to_faces() {
+    for size in 2..=5 {
+        let cube = Cube::new(size);
+        let mut forms = HashSet::new();
+        let mut recolored_forms = HashSet::new();
+
+        for symmetry in Symmetry::all() {
+            let image = cube.transformed(&symmetry);
+            // Whole faces move together, so the image still has uniform faces
+            assert!(image.is_solved());
+            forms.insert(image.canonical_form());
+            recolored_forms.insert(image.canonical_form_up_to_recoloring());
+        }
+
+        // Every image shares the same canonical form
+        assert_eq!(forms.len(), 1);
+        assert_eq!(recolored_forms.len(), 1);
+    }
+}
+
+#[test]
+fn test_canonical_form_is_a_fixed_point() {
+    let cube = Cube::new(3);
+    let canonical = cube.canonical_form();
+
+    for symmetry in Symmetry::all() {
+        assert_eq!(cube.transformed(&symmetry).canonical_form(), canonical);
+    }
+    assert!(cube.canonical_form_up_to_recoloring() <= canonical);
+}
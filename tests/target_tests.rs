@@ -0,0 +1,117 @@
+use qbitcoin_core::{Comparison, Cube, Prefix, Target};
+
+#[test]
+fn test_target_big_endian_comparison() {
+    let mut target = [0u8; 32];
+    target[0] = 0x01;
+    let target = Target::from_bytes(target);
+
+    // A hash with a smaller most significant byte is below the target even if
+    // its trailing bytes are larger
+    let mut below = [0xFF; 32];
+    below[0] = 0x00;
+    assert!(target.is_met_by(&below));
+
+    let mut above = [0u8; 32];
+    above[0] = 0x01;
+    above[31] = 0x01;
+    assert!(!target.is_met_by(&above));
+
+    // Equality meets the target
+    assert!(target.is_met_by(target.as_bytes()));
+}
+
+#[test]
+fn test_constant_time_matches_fast_path() {
+    let target = Target::from_difficulty(1000);
+    let samples = [
+        [0x00; 32],
+        [0xFF; 32],
+        *target.as_bytes(),
+        *Target::from_difficulty(999).as_bytes(),
+        *Target::from_difficulty(1001).as_bytes(),
+    ];
+
+    for hash in samples.iter() {
+        assert_eq!(
+            target.check(hash, Comparison::Fast),
+            target.check(hash, Comparison::ConstantTime)
+        );
+    }
+}
+
+#[test]
+fn test_target_from_difficulty() {
+    assert_eq!(Target::from_difficulty(1), Target::MAX);
+    assert_eq!(Target::from_difficulty(0), Target::MAX);
+
+    // MAX / 256 shifts the target right by one byte
+    let mut expected = [0xFF; 32];
+    expected[0] = 0x00;
+    assert_eq!(Target::from_difficulty(256), Target::from_bytes(expected));
+
+    // Higher difficulty means a lower target
+    assert!(Target::from_difficulty(5000) < Target::from_difficulty(4000));
+}
+
+#[test]
+fn test_cube_meets_target() {
+    let cube = Cube::new(3);
+    assert!(cube.meets_target(&Target::MAX, Comparison::ConstantTime));
+    assert!(!cube.meets_target(&Target::from_bytes([0x00; 32]), Comparison::ConstantTime));
+}
+
+#[test]
+fn test_saturating_mul() {
+    let target = Target::from_difficulty(1000);
+    assert_eq!(target.saturating_mul(1), target);
+    assert_eq!(target.saturating_mul(0), Target::from_bytes([0; 32]));
+
+    // MAX / 1000 * 10 is roughly MAX / 100, never above it
+    let relaxed = target.saturating_mul(10);
+    assert!(relaxed <= Target::from_difficulty(100));
+    assert!(relaxed > Target::from_difficulty(101));
+
+    assert_eq!(Target::MAX.saturating_mul(2), Target::MAX);
+}
+
+#[test]
+fn test_prefix_matches_leading_bytes() {
+    let mut hash = [0x55; 32];
+    hash[0] = 0xAB;
+    hash[1] = 0xCD;
+
+    assert!(Prefix::new(&[]).unwrap().is_met_by(&hash));
+    assert!(Prefix::new(&[0xAB]).unwrap().is_met_by(&hash));
+    assert!(Prefix::new(&[0xAB, 0xCD]).unwrap().is_met_by(&hash));
+    assert!(!Prefix::new(&[0xAB, 0xCE]).unwrap().is_met_by(&hash));
+    assert!(Prefix::new(&hash).unwrap().is_met_by(&hash));
+
+    assert_eq!(Prefix::new(&[0xAB, 0xCD]).unwrap().as_bytes(), &[0xAB, 0xCD]);
+    assert!(Prefix::new(&[0; 33]).is_none());
+}
+
+#[test]
+fn test_truncated_prefix_keeps_leading_bytes_of_the_hash() {
+    let mut hash = [0u8; 32];
+    for (i, byte) in hash.iter_mut().enumerate() {
+        *byte = i as u8 + 1;
+    }
+
+    let prefix = Prefix::truncated(&hash, 3);
+    assert_eq!(prefix.as_bytes(), &[1, 2, 3]);
+    assert_eq!(prefix.len(), 3);
+    assert!(prefix.is_met_by(&hash));
+    // Only the leading bytes count; a difference right after them does not
+    let mut other = hash;
+    other[3] ^= 0xFF;
+    assert!(prefix.is_met_by(&other));
+    other[2] ^= 0xFF;
+    assert!(!prefix.is_met_by(&other));
+
+    // Lengths past the hash are clamped, and zero matches everything
+    assert_eq!(Prefix::truncated(&hash, 40).as_bytes(), &hash);
+    let empty = Prefix::truncated(&hash, 0);
+    assert!(empty.is_empty());
+    assert!(empty.is_met_by(&[0xFF; 32]));
+}
@@ -0,0 +1,92 @@
+use std::future::Future;
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use qbitcoin_core::solver::ReverseScramble;
+use qbitcoin_core::task::{
+    solve_async, verify_async, CancelToken, Cancelled, Job, RayonExecutor, ThreadExecutor,
+};
+use qbitcoin_core::{Cube, PowSeal, Target, VerifyError};
+
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Minimal executor-agnostic `block_on`: polls, parking between wake-ups.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+#[test]
+fn test_solve_async_matches_solve() {
+    let mut cube = Cube::new(3);
+    let scramble = cube.scramble_deterministic(4, b"task");
+
+    let cancel = CancelToken::new();
+    let handle = solve_async(&RayonExecutor, Arc::new(ReverseScramble), cube.clone(), scramble, &cancel);
+    let solution = block_on(handle).unwrap().expect("reverse scramble always solves");
+    assert!(cube.verify_solution(&solution));
+}
+
+#[test]
+fn test_verify_async_reports_errors() {
+    let seal = PowSeal {
+        cube_size: 3,
+        nonce: 4,
+        moves: Vec::new(),
+    };
+    let cancel = CancelToken::new();
+    let handle = verify_async(&ThreadExecutor, seal, b"task".to_vec(), Target::MAX, &cancel);
+    assert_eq!(block_on(handle).unwrap().err(), Some(VerifyError::InvalidSolution));
+}
+
+#[test]
+fn test_closure_executor_and_cancel_before_start() {
+    // Holds jobs until told to run them
+    let queued: Arc<Mutex<Vec<Job>>> = Arc::default();
+    let executor = {
+        let queued = Arc::clone(&queued);
+        move |job: Job| queued.lock().unwrap().push(job)
+    };
+
+    let cancel = CancelToken::new();
+    let mut cube = Cube::new(2);
+    let scramble = cube.scramble_deterministic(1, b"task");
+    let handle = solve_async(&executor, Arc::new(ReverseScramble), cube, scramble, &cancel);
+    assert!(!handle.is_finished());
+
+    cancel.cancel();
+    assert_eq!(block_on(handle), Err(Cancelled));
+
+    // The queued job sees the cancellation and does nothing
+    let job = queued.lock().unwrap().pop().unwrap();
+    job();
+}
+
+#[test]
+fn test_cancel_wakes_a_pending_handle() {
+    let cancel = CancelToken::new();
+    // A job that never runs keeps the handle pending until cancelled
+    let handle = qbitcoin_core::task::spawn(&|_job: Job| {}, &cancel, || 1);
+
+    let canceller = {
+        let cancel = cancel.clone();
+        thread::spawn(move || cancel.cancel())
+    };
+    assert_eq!(block_on(handle), Err(Cancelled));
+    canceller.join().unwrap();
+}
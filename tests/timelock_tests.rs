@@ -0,0 +1,44 @@
+use qbitcoin_core::timelock::TimelockChallenge;
+
+#[test]
+fn test_evaluate_and_verify() {
+    let challenge = TimelockChallenge::new([7; 32], 3, 1000);
+    let proof = challenge.evaluate(64);
+    assert_eq!(proof.checkpoints.len(), 16);
+    assert!(challenge.verify(&proof));
+
+    // Checkpoint spacing does not change the result
+    assert_eq!(challenge.evaluate(1000).output, proof.output);
+    assert_ne!(TimelockChallenge::new([8; 32], 3, 1000).evaluate(64).output, proof.output);
+    assert_ne!(TimelockChallenge::new([7; 32], 3, 999).evaluate(64).output, proof.output);
+}
+
+#[test]
+fn test_verify_rejects_tampered_proofs() {
+    let challenge = TimelockChallenge::new([1; 32], 3, 200);
+    let proof = challenge.evaluate(50);
+
+    let mut wrong_output = proof.clone();
+    wrong_output.output[0] ^= 1;
+    assert!(!challenge.verify(&wrong_output));
+
+    let mut wrong_checkpoint = proof.clone();
+    wrong_checkpoint.checkpoints.swap(0, 1);
+    assert!(!challenge.verify(&wrong_checkpoint));
+
+    let mut missing = proof.clone();
+    missing.checkpoints.pop();
+    assert!(!challenge.verify(&missing));
+
+    let mut respaced = proof;
+    respaced.interval = 40;
+    assert!(!challenge.verify(&respaced));
+}
+
+#[test]
+fn test_zero_iterations() {
+    let challenge = TimelockChallenge::new([0; 32], 2, 0);
+    let proof = challenge.evaluate(10);
+    assert!(proof.checkpoints.is_empty());
+    assert!(challenge.verify(&proof));
+}
@@ -0,0 +1,60 @@
+use qbitcoin_core::optimal::OptimalSolver;
+use qbitcoin_core::solver::{ReverseScramble, Solver};
+use qbitcoin_core::transcript::annotate_solution;
+use qbitcoin_core::{Cube, Metric, Move};
+
+#[test]
+fn test_transcript_follows_the_solution() {
+    let mut cube = Cube::new(3);
+    let scramble = [Move::R(1), Move::U(2)];
+    for m in &scramble {
+        cube.apply_move(m);
+    }
+    let solution = ReverseScramble.solve(&cube, &scramble).unwrap();
+    let transcript = annotate_solution(&cube, &solution);
+
+    assert_eq!(transcript.pieces, 26);
+    assert!(transcript.initially_solved < 26);
+    assert!(transcript.solves);
+    assert_eq!(transcript.moves.len(), 2);
+    let last = &transcript.moves[1];
+    assert_eq!(last.solved_pieces, 26);
+    assert_eq!(last.length(Metric::Htm), 2);
+    assert_eq!(last.length(Metric::Qtm), 3);
+    assert_eq!(last.distance, None);
+
+    // Pieces only change state through the moves
+    let mut solved = transcript.initially_solved;
+    for step in &transcript.moves {
+        solved = solved + step.newly_solved - step.newly_unsolved;
+        assert_eq!(solved, step.solved_pieces);
+    }
+    // Undoing U2 leaves only R's 8 pieces out of place
+    assert_eq!(transcript.moves[0].solved_pieces, 18);
+    assert_eq!(
+        transcript.moves[0].to_string(),
+        format!(
+            "1. U2 HTM 1 QTM 2 STM 1, 18 in place (+{})",
+            18 - transcript.initially_solved
+        )
+    );
+}
+
+#[test]
+fn test_transcript_counts_down_on_2x2() {
+    let mut cube = Cube::new(2);
+    let scramble = cube.scramble_deterministic(3, b"transcript");
+    let solution = OptimalSolver.solve(&cube, &scramble).unwrap();
+    let transcript = annotate_solution(&cube, &solution);
+    assert_eq!(transcript.pieces, 8);
+    let distances: Vec<Option<u8>> = transcript.moves.iter().map(|step| step.distance).collect();
+    let expected: Vec<Option<u8>> = (0..solution.len() as u8).rev().map(Some).collect();
+    assert_eq!(distances, expected);
+    assert!(transcript.solves);
+
+    // A move away from solved is no progress
+    let transcript = annotate_solution(&Cube::new(2), &[Move::F(1)]);
+    assert_eq!(transcript.initially_solved, 8);
+    assert_eq!(transcript.moves[0].newly_unsolved, 4);
+    assert_eq!(transcript.moves[0].distance, Some(1));
+}
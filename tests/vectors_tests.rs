@@ -0,0 +1,104 @@
+use qbitcoin_core::spec::{challenge_seed, scramble_moves_with};
+use qbitcoin_core::vectors::{
+    certify, challenge_vectors, challenge_vectors_json, decode, encode, vectors, ChallengeVector,
+};
+use qbitcoin_core::{CommitmentVersion, Cube, ScrambleSpec, SpecVersion, Target};
+use sha3::{Digest, Sha3_256};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[test]
+fn test_vectors_round_trip() {
+    let all = vectors();
+    assert!(all.iter().any(|vector| vector.name.ends_with("verify") && vector.output.contains(&0)));
+    let encoded = encode(&all);
+    assert_eq!(decode(&encoded), Some(all));
+    assert_eq!(decode(&encoded[..encoded.len() - 1]), None);
+}
+
+#[test]
+fn test_vectors_record_every_draw_of_every_version() {
+    let all = vectors();
+    for spec in SpecVersion::ALL {
+        let draws: Vec<_> = all.iter().filter(|vector| vector.name.contains(&format!("{spec:?} scramble spec"))).collect();
+        assert_eq!(draws.len(), 6, "{spec:?}");
+        // A length draw, then faces with the filter that rejected them and counts
+        let mut rejected = false;
+        for vector in &draws {
+            assert_eq!(vector.output[0], 0);
+            let mut rest = &vector.output[5..];
+            while let [tag, tail @ ..] = rest {
+                match (tag, tail) {
+                    (1, [face, filter, tail @ ..]) if *face < 6 && *filter <= 2 => {
+                        rejected |= *filter != 0;
+                        rest = tail;
+                    }
+                    (2, [count, tail @ ..]) if (1..=3).contains(count) => rest = tail,
+                    _ => panic!("{}: malformed draw at {:?}", vector.name, rest),
+                }
+            }
+        }
+        // The filtered spec makes V1 and V2 redraw faces; V3 never draws a rejected one
+        assert_eq!(rejected, spec != SpecVersion::V3, "{spec:?}");
+    }
+}
+
+// Changing this value is a consensus change, unless the inputs changed
+#[cfg(not(feature = "color-shuffle"))]
+#[test]
+fn test_vectors_golden_digest() {
+    assert_eq!(hex(&qbitcoin_core::vectors::digest()), "4094d9ba4dfcd215f3313655158634cf4e7d3569c6e49803b1e780f2b4652e94");
+}
+
+/// Derives a vector from the spec functions rather than `Cube::scramble_with`.
+fn derive(input: &ChallengeVector) -> Option<ChallengeVector> {
+    let seed = challenge_seed(input.spec, input.nonce, &input.header);
+    let scramble = scramble_moves_with(input.spec, &ScrambleSpec::V1, &seed);
+    let mut cube = Cube::new(input.cube_size as usize);
+    for m in &scramble {
+        cube.apply_move(m);
+    }
+    let state_commitment = cube.state_commitment_with(input.commitment);
+    let meets_target = input
+        .meets_target
+        .iter()
+        .map(|&(difficulty, _)| (difficulty, Target::from_difficulty(difficulty).is_met_by(&state_commitment)))
+        .collect();
+    Some(ChallengeVector { seed, scramble, state_commitment, meets_target, ..input.clone() })
+}
+
+#[test]
+fn test_challenge_vectors_certify_an_independent_derivation() {
+    for spec in SpecVersion::ALL {
+        for commitment in CommitmentVersion::ALL {
+            let all = challenge_vectors(spec, commitment);
+            assert_eq!(all.len(), 12);
+            assert!(all.iter().all(|vector| vector.meets_target[0] == (1, true)));
+        }
+    }
+    assert_eq!(certify(derive), Ok(72));
+
+    // A miner that commits under the wrong version is caught
+    let mismatch = certify(|input| {
+        derive(&ChallengeVector { commitment: CommitmentVersion::V1FaceIds, ..input.clone() })
+            .map(|derived| ChallengeVector { commitment: input.commitment, ..derived })
+    })
+    .unwrap_err();
+    assert_eq!(mismatch.expected.commitment, CommitmentVersion::V2Packed);
+    assert_eq!(mismatch.field, "state_commitment");
+    assert_eq!(certify(|_| None).unwrap_err().field, "vector");
+}
+
+#[test]
+fn test_challenge_vectors_json() {
+    let json = challenge_vectors_json();
+    assert!(json.starts_with(r#"[{"spec":"V1","commitment":"V1FaceIds","cube_size":2,"nonce":0,"header":"","seed":""#));
+    assert_eq!(json.matches(r#""spec":"V1""#).count(), 24);
+    assert_eq!(json.matches(r#""spec":"V2""#).count(), 24);
+    assert_eq!(json.matches(r#""spec":"V3""#).count(), 24);
+    assert_eq!(json.matches(r#"{"difficulty":1,"met":true}"#).count(), 72);
+    // Changing this value is a consensus change, unless the inputs changed
+    assert_eq!(hex(&Sha3_256::digest(json.as_bytes())), "e780ecca48600580cc10c320095911047c034708aa2a09a47ddf8f3f0128542b");
+}
@@ -0,0 +1,102 @@
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use qbitcoin_core::miner::{attempt, BlockTemplate};
+use qbitcoin_core::solver::ReverseScramble;
+use qbitcoin_core::verifier::{SubmitError, VerifierConfig, VerifierService, VerifyJob};
+use qbitcoin_core::{PowSeal, Target, VerifyError};
+
+const HEADER: &[u8] = b"verifier";
+
+fn job(nonce: u64) -> VerifyJob {
+    let template = BlockTemplate {
+        parent_hash: [0; 32],
+        header: HEADER.to_vec(),
+        cube_size: 3,
+        target: Target::MAX,
+    };
+    VerifyJob {
+        seal: attempt(&ReverseScramble, &template, nonce).expect("reverse scramble solves every challenge"),
+        header: HEADER.to_vec(),
+        target: Target::MAX,
+    }
+}
+
+fn config(workers: usize, per_peer_queue: usize, max_queued: usize) -> VerifierConfig {
+    VerifierConfig {
+        workers,
+        per_peer_queue,
+        max_queued,
+    }
+}
+
+#[test]
+fn test_verifier_reports_every_result() {
+    let (service, results) = VerifierService::start(config(4, 64, 1024));
+
+    let mut expected = BTreeSet::new();
+    for peer in 0..4 {
+        for nonce in 0..8 {
+            let id = service.submit(peer, job(nonce)).unwrap();
+            expected.insert((peer, id));
+        }
+    }
+    let invalid = VerifyJob {
+        seal: PowSeal {
+            cube_size: 3,
+            nonce: 1,
+            moves: Vec::new(),
+        },
+        header: HEADER.to_vec(),
+        target: Target::MAX,
+    };
+    let invalid_id = service.submit(9, invalid).unwrap();
+
+    let mut seen = BTreeSet::new();
+    for _ in 0..expected.len() + 1 {
+        let outcome = results.recv_timeout(Duration::from_secs(30)).unwrap();
+        if outcome.id == invalid_id {
+            assert_eq!(outcome.peer, 9);
+            assert_eq!(outcome.result, Err(VerifyError::InvalidSolution));
+        } else {
+            assert_eq!(outcome.result, Ok(()));
+            assert!(seen.insert((outcome.peer, outcome.id)));
+        }
+    }
+    assert_eq!(seen, expected);
+    assert_eq!(service.queued(), 0);
+}
+
+#[test]
+fn test_verifier_refuses_past_peer_limit() {
+    let (service, _results) = VerifierService::start(config(1, 0, 8));
+    assert_eq!(service.submit(1, job(0)), Err(SubmitError::PeerQueueFull));
+}
+
+#[test]
+fn test_verifier_refuses_past_global_limit() {
+    let (service, _results) = VerifierService::start(config(1, 8, 0));
+    assert_eq!(service.submit(1, job(0)), Err(SubmitError::QueueFull));
+}
+
+#[test]
+fn test_verifier_forget_peer_empties_its_queue() {
+    let (service, _results) = VerifierService::start(config(1, 64, 1024));
+    for nonce in 0..32 {
+        service.submit(1, job(nonce)).unwrap();
+    }
+    let dropped = service.forget_peer(1);
+    assert!(dropped <= 32);
+    assert_eq!(service.queued_for(1), 0);
+    assert_eq!(service.queued(), 0);
+    assert_eq!(service.forget_peer(1), 0);
+}
+
+#[test]
+fn test_verifier_stops_on_drop() {
+    let (service, results) = VerifierService::start(config(2, 4, 8));
+    service.submit(1, job(0)).unwrap();
+    drop(service);
+    // The workers are gone, so the channel closes once drained
+    while results.recv_timeout(Duration::from_secs(30)).is_ok() {}
+}
@@ -0,0 +1,188 @@
+use qbitcoin_core::{
+    invert_sequence, verify_seal, verify_seal_matching, verify_seal_with_stats, verify_seal_with_versions, Axis, CommitmentVersion,
+    Cube, Face, Move, PowSeal, Prefix, SpecVersion, Target, VerifyError,
+};
+
+fn solved_seal(size: u32, nonce: u64, header: &[u8]) -> PowSeal {
+    let mut cube = Cube::new(size as usize);
+    let scramble = cube.scramble_deterministic(nonce, header);
+    let moves = invert_sequence(&scramble);
+    PowSeal {
+        cube_size: size,
+        nonce,
+        moves,
+    }
+}
+
+#[test]
+fn test_valid_seal_is_accepted() {
+    let seal = solved_seal(3, 42, b"header");
+    assert!(verify_seal(&seal, b"header", &Target::MAX).is_ok());
+}
+
+#[test]
+fn test_rejection_reasons() {
+    let seal = solved_seal(3, 42, b"header");
+
+    let tiny = PowSeal {
+        cube_size: 1,
+        ..seal.clone()
+    };
+    assert_eq!(
+        verify_seal(&tiny, b"header", &Target::MAX).unwrap_err(),
+        VerifyError::CubeTooSmall
+    );
+
+    // Crafted sizes are rejected before anything is allocated
+    let huge = PowSeal {
+        cube_size: u32::MAX,
+        ..seal.clone()
+    };
+    assert_eq!(
+        verify_seal(&huge, b"header", &Target::MAX).unwrap_err(),
+        VerifyError::CubeTooLarge
+    );
+
+    let mut wrong = seal.clone();
+    wrong.moves.push(Move::F(1));
+    assert_eq!(
+        verify_seal(&wrong, b"header", &Target::MAX).unwrap_err(),
+        VerifyError::InvalidSolution
+    );
+
+    let impossible = Target::from_bytes([0; 32]);
+    assert_eq!(
+        verify_seal(&seal, b"header", &impossible).unwrap_err(),
+        VerifyError::TargetNotMet
+    );
+}
+
+#[test]
+fn test_codes_round_trip() {
+    for error in VerifyError::ALL {
+        assert_eq!(VerifyError::from_code(error.code()), Some(error));
+        assert_eq!(error.to_string(), error.name());
+    }
+    assert_eq!(VerifyError::from_code(0), None);
+}
+
+#[test]
+fn test_vanity_prefix() {
+    // Find a nonce whose commitment starts with 0x00
+    let prefix = Prefix::new(&[0x00]).unwrap();
+    let seal = (0..)
+        .map(|nonce| solved_seal(2, nonce, b"vanity"))
+        .find(|seal| verify_seal_matching(seal, b"vanity", &prefix).is_ok())
+        .unwrap();
+
+    let cube = verify_seal_matching(&seal, b"vanity", &prefix).unwrap();
+    assert_eq!(cube.state_commitment()[0], 0x00);
+
+    let other = Prefix::new(&[cube.state_commitment()[0] ^ 1]).unwrap();
+    assert_eq!(
+        verify_seal_matching(&seal, b"vanity", &other).unwrap_err(),
+        VerifyError::TargetNotMet
+    );
+    // A target is just another check
+    assert!(verify_seal_matching(&seal, b"vanity", &Target::MAX).is_ok());
+}
+
+#[test]
+fn test_stats_count_replayed_moves() {
+    let mut seal = solved_seal(3, 42, b"header");
+    let scramble_len = seal.moves.len() as u64;
+    // (R U R' U') six times is the identity but does not cancel move by move
+    for _ in 0..6 {
+        seal.moves.extend([Move::R(1), Move::U(1), Move::R(3), Move::U(3)]);
+    }
+    let (result, stats) = verify_seal_with_stats(&seal, b"header", &Target::MAX);
+    assert!(result.is_ok());
+
+    assert!(!stats.fast_path);
+    assert_eq!(stats.moves_applied, scramble_len + seal.moves.len() as u64);
+    assert_eq!(stats.stickers_touched, stats.moves_applied * 54);
+    assert!(stats.ns_elapsed > 0);
+
+    let tiny = PowSeal {
+        cube_size: 1,
+        ..seal
+    };
+    let (result, stats) = verify_seal_with_stats(&tiny, b"header", &Target::MAX);
+    assert_eq!(result.unwrap_err(), VerifyError::CubeTooSmall);
+    assert_eq!(stats.moves_applied, 0);
+}
+
+#[test]
+fn test_fast_path_accepts_what_replay_accepts() {
+    let seal = solved_seal(3, 42, b"header");
+    let (result, stats) = verify_seal_with_stats(&seal, b"header", &Target::MAX);
+    assert!(result.is_ok());
+    // Only the scramble is replayed
+    assert!(stats.fast_path);
+    assert_eq!(stats.moves_applied, seal.moves.len() as u64);
+
+    // Split and merged turns and a trailing rotation still cancel
+    let mut split = Vec::new();
+    for m in &seal.moves {
+        split.extend((0..m.count()).map(|_| Move::from_face_and_count(m.face(), 1)));
+    }
+    split.push(Move::Rotation(Axis::Y, 1));
+    let split = PowSeal { moves: split, ..seal.clone() };
+    let (result, stats) = verify_seal_with_stats(&split, b"header", &Target::MAX);
+    assert!(result.is_ok());
+    assert!(stats.fast_path);
+
+    // A near-inverse that leaves one layer turned is rejected by the replay
+    let mut wrong = seal.clone();
+    wrong.moves.push(Move::R(1));
+    let (result, stats) = verify_seal_with_stats(&wrong, b"header", &Target::MAX);
+    assert_eq!(result.unwrap_err(), VerifyError::InvalidSolution);
+    assert!(!stats.fast_path);
+}
+
+#[test]
+fn test_slices_are_accepted_from_v2() {
+    let mut seal = solved_seal(3, 42, b"header");
+    seal.moves.extend([Move::Slice(Axis::X, 1), Move::Slice(Axis::X, 3)]);
+    let verify = |spec| verify_seal_with_versions(spec, CommitmentVersion::CURRENT, &seal, b"header", &Target::MAX);
+    assert_eq!(verify(SpecVersion::V1).unwrap_err(), VerifyError::MalformedSeal);
+    assert!(verify(SpecVersion::V2).is_ok());
+
+    // Numbered layers cannot be carried by seal bytes under any version
+    let mut numbered = solved_seal(4, 42, b"header");
+    numbered.moves.extend([Move::Layer(Face::Right, 2, 1), Move::Layer(Face::Right, 2, 3)]);
+    let result = verify_seal_with_versions(SpecVersion::V2, CommitmentVersion::CURRENT, &numbered, b"header", &Target::MAX);
+    assert_eq!(result.unwrap_err(), VerifyError::MalformedSeal);
+
+    // Not a face turn of the left layer: M cancels only with M
+    let mut wrong = seal.clone();
+    wrong.moves.push(Move::L(1));
+    wrong.moves.push(Move::Slice(Axis::X, 3));
+    let result = verify_seal_with_versions(SpecVersion::V2, CommitmentVersion::CURRENT, &wrong, b"header", &Target::MAX);
+    assert_eq!(result.unwrap_err(), VerifyError::InvalidSolution);
+}
+
+#[test]
+fn test_versions_pick_the_commitment_checked() {
+    let seal = solved_seal(3, 42, b"header");
+    let cube = verify_seal(&seal, b"header", &Target::MAX).unwrap();
+    let v1 = cube.state_commitment_with(CommitmentVersion::V1FaceIds);
+    let v2 = cube.state_commitment_with(CommitmentVersion::V2Packed);
+
+    // A target between the two commitments is only met by the lower one
+    let (lower, higher) = if v1 < v2 {
+        (CommitmentVersion::V1FaceIds, CommitmentVersion::V2Packed)
+    } else {
+        (CommitmentVersion::V2Packed, CommitmentVersion::V1FaceIds)
+    };
+    let target = Target::from_bytes(v1.min(v2));
+    assert!(verify_seal_with_versions(SpecVersion::V1, lower, &seal, b"header", &target).is_ok());
+    assert_eq!(
+        verify_seal_with_versions(SpecVersion::V1, higher, &seal, b"header", &target).unwrap_err(),
+        VerifyError::TargetNotMet
+    );
+    assert_eq!(
+        verify_seal(&seal, b"header", &target).is_ok(),
+        lower == CommitmentVersion::CURRENT
+    );
+}
@@ -0,0 +1,61 @@
+//! Runs the consensus vectors in the Wasm build of the crate and compares them
+//! with the native build's. Needs the `wasm-vectors` feature and the
+//! `wasm32-unknown-unknown` target (`rustup target add wasm32-unknown-unknown`);
+//! CI runs it in the `wasm-vectors` job.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use qbitcoin_core::vectors::{decode, encode, vectors};
+use wasmtime::{Engine, Instance, Module, Store};
+
+/// Builds `examples/wasm_vectors.rs` for Wasm against the `no_std` core, as
+/// runtimes build it, with the same protocol features as this build. It gets
+/// a target directory of its own so it does not wait on the lock of the one
+/// running the tests.
+fn build_module() -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = manifest_dir.join("target").join("wasm-vectors");
+    let mut build = Command::new(env!("CARGO"));
+    build
+        .current_dir(manifest_dir)
+        .args(["build", "--release", "--example", "wasm_vectors", "--target", "wasm32-unknown-unknown"])
+        .args(["--no-default-features", "--target-dir"])
+        .arg(&target_dir);
+    if cfg!(feature = "color-shuffle") {
+        build.args(["--features", "color-shuffle"]);
+    }
+    let status = build.status().expect("cargo runs");
+    assert!(status.success(), "building the Wasm vectors failed; is wasm32-unknown-unknown installed?");
+    target_dir.join("wasm32-unknown-unknown/release/examples/wasm_vectors.wasm")
+}
+
+fn wasm_vectors(path: &Path) -> Vec<u8> {
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, path).unwrap();
+    let mut store = Store::new(&engine, ());
+    let instance = Instance::new(&mut store, &module, &[]).unwrap();
+    let packed = instance
+        .get_typed_func::<(), u64>(&mut store, "vectors")
+        .unwrap()
+        .call(&mut store, ())
+        .unwrap();
+    let (pointer, length) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+    let memory = instance.get_memory(&mut store, "memory").unwrap();
+    memory.data(&store)[pointer..pointer + length].to_vec()
+}
+
+#[test]
+fn test_wasm_and_native_vectors_match() {
+    let native = vectors();
+    let wasm = wasm_vectors(&build_module());
+    if wasm == encode(&native) {
+        return;
+    }
+
+    let wasm = decode(&wasm).expect("the Wasm vectors decode");
+    for (native, wasm) in native.iter().zip(&wasm) {
+        assert_eq!(native, wasm, "first vector to differ");
+    }
+    assert_eq!(native.len(), wasm.len(), "vector counts differ");
+}
@@ -0,0 +1,60 @@
+use qbitcoin_core::group::sequences_equivalent;
+use qbitcoin_core::watermark::{embed_tag, extract_tag, tag_capacity};
+use qbitcoin_core::Move;
+
+fn solution() -> Vec<Move> {
+    // Eight back-to-back U/D, L/R and F/B pairs
+    let pairs = [
+        (Move::U(1), Move::D(3)),
+        (Move::L(2), Move::R(1)),
+        (Move::F(1), Move::B(1)),
+        (Move::R(3), Move::L(1)),
+        (Move::D(2), Move::U(2)),
+        (Move::B(3), Move::F(2)),
+        (Move::U(3), Move::D(1)),
+        (Move::L(1), Move::R(2)),
+    ];
+    pairs.iter().flat_map(|&(a, b)| vec![a, b]).collect()
+}
+
+#[test]
+fn test_tag_round_trip() {
+    let moves = solution();
+    assert_eq!(tag_capacity(&moves), 8);
+
+    for tag in [0x00u8, 0xA5, 0xFF] {
+        let tagged = embed_tag(&moves, &[tag]).unwrap();
+        assert_eq!(tagged.len(), moves.len());
+        assert_eq!(extract_tag(&tagged, 1), Some(vec![tag]));
+        // The tagged solution performs exactly the same permutation
+        assert!(sequences_equivalent(&moves, &tagged, 3));
+    }
+}
+
+#[test]
+fn test_tag_too_long() {
+    let moves = solution();
+    assert!(embed_tag(&moves, &[1, 2]).is_none());
+    assert!(extract_tag(&moves, 2).is_none());
+    assert_eq!(tag_capacity(&[Move::U(1), Move::R(1)]), 0);
+}
+
+#[test]
+fn test_pairs_after_a_turn_on_their_axis_carry_no_bit() {
+    // Swapping the pair of [U, U, D] would give [U, D, U], whose pair starts
+    // one move earlier
+    assert_eq!(tag_capacity(&[Move::U(1), Move::U(1), Move::D(1)]), 0);
+    assert_eq!(tag_capacity(&[Move::R(1), Move::U(1), Move::D(1)]), 1);
+
+    // The U/D run in front takes the first pair of the solution with it; one
+    // more pair at the end makes up for it
+    let mut moves = vec![Move::U(1), Move::U(1), Move::D(3)];
+    moves.extend(solution());
+    moves.extend([Move::F(1), Move::B(2)]);
+    assert_eq!(tag_capacity(&moves), 8);
+    for tag in [0x00u8, 0xA5, 0xFF] {
+        let tagged = embed_tag(&moves, &[tag]).unwrap();
+        assert_eq!(extract_tag(&tagged, 1), Some(vec![tag]));
+        assert!(sequences_equivalent(&moves, &tagged, 3));
+    }
+}
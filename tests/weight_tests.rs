@@ -0,0 +1,23 @@
+use qbitcoin_core::weight::{estimate_verification_weight, WeightHint, BASE_WEIGHT, SEAL_WRITES, WEIGHT_PER_MOVE};
+
+#[test]
+fn test_weight_grows_with_moves_and_size() {
+    let empty = estimate_verification_weight(3, 0);
+    assert_eq!(empty, WeightHint { ref_time: BASE_WEIGHT, writes: SEAL_WRITES });
+    assert_eq!(estimate_verification_weight(3, 20).ref_time, BASE_WEIGHT + 20 * WEIGHT_PER_MOVE);
+    assert_eq!(estimate_verification_weight(6, 20).ref_time, BASE_WEIGHT + 40 * WEIGHT_PER_MOVE);
+    assert!(estimate_verification_weight(2, 20).ref_time < estimate_verification_weight(3, 20).ref_time);
+    assert_eq!(estimate_verification_weight(u32::MAX, usize::MAX).ref_time, u64::MAX);
+}
+
+#[test]
+fn test_weight_against_a_budget() {
+    let one = estimate_verification_weight(3, 20);
+    let two = one.saturating_add(one);
+    assert_eq!(two, WeightHint { ref_time: 2 * one.ref_time, writes: 2 * SEAL_WRITES });
+
+    let write_weight = 100;
+    assert_eq!(one.total(write_weight), one.ref_time + SEAL_WRITES * write_weight);
+    assert!(!one.exceeds(one.total(write_weight), write_weight));
+    assert!(two.exceeds(one.total(write_weight) * 3 / 2, write_weight));
+}
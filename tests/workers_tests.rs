@@ -0,0 +1,95 @@
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use qbitcoin_core::miner::{BlockTemplate, PerNode, Topology, WorkerConfig, WorkerPool};
+use qbitcoin_core::solver::ReverseScramble;
+use qbitcoin_core::{verify_seal, Target};
+
+fn template(header: &[u8]) -> BlockTemplate {
+    BlockTemplate {
+        parent_hash: [0; 32],
+        header: header.to_vec(),
+        cube_size: 2,
+        target: Target::MAX,
+    }
+}
+
+#[test]
+fn test_topology_from_sysfs() {
+    let root = std::env::temp_dir().join(format!("qbit-numa-{}", std::process::id()));
+    for (node, cpus) in [("node1", "4-5,7\n"), ("node0", "0-1\n"), ("node2", "\n")] {
+        fs::create_dir_all(root.join(node)).unwrap();
+        fs::write(root.join(node).join("cpulist"), cpus).unwrap();
+    }
+    fs::create_dir_all(root.join("possible")).unwrap();
+
+    let topology = Topology::open(&root).unwrap();
+    // The memory-only node is dropped
+    assert_eq!(topology.nodes(), 2);
+    assert_eq!(topology.cpus_of(0), &[0, 1]);
+    assert_eq!(topology.cpus_of(1), &[4, 5, 7]);
+    assert_eq!(topology.placements(), vec![(0, 0), (4, 1), (1, 0), (5, 1), (7, 1)]);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_missing_sysfs_has_no_topology() {
+    assert!(Topology::open(std::path::Path::new("/nonexistent/node")).is_none());
+    assert!(!Topology::detect().placements().is_empty());
+}
+
+#[test]
+fn test_per_node_builds_each_replica_once() {
+    let topology = Topology::from_nodes(vec![vec![0, 1], vec![2, 3]]).unwrap();
+    let replicas = PerNode::new(&topology);
+    let builds = AtomicUsize::new(0);
+    for node in [0, 1, 0, 1] {
+        let table = replicas.get_or_init(node, || {
+            builds.fetch_add(1, Ordering::SeqCst);
+            vec![node; 4]
+        });
+        assert_eq!(table, &vec![node; 4]);
+    }
+    assert_eq!(builds.load(Ordering::SeqCst), 2);
+    assert!(replicas.get(2).is_none());
+}
+
+#[test]
+fn test_pool_finds_seals_for_current_job() {
+    let topology = Topology::from_nodes(vec![vec![0], vec![1]]).unwrap();
+    let config = WorkerConfig { threads: 3, pin: false };
+    let solvers = Arc::new(AtomicUsize::new(0));
+    let built = Arc::clone(&solvers);
+    let (pool, seals) = WorkerPool::start(&config, &topology, move |_| {
+        built.fetch_add(1, Ordering::SeqCst);
+        ReverseScramble
+    });
+
+    assert_eq!(solvers.load(Ordering::SeqCst), 3);
+    let nodes: Vec<_> = pool.workers().iter().map(|worker| worker.node).collect();
+    assert_eq!(nodes, vec![0, 1, 0]);
+    assert!(pool.workers().iter().all(|worker| !worker.pinned));
+    // Idle until there is a template
+    assert!(seals.recv_timeout(Duration::from_millis(50)).is_err());
+
+    let first = template(b"first");
+    pool.set_template(1, first.clone());
+    let mut nonces = Vec::new();
+    for _ in 0..30 {
+        let found = seals.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(found.job, 1);
+        assert_eq!(found.seal.nonce % 3, found.worker as u64);
+        verify_seal(&found.seal, &first.header, &first.target).unwrap();
+        nonces.push(found.seal.nonce);
+    }
+    nonces.sort();
+    nonces.dedup();
+    assert_eq!(nonces.len(), 30);
+
+    pool.set_template(2, template(b"second"));
+    let found = seals.iter().find(|found| found.job == 2).unwrap();
+    verify_seal(&found.seal, b"second", &Target::MAX).unwrap();
+}